@@ -16,7 +16,8 @@ fn main() {
     let solution = fs::read_to_string(&args[2]).unwrap();
 
     let problem: ProblemDefinition = serde_json::from_str(&problem).unwrap();
-    let problem: Problem = problem.into();
+    problem.validate().unwrap();
+    let problem = Problem::try_from(problem).unwrap();
     let program = Compiler::default().compile(&solution).unwrap();
 
     program.validate(&problem).unwrap();
@@ -0,0 +1,367 @@
+//! Python Bindings
+//!
+//! Compile, validate, run & trace programs from Python, e.g. to grade submissions in Jupyter.
+//! Problems are built from dicts (or JSON text) in the [ProblemDefinition] format or loaded from
+//! the official levels. Scores, diagnostics & traces are returned as plain dicts & lists.
+//!
+//! ```python
+//! import hrm
+//!
+//! problem = hrm.Problem.level("year_2")
+//! result = hrm.run(problem, source)
+//! if result["score"] is None:
+//!     print(result["diagnostics"][0]["message"])
+//! ```
+
+use hrm::{
+    code::program::Program,
+    compiler::compile::Compiler,
+    game::problem::Problem,
+    levels,
+    model::{
+        diagnostic::Diagnostic,
+        problem_definition::ProblemDefinition,
+        session::{self, run_source, validate_source, Session},
+        trace::Trace,
+    },
+};
+use pyo3::{exceptions::PyValueError, prelude::*};
+use serde::Serialize;
+
+/// Steps a trace takes unless told otherwise
+const MAX_TRACE_STEPS: u32 = 10_000;
+
+/// Problem
+///
+/// A problem built from a [ProblemDefinition].
+#[pyclass(name = "Problem", module = "hrm", frozen)]
+struct PyProblem {
+    definition: ProblemDefinition,
+    problem: Problem,
+}
+
+#[pymethods]
+impl PyProblem {
+    /// New
+    ///
+    /// Build a problem from a definition dict or JSON text. Raises `ValueError` if the definition
+    /// is invalid.
+    #[new]
+    fn new(definition: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let json = match definition.extract::<String>() {
+            Ok(json) => json,
+            Err(_) => definition
+                .py()
+                .import("json")?
+                .call_method1("dumps", (definition,))?
+                .extract()?,
+        };
+        let definition = serde_json::from_str(&json).map_err(value_error)?;
+        Self::from_definition(definition)
+    }
+
+    /// Level
+    ///
+    /// Load the official level with given id, e.g. `year_2`.
+    #[staticmethod]
+    fn level(id: &str) -> PyResult<Self> {
+        let definition = levels::get(id)
+            .ok_or_else(|| PyValueError::new_err(format!("Level {id} does not exist")))?;
+        Self::from_definition(definition)
+    }
+
+    /// Levels
+    ///
+    /// Returns ids of all official levels in game order.
+    #[staticmethod]
+    fn levels() -> Vec<&'static str> {
+        levels::ids().collect()
+    }
+
+    #[getter]
+    fn title(&self) -> &str {
+        &self.problem.title
+    }
+
+    #[getter]
+    fn description(&self) -> &str {
+        &self.problem.description
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Problem({:?})", self.problem.title)
+    }
+}
+
+impl PyProblem {
+    fn from_definition(definition: ProblemDefinition) -> PyResult<Self> {
+        definition.validate().map_err(value_error)?;
+        let problem = Self::build(definition.clone())?;
+        Ok(Self {
+            definition,
+            problem,
+        })
+    }
+
+    fn session(&self, source: &str, io: usize) -> PyResult<Session> {
+        let problem = Self::build(self.definition.clone())?;
+        Session::create(problem, source, io).map_err(PyValueError::new_err)
+    }
+
+    /// Build a new [Problem], problems can't be cloned
+    fn build(definition: ProblemDefinition) -> PyResult<Problem> {
+        Problem::try_from(definition).map_err(|errors| {
+            let errors: Vec<String> = errors.iter().map(ToString::to_string).collect();
+            PyValueError::new_err(errors.join(", "))
+        })
+    }
+}
+
+/// Program
+///
+/// A compiled program & its source.
+#[pyclass(name = "Program", module = "hrm", frozen)]
+struct PyProgram {
+    program: Program,
+    source: String,
+}
+
+#[pymethods]
+impl PyProgram {
+    /// New
+    ///
+    /// Compile `source` with the vanilla or the extended instruction set. Raises `ValueError` if
+    /// it doesn't compile.
+    #[new]
+    #[pyo3(signature = (source, extended = false))]
+    fn new(source: &str, extended: bool) -> PyResult<Self> {
+        let compiler = if extended {
+            Compiler::extended()
+        } else {
+            Compiler::default()
+        };
+        let program = compiler.compile(source).map_err(|err| {
+            let diagnostic = Diagnostic::from_parse_error(&compiler, source, &err);
+            PyValueError::new_err(diagnostic.message)
+        })?;
+
+        Ok(Self {
+            program,
+            source: source.to_string(),
+        })
+    }
+
+    /// Validate
+    ///
+    /// Returns the validation & analysis diagnostics for `problem`.
+    fn validate(&self, py: Python<'_>, problem: &PyProblem) -> PyResult<Py<PyAny>> {
+        to_py(py, &validate_source(&problem.problem, &self.source))
+    }
+
+    /// Run
+    ///
+    /// See [run].
+    fn run(&self, py: Python<'_>, problem: &PyProblem) -> PyResult<Py<PyAny>> {
+        run(py, problem, &self.source)
+    }
+
+    /// Trace
+    ///
+    /// See [trace].
+    #[pyo3(signature = (problem, io = 0, max_steps = MAX_TRACE_STEPS))]
+    fn trace(
+        &self,
+        py: Python<'_>,
+        problem: &PyProblem,
+        io: usize,
+        max_steps: u32,
+    ) -> PyResult<Py<PyAny>> {
+        trace(py, problem, &self.source, io, max_steps)
+    }
+
+    /// Export Trace
+    ///
+    /// See [export_trace].
+    #[pyo3(signature = (problem, io = 0, max_steps = MAX_TRACE_STEPS))]
+    fn export_trace(
+        &self,
+        py: Python<'_>,
+        problem: &PyProblem,
+        io: usize,
+        max_steps: u32,
+    ) -> PyResult<Py<PyAny>> {
+        export_trace(py, problem, &self.source, io, max_steps)
+    }
+
+    #[getter]
+    fn size(&self) -> usize {
+        self.program.size()
+    }
+
+    #[getter]
+    fn source(&self) -> &str {
+        &self.source
+    }
+
+    fn __len__(&self) -> usize {
+        self.program.get_commands().len()
+    }
+}
+
+/// Run
+///
+/// Compile, validate & run `source` for every IO of `problem`. Returns a dict with the `score`, or
+/// `None` & the `diagnostics` explaining why it failed.
+#[pyfunction]
+fn run(py: Python<'_>, problem: &PyProblem, source: &str) -> PyResult<Py<PyAny>> {
+    to_py(py, &run_source(&problem.problem, source))
+}
+
+/// Trace
+///
+/// Run `source` on IO `io` of `problem` & return the state after every step, at most
+/// `max_steps`. The last state is `finished` once the run ended, with an `error` if it failed.
+/// Raises `ValueError` if the program doesn't compile or validate, or the IO doesn't exist.
+#[pyfunction]
+#[pyo3(signature = (problem, source, io = 0, max_steps = MAX_TRACE_STEPS))]
+fn trace(
+    py: Python<'_>,
+    problem: &PyProblem,
+    source: &str,
+    io: usize,
+    max_steps: u32,
+) -> PyResult<Py<PyAny>> {
+    let mut session = problem.session(source, io)?;
+    let trace: Vec<session::StepState> = session.trace(max_steps);
+    to_py(py, &trace)
+}
+
+/// Export Trace
+///
+/// Like [trace], but returns a replayable trace in the stable schema of [Trace], with memory
+/// changes instead of full memory per step. `json.dumps` it for visualizers.
+#[pyfunction]
+#[pyo3(signature = (problem, source, io = 0, max_steps = MAX_TRACE_STEPS))]
+fn export_trace(
+    py: Python<'_>,
+    problem: &PyProblem,
+    source: &str,
+    io: usize,
+    max_steps: u32,
+) -> PyResult<Py<PyAny>> {
+    let trace: Trace = problem.session(source, io)?.export_trace(max_steps);
+    to_py(py, &trace)
+}
+
+#[pymodule]
+#[pyo3(name = "hrm")]
+fn hrm_python(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyProblem>()?;
+    m.add_class::<PyProgram>()?;
+    m.add_function(wrap_pyfunction!(run, m)?)?;
+    m.add_function(wrap_pyfunction!(trace, m)?)?;
+    m.add_function(wrap_pyfunction!(export_trace, m)?)?;
+    Ok(())
+}
+
+/// Convert to plain Python objects through JSON
+fn to_py<T: Serialize>(py: Python<'_>, value: &T) -> PyResult<Py<PyAny>> {
+    let json = serde_json::to_string(value).map_err(value_error)?;
+    Ok(py.import("json")?.call_method1("loads", (json,))?.unbind())
+}
+
+fn value_error(err: impl ToString) -> PyErr {
+    PyValueError::new_err(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use pyo3::types::{PyDict, PyList, PyString};
+
+    use super::*;
+
+    fn problem(py: Python<'_>) -> Bound<'_, PyProblem> {
+        let definition = r#"{
+            "title": "Echo",
+            "description": "Output every input",
+            "ios": [{"input": [1, 2], "output": [1, 2]}],
+            "commands": ["INBOX", "OUTBOX", "JUMP"]
+        }"#;
+        Bound::new(py, PyProblem::new(&PyString::new(py, definition)).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn run_test() {
+        Python::initialize();
+        Python::attach(|py| {
+            let problem = problem(py);
+            let program = PyProgram::new("a:\nINBOX\nOUTBOX\nJUMP a\n", false).unwrap();
+            assert_eq!(3, program.__len__());
+
+            let result = program.run(py, &problem.borrow()).unwrap();
+            let result = result.cast_bound::<PyDict>(py).unwrap();
+            let score = result.get_item("score").unwrap().unwrap();
+            assert_eq!(
+                6,
+                score
+                    .get_item("speed_max")
+                    .unwrap()
+                    .extract::<u64>()
+                    .unwrap()
+            );
+
+            let result = run(py, &problem.borrow(), "INBOX\nOUTBOX\n").unwrap();
+            let result = result.cast_bound::<PyDict>(py).unwrap();
+            assert!(result.get_item("score").unwrap().unwrap().is_none());
+
+            assert!(PyProgram::new("MUL 0\n", false).is_err());
+        });
+    }
+
+    #[test]
+    fn trace_test() {
+        Python::initialize();
+        Python::attach(|py| {
+            let problem = problem(py);
+            let states = trace(py, &problem.borrow(), "INBOX\nOUTBOX\n", 0, 10).unwrap();
+            let states = states.cast_bound::<PyList>(py).unwrap();
+            assert_eq!(2, states.len());
+
+            let last = states.get_item(1).unwrap();
+            assert!(last
+                .get_item("finished")
+                .unwrap()
+                .extract::<bool>()
+                .unwrap());
+            let error = last.get_item("error").unwrap();
+            assert_eq!(
+                "E0305",
+                error.get_item("code").unwrap().extract::<String>().unwrap()
+            );
+
+            assert!(trace(py, &problem.borrow(), "INBOX\n", 1, 10).is_err());
+
+            let exported = export_trace(py, &problem.borrow(), "INBOX\nOUTBOX\n", 0, 10).unwrap();
+            let exported = exported.cast_bound::<PyDict>(py).unwrap();
+            let steps = exported.get_item("steps").unwrap().unwrap();
+            assert_eq!(2, steps.len().unwrap());
+            let output = steps.get_item(1).unwrap().get_item("output").unwrap();
+            assert_eq!(1, output.extract::<i32>().unwrap());
+        });
+    }
+
+    #[test]
+    fn problem_test() {
+        Python::initialize();
+        Python::attach(|py| {
+            let problem = PyProblem::level("year_2").unwrap();
+            assert_eq!("Problem(\"Busy Mail Room\")", problem.__repr__());
+            assert!(PyProblem::levels().contains(&"year_41"));
+            assert!(PyProblem::level("year_0").is_err());
+
+            let definition = PyDict::new(py);
+            definition.set_item("title", "Missing IOs").unwrap();
+            assert!(PyProblem::new(&definition).is_err());
+        });
+    }
+}
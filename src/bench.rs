@@ -0,0 +1,203 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    code::program::{FailureReport, Program},
+    compiler::compile::{Compiler, ParseError},
+    game::problem::Problem,
+};
+
+/// Throughput
+///
+/// Number of processed items (source lines or executed commands) in the measured time.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Throughput {
+    pub items: u64,
+    pub elapsed: Duration,
+}
+
+impl Throughput {
+    /// Per Second
+    ///
+    /// Returns items per second, `0` if no time elapsed.
+    pub fn per_second(&self) -> f64 {
+        let seconds = self.elapsed.as_secs_f64();
+        if seconds > 0.0 {
+            self.items as f64 / seconds
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Bench Case
+///
+/// Program source & the problem it solves.
+pub struct BenchCase<'a> {
+    pub problem: &'a Problem,
+    pub source: &'a str,
+}
+
+/// Bench Report
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct BenchReport {
+    /// Compiled source lines
+    pub compile: Throughput,
+    /// Executed commands, see [crate::code::program::Score::steps]
+    pub execution: Throughput,
+}
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum BenchError {
+    #[error("Case {0}: {1}")]
+    Parse(usize, ParseError),
+    #[error("Case {0}: {1}")]
+    Run(usize, Box<FailureReport>),
+}
+
+/// Bench Compile
+///
+/// Compile every source `iterations` times, measuring source lines per second.
+pub fn bench_compile(
+    compiler: &Compiler,
+    sources: &[&str],
+    iterations: u32,
+) -> Result<Throughput, BenchError> {
+    let lines: u64 = sources
+        .iter()
+        .map(|source| source.lines().count() as u64)
+        .sum();
+
+    let start = Instant::now();
+    for _ in 0..iterations {
+        for (i, source) in sources.iter().enumerate() {
+            compiler
+                .compile(source)
+                .map_err(|err| BenchError::Parse(i, err))?;
+        }
+    }
+
+    Ok(Throughput {
+        items: lines * iterations as u64,
+        elapsed: start.elapsed(),
+    })
+}
+
+/// Bench Execution
+///
+/// Run every program on its problem `iterations` times, measuring executed commands per second.
+pub fn bench_execution(
+    cases: &[(&Problem, &Program)],
+    iterations: u32,
+) -> Result<Throughput, BenchError> {
+    let mut items = 0;
+    let mut elapsed = Duration::ZERO;
+
+    for _ in 0..iterations {
+        for (i, (problem, program)) in cases.iter().enumerate() {
+            let start = Instant::now();
+            let score = program
+                .run(problem)
+                .map_err(|report| BenchError::Run(i, Box::new(report)))?;
+            elapsed += start.elapsed();
+            items += score.steps.iter().sum::<u64>();
+        }
+    }
+
+    Ok(Throughput { items, elapsed })
+}
+
+/// Bench
+///
+/// Measure compile & execution throughput of a corpus of programs.
+pub fn bench(
+    compiler: &Compiler,
+    cases: &[BenchCase],
+    iterations: u32,
+) -> Result<BenchReport, BenchError> {
+    let sources: Vec<&str> = cases.iter().map(|case| case.source).collect();
+    let compile = bench_compile(compiler, &sources, iterations)?;
+
+    let programs = cases
+        .iter()
+        .enumerate()
+        .map(|(i, case)| {
+            compiler
+                .compile(case.source)
+                .map_err(|err| BenchError::Parse(i, err))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let runs: Vec<(&Problem, &Program)> = cases
+        .iter()
+        .zip(&programs)
+        .map(|(case, program)| (case.problem, program))
+        .collect();
+    let execution = bench_execution(&runs, iterations)?;
+
+    Ok(BenchReport { compile, execution })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::problem::{ProblemBuilder, ProblemIO};
+    use crate::game::value::Value;
+
+    use super::*;
+
+    fn problem() -> Problem {
+        ProblemBuilder::new()
+            .add_io(ProblemIO {
+                input: vec![Value::Int(1), Value::Int(2)],
+                output: vec![Value::Int(1), Value::Int(2)],
+            })
+            .enable_all_commands()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn bench_test() {
+        let problem = problem();
+        let cases = [BenchCase {
+            problem: &problem,
+            source: "a:\nINBOX\nOUTBOX\nJUMP a\n",
+        }];
+
+        let report = bench(&Compiler::default(), &cases, 3).unwrap();
+        assert_eq!(12, report.compile.items);
+        assert_eq!(18, report.execution.items);
+    }
+
+    #[test]
+    fn bench_fails() {
+        let problem = problem();
+        let cases = [
+            BenchCase {
+                problem: &problem,
+                source: "INBOX\nOUTBOX\nINBOX\nOUTBOX",
+            },
+            BenchCase {
+                problem: &problem,
+                source: "INBOX\nOUTBOX",
+            },
+        ];
+
+        let err = bench(&Compiler::default(), &cases, 1).unwrap_err();
+        assert!(matches!(err, BenchError::Run(1, _)));
+
+        let err = bench_compile(&Compiler::default(), &["FOO"], 1).unwrap_err();
+        assert!(matches!(
+            err,
+            BenchError::Parse(0, ParseError::IllegalLine(_))
+        ));
+    }
+
+    #[test]
+    fn per_second_test() {
+        let throughput = Throughput {
+            items: 10,
+            elapsed: Duration::from_millis(500),
+        };
+        assert_eq!(20.0, throughput.per_second());
+        assert_eq!(0.0, Throughput::default().per_second());
+    }
+}
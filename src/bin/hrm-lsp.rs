@@ -0,0 +1,15 @@
+//! `hrm-lsp` language server
+//!
+//! Serves the Language Server Protocol over stdio, see [hrm::lsp].
+
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    match hrm::lsp::run_stdio() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("hrm-lsp: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
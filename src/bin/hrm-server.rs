@@ -0,0 +1,96 @@
+//! `hrm-server` grading service
+//!
+//! Serves the HTTP API of [hrm::server]. See [USAGE].
+
+use std::{env, process::ExitCode, time::Duration};
+
+use tokio::{net::TcpListener, runtime::Runtime};
+
+use hrm::server::{serve, ServerLimits};
+
+const USAGE: &str = "\
+Usage:
+    hrm-server [--addr <addr>] [--max-steps <n>] [--timeout <ms>] [--max-body <bytes>]
+
+Options:
+    --addr <addr>        Address to listen on [default: 127.0.0.1:8080]
+    --max-steps <n>      Steps per IO of a run [default: 1000000]
+    --timeout <ms>       Time to answer a request [default: 5000]
+    --max-body <bytes>   Largest request body [default: 1048576]";
+
+/// Exit code of invalid arguments, like `hrm`
+const USAGE_ERROR: u8 = 64;
+
+fn main() -> ExitCode {
+    let (addr, limits) = match parse_args(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("{message}\n\n{USAGE}");
+            return ExitCode::from(USAGE_ERROR);
+        }
+    };
+
+    let result = Runtime::new().and_then(|runtime| {
+        runtime.block_on(async {
+            let listener = TcpListener::bind(&addr).await?;
+            eprintln!("hrm-server: listening on {}", listener.local_addr()?);
+            serve(listener, limits).await
+        })
+    });
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("hrm-server: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<(String, ServerLimits), String> {
+    let mut addr = String::from("127.0.0.1:8080");
+    let mut limits = ServerLimits::default();
+    while let Some(arg) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("Missing value of `{arg}`"))?;
+        let number = || {
+            value
+                .parse::<u64>()
+                .map_err(|_| format!("Invalid value of `{arg}`: `{value}`"))
+        };
+        match arg.as_str() {
+            "--addr" => addr = value.clone(),
+            "--max-steps" => {
+                limits.max_steps = number()?
+                    .try_into()
+                    .map_err(|_| format!("Invalid value of `{arg}`: `{value}`"))?
+            }
+            "--timeout" => limits.timeout = Duration::from_millis(number()?),
+            "--max-body" => limits.max_body = number()? as usize,
+            _ => return Err(format!("Unknown option `{arg}`")),
+        }
+    }
+    Ok((addr, limits))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<(String, ServerLimits), String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parse_args_test() {
+        let (addr, limits) = parse(&["--addr", "0.0.0.0:80", "--timeout", "100"]).unwrap();
+        assert_eq!("0.0.0.0:80", addr);
+        assert_eq!(Duration::from_millis(100), limits.timeout);
+        assert_eq!(ServerLimits::default().max_steps, limits.max_steps);
+
+        assert!(parse(&["--max-steps", "-1"]).is_err());
+        assert!(parse(&["--max-steps", "5000000000"]).is_err());
+        assert!(parse(&["--timeout"]).is_err());
+        assert!(parse(&["--port", "80"]).is_err());
+    }
+}
@@ -0,0 +1,936 @@
+//! `hrm` command line tool
+//!
+//! Compile, validate, run & score solutions against problem definitions. See [USAGE].
+
+use std::{
+    env,
+    fmt::Write as _,
+    fs,
+    io::{self, BufRead, ErrorKind},
+    path::Path,
+    process::ExitCode,
+};
+
+use serde_json::json;
+
+use hrm::code::commands::InstructionSet;
+use hrm::code::program::{ChallengeResult, Program, Score};
+use hrm::compiler::compile::Compiler;
+use hrm::game::problem::{Problem, ProblemBuilder};
+use hrm::model::diagnostic::{Diagnostic, MessageStyle, Severity};
+use hrm::model::junit::{evaluate_junit, to_junit};
+use hrm::model::problem_definition::ProblemDefinition;
+use hrm::model::program_definition::ProgramDefinition;
+use hrm::model::report::Report;
+#[cfg(feature = "levels")]
+use hrm::model::repository::{verify_repository, Verdict, Verification};
+#[cfg(feature = "tui")]
+use hrm::model::session::Session;
+use hrm::model::stream::{run_stream, StreamError};
+
+const USAGE: &str = "\
+Usage:
+    hrm run <problem> <solution> [--format text|json|junit|markdown|html]
+    hrm validate <problem> <solution> [--format text|json]
+    hrm compile <solution> [--emit text|json]
+    hrm score <problem> <solution> [--format text|json|junit|markdown|html]
+    hrm pipe <solution> [<problem>]
+    hrm watch <problem> <solution> [<io>]
+    hrm verify <directory> [--format text|json]
+
+Problems are read as JSON, or by extension as YAML (.yaml, .yml) & JSON5 (.json5) if enabled.
+JUnit XML reports every IO as a test case, running all of them even if one fails.
+Markdown & HTML reports also run every IO, adding the score, command histogram & coverage.
+`pipe` runs the solution as a filter: INBOX reads whitespace separated values from stdin &
+OUTBOX writes each value on its own line to stdout. Without a problem, every command is
+available on an unbounded floor. Errors go to stderr.
+`watch` steps through IO <io> (default 0) in the terminal, if built with the `tui` feature.
+`verify` checks the claimed size & speed of every solution in an `hrm-solutions` style tree,
+e.g. `solutions/01-Mail-Room/6.6-author.asm`, against the official levels. It fails with 2 if
+any solution fails, or is better or worse than claimed.
+
+Exit codes:
+    0   success
+    1   solution doesn't compile or is invalid
+    2   solution fails to run
+    64  invalid arguments
+    65  invalid problem
+    66  unreadable file
+    74  terminal error";
+
+/// Exit codes, following `sysexits.h` for usage & input errors
+mod exit {
+    pub const SUCCESS: u8 = 0;
+    pub const INVALID: u8 = 1;
+    pub const FAILED: u8 = 2;
+    pub const USAGE: u8 = 64;
+    pub const PROBLEM: u8 = 65;
+    pub const FILE: u8 = 66;
+    #[cfg_attr(not(feature = "tui"), allow(dead_code))]
+    pub const IO: u8 = 74;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    Json,
+    /// JUnit XML, see [hrm::model::junit]
+    Junit,
+    /// Evaluation report, see [hrm::model::report]
+    Markdown,
+    Html,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Subcommand {
+    Run {
+        problem: String,
+        solution: String,
+    },
+    Validate {
+        problem: String,
+        solution: String,
+    },
+    Compile {
+        solution: String,
+    },
+    Score {
+        problem: String,
+        solution: String,
+    },
+    Pipe {
+        solution: String,
+        problem: Option<String>,
+    },
+    #[cfg(feature = "tui")]
+    Watch {
+        problem: String,
+        solution: String,
+        io: usize,
+    },
+    #[cfg(feature = "levels")]
+    Verify {
+        directory: String,
+    },
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Args {
+    subcommand: Subcommand,
+    format: Format,
+}
+
+/// Error ending the tool, with its exit code & message
+#[derive(Debug)]
+struct Exit {
+    code: u8,
+    message: String,
+}
+
+impl Exit {
+    fn new(code: u8, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(exit) => {
+            eprintln!("{}\n\n{USAGE}", exit.message);
+            return ExitCode::from(exit.code);
+        }
+    };
+
+    let mut out = String::new();
+    let code = match execute(&args, &mut out) {
+        Ok(code) => code,
+        Err(exit) => {
+            eprintln!("error: {}", exit.message);
+            exit.code
+        }
+    };
+    // Stdout carries the outputs of `pipe`
+    if matches!(args.subcommand, Subcommand::Pipe { .. }) {
+        eprint!("{out}");
+    } else {
+        print!("{out}");
+    }
+    ExitCode::from(code)
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Args, Exit> {
+    let mut positional = vec![];
+    let mut format = Format::Text;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" | "--emit" => {
+                format = match args.next().as_deref() {
+                    Some("text") => Format::Text,
+                    Some("json") => Format::Json,
+                    Some("junit") => Format::Junit,
+                    Some("markdown") => Format::Markdown,
+                    Some("html") => Format::Html,
+                    Some(other) => {
+                        return Err(Exit::new(exit::USAGE, format!("unknown format `{other}`")))
+                    }
+                    None => {
+                        return Err(Exit::new(exit::USAGE, format!("missing value of `{arg}`")))
+                    }
+                }
+            }
+            "-h" | "--help" => {
+                return Err(Exit::new(exit::SUCCESS, "hrm - Human Resource Machine"))
+            }
+            flag if flag.starts_with("--") => {
+                return Err(Exit::new(exit::USAGE, format!("unknown option `{flag}`")))
+            }
+            _ => positional.push(arg),
+        }
+    }
+
+    let mut positional = positional.into_iter();
+    let subcommand = positional
+        .next()
+        .ok_or_else(|| Exit::new(exit::USAGE, "missing subcommand"))?;
+    let mut file = |name: &str| {
+        positional
+            .next()
+            .ok_or_else(|| Exit::new(exit::USAGE, format!("missing <{name}>")))
+    };
+
+    let subcommand = match subcommand.as_str() {
+        "run" => Subcommand::Run {
+            problem: file("problem")?,
+            solution: file("solution")?,
+        },
+        "validate" => Subcommand::Validate {
+            problem: file("problem")?,
+            solution: file("solution")?,
+        },
+        "compile" => Subcommand::Compile {
+            solution: file("solution")?,
+        },
+        "score" => Subcommand::Score {
+            problem: file("problem")?,
+            solution: file("solution")?,
+        },
+        "pipe" => Subcommand::Pipe {
+            solution: file("solution")?,
+            problem: positional.next(),
+        },
+        #[cfg(feature = "tui")]
+        "watch" => Subcommand::Watch {
+            problem: file("problem")?,
+            solution: file("solution")?,
+            io: match positional.next() {
+                Some(io) => io
+                    .parse()
+                    .map_err(|_| Exit::new(exit::USAGE, format!("invalid <io> `{io}`")))?,
+                None => 0,
+            },
+        },
+        #[cfg(feature = "levels")]
+        "verify" => Subcommand::Verify {
+            directory: file("directory")?,
+        },
+        other => {
+            return Err(Exit::new(
+                exit::USAGE,
+                format!("unknown subcommand `{other}`"),
+            ))
+        }
+    };
+
+    if let Some(extra) = positional.next() {
+        return Err(Exit::new(
+            exit::USAGE,
+            format!("unexpected argument `{extra}`"),
+        ));
+    }
+
+    let scored = matches!(
+        subcommand,
+        Subcommand::Run { .. } | Subcommand::Score { .. }
+    );
+    if matches!(format, Format::Junit | Format::Markdown | Format::Html) && !scored {
+        return Err(Exit::new(
+            exit::USAGE,
+            "reports only apply to `run` & `score`",
+        ));
+    }
+
+    if format != Format::Text && matches!(subcommand, Subcommand::Pipe { .. }) {
+        return Err(Exit::new(exit::USAGE, "`pipe` only writes text"));
+    }
+    #[cfg(feature = "tui")]
+    if format != Format::Text && matches!(subcommand, Subcommand::Watch { .. }) {
+        return Err(Exit::new(exit::USAGE, "`watch` has no output format"));
+    }
+
+    Ok(Args { subcommand, format })
+}
+
+/// Execute
+///
+/// Run the subcommand, writing its report to `out`. Returns the exit code, or [Exit] if the
+/// inputs can't be read.
+fn execute(args: &Args, out: &mut String) -> Result<u8, Exit> {
+    match &args.subcommand {
+        Subcommand::Run { problem, solution } | Subcommand::Score { problem, solution } => {
+            let problem = read_problem(problem)?;
+            let source = read(solution)?;
+            if matches!(args.format, Format::Markdown | Format::Html) {
+                let report = Report::new(&problem, &source);
+                match args.format {
+                    Format::Html => out.push_str(&report.to_html()),
+                    _ => out.push_str(&report.to_markdown()),
+                }
+                return Ok(if !report.valid {
+                    exit::INVALID
+                } else if !report.solved() {
+                    exit::FAILED
+                } else {
+                    exit::SUCCESS
+                });
+            }
+            let program = match check(&problem, &source) {
+                Ok(program) if args.format == Format::Junit => {
+                    let results = program.run_each(&problem);
+                    out.push_str(&to_junit(&problem.title, &source, &program, &results));
+                    let failed = results.iter().any(Result::is_err);
+                    return Ok(if failed { exit::FAILED } else { exit::SUCCESS });
+                }
+                Ok(program) => program,
+                Err(_) if args.format == Format::Junit => {
+                    out.push_str(&evaluate_junit(&problem, &source));
+                    return Ok(exit::INVALID);
+                }
+                Err(diagnostics) => {
+                    write_diagnostics(args.format, &diagnostics, out);
+                    return Ok(exit::INVALID);
+                }
+            };
+
+            let score = match program.run(&problem) {
+                Ok(score) => score,
+                Err(report) => {
+                    let diagnostic =
+                        Diagnostic::from_failure(&source, &program, &report, MessageStyle::Plain);
+                    write_diagnostics(args.format, &[diagnostic], out);
+                    return Ok(exit::FAILED);
+                }
+            };
+
+            let challenges = match args.subcommand {
+                Subcommand::Score { .. } => Some(score.evaluate(&problem)),
+                _ => None,
+            };
+            write_score(args.format, &score, challenges, out);
+            Ok(exit::SUCCESS)
+        }
+        Subcommand::Validate { problem, solution } => {
+            let problem = read_problem(problem)?;
+            let source = read(solution)?;
+            let diagnostics = match check(&problem, &source) {
+                Ok(program) => Diagnostic::from_analysis(&compiler(&problem), &source, &program),
+                Err(diagnostics) => diagnostics,
+            };
+
+            write_diagnostics(args.format, &diagnostics, out);
+            let failed = diagnostics
+                .iter()
+                .any(|diagnostic| diagnostic.severity == Severity::Error);
+            Ok(if failed { exit::INVALID } else { exit::SUCCESS })
+        }
+        Subcommand::Compile { solution } => {
+            let source = read(solution)?;
+            let compiler = Compiler::extended();
+            let program = match compiler.compile(&source) {
+                Ok(program) => program,
+                Err(err) => {
+                    let diagnostic = Diagnostic::from_parse_error(&compiler, &source, &err);
+                    write_diagnostics(args.format, &[diagnostic], out);
+                    return Ok(exit::INVALID);
+                }
+            };
+
+            match args.format {
+                Format::Json => {
+                    let definition = ProgramDefinition::from(&program);
+                    write_json(&serde_json::to_value(definition).unwrap(), out);
+                }
+                _ => write_source(&program, out),
+            }
+            Ok(exit::SUCCESS)
+        }
+        Subcommand::Pipe { solution, problem } => {
+            let problem = match problem {
+                Some(problem) => read_problem(problem)?,
+                None => pipe_problem(),
+            };
+            let source = read(solution)?;
+            pipe(
+                &problem,
+                &source,
+                io::stdin().lock(),
+                io::stdout().lock(),
+                out,
+            )
+        }
+        #[cfg(feature = "tui")]
+        Subcommand::Watch {
+            problem,
+            solution,
+            io,
+        } => {
+            let problem = read_problem(problem)?;
+            let source = read(solution)?;
+            if let Err(diagnostics) = check(&problem, &source) {
+                write_diagnostics(Format::Text, &diagnostics, out);
+                return Ok(exit::INVALID);
+            }
+
+            let session = Session::create(problem, &source, *io)
+                .map_err(|err| Exit::new(exit::USAGE, err))?;
+            hrm::tui::watch(session).map_err(|err| Exit::new(exit::IO, err.to_string()))?;
+            Ok(exit::SUCCESS)
+        }
+        #[cfg(feature = "levels")]
+        Subcommand::Verify { directory } => {
+            let results = verify_repository(Path::new(directory))
+                .map_err(|err| Exit::new(exit::FILE, format!("{directory}: {err}")))?;
+            write_verifications(args.format, &results, out);
+            let verified = results
+                .iter()
+                .all(|result| result.verdict() == Verdict::Verified);
+            Ok(if verified {
+                exit::SUCCESS
+            } else {
+                exit::FAILED
+            })
+        }
+    }
+}
+
+/// Problem of `pipe` if none is given
+fn pipe_problem() -> Problem {
+    ProblemBuilder::new()
+        .instruction_set(InstructionSet::Extended)
+        .enable_all_commands()
+        .unbounded_memory(true)
+        .build()
+        .unwrap()
+}
+
+/// Run the solution as a filter from `reader` to `writer`, errors are written to `out`
+fn pipe(
+    problem: &Problem,
+    source: &str,
+    reader: impl BufRead,
+    writer: impl io::Write,
+    out: &mut String,
+) -> Result<u8, Exit> {
+    let program = match check(problem, source) {
+        Ok(program) => program,
+        Err(diagnostics) => {
+            write_diagnostics(Format::Text, &diagnostics, out);
+            return Ok(exit::INVALID);
+        }
+    };
+
+    match run_stream(&program, problem, None, reader, writer) {
+        Ok(_) => Ok(exit::SUCCESS),
+        Err(StreamError::Run(report)) => {
+            let diagnostic =
+                Diagnostic::from_failure(source, &program, &report, MessageStyle::Plain);
+            write_diagnostics(Format::Text, &[diagnostic], out);
+            Ok(exit::FAILED)
+        }
+        // Reader of the outputs is gone, e.g. `| head`
+        Err(StreamError::Io(err)) if err.kind() == ErrorKind::BrokenPipe => Ok(exit::SUCCESS),
+        Err(StreamError::Io(err)) => Err(Exit::new(exit::FILE, err.to_string())),
+        Err(err) => Err(Exit::new(exit::FAILED, format!("{}: {err}", err.code()))),
+    }
+}
+
+fn read(path: &str) -> Result<String, Exit> {
+    fs::read_to_string(path).map_err(|err| Exit::new(exit::FILE, format!("{path}: {err}")))
+}
+
+fn read_problem(path: &str) -> Result<Problem, Exit> {
+    let text = read(path)?;
+    let invalid = |err: &dyn std::fmt::Display| Exit::new(exit::PROBLEM, format!("{path}: {err}"));
+
+    let extension = Path::new(path).extension().and_then(|ext| ext.to_str());
+    let definition: ProblemDefinition = match extension {
+        #[cfg(feature = "yaml")]
+        Some("yaml" | "yml") => ProblemDefinition::from_yaml(&text).map_err(|err| invalid(&err))?,
+        #[cfg(feature = "json5")]
+        Some("json5") => ProblemDefinition::from_json5(&text).map_err(|err| invalid(&err))?,
+        _ => serde_json::from_str(&text).map_err(|err| invalid(&err))?,
+    };
+    definition.validate().map_err(|err| invalid(&err))?;
+
+    Problem::try_from(definition).map_err(|errors| {
+        let errors: Vec<String> = errors.iter().map(ToString::to_string).collect();
+        invalid(&errors.join(", "))
+    })
+}
+
+fn compiler(problem: &Problem) -> Compiler {
+    Compiler::new(problem.get_instruction_set())
+}
+
+/// Compile & validate the solution, returns the errors if either fails
+fn check(problem: &Problem, source: &str) -> Result<Program, Vec<Diagnostic>> {
+    let compiler = compiler(problem);
+    let program = compiler
+        .compile(source)
+        .map_err(|err| vec![Diagnostic::from_parse_error(&compiler, source, &err)])?;
+
+    let diagnostics = Diagnostic::from_validation(&compiler, source, &program, problem);
+    if diagnostics.is_empty() {
+        Ok(program)
+    } else {
+        Err(diagnostics)
+    }
+}
+
+fn write_json(value: &serde_json::Value, out: &mut String) {
+    out.push_str(&serde_json::to_string_pretty(value).unwrap());
+    out.push('\n');
+}
+
+fn write_diagnostics(format: Format, diagnostics: &[Diagnostic], out: &mut String) {
+    if format == Format::Json {
+        write_json(&json!({ "diagnostics": diagnostics }), out);
+        return;
+    }
+
+    for diagnostic in diagnostics {
+        let severity = match diagnostic.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        let line = diagnostic
+            .span
+            .as_ref()
+            .map(|span| format!(" (line {})", span.line + 1))
+            .unwrap_or_default();
+        writeln!(
+            out,
+            "{severity}[{}]{line}: {}",
+            diagnostic.code, diagnostic.message
+        )
+        .unwrap();
+        for related in &diagnostic.related {
+            writeln!(out, "    note: {}", related.message).unwrap();
+        }
+    }
+}
+
+fn write_score(
+    format: Format,
+    score: &Score,
+    challenges: Option<ChallengeResult>,
+    out: &mut String,
+) {
+    if format == Format::Json {
+        let value = match challenges {
+            Some(challenges) => json!({ "score": score, "challenges": challenges }),
+            None => json!({ "score": score }),
+        };
+        write_json(&value, out);
+        return;
+    }
+
+    writeln!(out, "size:  {}", score.size).unwrap();
+    writeln!(
+        out,
+        "speed: {:.2} (min {}, max {})",
+        score.speed_avg, score.speed_min, score.speed_max
+    )
+    .unwrap();
+    if let Some(challenges) = challenges {
+        let met = |result: Option<bool>| match result {
+            Some(true) => "met",
+            Some(false) => "missed",
+            None => "none",
+        };
+        writeln!(out, "size challenge:  {}", met(challenges.size)).unwrap();
+        writeln!(out, "speed challenge: {}", met(challenges.speed)).unwrap();
+    }
+}
+
+/// Write every solution that isn't [Verdict::Verified] & a summary of all verdicts
+#[cfg(feature = "levels")]
+fn write_verifications(format: Format, results: &[Verification], out: &mut String) {
+    let verdicts = [
+        Verdict::Verified,
+        Verdict::Mismatched,
+        Verdict::Regressed,
+        Verdict::Failed,
+    ];
+    let count = |verdict| {
+        results
+            .iter()
+            .filter(|result| result.verdict() == verdict)
+            .count()
+    };
+
+    if format == Format::Json {
+        let solutions: Vec<_> = results
+            .iter()
+            .map(|result| {
+                json!({
+                    "path": result.path,
+                    "level": result.solution.level,
+                    "author": result.solution.author,
+                    "claimed": result.solution.claimed,
+                    "measured": result.result.as_ref().ok(),
+                    "verdict": result.verdict(),
+                    "error": result.result.as_ref().err().map(|err| {
+                        json!({ "code": err.code(), "message": err.to_string() })
+                    }),
+                })
+            })
+            .collect();
+        let summary: serde_json::Map<_, _> = verdicts
+            .into_iter()
+            .map(|verdict| (verdict.as_str().to_string(), json!(count(verdict))))
+            .collect();
+        write_json(&json!({ "solutions": solutions, "summary": summary }), out);
+        return;
+    }
+
+    for result in results {
+        let claimed = &result.solution.claimed;
+        let claimed = format!(
+            "{}/{}",
+            claimed.size.unwrap_or_default(),
+            claimed.speed.unwrap_or_default()
+        );
+        let path = result.path.display();
+        match (&result.result, result.verdict()) {
+            (_, Verdict::Verified) => {}
+            (Err(err), _) => writeln!(out, "failed {path}: {}: {err}", err.code()).unwrap(),
+            (Ok(measured), verdict) => writeln!(
+                out,
+                "{} {path}: claimed {claimed}, measured {}/{:.2}",
+                verdict.as_str(),
+                measured.size,
+                measured.speed_avg
+            )
+            .unwrap(),
+        }
+    }
+    let counts: Vec<String> = verdicts
+        .into_iter()
+        .map(|verdict| format!("{} {}", count(verdict), verdict.as_str()))
+        .collect();
+    writeln!(out, "{} solutions: {}", results.len(), counts.join(", ")).unwrap();
+}
+
+/// Write the program as source, labels & comments before the commands they precede
+fn write_source(program: &Program, out: &mut String) {
+    let mut labels: Vec<(usize, &str)> = program
+        .get_labels()
+        .map(|(label, idx)| (idx, label.as_str()))
+        .collect();
+    labels.sort();
+    let mut labels = labels.into_iter().peekable();
+    let mut comments = program.get_comments().iter().peekable();
+
+    for i in 0..=program.get_commands().len() {
+        while let Some((_, label)) = labels.next_if(|(idx, _)| *idx <= i) {
+            writeln!(out, "{label}:").unwrap();
+        }
+        while let Some((_, comment)) = comments.next_if(|(idx, _)| *idx <= i) {
+            writeln!(out, "    COMMENT {comment}").unwrap();
+        }
+        if let Some(command) = program.get_commands().get(i) {
+            writeln!(out, "    {}", command.to_source()).unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Result<Args, Exit> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parse_args_test() {
+        assert_eq!(
+            Args {
+                subcommand: Subcommand::Run {
+                    problem: String::from("p.json"),
+                    solution: String::from("s.hrm"),
+                },
+                format: Format::Text,
+            },
+            args(&["run", "p.json", "s.hrm"]).unwrap()
+        );
+        assert_eq!(
+            Args {
+                subcommand: Subcommand::Compile {
+                    solution: String::from("s.hrm"),
+                },
+                format: Format::Json,
+            },
+            args(&["compile", "--emit", "json", "s.hrm"]).unwrap()
+        );
+        assert_eq!(
+            Subcommand::Pipe {
+                solution: String::from("s.hrm"),
+                problem: None,
+            },
+            args(&["pipe", "s.hrm"]).unwrap().subcommand
+        );
+        #[cfg(feature = "tui")]
+        assert_eq!(
+            Subcommand::Watch {
+                problem: String::from("p"),
+                solution: String::from("s"),
+                io: 1,
+            },
+            args(&["watch", "p", "s", "1"]).unwrap().subcommand
+        );
+        #[cfg(feature = "levels")]
+        assert_eq!(
+            Subcommand::Verify {
+                directory: String::from("solutions"),
+            },
+            args(&["verify", "solutions"]).unwrap().subcommand
+        );
+        assert_eq!(
+            Format::Json,
+            args(&["score", "p", "s", "--format", "json"])
+                .unwrap()
+                .format
+        );
+    }
+
+    #[test]
+    fn parse_args_fails() {
+        for invalid in [
+            &[][..],
+            &["run", "p.json"],
+            &["play", "p", "s"],
+            &["compile", "a", "b"],
+            &["run", "p", "s", "--format"],
+            &["run", "p", "s", "--format", "xml"],
+            &["run", "p", "s", "--fast"],
+            &["validate", "p", "s", "--format", "junit"],
+            &["compile", "s", "--format", "html"],
+            &["pipe"],
+            &["pipe", "s", "p", "x"],
+            &["pipe", "s", "--format", "json"],
+            &["watch", "p", "s", "first"],
+            &["verify", "solutions", "--format", "junit"],
+        ] {
+            assert_eq!(exit::USAGE, args(invalid).unwrap_err().code, "{invalid:?}");
+        }
+    }
+
+    /// Writes the files to a fresh directory & returns their paths
+    fn files(name: &str, problem: &str, solution: &str) -> (String, String) {
+        let dir = env::temp_dir().join(format!("hrm-cli-{name}-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (problem_path, solution_path) = (dir.join("problem.json"), dir.join("solution.hrm"));
+        fs::write(&problem_path, problem).unwrap();
+        fs::write(&solution_path, solution).unwrap();
+        (
+            problem_path.to_string_lossy().into_owned(),
+            solution_path.to_string_lossy().into_owned(),
+        )
+    }
+
+    const PROBLEM: &str = r#"{
+        "title": "Mail Room",
+        "description": "Deliver each input to the outbox",
+        "ios": [{"input": [1, 2], "output": [1, 2]}],
+        "commands": ["INBOX", "OUTBOX", "JUMP"],
+        "size_target": 3
+    }"#;
+
+    fn execute_with(subcommand: Subcommand, format: Format) -> (u8, String) {
+        let mut out = String::new();
+        let code = execute(&Args { subcommand, format }, &mut out).unwrap();
+        (code, out)
+    }
+
+    #[test]
+    fn execute_exit_codes() {
+        let cases = [
+            ("valid", "a:\nINBOX\nOUTBOX\nJUMP a\n", exit::SUCCESS),
+            ("parse", "a:\nINBOX\nOUTBOX\nJUMP b\n", exit::INVALID),
+            ("invalid", "INBOX\nCOPYTO 0\n", exit::INVALID),
+            ("failed", "INBOX\nOUTBOX\n", exit::FAILED),
+        ];
+
+        for (name, solution, code) in cases {
+            let (problem, solution) = files(name, PROBLEM, solution);
+            let run = Subcommand::Run {
+                problem: problem.clone(),
+                solution: solution.clone(),
+            };
+            assert_eq!(code, execute_with(run, Format::Text).0, "{name}");
+
+            let run = Subcommand::Run {
+                problem: problem.clone(),
+                solution: solution.clone(),
+            };
+            assert_eq!(code, execute_with(run, Format::Junit).0, "{name}");
+
+            let score = Subcommand::Score { problem, solution };
+            assert_eq!(code, execute_with(score, Format::Markdown).0, "{name}");
+        }
+    }
+
+    #[test]
+    fn execute_formats() {
+        let (problem, solution) = files("formats", PROBLEM, "a:\nINBOX\nOUTBOX\nJUMP a\n");
+
+        let score = Subcommand::Score {
+            problem: problem.clone(),
+            solution: solution.clone(),
+        };
+        let (_, out) = execute_with(score, Format::Json);
+        let json: serde_json::Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(3, json["score"]["size"]);
+        assert_eq!(true, json["challenges"]["size"]);
+
+        let validate = Subcommand::Validate {
+            problem: problem.clone(),
+            solution: solution.clone(),
+        };
+        let (code, out) = execute_with(validate, Format::Json);
+        assert_eq!(exit::SUCCESS, code);
+        assert!(
+            serde_json::from_str::<serde_json::Value>(&out).unwrap()["diagnostics"]
+                .as_array()
+                .unwrap()
+                .is_empty()
+        );
+
+        let run = Subcommand::Run {
+            problem: problem.clone(),
+            solution: solution.clone(),
+        };
+        let (code, out) = execute_with(run, Format::Junit);
+        assert_eq!(exit::SUCCESS, code);
+        assert!(out.contains("<testcase classname=\"Mail Room\" name=\"io 0\">"));
+
+        let score = Subcommand::Score {
+            problem: problem.clone(),
+            solution: solution.clone(),
+        };
+        let (code, out) = execute_with(score, Format::Html);
+        assert_eq!(exit::SUCCESS, code);
+        assert!(out.contains("<tr><td>Size challenge</td><td>met</td></tr>"));
+
+        let (code, out) = execute_with(Subcommand::Compile { solution }, Format::Text);
+        assert_eq!(exit::SUCCESS, code);
+        assert_eq!("a:\n    INBOX\n    OUTBOX\n    JUMP a\n", out);
+    }
+
+    #[cfg(feature = "levels")]
+    #[test]
+    fn execute_verify() {
+        let dir = env::temp_dir().join(format!("hrm-cli-verify-{}", std::process::id()));
+        let level = dir.join("01-Mail-Room");
+        fs::create_dir_all(&level).unwrap();
+        let mail_room = "INBOX\nOUTBOX\nINBOX\nOUTBOX\nINBOX\nOUTBOX\n";
+        fs::write(level.join("6.6-a.asm"), mail_room).unwrap();
+        let directory = dir.to_string_lossy().into_owned();
+
+        let verify = || Subcommand::Verify {
+            directory: directory.clone(),
+        };
+        assert_eq!(
+            (
+                exit::SUCCESS,
+                String::from("1 solutions: 1 verified, 0 mismatched, 0 regressed, 0 failed\n")
+            ),
+            execute_with(verify(), Format::Text)
+        );
+
+        fs::write(level.join("5.6-b.asm"), mail_room).unwrap();
+        let (code, out) = execute_with(verify(), Format::Text);
+        assert_eq!(exit::FAILED, code);
+        assert!(out.contains("5.6-b.asm: claimed 5/6, measured 6/6.00\n"));
+        assert!(out.starts_with("regressed "));
+
+        let (_, out) = execute_with(verify(), Format::Json);
+        let json: serde_json::Value = serde_json::from_str(&out).unwrap();
+        assert_eq!("regressed", json["solutions"][0]["verdict"]);
+        assert_eq!(1, json["summary"]["verified"]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn execute_unreadable() {
+        let run = Subcommand::Run {
+            problem: String::from("/nonexistent/problem.json"),
+            solution: String::from("/nonexistent/solution.hrm"),
+        };
+        let mut out = String::new();
+        let exit = execute(
+            &Args {
+                subcommand: run,
+                format: Format::Text,
+            },
+            &mut out,
+        )
+        .unwrap_err();
+        assert_eq!(exit::FILE, exit.code);
+
+        let (problem, solution) = files("bad-problem", "{}", "INBOX\n");
+        let run = Subcommand::Run { problem, solution };
+        let exit = execute(
+            &Args {
+                subcommand: run,
+                format: Format::Text,
+            },
+            &mut out,
+        )
+        .unwrap_err();
+        assert_eq!(exit::PROBLEM, exit.code);
+    }
+
+    #[test]
+    fn pipe_test() {
+        let problem = pipe_problem();
+        let pipe_with = |source: &str, input: &str| {
+            let (mut written, mut out) = (vec![], String::new());
+            let code = pipe(&problem, source, input.as_bytes(), &mut written, &mut out);
+            (code, String::from_utf8(written).unwrap(), out)
+        };
+
+        let double = "a:\nINBOX\nCOPYTO 0\nADD 0\nOUTBOX\nJUMP a\n";
+        let (code, written, out) = pipe_with(double, "1 2\n3\n");
+        assert_eq!(exit::SUCCESS, code.unwrap());
+        assert_eq!(("2\n4\n6\n", ""), (written.as_str(), out.as_str()));
+
+        let (code, written, out) = pipe_with(double, "4 A");
+        assert_eq!(exit::FAILED, code.unwrap());
+        assert_eq!("8\n", written);
+        assert!(out.starts_with("error[E0311] (line 4): "), "{out}");
+
+        let (code, _, _) = pipe_with(double, "4 AB");
+        assert_eq!(exit::FAILED, code.unwrap_err().code);
+
+        let (code, _, out) = pipe_with("JUMP b\n", "");
+        assert_eq!(exit::INVALID, code.unwrap());
+        assert!(out.starts_with("error[E0102]"), "{out}");
+    }
+}
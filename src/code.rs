@@ -1,3 +1,21 @@
+pub mod analysis;
+pub mod arena;
+pub mod batch;
+pub mod bytecode;
 pub mod commands;
+pub mod equivalence;
+pub mod flow_graph;
 pub mod game_state;
+pub mod genetic;
+pub mod int_program;
+pub mod leaderboard;
+pub mod optimizer;
+#[cfg(feature = "profiling")]
+pub mod profiling;
 pub mod program;
+pub mod search;
+pub mod state_set;
+#[cfg(feature = "std")]
+pub mod statistics;
+pub mod symbol;
+pub mod worst_case;
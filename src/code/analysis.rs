@@ -0,0 +1,377 @@
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use crate::code::commands::Flow;
+use crate::code::program::Program;
+
+/// Analysis Report
+///
+/// Findings of the static analysis, see [analyze].
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AnalysisReport {
+    /// Indices of commands that can never execute
+    pub unreachable: Vec<usize>,
+    /// Loops that can never be left
+    pub infinite_loops: Vec<InfiniteLoop>,
+}
+
+/// Infinite Loop
+///
+/// Cycle of commands without branches or IO. Once entered, the program never terminates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InfiniteLoop {
+    /// Label jumped to at the end of the loop
+    pub label: String,
+    /// Indices of commands in the loop, in execution order
+    pub commands: Vec<usize>,
+}
+
+impl AnalysisReport {
+    pub fn is_empty(&self) -> bool {
+        *self == AnalysisReport::default()
+    }
+}
+
+/// Basic Block
+///
+/// Commands `start..end` that always execute in sequence - only the first is jumped to & only the
+/// last jumps, see [basic_blocks].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BasicBlock {
+    pub start: usize,
+    pub end: usize,
+    /// Indices of blocks that may execute right after this one
+    pub successors: Vec<usize>,
+    /// Program may end right after this block, by `HALT`, a jump to the end or running past the
+    /// last command. Ending on `INBOX` is not included.
+    pub ends: bool,
+}
+
+/// Analyze
+///
+/// Run static analysis passes over the program. The program should be validated, unresolved
+/// labels are ignored.
+pub fn analyze(program: &Program) -> AnalysisReport {
+    AnalysisReport {
+        unreachable: unreachable(program),
+        infinite_loops: infinite_loops(program),
+    }
+}
+
+/// Successors
+///
+/// Returns indices of commands that may execute right after the command at `i_command`. Program
+/// end is not included.
+pub fn successors(program: &Program, i_command: usize) -> Vec<usize> {
+    let commands = program.get_commands();
+    let target = || program.get_targets()[i_command];
+    let next = i_command + 1;
+
+    let successors = match commands[i_command].flow() {
+        Flow::Next => vec![next],
+        Flow::Jump => target().into_iter().collect(),
+        Flow::Branch | Flow::Call => [Some(next), target()].into_iter().flatten().collect(),
+        Flow::Return => commands
+            .iter()
+            .enumerate()
+            .filter(|(_, command)| command.flow() == Flow::Call)
+            .map(|(i, _)| i + 1)
+            .collect(),
+        Flow::Indirect => (0..commands.len()).collect(),
+        Flow::Halt => vec![],
+    };
+
+    successors
+        .into_iter()
+        .filter(|&i| i < commands.len())
+        .collect()
+}
+
+/// Unreachable
+///
+/// Returns indices of commands that can never execute, e.g. code after an unconditional `JUMP`
+/// that no label points to.
+pub fn unreachable(program: &Program) -> Vec<usize> {
+    let len = program.get_commands().len();
+    let mut reachable = vec![false; len];
+    let mut stack = if len > 0 { vec![0] } else { vec![] };
+
+    while let Some(i) = stack.pop() {
+        if reachable[i] {
+            continue;
+        }
+        reachable[i] = true;
+        stack.extend(successors(program, i));
+    }
+
+    (0..len).filter(|&i| !reachable[i]).collect()
+}
+
+/// Infinite Loops
+///
+/// Best-effort detection of trivially non-terminating structures - cycles consisting only of
+/// unconditional commands, none of which is `INBOX`, `OUTBOX`, `BUMPUP` or `BUMPDN`. Bumping a tile
+/// forever always ends in an overflow.
+pub fn infinite_loops(program: &Program) -> Vec<InfiniteLoop> {
+    let commands = program.get_commands();
+    // 0 - unvisited, 1 - on the current path, 2 - done
+    let mut state = vec![0u8; commands.len()];
+    let mut loops = vec![];
+
+    for start in 0..commands.len() {
+        let mut path: Vec<usize> = vec![];
+        let mut current = Some(start);
+
+        while let Some(i) = current {
+            match state[i] {
+                0 => {}
+                1 => {
+                    let position = path.iter().position(|&j| j == i).unwrap();
+                    let cycle = path[position..].to_vec();
+                    let label = cycle
+                        .iter()
+                        .find_map(|&j| commands[j].requires_label())
+                        .map(ToString::to_string)
+                        .unwrap_or_default();
+                    loops.push(InfiniteLoop {
+                        label,
+                        commands: cycle,
+                    });
+                    break;
+                }
+                _ => break,
+            }
+
+            state[i] = 1;
+            path.push(i);
+            current = unconditional_successor(program, i);
+        }
+
+        path.into_iter().for_each(|i| state[i] = 2);
+    }
+
+    loops.sort_by_key(|infinite_loop| infinite_loop.commands.iter().min().copied());
+    loops
+}
+
+/// Basic Blocks
+///
+/// Split the program into the [BasicBlock]s of its control-flow graph, ordered by their first
+/// command. A block starts at the first command, at jump targets & after commands that don't just
+/// continue with the next one.
+pub fn basic_blocks(program: &Program) -> Vec<BasicBlock> {
+    let commands = program.get_commands();
+    let len = commands.len();
+    let mut leader = vec![false; len + 1];
+    leader[0] = true;
+    leader[len] = true;
+    for (i, command) in commands.iter().enumerate() {
+        if command.flow() != Flow::Next {
+            leader[i + 1] = true;
+        }
+        successors(program, i)
+            .into_iter()
+            .filter(|&j| j != i + 1)
+            .for_each(|j| leader[j] = true);
+    }
+
+    let starts: Vec<usize> = (0..len).filter(|&i| leader[i]).collect();
+    // Every command belongs to the last block starting at or before it
+    let block_of = |i: usize| starts.partition_point(|&start| start <= i) - 1;
+
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i_block, &start)| {
+            let end = starts.get(i_block + 1).copied().unwrap_or(len);
+            let last = end - 1;
+            let mut successors: Vec<usize> = successors(program, last)
+                .into_iter()
+                .map(block_of)
+                .collect();
+            successors.sort_unstable();
+            successors.dedup();
+
+            let ends = match commands[last].flow() {
+                Flow::Halt => true,
+                Flow::Next => end == len,
+                Flow::Jump => program.get_targets()[last] == Some(len),
+                Flow::Branch | Flow::Call => end == len || program.get_targets()[last] == Some(len),
+                Flow::Return | Flow::Indirect => false,
+            };
+
+            BasicBlock {
+                start,
+                end,
+                successors,
+                ends,
+            }
+        })
+        .collect()
+}
+
+fn unconditional_successor(program: &Program, i_command: usize) -> Option<usize> {
+    let command = &program.get_commands()[i_command];
+    if matches!(
+        command.factory().command(),
+        "INBOX" | "OUTBOX" | "BUMPUP" | "BUMPDN"
+    ) {
+        return None;
+    }
+
+    match command.flow() {
+        Flow::Next | Flow::Jump => successors(program, i_command).first().copied(),
+        _ => None,
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::compiler::compile::Compiler;
+
+    use super::*;
+
+    #[test]
+    fn unreachable_test() {
+        let code = "\
+a:
+    INBOX
+    JUMPZ b
+    OUTBOX
+    JUMP a
+    COPYTO 0
+    OUTBOX
+b:
+    JUMP a
+    OUTBOX
+";
+        let program = Compiler::default().compile(code).unwrap();
+        assert_eq!(vec![4, 5, 7], unreachable(&program));
+        assert_eq!(
+            AnalysisReport {
+                unreachable: vec![4, 5, 7],
+                infinite_loops: vec![],
+            },
+            analyze(&program)
+        );
+    }
+
+    #[test]
+    fn unreachable_call() {
+        let code = "\
+    CALL f
+    OUTBOX
+    HALT
+    INBOX
+f:
+    INBOX
+    RET
+";
+        let program = Compiler::extended().compile(code).unwrap();
+        assert_eq!(vec![3], unreachable(&program));
+    }
+
+    #[test]
+    fn unreachable_indirect() {
+        let program = Compiler::extended()
+            .compile("JUMP [0]\nINBOX\nOUTBOX")
+            .unwrap();
+        assert!(analyze(&program).is_empty());
+    }
+
+    #[test]
+    fn basic_blocks_test() {
+        let code = "\
+a:
+    INBOX
+    JUMPZ b
+    COPYTO 0
+    OUTBOX
+    JUMP a
+b:
+    BUMPUP 0
+c:
+";
+        let program = Compiler::default().compile(code).unwrap();
+        let block = |start, end, successors: Vec<usize>, ends| BasicBlock {
+            start,
+            end,
+            successors,
+            ends,
+        };
+        assert_eq!(
+            vec![
+                block(0, 2, vec![1, 2], false),
+                block(2, 5, vec![0], false),
+                block(5, 6, vec![], true),
+            ],
+            basic_blocks(&program)
+        );
+
+        let program = Compiler::extended()
+            .compile("CALL f\nHALT\nf:\nJUMPZ c\nRET\nc:")
+            .unwrap();
+        assert_eq!(
+            vec![
+                block(0, 1, vec![1, 2], false),
+                block(1, 2, vec![], true),
+                block(2, 3, vec![3], true),
+                block(3, 4, vec![1], false),
+            ],
+            basic_blocks(&program)
+        );
+        assert!(basic_blocks(&Program::EMPTY).is_empty());
+    }
+
+    #[test]
+    fn infinite_loops_test() {
+        let code = "\
+    INBOX
+    JUMPZ b
+a:
+    COPYTO 0
+    COPYFROM 0
+    JUMP a
+b:
+    JUMP b
+c:
+    OUTBOX
+    JUMP c
+";
+        let program = Compiler::default().compile(code).unwrap();
+        assert_eq!(
+            vec![
+                InfiniteLoop {
+                    label: String::from("a"),
+                    commands: vec![2, 3, 4],
+                },
+                InfiniteLoop {
+                    label: String::from("b"),
+                    commands: vec![5],
+                },
+            ],
+            analyze(&program).infinite_loops
+        );
+    }
+
+    #[test]
+    fn infinite_loops_none() {
+        let code = "\
+a:
+    INBOX
+    JUMPN b
+    JUMP a
+b:
+    OUTBOX
+    JUMP a
+c:
+    BUMPUP 0
+    JUMP c
+";
+        let program = Compiler::default().compile(code).unwrap();
+        assert!(infinite_loops(&program).is_empty());
+    }
+}
@@ -0,0 +1,240 @@
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    code::{
+        bytecode::Op,
+        game_state::GameState,
+        program::{Execution, Program, RunError},
+    },
+    game::{problem::Problem, value::Value},
+};
+
+/// Arena Program
+///
+/// Handle of a program stored in a [ProgramArena].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ArenaProgram(usize);
+
+/// Program Arena
+///
+/// Stores the [Op]s of many programs back to back in a single buffer, without any boxed commands,
+/// for tools that create & discard programs at a high rate (search, genetic programming).
+/// [ProgramArena::clear] drops every program at once & keeps the allocation for the next batch.
+///
+/// Only built-in commands are supported, i.e. no [Op::Dynamic].
+#[derive(Debug, Default, Clone)]
+pub struct ProgramArena {
+    ops: Vec<Op>,
+    /// `(start, end)` of each program in `ops`
+    programs: Vec<(usize, usize)>,
+}
+
+impl ProgramArena {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// With Capacity
+    ///
+    /// Create an arena with room for `ops` ops in total, before reallocating.
+    pub fn with_capacity(ops: usize) -> Self {
+        Self {
+            ops: Vec::with_capacity(ops),
+            programs: vec![],
+        }
+    }
+
+    /// Number of programs
+    pub fn len(&self) -> usize {
+        self.programs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.programs.is_empty()
+    }
+
+    /// Alloc
+    ///
+    /// Add a program consisting of `ops`. Returns [None] if an op is [Op::Dynamic] or jumps past
+    /// the end of the program.
+    pub fn alloc(&mut self, ops: &[Op]) -> Option<ArenaProgram> {
+        let valid = ops.iter().all(|op| match op {
+            Op::Dynamic => false,
+            Op::Jump(target)
+            | Op::JumpZero(target)
+            | Op::JumpNegative(target)
+            | Op::JumpPositive(target)
+            | Op::Call(target) => *target <= ops.len(),
+            _ => true,
+        });
+        if !valid {
+            return None;
+        }
+
+        let start = self.ops.len();
+        self.ops.extend_from_slice(ops);
+        self.programs.push((start, self.ops.len()));
+        Some(ArenaProgram(self.programs.len() - 1))
+    }
+
+    /// Alloc Program
+    ///
+    /// Add the [crate::code::bytecode::Bytecode] of `program`. Returns [None] if the program
+    /// contains commands other than the built-in ones, or jumps to undefined labels.
+    pub fn alloc_program(&mut self, program: &Program) -> Option<ArenaProgram> {
+        self.alloc(program.get_bytecode().get_ops())
+    }
+
+    /// Get
+    ///
+    /// Returns the ops of `program`.
+    pub fn get(&self, program: ArenaProgram) -> &[Op] {
+        let (start, end) = self.programs[program.0];
+        &self.ops[start..end]
+    }
+
+    /// Get Mut
+    ///
+    /// Returns the ops of `program` for in-place mutation. Jump targets must stay within the
+    /// program, i.e. at most its length.
+    pub fn get_mut(&mut self, program: ArenaProgram) -> &mut [Op] {
+        let (start, end) = self.programs[program.0];
+        &mut self.ops[start..end]
+    }
+
+    /// Clear
+    ///
+    /// Remove all programs, invalidating their handles, while keeping the allocation.
+    pub fn clear(&mut self) {
+        self.ops.clear();
+        self.programs.clear();
+    }
+
+    /// Execute
+    ///
+    /// Run `program` on a single input without checking its outputs, see [Program::execute].
+    pub fn execute(
+        &self,
+        program: ArenaProgram,
+        problem: &Problem,
+        input: &[Value],
+        max_steps: u32,
+    ) -> Result<Execution, RunError> {
+        let ops = self.get(program);
+        let (input, unchecked) = (input.to_vec(), vec![]);
+        let mut game_state = GameState::new(&input, &unchecked, problem.get_memory().clone());
+        game_state.output_matching = problem.get_output_matching();
+        game_state.bounds = problem.get_value_bounds();
+        game_state.holes = problem.get_holes().to_vec();
+        game_state.unbounded_memory = problem.has_unbounded_memory();
+        game_state.check_output = false;
+
+        let mut steps = 0;
+        while let Some(op) = ops.get(game_state.i_command) {
+            if *op == Op::Debug {
+                game_state.i_command += 1;
+                continue;
+            }
+
+            if steps == max_steps {
+                return Err(RunError::StepLimit(max_steps));
+            }
+
+            op.execute_static(ops.len(), &mut game_state)?;
+            if game_state.halted {
+                break;
+            }
+            game_state.speed += 1; // Built-in commands all cost 1
+            steps += 1;
+            game_state.i_command = op.next_static(ops.len(), &game_state)?;
+        }
+
+        Ok(Execution {
+            output: game_state.produced,
+            speed: game_state.speed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::code::commands::{copy_from::CopyFrom, CommandValue};
+    #[cfg(feature = "std")]
+    use crate::compiler::compile::Compiler;
+    use crate::game::problem::ProblemBuilder;
+
+    fn problem() -> Problem {
+        ProblemBuilder::new()
+            .memory_dim(4)
+            .add_memory_slot(3, Value::Int(5))
+            .enable_all_commands()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn execute_matches_program() {
+        let problem = problem();
+        let sources = [
+            "a:\nINBOX\nJUMPZ b\nCOPYTO 0\nADD 0\nOUTBOX\nJUMP a\nb:\n",
+            "INBOX\nCOPYTO 0\nCOPYFROM 3\nJUMP [0]\nOUTBOX\nOUTBOX\n",
+            "a:\nINBOX\nDEBUG \"here\" 0\nOUTBOX\nJUMP a\n",
+            "a:\nJUMP a\n",
+        ];
+
+        let mut arena = ProgramArena::new();
+        for source in sources {
+            let program = Compiler::extended().compile(source).unwrap();
+            let handle = arena.alloc_program(&program).unwrap();
+            assert_eq!(program.get_commands().len(), arena.get(handle).len());
+
+            for input in [
+                vec![],
+                vec![Value::Int(4), Value::Int(0)],
+                vec![Value::Int(9)],
+            ] {
+                assert_eq!(
+                    program.execute(&problem, &input, 100),
+                    arena.execute(handle, &problem, &input, 100)
+                );
+            }
+        }
+        assert_eq!(sources.len(), arena.len());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn alloc_fails() {
+        let mut arena = ProgramArena::new();
+        assert!(arena.alloc(&[Op::Dynamic]).is_none());
+        assert!(arena.alloc(&[Op::Inbox, Op::Jump(3)]).is_none());
+        assert!(arena.is_empty());
+
+        let program = Compiler::extended().compile("a:\nCALL a").unwrap();
+        assert!(arena.alloc_program(&program).is_some());
+    }
+
+    #[test]
+    fn get_mut_clear() {
+        let problem = problem();
+        let mut arena = ProgramArena::with_capacity(8);
+        let first = arena.alloc(&[Op::Inbox, Op::Outbox]).unwrap();
+        let second = arena.alloc(&[Op::Inbox, Op::Outbox]).unwrap();
+
+        arena.get_mut(second)[0] = Op::CopyFrom(CopyFrom(CommandValue::Value(3)));
+        let execution = arena
+            .execute(second, &problem, &[Value::Int(1)], 10)
+            .unwrap();
+        assert_eq!(vec![Value::Int(5)], execution.output);
+        let execution = arena
+            .execute(first, &problem, &[Value::Int(1)], 10)
+            .unwrap();
+        assert_eq!(vec![Value::Int(1)], execution.output);
+
+        arena.clear();
+        assert!(arena.is_empty());
+        assert_eq!(first, arena.alloc(&[Op::Nop]).unwrap());
+    }
+}
@@ -0,0 +1,161 @@
+#[cfg(feature = "std")]
+use std::{
+    num::NonZeroUsize,
+    sync::atomic::{AtomicUsize, Ordering},
+    sync::mpsc,
+    thread,
+};
+
+use crate::code::program::{Program, ProgramError, Score};
+use crate::game::problem::Problem;
+
+/// Batch Result
+///
+/// Result of a single program, `index` being its position in the evaluated batch.
+#[derive(Debug, PartialEq)]
+pub struct BatchResult {
+    pub index: usize,
+    pub score: Result<Score, ProgramError>,
+}
+
+/// Evaluate
+///
+/// Validate & run the program on the problem.
+pub fn evaluate(program: &Program, problem: &Problem) -> Result<Score, ProgramError> {
+    program
+        .validate(problem)
+        .and_then(|_| program.run(problem).map_err(ProgramError::Run))
+}
+
+/// Evaluate Batch
+///
+/// [evaluate] every program on a pool of one worker per available core. Results are ordered by
+/// index.
+#[cfg(feature = "std")]
+pub fn evaluate_batch(programs: &[Program], problem: &Problem) -> Vec<BatchResult> {
+    let workers = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+
+    let mut results = Vec::with_capacity(programs.len());
+    evaluate_batch_with(programs, problem, workers, |result| results.push(result));
+    results.sort_by_key(|result| result.index);
+    results
+}
+
+/// Evaluate Batch With
+///
+/// [evaluate] every program on a pool of `workers` threads (at least one), streaming each result
+/// to `on_result` on the calling thread as soon as it completes. Returns once every program is
+/// evaluated.
+#[cfg(feature = "std")]
+pub fn evaluate_batch_with(
+    programs: &[Program],
+    problem: &Problem,
+    workers: usize,
+    mut on_result: impl FnMut(BatchResult),
+) {
+    let workers = workers.clamp(1, programs.len().max(1));
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            let sender = sender.clone();
+            let next = &next;
+            scope.spawn(move || loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(program) = programs.get(index) else {
+                    break;
+                };
+
+                let score = evaluate(program, problem);
+                if sender.send(BatchResult { index, score }).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        receiver.into_iter().for_each(&mut on_result);
+    });
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::code::program::{RunError, ValidationError};
+    use crate::compiler::compile::Compiler;
+    use crate::game::problem::{ProblemBuilder, ProblemIO};
+    use crate::game::value::Value;
+
+    fn problem() -> Problem {
+        ProblemBuilder::new()
+            .add_io(ProblemIO {
+                input: vec![Value::Int(1), Value::Int(2)],
+                output: vec![Value::Int(1), Value::Int(2)],
+            })
+            .enable_all_commands()
+            .build()
+            .unwrap()
+    }
+
+    fn programs() -> Vec<Program> {
+        let compiler = Compiler::default();
+        [
+            "a:\nINBOX\nOUTBOX\nJUMP a\n",
+            "INBOX\nOUTBOX\n",
+            "a:\nINBOX\nOUTBOX\nJUMP a\n",
+            "COPYFROM 9\n",
+        ]
+        .into_iter()
+        .map(|code| compiler.compile(code).unwrap())
+        .collect()
+    }
+
+    #[test]
+    fn evaluate_batch_test() {
+        let problem = problem();
+        let programs = programs();
+
+        let results = evaluate_batch(&programs, &problem);
+
+        assert_eq!(
+            vec![0, 1, 2, 3],
+            results
+                .iter()
+                .map(|result| result.index)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(3, results[0].score.as_ref().unwrap().size);
+        assert_eq!(results[0].score, results[2].score);
+        let Err(ProgramError::Run(report)) = &results[1].score else {
+            panic!("expected a run error");
+        };
+        assert!(matches!(report.error, RunError::MissingOutput { .. }));
+        assert!(matches!(
+            results[3].score,
+            Err(ProgramError::Validation(ValidationError::CommandIndex(_)))
+        ));
+    }
+
+    #[test]
+    fn evaluate_batch_with_streams() {
+        let problem = problem();
+        let programs = programs();
+
+        for workers in [0, 1, 3, 16] {
+            let mut indices = vec![];
+            evaluate_batch_with(&programs, &problem, workers, |result| {
+                assert_eq!(evaluate(&programs[result.index], &problem), result.score);
+                indices.push(result.index);
+            });
+
+            indices.sort();
+            assert_eq!(vec![0, 1, 2, 3], indices);
+        }
+    }
+
+    #[test]
+    fn evaluate_batch_empty() {
+        assert!(evaluate_batch(&[], &problem()).is_empty());
+    }
+}
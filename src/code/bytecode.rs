@@ -0,0 +1,335 @@
+use alloc::vec::Vec;
+
+use crate::{
+    code::{
+        commands::{
+            add::Add,
+            bump_down::BumpDown,
+            bump_up::BumpUp,
+            call::{push_return_address, Call},
+            copy_from::CopyFrom,
+            copy_to::CopyTo,
+            div::Div,
+            halt::Halt,
+            inbox::Inbox,
+            jump::{Jump, JumpIndirect},
+            jump_negative::JumpNegative,
+            jump_positive::JumpPositive,
+            jump_zero::JumpZero,
+            modulo::Mod,
+            mul::Mul,
+            neg::Neg,
+            nop::Nop,
+            outbox::Outbox,
+            pop::Pop,
+            push::Push,
+            ret::Ret,
+            sub::Sub,
+            AnyCommand, Command,
+        },
+        game_state::GameState,
+        int_program::IntProgram,
+        program::{get_acc, Program, RunError},
+    },
+    game::value::Value,
+};
+
+/// Op
+///
+/// Label-resolved instruction executed with static dispatch. Jump targets are command indices.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Op {
+    Inbox,
+    Outbox,
+    CopyFrom(CopyFrom),
+    CopyTo(CopyTo),
+    Add(Add),
+    Sub(Sub),
+    BumpUp(BumpUp),
+    BumpDown(BumpDown),
+    Jump(usize),
+    JumpZero(usize),
+    JumpNegative(usize),
+    JumpPositive(usize),
+    JumpIndirect(JumpIndirect),
+    Mul(Mul),
+    Div(Div),
+    Mod(Mod),
+    Neg,
+    Nop,
+    Halt,
+    Push,
+    Pop,
+    Call(usize),
+    Ret,
+    /// Debug command, never executed
+    Debug,
+    /// Any other command, or a jump with an unresolved label - executed through the [Command]
+    /// trait object
+    Dynamic,
+}
+
+impl Op {
+    /// Lower
+    ///
+    /// Returns the [Op] of `command`, whose label (if any) resolved to `target`.
+    fn lower(command: &AnyCommand, target: Option<usize>) -> Self {
+        if command.is_debug() {
+            return Op::Debug;
+        }
+
+        let any = command.as_any();
+        macro_rules! lower {
+            ($($command:ty => $op:expr),* $(,)?) => {
+                $(if let Some(command) = any.downcast_ref::<$command>() {
+                    #[allow(clippy::redundant_closure_call)]
+                    return ($op)(command);
+                })*
+            };
+        }
+
+        lower!(
+            Inbox => |_| Op::Inbox,
+            Outbox => |_| Op::Outbox,
+            CopyFrom => |c: &CopyFrom| Op::CopyFrom(*c),
+            CopyTo => |c: &CopyTo| Op::CopyTo(*c),
+            Add => |c: &Add| Op::Add(*c),
+            Sub => |c: &Sub| Op::Sub(*c),
+            BumpUp => |c: &BumpUp| Op::BumpUp(*c),
+            BumpDown => |c: &BumpDown| Op::BumpDown(*c),
+            JumpIndirect => |c: &JumpIndirect| Op::JumpIndirect(*c),
+            Mul => |c: &Mul| Op::Mul(*c),
+            Div => |c: &Div| Op::Div(*c),
+            Mod => |c: &Mod| Op::Mod(*c),
+            Neg => |_| Op::Neg,
+            Nop => |_| Op::Nop,
+            Halt => |_| Op::Halt,
+            Push => |_| Op::Push,
+            Pop => |_| Op::Pop,
+            Ret => |_| Op::Ret,
+        );
+
+        match target {
+            Some(target) => {
+                lower!(
+                    Jump => |_| Op::Jump(target),
+                    JumpZero => |_| Op::JumpZero(target),
+                    JumpNegative => |_| Op::JumpNegative(target),
+                    JumpPositive => |_| Op::JumpPositive(target),
+                    Call => |_| Op::Call(target),
+                );
+                Op::Dynamic
+            }
+            None => Op::Dynamic,
+        }
+    }
+
+    /// Execute
+    ///
+    /// See [Command::execute]. `command` is only used by [Op::Dynamic].
+    #[inline]
+    pub(crate) fn execute(
+        &self,
+        program: &Program,
+        command: &dyn Command,
+        game_state: &mut GameState,
+    ) -> Result<(), RunError> {
+        match self {
+            Op::Dynamic => command.execute(program, game_state),
+            _ => self.execute_static(program.get_commands().len(), game_state),
+        }
+    }
+
+    /// Execute Static
+    ///
+    /// See [Op::execute], in a program of `len` commands. [Op::Dynamic] does nothing.
+    #[inline]
+    pub(crate) fn execute_static(
+        &self,
+        len: usize,
+        game_state: &mut GameState,
+    ) -> Result<(), RunError> {
+        // Built-in commands don't read the program
+        let program = &Program::EMPTY;
+        match self {
+            Op::Inbox => Inbox.execute(program, game_state),
+            Op::Outbox => Outbox.execute(program, game_state),
+            Op::CopyFrom(command) => command.execute(program, game_state),
+            Op::CopyTo(command) => command.execute(program, game_state),
+            Op::Add(command) => command.execute(program, game_state),
+            Op::Sub(command) => command.execute(program, game_state),
+            Op::BumpUp(command) => command.execute(program, game_state),
+            Op::BumpDown(command) => command.execute(program, game_state),
+            Op::Jump(_) | Op::Nop | Op::Debug => Ok(()),
+            Op::JumpZero(_) | Op::JumpNegative(_) | Op::JumpPositive(_) => {
+                get_acc(game_state.acc).map(|_| ())
+            }
+            Op::JumpIndirect(command) => {
+                let value = game_state.read(command.0);
+                command.target(len, value).map(|_| ())
+            }
+            Op::Mul(command) => command.execute(program, game_state),
+            Op::Div(command) => command.execute(program, game_state),
+            Op::Mod(command) => command.execute(program, game_state),
+            Op::Neg => Neg.execute(program, game_state),
+            Op::Halt => Halt.execute(program, game_state),
+            Op::Push => Push.execute(program, game_state),
+            Op::Pop => Pop.execute(program, game_state),
+            Op::Call(_) => push_return_address(game_state),
+            Op::Ret => Ret.execute(program, game_state),
+            Op::Dynamic => Ok(()),
+        }
+    }
+
+    /// Next
+    ///
+    /// See [Command::next]. `command` is only used by [Op::Dynamic].
+    #[inline]
+    pub(crate) fn next(
+        &self,
+        program: &Program,
+        command: &dyn Command,
+        game_state: &GameState,
+    ) -> Result<usize, RunError> {
+        match self {
+            Op::Dynamic => command.next(program, game_state),
+            _ => self.next_static(program.get_commands().len(), game_state),
+        }
+    }
+
+    /// Next Static
+    ///
+    /// See [Op::next], in a program of `len` commands. [Op::Dynamic] continues with the next
+    /// command.
+    #[inline]
+    pub(crate) fn next_static(
+        &self,
+        len: usize,
+        game_state: &GameState,
+    ) -> Result<usize, RunError> {
+        let next = game_state.i_command + 1;
+        let branch = |target: usize, jump: fn(Value) -> bool| {
+            get_acc(game_state.acc).map(|acc| if jump(acc) { target } else { next })
+        };
+
+        match self {
+            Op::Jump(target) | Op::Call(target) => Ok(*target),
+            Op::JumpZero(target) => branch(*target, |acc| acc == 0),
+            Op::JumpNegative(target) => branch(*target, |acc| acc < 0),
+            Op::JumpPositive(target) => branch(*target, |acc| acc > 0),
+            Op::JumpIndirect(command) => command.target(len, game_state.peek(command.0)),
+            Op::Ret => Ok(game_state.i_command),
+            _ => Ok(next),
+        }
+    }
+
+    /// Cost
+    ///
+    /// See [Command::cost]. `command` is only used by [Op::Dynamic].
+    #[inline]
+    pub(crate) fn cost(&self, command: &dyn Command) -> u32 {
+        match self {
+            Op::Debug => 0,
+            Op::Dynamic => command.cost(),
+            _ => 1,
+        }
+    }
+}
+
+/// Bytecode
+///
+/// [Op]s of a [Program], one per command. Built by [crate::code::program::ProgramBuilder::build],
+/// so running a program needs neither trait object dispatch nor label lookups for the built-in
+/// commands.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Bytecode {
+    ops: Vec<Op>,
+    /// [None] unless every op is supported by [IntProgram]
+    int_program: Option<IntProgram>,
+}
+
+impl Bytecode {
+    pub(crate) const EMPTY: Bytecode = Bytecode {
+        ops: Vec::new(),
+        int_program: None,
+    };
+
+    /// Compile
+    ///
+    /// Lower commands to [Op]s, with jump targets resolved from `targets` (see
+    /// [Program::get_targets]).
+    pub fn compile(commands: &[AnyCommand], targets: &[Option<usize>]) -> Self {
+        let ops = commands
+            .iter()
+            .enumerate()
+            .map(|(i, command)| Op::lower(command, targets.get(i).copied().flatten()))
+            .collect::<Vec<_>>();
+        let int_program = IntProgram::compile(&ops);
+
+        Self { ops, int_program }
+    }
+
+    pub fn get_ops(&self) -> &Vec<Op> {
+        &self.ops
+    }
+
+    /// Get Int Program
+    ///
+    /// Returns the [IntProgram] the ops specialize to, if any.
+    pub fn get_int_program(&self) -> Option<&IntProgram> {
+        self.int_program.as_ref()
+    }
+
+    /// Get Op
+    ///
+    /// Returns the [Op] of the command at index `i_command`, [Op::Dynamic] if there is none.
+    #[inline]
+    pub fn get_op(&self, i_command: usize) -> &Op {
+        self.ops.get(i_command).unwrap_or(&Op::Dynamic)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::code::commands::CommandValue;
+    use crate::code::symbol::Label;
+    use crate::compiler::compile::Compiler;
+
+    use super::*;
+
+    #[test]
+    fn compile_test() {
+        let code = "\
+a:
+    INBOX
+    COPYTO [0]
+    JUMPZ b
+    DEBUG 0
+    JUMP a
+b:
+    CALL a
+    JUMP [1]
+";
+        let program = Compiler::extended().compile(code).unwrap();
+        assert_eq!(
+            &vec![
+                Op::Inbox,
+                Op::CopyTo(CopyTo(CommandValue::Index(0))),
+                Op::JumpZero(5),
+                Op::Debug,
+                Op::Jump(0),
+                Op::Call(0),
+                Op::JumpIndirect(JumpIndirect(1)),
+            ],
+            program.get_bytecode().get_ops()
+        );
+    }
+
+    #[test]
+    fn compile_unresolved() {
+        let commands: Vec<AnyCommand> = vec![Box::new(Jump(Label::from("a")))];
+        let bytecode = Bytecode::compile(&commands, &[None]);
+        assert_eq!(&vec![Op::Dynamic], bytecode.get_ops());
+        assert_eq!(&Op::Dynamic, bytecode.get_op(1));
+    }
+}
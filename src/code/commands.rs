@@ -1,20 +1,46 @@
-use std::fmt::Debug;
+use alloc::collections::BTreeSet;
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::any::Any;
+use core::fmt::{Debug, Display, Formatter};
+
+use serde::{Deserialize, Serialize};
 
 use crate::code::{
+    commands::debug::DebugEvent,
     game_state::GameState,
     program::{Program, RunError},
+    symbol::Label,
 };
 
 pub mod add;
+pub mod builtin;
 pub mod bump_down;
 pub mod bump_up;
+pub mod call;
 pub mod copy_from;
 pub mod copy_to;
+pub mod debug;
+pub mod div;
+pub mod halt;
 pub mod inbox;
 pub mod jump;
 pub mod jump_negative;
+pub mod jump_positive;
 pub mod jump_zero;
+pub mod modulo;
+pub mod mul;
+pub mod neg;
+pub mod nop;
 pub mod outbox;
+pub mod pop;
+pub mod push;
+pub mod ret;
 pub mod sub;
 
 pub const ALL_COMMANDS: [&str; 11] = [
@@ -22,15 +48,137 @@ pub const ALL_COMMANDS: [&str; 11] = [
     "JUMPN",
 ];
 
+/// Commands beyond vanilla HRM, available only if explicitly enabled.
+pub const EXTENDED_COMMANDS: [&str; 11] = [
+    "MUL", "DIV", "MOD", "NEG", "NOP", "JUMPP", "HALT", "PUSH", "POP", "CALL", "RET",
+];
+
 pub type AnyCommand = Box<dyn Command>;
 
+/// Instruction Set
+///
+/// Commands that exist at all, as opposed to the commands a [Problem](crate::game::problem::Problem)
+/// allows.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum InstructionSet {
+    /// [ALL_COMMANDS]
+    #[default]
+    Vanilla,
+    /// [ALL_COMMANDS] & [EXTENDED_COMMANDS]
+    Extended,
+    /// Any subset of [ALL_COMMANDS] & [EXTENDED_COMMANDS]
+    Custom(BTreeSet<String>),
+}
+
+impl InstructionSet {
+    pub fn is_vanilla(&self) -> bool {
+        *self == InstructionSet::Vanilla
+    }
+
+    pub fn contains(&self, command: &str) -> bool {
+        match self {
+            InstructionSet::Vanilla => ALL_COMMANDS.contains(&command),
+            InstructionSet::Extended => {
+                ALL_COMMANDS.contains(&command) || EXTENDED_COMMANDS.contains(&command)
+            }
+            InstructionSet::Custom(commands) => commands.contains(command),
+        }
+    }
+
+    /// Factories
+    ///
+    /// Returns factories of all known commands in the instruction set. Extended forms of vanilla
+    /// commands, e.g. `JUMP [addr]`, are only part of non-vanilla instruction sets.
+    pub fn factories(&self) -> Vec<Box<dyn CommandFactory>> {
+        let mut factories: Vec<Box<dyn CommandFactory>> = crate::commands!();
+        if !self.is_vanilla() {
+            factories.extend::<Vec<Box<dyn CommandFactory>>>(crate::extended_commands!());
+        }
+        factories.retain(|factory| self.contains(factory.command()));
+        factories
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum CommandValue {
     Value(usize),
     Index(usize),
 }
 
-pub trait Command: Debug {
+impl CommandValue {
+    /// Tile
+    ///
+    /// Returns the tile named by the value, regardless of addressing.
+    pub fn tile(&self) -> usize {
+        match *self {
+            CommandValue::Value(tile) | CommandValue::Index(tile) => tile,
+        }
+    }
+}
+
+impl Display for CommandValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            CommandValue::Value(value) => write!(f, "{value}"),
+            CommandValue::Index(index) => write!(f, "[{index}]"),
+        }
+    }
+}
+
+/// Compile Command Value
+///
+/// Returns [Ok(Value)] if input matches one of:
+/// - <code>\d+</code>
+/// - <code>\[\d+\]</code>
+///
+/// Returns [None] otherwise.
+pub fn compile_command_value(value: &str) -> Option<CommandValue> {
+    match value.strip_prefix('[') {
+        Some(index) => compile_number(index.strip_suffix(']')?).map(CommandValue::Index),
+        None => compile_number(value).map(CommandValue::Value),
+    }
+}
+
+fn compile_number(number: &str) -> Option<usize> {
+    if number.is_empty() || !number.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    number.parse().ok()
+}
+
+/// Compile Label
+///
+/// Returns [Ok(&str)] if input matches <code>\[a-z\]+</code>, else returns [None].
+pub fn compile_label(label: &str) -> Option<&str> {
+    if !label.is_empty() && label.bytes().all(|byte| byte.is_ascii_lowercase()) {
+        Some(label)
+    } else {
+        None
+    }
+}
+
+/// Flow
+///
+/// How control leaves a command, used by static analysis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    /// Continues with the next command
+    Next,
+    /// Always jumps to its label
+    Jump,
+    /// Jumps to its label or continues with the next command
+    Branch,
+    /// Jumps to its label & later returns to the next command
+    Call,
+    /// Returns to the command after a `CALL`
+    Return,
+    /// Jumps to a command index read at runtime
+    Indirect,
+    /// Ends execution
+    Halt,
+}
+
+pub trait Command: Debug + CommandClone + CommandEq + Send + Sync {
     /// Execute
     ///
     /// Execute the command & return the index of the next command.
@@ -38,9 +186,16 @@ pub trait Command: Debug {
 
     /// Next
     ///
-    /// Get next command index if it exists, else [None].
-    fn next(&self, _program: &Program, game_state: &GameState) -> Option<usize> {
-        Some(game_state.i_command + 1)
+    /// Get next command index. Index past the last command ends the program.
+    fn next(&self, _program: &Program, game_state: &GameState) -> Result<usize, RunError> {
+        Ok(game_state.i_command + 1)
+    }
+
+    /// Cost
+    ///
+    /// Number of cycles the command adds to speed when executed.
+    fn cost(&self) -> u32 {
+        1
     }
 
     /// Requires Index
@@ -50,19 +205,117 @@ pub trait Command: Debug {
         None
     }
 
+    /// Flow
+    ///
+    /// Returns how control leaves the command, defaults to [Flow::Next].
+    fn flow(&self) -> Flow {
+        Flow::Next
+    }
+
+    /// Tile
+    ///
+    /// Returns [Some(usize)] with the tile named by the command's argument, i.e. `x` of both `x`
+    /// & `[x]`, else [None].
+    fn tile(&self) -> Option<usize> {
+        None
+    }
+
     /// Requires Label
     ///
-    /// Returns [Some(&str)] if a label must exist for the command to work, else [None].
-    fn requires_label(&self) -> Option<&str> {
+    /// Returns [Some(Label)] if a label must exist for the command to work, else [None].
+    fn requires_label(&self) -> Option<&Label> {
+        None
+    }
+
+    /// Is Debug
+    ///
+    /// Returns `true` if the command is a debugging aid. Such commands are never executed, are
+    /// available in every problem & count towards neither size nor speed.
+    fn is_debug(&self) -> bool {
+        false
+    }
+
+    /// Debug
+    ///
+    /// Returns [Some(DebugEvent)] for the runner's debug callback if [Command::is_debug], else
+    /// [None].
+    fn debug(&self, _game_state: &GameState) -> Option<DebugEvent> {
         None
     }
 
+    /// Arguments
+    ///
+    /// Returns [Some(String)] with the command's arguments as written in code (e.g. `[4]` for
+    /// `COPYFROM [4]`), else [None].
+    fn args(&self) -> Option<String> {
+        None
+    }
+
+    /// To Source
+    ///
+    /// Returns the command as written in code, e.g. `COPYFROM [4]`.
+    fn to_source(&self) -> String {
+        let keyword = self.factory().command();
+        match self.args() {
+            Some(args) => format!("{keyword} {args}"),
+            None => keyword.to_string(),
+        }
+    }
+
     /// Factory
     ///
     /// Returns factory for given command.
     fn factory(&self) -> Box<dyn CommandFactory>;
 }
 
+/// Command Clone
+///
+/// Object-safe cloning of boxed commands. Implemented for every [Command] that is [Clone].
+pub trait CommandClone {
+    fn clone_box(&self) -> AnyCommand;
+}
+
+impl<T: Command + Clone + 'static> CommandClone for T {
+    fn clone_box(&self) -> AnyCommand {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for AnyCommand {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// Command Eq
+///
+/// Object-safe structural equality of boxed commands. Implemented for every [Command] that is
+/// [PartialEq]. Commands of different types are never equal.
+pub trait CommandEq {
+    fn as_any(&self) -> &dyn Any;
+
+    fn eq_command(&self, other: &dyn Command) -> bool;
+}
+
+impl<T: Command + PartialEq + 'static> CommandEq for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn eq_command(&self, other: &dyn Command) -> bool {
+        other
+            .as_any()
+            .downcast_ref::<T>()
+            .is_some_and(|other| self == other)
+    }
+}
+
+impl PartialEq for AnyCommand {
+    fn eq(&self, other: &Self) -> bool {
+        self.eq_command(other.as_ref())
+    }
+}
+
 pub trait CommandFactory {
     /// Command
     ///
@@ -101,8 +354,193 @@ macro_rules! commands {
     };
 }
 
+#[macro_export]
+macro_rules! extended_commands {
+    () => {
+        vec![
+            Box::new($crate::code::commands::call::CallFactory),
+            Box::new($crate::code::commands::div::DivFactory),
+            Box::new($crate::code::commands::halt::HaltFactory),
+            Box::new($crate::code::commands::jump::JumpIndirectFactory),
+            Box::new($crate::code::commands::jump_positive::JumpPositiveFactory),
+            Box::new($crate::code::commands::modulo::ModFactory),
+            Box::new($crate::code::commands::mul::MulFactory),
+            Box::new($crate::code::commands::neg::NegFactory),
+            Box::new($crate::code::commands::nop::NopFactory),
+            Box::new($crate::code::commands::pop::PopFactory),
+            Box::new($crate::code::commands::push::PushFactory),
+            Box::new($crate::code::commands::ret::RetFactory),
+        ]
+    };
+}
+
+/// Command Enum
+///
+/// Generate a closed enum over command types, implementing [Command] by static dispatch. Keeps the
+/// factory API open to extensions, while a hot loop over a known command set avoids trait objects:
+///
+/// ```
+/// use hrm::code::commands::{add::Add, inbox::Inbox, outbox::Outbox};
+///
+/// hrm::command_enum! {
+///     /// Commands of my level
+///     #[derive(Debug, Clone, PartialEq)]
+///     pub enum LevelCommand {
+///         Inbox(Inbox),
+///         Outbox(Outbox),
+///         Add(Add),
+///     }
+/// }
+/// ```
+///
+/// Every command type must be [Clone]. Besides [Command], the enum gets `From` for each command
+/// type, `from_command` to downcast an [AnyCommand] & `from_program` to convert all commands of a
+/// [Program], [None] if any command is not in the enum.
+#[macro_export]
+macro_rules! command_enum {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $($variant:ident($command:ty)),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis enum $name {
+            $($variant($command)),*
+        }
+
+        impl $name {
+            /// From Command
+            ///
+            /// Returns [Some] if `command` is one of the enum's command types.
+            #[allow(dead_code)]
+            pub fn from_command(
+                command: &dyn $crate::code::commands::Command,
+            ) -> ::core::option::Option<Self> {
+                let any = command.as_any();
+                $(if let ::core::option::Option::Some(command) = any.downcast_ref::<$command>() {
+                    return ::core::option::Option::Some(Self::$variant(command.clone()));
+                })*
+                ::core::option::Option::None
+            }
+
+            /// From Program
+            ///
+            /// Returns [Some] if every command of `program` is one of the enum's command types.
+            #[allow(dead_code)]
+            pub fn from_program(
+                program: &$crate::code::program::Program,
+            ) -> ::core::option::Option<$crate::alloc::vec::Vec<Self>> {
+                program
+                    .get_commands()
+                    .iter()
+                    .map(|command| Self::from_command(command.as_ref()))
+                    .collect()
+            }
+        }
+
+        $(impl ::core::convert::From<$command> for $name {
+            fn from(command: $command) -> Self {
+                Self::$variant(command)
+            }
+        })*
+
+        impl $crate::code::commands::Command for $name {
+            fn execute(
+                &self,
+                program: &$crate::code::program::Program,
+                game_state: &mut $crate::code::game_state::GameState,
+            ) -> ::core::result::Result<(), $crate::code::program::RunError> {
+                match self {
+                    $(Self::$variant(command) => command.execute(program, game_state)),*
+                }
+            }
+
+            fn next(
+                &self,
+                program: &$crate::code::program::Program,
+                game_state: &$crate::code::game_state::GameState,
+            ) -> ::core::result::Result<usize, $crate::code::program::RunError> {
+                match self {
+                    $(Self::$variant(command) => command.next(program, game_state)),*
+                }
+            }
+
+            fn cost(&self) -> u32 {
+                match self {
+                    $(Self::$variant(command) => command.cost()),*
+                }
+            }
+
+            fn requires_index(&self) -> ::core::option::Option<usize> {
+                match self {
+                    $(Self::$variant(command) => command.requires_index()),*
+                }
+            }
+
+            fn flow(&self) -> $crate::code::commands::Flow {
+                match self {
+                    $(Self::$variant(command) => command.flow()),*
+                }
+            }
+
+            fn tile(&self) -> ::core::option::Option<usize> {
+                match self {
+                    $(Self::$variant(command) => command.tile()),*
+                }
+            }
+
+            fn requires_label(
+                &self,
+            ) -> ::core::option::Option<&$crate::code::symbol::Label> {
+                match self {
+                    $(Self::$variant(command) => command.requires_label()),*
+                }
+            }
+
+            fn is_debug(&self) -> bool {
+                match self {
+                    $(Self::$variant(command) => command.is_debug()),*
+                }
+            }
+
+            fn debug(
+                &self,
+                game_state: &$crate::code::game_state::GameState,
+            ) -> ::core::option::Option<$crate::code::commands::debug::DebugEvent> {
+                match self {
+                    $(Self::$variant(command) => command.debug(game_state)),*
+                }
+            }
+
+            fn args(&self) -> ::core::option::Option<$crate::alloc::string::String> {
+                match self {
+                    $(Self::$variant(command) => command.args()),*
+                }
+            }
+
+            fn to_source(&self) -> $crate::alloc::string::String {
+                match self {
+                    $(Self::$variant(command) => command.to_source()),*
+                }
+            }
+
+            fn factory(
+                &self,
+            ) -> $crate::alloc::boxed::Box<dyn $crate::code::commands::CommandFactory> {
+                match self {
+                    $(Self::$variant(command) => command.factory()),*
+                }
+            }
+        }
+    };
+}
+
 #[cfg(test)]
 mod tests {
+    #[cfg(feature = "std")]
+    use crate::compiler::compile::Compiler;
+
     use super::*;
 
     #[test]
@@ -111,11 +549,100 @@ mod tests {
             "INBOX", "OUTBOX", "ADD", "SUB", "BUMPUP", "BUMPDN", "COPYTO", "COPYFROM", "JUMP",
             "JUMPN", "JUMPZ",
         ];
-        let cmds: Vec<Box<dyn CommandFactory>> = commands!();
+        let cmds: Vec<Box<dyn CommandFactory>> = crate::commands!();
 
         assert_eq!(expected.len(), cmds.len());
         for cmd in cmds {
             assert!(expected.contains(&cmd.command()));
         }
     }
+
+    #[test]
+    fn extended_commands_macro_test() {
+        let cmds: Vec<Box<dyn CommandFactory>> = extended_commands!();
+
+        // Extended commands & `JUMP [addr]`
+        assert_eq!(EXTENDED_COMMANDS.len() + 1, cmds.len());
+        for cmd in cmds {
+            assert!(EXTENDED_COMMANDS.contains(&cmd.command()) || cmd.command() == "JUMP");
+        }
+    }
+
+    #[test]
+    fn instruction_set_test() {
+        let vanilla = InstructionSet::Vanilla;
+        assert!(vanilla.contains("INBOX"));
+        assert!(!vanilla.contains("MUL"));
+        assert_eq!(ALL_COMMANDS.len(), vanilla.factories().len());
+
+        let extended = InstructionSet::Extended;
+        assert!(extended.contains("INBOX"));
+        assert!(extended.contains("MUL"));
+        assert_eq!(
+            ALL_COMMANDS.len() + EXTENDED_COMMANDS.len() + 1,
+            extended.factories().len()
+        );
+
+        let custom = InstructionSet::Custom(BTreeSet::from([
+            String::from("INBOX"),
+            String::from("NEG"),
+            String::from("FOO"),
+        ]));
+        assert!(custom.contains("NEG"));
+        assert!(!custom.contains("OUTBOX"));
+        assert_eq!(2, custom.factories().len());
+    }
+
+    #[test]
+    fn command_value_display_test() {
+        assert_eq!("42", CommandValue::Value(42).to_string());
+        assert_eq!("[42]", CommandValue::Index(42).to_string());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn clone_eq_test() {
+        let compiler = Compiler::extended();
+        let command = compiler.compile_command("COPYFROM [4]").unwrap();
+
+        assert_eq!(&command, &command.clone());
+        assert_ne!(&command, &compiler.compile_command("COPYFROM 4").unwrap());
+        assert_ne!(&command, &compiler.compile_command("COPYTO [4]").unwrap());
+        assert_ne!(&command, &compiler.compile_command("INBOX").unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn to_source_test() {
+        let compiler = Compiler::extended();
+        for source in [
+            "INBOX",
+            "COPYFROM [4]",
+            "ADD 2",
+            "JUMPZ a",
+            "JUMP [3]",
+            "DEBUG \"m\" 1",
+        ] {
+            let command = compiler.compile_command(source).unwrap();
+            assert_eq!(source, command.to_source());
+        }
+    }
+
+    /// Shadows the prelude types the generated code uses
+    #[allow(dead_code)]
+    mod shadowed {
+        struct Option;
+        struct Result;
+        struct Vec;
+        struct String;
+        struct Box;
+
+        crate::command_enum! {
+            #[derive(Debug, Clone, PartialEq)]
+            enum ShadowedCommand {
+                Inbox(crate::code::commands::inbox::Inbox),
+                Outbox(crate::code::commands::outbox::Outbox),
+            }
+        }
+    }
 }
@@ -1,10 +1,16 @@
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+};
+
 use crate::{
     code::{
-        commands::{AnyCommand, Command, CommandFactory, CommandValue},
+        commands::{compile_command_value, AnyCommand, Command, CommandFactory, CommandValue},
         game_state::GameState,
-        program::{get_acc, get_from_memory, get_index, Program, RunError},
+        program::{
+            arithmetic, check_bounds, get_acc, get_from_memory, get_index, Program, RunError,
+        },
     },
-    compiler::compile::compile_command_value,
     create_with_args,
 };
 
@@ -20,9 +26,10 @@ impl Add {
 impl Command for Add {
     fn execute(&self, _program: &Program, game_state: &mut GameState) -> Result<(), RunError> {
         let value = get_acc(game_state.acc)?;
-        let index = get_index(&self.0, &game_state.memory)?;
-        let to_add = get_from_memory(game_state.memory[index])?;
-        let sum = value.hrm_add(to_add).ok_or(RunError::Add)?;
+        let index = get_index(&self.0, game_state)?;
+        let to_add = get_from_memory(game_state.read(index))?;
+        let sum = arithmetic(value.hrm_add(to_add), RunError::Add)?;
+        let sum = check_bounds(sum, game_state.bounds)?;
         game_state.acc = Some(sum);
         Ok(())
     }
@@ -34,6 +41,14 @@ impl Command for Add {
         }
     }
 
+    fn tile(&self) -> Option<usize> {
+        Some(self.0.tile())
+    }
+
+    fn args(&self) -> Option<String> {
+        Some(self.0.to_string())
+    }
+
     fn factory(&self) -> Box<dyn CommandFactory> {
         Box::new(AddFactory)
     }
@@ -54,7 +69,10 @@ impl CommandFactory for AddFactory {
 
 #[cfg(test)]
 mod tests {
-    use crate::game::value::Value;
+    use alloc::vec;
+
+    use crate::game::problem::OutputMatching;
+    use crate::game::value::{Value, ValueBounds};
 
     use super::*;
 
@@ -139,6 +157,17 @@ mod tests {
             i_output: 0,
             i_command: 0,
             speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
         };
 
         Add(CommandValue::Value(0))
@@ -163,6 +192,17 @@ mod tests {
             i_output: 0,
             i_command: 0,
             speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
         };
 
         let result = Add(CommandValue::Value(0))
@@ -182,6 +222,17 @@ mod tests {
             i_output: 0,
             i_command: 0,
             speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
         };
 
         let result = Add(CommandValue::Index(0))
@@ -200,6 +251,71 @@ mod tests {
         assert_eq!(RunError::EmptyMemory, result);
     }
 
+    #[test]
+    fn execute_floor_hole() {
+        let mut game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![Some(Value::Int(2)), Some(Value::Int(1)), None],
+            acc: Some(Value::Int(1)),
+            i_input: 0,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![2],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        let result = Add(CommandValue::Index(0))
+            .execute(&Default::default(), &mut game_state)
+            .unwrap_err();
+        assert_eq!(RunError::FloorHole(2), result);
+
+        Add(CommandValue::Index(1))
+            .execute(&Default::default(), &mut game_state)
+            .unwrap();
+        assert_eq!(Some(Value::Int(2)), game_state.acc);
+    }
+
+    #[test]
+    fn execute_overflow() {
+        let mut game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![Some(Value::Int(500))],
+            acc: Some(Value::Int(500)),
+            i_input: 0,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: Some(ValueBounds::GAME),
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        let result = Add(CommandValue::Value(0))
+            .execute(&Default::default(), &mut game_state)
+            .unwrap_err();
+        assert_eq!(RunError::Overflow(Value::Int(1000)), result);
+    }
+
     #[test]
     fn next_test() {
         let game_state = GameState {
@@ -211,6 +327,17 @@ mod tests {
             i_output: 0,
             i_command: 0,
             speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
         };
 
         assert_eq!(
@@ -0,0 +1,138 @@
+use crate::{
+    code::commands::{
+        add::Add, bump_down::BumpDown, bump_up::BumpUp, call::Call, copy_from::CopyFrom,
+        copy_to::CopyTo, debug::DebugPrint, div::Div, halt::Halt, inbox::Inbox, jump::Jump,
+        jump::JumpIndirect, jump_negative::JumpNegative, jump_positive::JumpPositive,
+        jump_zero::JumpZero, modulo::Mod, mul::Mul, neg::Neg, nop::Nop, outbox::Outbox, pop::Pop,
+        push::Push, ret::Ret, sub::Sub,
+    },
+    command_enum,
+};
+
+command_enum! {
+    /// Builtin Command
+    ///
+    /// Every command of [crate::code::commands::InstructionSet::Extended] & `DEBUG`, see
+    /// [crate::command_enum].
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum BuiltinCommand {
+        Inbox(Inbox),
+        Outbox(Outbox),
+        CopyFrom(CopyFrom),
+        CopyTo(CopyTo),
+        Add(Add),
+        Sub(Sub),
+        BumpUp(BumpUp),
+        BumpDown(BumpDown),
+        Jump(Jump),
+        JumpZero(JumpZero),
+        JumpNegative(JumpNegative),
+        JumpPositive(JumpPositive),
+        JumpIndirect(JumpIndirect),
+        Mul(Mul),
+        Div(Div),
+        Mod(Mod),
+        Neg(Neg),
+        Nop(Nop),
+        Halt(Halt),
+        Push(Push),
+        Pop(Pop),
+        Call(Call),
+        Ret(Ret),
+        DebugPrint(DebugPrint),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{boxed::Box, vec};
+
+    use super::*;
+    use crate::code::commands::{AnyCommand, Command, CommandFactory, CommandValue};
+    use crate::code::game_state::GameState;
+    use crate::code::program::{Program, RunError};
+    #[cfg(feature = "std")]
+    use crate::compiler::compile::Compiler;
+    use crate::game::value::Value;
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn from_program_test() {
+        let code = "\
+a:
+    INBOX
+    COPYTO [0]
+    JUMPZ b
+    DEBUG \"x\" 0
+    MUL 2
+    JUMP a
+b:
+    CALL a
+    JUMP [1]
+";
+        let program = Compiler::extended().compile(code).unwrap();
+        let commands = BuiltinCommand::from_program(&program).unwrap();
+
+        assert_eq!(program.get_commands().len(), commands.len());
+        for (command, builtin) in program.get_commands().iter().zip(&commands) {
+            assert_eq!(command.to_source(), builtin.to_source());
+            assert_eq!(command.flow(), builtin.flow());
+            assert_eq!(command.tile(), builtin.tile());
+            assert_eq!(command.requires_label(), builtin.requires_label());
+            assert_eq!(command.is_debug(), builtin.is_debug());
+            assert_eq!(command.factory().command(), builtin.factory().command());
+        }
+        assert_eq!(BuiltinCommand::Inbox(Inbox), commands[0]);
+    }
+
+    #[test]
+    fn execute_test() {
+        let (input, output) = (vec![Value::Int(3)], vec![]);
+        let mut game_state = GameState::new(&input, &output, vec![None]);
+        let program = Program::EMPTY;
+
+        for command in [
+            BuiltinCommand::from(Inbox),
+            BuiltinCommand::from(CopyTo(CommandValue::Value(0))),
+            BuiltinCommand::from(Add(CommandValue::Value(0))),
+        ] {
+            command.execute(&program, &mut game_state).unwrap();
+            game_state.i_command = command.next(&program, &game_state).unwrap();
+        }
+
+        assert_eq!(Some(Value::Int(6)), game_state.acc);
+        assert_eq!(3, game_state.i_command);
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Extension;
+
+    impl Command for Extension {
+        fn execute(&self, _program: &Program, _game_state: &mut GameState) -> Result<(), RunError> {
+            Ok(())
+        }
+
+        fn factory(&self) -> Box<dyn CommandFactory> {
+            Box::new(ExtensionFactory)
+        }
+    }
+
+    struct ExtensionFactory;
+
+    impl CommandFactory for ExtensionFactory {
+        fn command(&self) -> &'static str {
+            "EXTENSION"
+        }
+
+        fn create(&self, args: &str) -> Option<AnyCommand> {
+            args.is_empty().then(|| Box::new(Extension) as AnyCommand)
+        }
+    }
+
+    #[test]
+    fn from_command_fails() {
+        let command: AnyCommand = Box::new(Extension);
+        assert_eq!("EXTENSION", command.factory().command());
+        assert!(BuiltinCommand::from_command(command.as_ref()).is_none());
+    }
+}
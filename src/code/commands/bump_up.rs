@@ -1,12 +1,16 @@
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+};
+
 use crate::{
     code::{
-        commands::{AnyCommand, Command, CommandFactory, CommandValue},
+        commands::{compile_command_value, AnyCommand, Command, CommandFactory, CommandValue},
         game_state::GameState,
         program::{
-            Program, RunError, {get_from_memory, get_index},
+            arithmetic, check_bounds, Program, RunError, {get_from_memory, get_index},
         },
     },
-    compiler::compile::compile_command_value,
     create_with_args,
     game::value::Value,
 };
@@ -22,10 +26,11 @@ impl BumpUp {
 
 impl Command for BumpUp {
     fn execute(&self, _program: &Program, game_state: &mut GameState) -> Result<(), RunError> {
-        let index = get_index(&self.0, &game_state.memory)?;
-        let to_bump = get_from_memory(game_state.memory[index])?;
-        let bumped = to_bump.hrm_add(Value::Int(1)).ok_or(RunError::Add)?;
-        game_state.memory[index] = Some(bumped);
+        let index = get_index(&self.0, game_state)?;
+        let to_bump = get_from_memory(game_state.read(index))?;
+        let bumped = arithmetic(to_bump.hrm_add(Value::Int(1)), RunError::Add)?;
+        let bumped = check_bounds(bumped, game_state.bounds)?;
+        game_state.write(index, bumped);
         game_state.acc = Some(bumped);
         Ok(())
     }
@@ -37,6 +42,14 @@ impl Command for BumpUp {
         }
     }
 
+    fn tile(&self) -> Option<usize> {
+        Some(self.0.tile())
+    }
+
+    fn args(&self) -> Option<String> {
+        Some(self.0.to_string())
+    }
+
     fn factory(&self) -> Box<dyn CommandFactory> {
         Box::new(BumpUpFactory)
     }
@@ -56,6 +69,9 @@ impl CommandFactory for BumpUpFactory {
 
 #[cfg(test)]
 mod tests {
+    use alloc::vec;
+
+    use crate::game::problem::OutputMatching;
     use crate::game::value::Value;
 
     use super::*;
@@ -141,6 +157,17 @@ mod tests {
             i_output: 0,
             i_command: 0,
             speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
         };
 
         BumpUp(CommandValue::Value(0))
@@ -169,6 +196,17 @@ mod tests {
             i_output: 0,
             i_command: 0,
             speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
         };
 
         let result = BumpUp(CommandValue::Value(0))
@@ -188,6 +226,17 @@ mod tests {
             i_output: 0,
             i_command: 0,
             speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
         };
 
         let result = BumpUp(CommandValue::Index(0))
@@ -217,6 +266,17 @@ mod tests {
             i_output: 0,
             i_command: 0,
             speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
         };
 
         assert_eq!(
@@ -0,0 +1,237 @@
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+};
+
+use crate::{
+    code::{
+        commands::{compile_label, AnyCommand, Command, CommandFactory, Flow},
+        game_state::{GameState, MAX_CALL_DEPTH},
+        program::{Program, RunError},
+        symbol::Label,
+    },
+    create_with_args,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Call(pub Label);
+
+impl Call {
+    fn create(args: &str) -> Option<Self> {
+        compile_label(args).map(|label| Call(Label::from(label)))
+    }
+}
+
+impl Command for Call {
+    /// Call
+    ///
+    /// Push the return address onto the call stack.
+    fn execute(&self, _program: &Program, game_state: &mut GameState) -> Result<(), RunError> {
+        push_return_address(game_state)
+    }
+
+    /// Jump To
+    ///
+    /// See [Program::get_target].
+    fn next(&self, program: &Program, game_state: &GameState) -> Result<usize, RunError> {
+        program.get_target(game_state.i_command)
+    }
+
+    fn requires_label(&self) -> Option<&Label> {
+        Some(&self.0)
+    }
+
+    fn args(&self) -> Option<String> {
+        Some(self.0.to_string())
+    }
+
+    fn flow(&self) -> Flow {
+        Flow::Call
+    }
+
+    fn factory(&self) -> Box<dyn CommandFactory> {
+        Box::new(CallFactory)
+    }
+}
+
+/// Push Return Address
+///
+/// Push the index of the command after the current one onto the call stack.
+pub(crate) fn push_return_address(game_state: &mut GameState) -> Result<(), RunError> {
+    if game_state.call_stack.len() >= MAX_CALL_DEPTH {
+        return Err(RunError::CallStackOverflow {
+            depth: MAX_CALL_DEPTH,
+        });
+    }
+
+    game_state.call_stack.push(game_state.i_command + 1);
+    Ok(())
+}
+
+pub struct CallFactory;
+
+impl CommandFactory for CallFactory {
+    fn command(&self) -> &'static str {
+        "CALL"
+    }
+
+    fn create(&self, args: &str) -> Option<AnyCommand> {
+        create_with_args!(Call, args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use crate::code::program::ProgramBuilder;
+    use crate::game::problem::OutputMatching;
+
+    use super::*;
+
+    // region:call
+    #[test]
+    fn create_succeeds() {
+        let command = Call::create("a").unwrap();
+        assert_eq!(Call(Label::from("a")), command);
+    }
+
+    #[test]
+    fn create_fails() {
+        for args in ["", "1", "a1", " ", " a "] {
+            assert!(Call::create(args).is_none());
+        }
+    }
+    // endregion
+
+    // region:factory
+    #[test]
+    fn command_test() {
+        assert_eq!("CALL", CallFactory.command());
+    }
+
+    #[test]
+    fn factory_create_succeeds() {
+        let command = CallFactory.create("a");
+        assert!(command.is_some());
+    }
+
+    #[test]
+    fn factory_create_fails() {
+        let command = CallFactory.create("1");
+        assert!(command.is_none());
+    }
+    // endregion
+
+    // region:command
+    #[test]
+    fn execute_succeeds() {
+        let mut game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![],
+            acc: None,
+            i_input: 0,
+            i_output: 0,
+            i_command: 3,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![1],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        Call(Label::from("a"))
+            .execute(&Default::default(), &mut game_state)
+            .unwrap();
+        assert_eq!(vec![1, 4], game_state.call_stack);
+    }
+
+    #[test]
+    fn execute_overflow() {
+        let mut game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![],
+            acc: None,
+            i_input: 0,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![0; MAX_CALL_DEPTH],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        let result = Call(Label::from("a"))
+            .execute(&Default::default(), &mut game_state)
+            .unwrap_err();
+        assert_eq!(
+            RunError::CallStackOverflow {
+                depth: MAX_CALL_DEPTH
+            },
+            result
+        );
+    }
+
+    #[test]
+    fn next_test() {
+        let game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![],
+            acc: None,
+            i_input: 0,
+            i_output: 0,
+            i_command: 1,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![2],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        let program = ProgramBuilder::new()
+            .add_label(Label::from("a"))
+            .add_command(Box::new(Call(Label::from("a"))))
+            .add_command(Box::new(Call(Label::from("a"))))
+            .build();
+
+        let i_next = Call(Label::from("a")).next(&program, &game_state).unwrap();
+        assert_eq!(0, i_next);
+    }
+
+    #[test]
+    fn requires_label_test() {
+        let command = Call(Label::from("a"));
+        assert_eq!("a", command.requires_label().unwrap().as_str());
+    }
+
+    #[test]
+    fn factory_test() {
+        assert_eq!("CALL", Call(Label::from("a")).factory().command());
+    }
+    // endregion
+}
@@ -1,10 +1,14 @@
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+};
+
 use crate::{
     code::{
-        commands::{AnyCommand, Command, CommandFactory, CommandValue},
+        commands::{compile_command_value, AnyCommand, Command, CommandFactory, CommandValue},
         game_state::GameState,
         program::{get_from_memory, get_index, Program, RunError},
     },
-    compiler::compile::compile_command_value,
     create_with_args,
 };
 
@@ -19,8 +23,8 @@ impl CopyFrom {
 
 impl Command for CopyFrom {
     fn execute(&self, _program: &Program, game_state: &mut GameState) -> Result<(), RunError> {
-        let index = get_index(&self.0, &game_state.memory)?;
-        game_state.acc = Some(get_from_memory(game_state.memory[index])?);
+        let index = get_index(&self.0, game_state)?;
+        game_state.acc = Some(get_from_memory(game_state.read(index))?);
 
         Ok(())
     }
@@ -32,6 +36,14 @@ impl Command for CopyFrom {
         }
     }
 
+    fn tile(&self) -> Option<usize> {
+        Some(self.0.tile())
+    }
+
+    fn args(&self) -> Option<String> {
+        Some(self.0.to_string())
+    }
+
     fn factory(&self) -> Box<dyn CommandFactory> {
         Box::new(CopyFromFactory)
     }
@@ -51,6 +63,9 @@ impl CommandFactory for CopyFromFactory {
 
 #[cfg(test)]
 mod tests {
+    use alloc::vec;
+
+    use crate::game::problem::OutputMatching;
     use crate::game::value::Value;
 
     use super::*;
@@ -136,6 +151,17 @@ mod tests {
             i_output: 0,
             i_command: 0,
             speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
         };
 
         CopyFrom(CommandValue::Value(0))
@@ -160,6 +186,17 @@ mod tests {
             i_output: 0,
             i_command: 0,
             speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
         };
 
         let result = CopyFrom(CommandValue::Value(0))
@@ -179,6 +216,17 @@ mod tests {
             i_output: 0,
             i_command: 0,
             speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
         };
 
         let result = CopyFrom(CommandValue::Index(0))
@@ -208,6 +256,17 @@ mod tests {
             i_output: 0,
             i_command: 0,
             speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
         };
 
         assert_eq!(
@@ -1,10 +1,14 @@
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+};
+
 use crate::{
     code::{
-        commands::{AnyCommand, Command, CommandFactory, CommandValue},
+        commands::{compile_command_value, AnyCommand, Command, CommandFactory, CommandValue},
         game_state::GameState,
         program::{get_acc, get_index, Program, RunError},
     },
-    compiler::compile::compile_command_value,
     create_with_args,
 };
 
@@ -20,8 +24,8 @@ impl CopyTo {
 impl Command for CopyTo {
     fn execute(&self, _program: &Program, game_state: &mut GameState) -> Result<(), RunError> {
         let value = get_acc(game_state.acc)?;
-        let index = get_index(&self.0, &game_state.memory)?;
-        game_state.memory[index] = Some(value);
+        let index = get_index(&self.0, game_state)?;
+        game_state.write(index, value);
 
         Ok(())
     }
@@ -33,6 +37,14 @@ impl Command for CopyTo {
         }
     }
 
+    fn tile(&self) -> Option<usize> {
+        Some(self.0.tile())
+    }
+
+    fn args(&self) -> Option<String> {
+        Some(self.0.to_string())
+    }
+
     fn factory(&self) -> Box<dyn CommandFactory> {
         Box::new(CopyToFactory)
     }
@@ -52,6 +64,9 @@ impl CommandFactory for CopyToFactory {
 
 #[cfg(test)]
 mod tests {
+    use alloc::vec;
+
+    use crate::game::problem::OutputMatching;
     use crate::game::value::Value;
 
     use super::*;
@@ -137,6 +152,17 @@ mod tests {
             i_output: 0,
             i_command: 0,
             speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
         };
 
         CopyTo(CommandValue::Value(0))
@@ -163,6 +189,17 @@ mod tests {
             i_output: 0,
             i_command: 0,
             speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
         };
 
         let result = CopyTo(CommandValue::Value(0))
@@ -182,6 +219,17 @@ mod tests {
             i_output: 0,
             i_command: 0,
             speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
         };
 
         let result = CopyTo(CommandValue::Index(0))
@@ -211,6 +259,17 @@ mod tests {
             i_output: 0,
             i_command: 0,
             speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
         };
 
         assert_eq!(
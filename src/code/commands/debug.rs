@@ -0,0 +1,272 @@
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt::{Display, Formatter};
+
+use crate::{
+    code::{
+        commands::{AnyCommand, Command, CommandFactory},
+        game_state::GameState,
+        program::{Program, RunError},
+    },
+    create_with_args,
+    game::value::Value,
+};
+
+/// Debug Event
+///
+/// State snapshot passed to the runner's debug callback by a [DebugPrint] command.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DebugEvent {
+    pub i_command: usize,
+    pub message: String,
+    pub acc: Option<Value>,
+    pub memory: Vec<(usize, Option<Value>)>,
+}
+
+impl Display for DebugEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} | acc = ", self.message)?;
+        write_value(f, self.acc)?;
+        for (idx, value) in &self.memory {
+            write!(f, ", [{idx}] = ")?;
+            write_value(f, *value)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_value(f: &mut Formatter<'_>, value: Option<Value>) -> core::fmt::Result {
+    match value {
+        Some(value) => write!(f, "{value}"),
+        None => f.write_str("_"),
+    }
+}
+
+/// `DEBUG "message" slot...`
+///
+/// Reports the acc & selected memory slots. Debug commands are never executed, are available in
+/// every problem & count towards neither size nor speed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DebugPrint {
+    pub message: String,
+    pub slots: Vec<usize>,
+}
+
+impl DebugPrint {
+    fn create(args: &str) -> Option<Self> {
+        let (message, slots) = match args.strip_prefix('"') {
+            Some(rest) => {
+                let end = rest.find('"')?;
+                let slots = &rest[end + 1..];
+                if !slots.is_empty() && !slots.starts_with(char::is_whitespace) {
+                    return None;
+                }
+                (rest[..end].to_string(), slots)
+            }
+            None => (String::new(), args),
+        };
+
+        let slots = slots
+            .split_whitespace()
+            .map(|slot| slot.parse().ok())
+            .collect::<Option<Vec<usize>>>()?;
+
+        Some(Self { message, slots })
+    }
+}
+
+impl Command for DebugPrint {
+    fn execute(&self, _program: &Program, _game_state: &mut GameState) -> Result<(), RunError> {
+        Ok(())
+    }
+
+    fn cost(&self) -> u32 {
+        0
+    }
+
+    /// Requires Index
+    ///
+    /// Returns the largest slot, so that all slots are validated to exist.
+    fn requires_index(&self) -> Option<usize> {
+        self.slots.iter().max().copied()
+    }
+
+    fn is_debug(&self) -> bool {
+        true
+    }
+
+    fn debug(&self, game_state: &GameState) -> Option<DebugEvent> {
+        Some(DebugEvent {
+            i_command: game_state.i_command,
+            message: self.message.clone(),
+            acc: game_state.acc,
+            memory: self
+                .slots
+                .iter()
+                .map(|&slot| (slot, game_state.memory.get(slot).copied().flatten()))
+                .collect(),
+        })
+    }
+
+    fn args(&self) -> Option<String> {
+        let mut args = vec![];
+        if !self.message.is_empty() {
+            args.push(format!("\"{}\"", self.message));
+        }
+        args.extend(self.slots.iter().map(|slot| slot.to_string()));
+
+        if args.is_empty() {
+            None
+        } else {
+            Some(args.join(" "))
+        }
+    }
+
+    fn factory(&self) -> Box<dyn CommandFactory> {
+        Box::new(DebugPrintFactory)
+    }
+}
+
+pub struct DebugPrintFactory;
+
+impl CommandFactory for DebugPrintFactory {
+    fn command(&self) -> &'static str {
+        "DEBUG"
+    }
+
+    fn create(&self, args: &str) -> Option<AnyCommand> {
+        create_with_args!(DebugPrint, args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::problem::OutputMatching;
+
+    // region:debug
+    #[test]
+    fn create_succeeds() {
+        let command = DebugPrint::create("\"loop start\" 0 3").unwrap();
+        assert_eq!(
+            DebugPrint {
+                message: String::from("loop start"),
+                slots: vec![0, 3],
+            },
+            command
+        );
+
+        let command = DebugPrint::create("").unwrap();
+        assert_eq!(
+            DebugPrint {
+                message: String::new(),
+                slots: vec![],
+            },
+            command
+        );
+
+        let command = DebugPrint::create("1").unwrap();
+        assert_eq!(vec![1], command.slots);
+    }
+
+    #[test]
+    fn create_fails() {
+        for args in ["\"a", "\"a\"1", "a", "[1]", "\"a\" b"] {
+            assert!(DebugPrint::create(args).is_none());
+        }
+    }
+    // endregion
+
+    // region:factory
+    #[test]
+    fn command_test() {
+        assert_eq!("DEBUG", DebugPrintFactory.command());
+    }
+
+    #[test]
+    fn factory_create_succeeds() {
+        let command = DebugPrintFactory.create("\"msg\" 1");
+        assert!(command.is_some());
+    }
+
+    #[test]
+    fn factory_create_fails() {
+        let command = DebugPrintFactory.create("msg");
+        assert!(command.is_none());
+    }
+    // endregion
+
+    // region:command
+    #[test]
+    fn debug_test() {
+        let game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![Some(Value::Int(1)), None, Some(Value::Char('A'))],
+            acc: Some(Value::Int(5)),
+            i_input: 0,
+            i_output: 0,
+            i_command: 4,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        let event = DebugPrint::create("\"here\" 2 1")
+            .unwrap()
+            .debug(&game_state)
+            .unwrap();
+        assert_eq!(
+            DebugEvent {
+                i_command: 4,
+                message: String::from("here"),
+                acc: Some(Value::Int(5)),
+                memory: vec![(2, Some(Value::Char('A'))), (1, None)],
+            },
+            event
+        );
+        assert_eq!("here | acc = 5, [2] = A, [1] = _", event.to_string());
+    }
+
+    #[test]
+    fn cost_test() {
+        assert_eq!(0, DebugPrint::create("").unwrap().cost());
+    }
+
+    #[test]
+    fn requires_index_test() {
+        assert!(DebugPrint::create("").unwrap().requires_index().is_none());
+        assert_eq!(
+            Some(7),
+            DebugPrint::create("3 7 1").unwrap().requires_index()
+        );
+    }
+
+    #[test]
+    fn args_test() {
+        for args in ["\"a b\" 1 2", "\"a\"", "3"] {
+            assert_eq!(args, DebugPrint::create(args).unwrap().args().unwrap());
+        }
+        assert!(DebugPrint::create("").unwrap().args().is_none());
+    }
+
+    #[test]
+    fn factory_test() {
+        assert_eq!("DEBUG", DebugPrint::create("").unwrap().factory().command());
+    }
+    // endregion
+}
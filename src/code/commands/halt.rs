@@ -0,0 +1,176 @@
+use alloc::boxed::Box;
+
+use crate::{
+    code::{
+        commands::{AnyCommand, Command, CommandFactory, Flow},
+        game_state::GameState,
+        program::{Program, RunError},
+    },
+    create_with_args,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Halt;
+
+impl Halt {
+    fn create(args: &str) -> Option<Self> {
+        if args.is_empty() {
+            Some(Self)
+        } else {
+            None
+        }
+    }
+}
+
+impl Command for Halt {
+    /// Halt
+    ///
+    /// End execution as if there were no more commands. Like `INBOX` with no inputs left, the
+    /// halting command does not count towards speed.
+    fn execute(&self, _program: &Program, game_state: &mut GameState) -> Result<(), RunError> {
+        game_state.halted = true;
+        Ok(())
+    }
+
+    fn flow(&self) -> Flow {
+        Flow::Halt
+    }
+
+    fn factory(&self) -> Box<dyn CommandFactory> {
+        Box::new(HaltFactory)
+    }
+}
+
+pub struct HaltFactory;
+
+impl CommandFactory for HaltFactory {
+    fn command(&self) -> &'static str {
+        "HALT"
+    }
+
+    fn create(&self, args: &str) -> Option<AnyCommand> {
+        create_with_args!(Halt, args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+    use crate::game::problem::OutputMatching;
+
+    // region:halt
+    #[test]
+    fn create_succeeds() {
+        let command = Halt::create("").unwrap();
+        assert_eq!(Halt, command);
+    }
+
+    #[test]
+    fn create_fails() {
+        let command = Halt::create("a");
+        assert!(command.is_none());
+
+        let command = Halt::create("1");
+        assert!(command.is_none());
+
+        let command = Halt::create(" ");
+        assert!(command.is_none());
+    }
+    // endregion
+
+    // region:factory
+    #[test]
+    fn command_test() {
+        assert_eq!("HALT", HaltFactory.command());
+    }
+
+    #[test]
+    fn factory_create_succeeds() {
+        let command = HaltFactory.create("");
+        assert!(command.is_some());
+    }
+
+    #[test]
+    fn factory_create_fails() {
+        let command = HaltFactory.create("a");
+        assert!(command.is_none());
+
+        let command = HaltFactory.create("1");
+        assert!(command.is_none());
+    }
+    // endregion
+
+    // region:command
+    #[test]
+    fn execute_succeeds() {
+        let mut game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![],
+            acc: None,
+            i_input: 0,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        Halt.execute(&Default::default(), &mut game_state).unwrap();
+        assert!(game_state.halted);
+    }
+
+    #[test]
+    fn next_test() {
+        let game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![],
+            acc: None,
+            i_input: 0,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        assert_eq!(1, Halt.next(&Default::default(), &game_state).unwrap());
+    }
+
+    #[test]
+    fn requires_index_test() {
+        assert!(Halt.requires_index().is_none());
+    }
+
+    #[test]
+    fn requires_label_test() {
+        assert!(Halt.requires_label().is_none());
+    }
+
+    #[test]
+    fn factory_test() {
+        assert_eq!("HALT", Halt.factory().command());
+    }
+    // endregion
+}
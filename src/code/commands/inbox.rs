@@ -1,5 +1,4 @@
-use std::cell::RefCell;
-use std::fmt::{Debug, Formatter};
+use alloc::boxed::Box;
 
 use crate::{
     code::{
@@ -10,16 +9,8 @@ use crate::{
     create_with_args,
 };
 
-#[derive(Clone, PartialEq)]
-pub struct Inbox {
-    is_over: RefCell<bool>,
-}
-
-impl Debug for Inbox {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_str("Input")
-    }
-}
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Inbox;
 
 impl Default for Inbox {
     fn default() -> Self {
@@ -29,9 +20,7 @@ impl Default for Inbox {
 
 impl Inbox {
     pub fn new() -> Self {
-        Self {
-            is_over: RefCell::new(false),
-        }
+        Self
     }
 
     fn create(args: &str) -> Option<Self> {
@@ -46,7 +35,7 @@ impl Inbox {
 impl Command for Inbox {
     fn execute(&self, _program: &Program, game_state: &mut GameState) -> Result<(), RunError> {
         if game_state.i_input == game_state.input.len() {
-            *self.is_over.borrow_mut() = true;
+            game_state.halted = true;
             return Ok(());
         }
 
@@ -55,14 +44,6 @@ impl Command for Inbox {
         Ok(())
     }
 
-    fn next(&self, _program: &Program, game_state: &GameState) -> Option<usize> {
-        if *self.is_over.borrow() {
-            None
-        } else {
-            Some(game_state.i_command + 1)
-        }
-    }
-
     fn factory(&self) -> Box<dyn CommandFactory> {
         Box::new(InboxFactory)
     }
@@ -82,6 +63,9 @@ impl CommandFactory for InboxFactory {
 
 #[cfg(test)]
 mod tests {
+    use alloc::vec;
+
+    use crate::game::problem::OutputMatching;
     use crate::game::value::Value;
 
     use super::*;
@@ -143,12 +127,24 @@ mod tests {
             i_output: 0,
             i_command: 0,
             speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
         };
 
         Inbox::new()
             .execute(&Default::default(), &mut game_state)
             .unwrap();
         assert_eq!(1, game_state.i_input);
+        assert!(!game_state.halted);
     }
 
     #[test]
@@ -162,12 +158,24 @@ mod tests {
             i_output: 0,
             i_command: 0,
             speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
         };
 
         Inbox::new()
             .execute(&Default::default(), &mut game_state)
             .unwrap();
         assert_eq!(1, game_state.i_input);
+        assert!(game_state.halted);
     }
 
     #[test]
@@ -181,6 +189,17 @@ mod tests {
             i_output: 0,
             i_command: 0,
             speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
         };
 
         assert_eq!(
@@ -189,26 +208,6 @@ mod tests {
         );
     }
 
-    #[test]
-    fn next_is_over() {
-        let game_state = GameState {
-            input: &vec![Value::Int(5)],
-            output: &vec![],
-            memory: vec![],
-            acc: None,
-            i_input: 1,
-            i_output: 0,
-            i_command: 0,
-            speed: 0,
-        };
-
-        assert!(Inbox {
-            is_over: RefCell::new(true)
-        }
-        .next(&Default::default(), &game_state)
-        .is_none());
-    }
-
     #[test]
     fn requires_index_test() {
         assert!(Inbox::new().requires_index().is_none());
@@ -1,19 +1,28 @@
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+};
+
 use crate::{
     code::{
-        commands::{AnyCommand, Command, CommandFactory},
+        commands::{
+            compile_command_value, compile_label, AnyCommand, Command, CommandFactory,
+            CommandValue, Flow,
+        },
         game_state::GameState,
-        program::{Program, RunError},
+        program::{get_from_memory, Program, RunError},
+        symbol::Label,
     },
-    compiler::compile::compile_label,
     create_with_args,
+    game::value::Value,
 };
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct Jump(pub String);
+pub struct Jump(pub Label);
 
 impl Jump {
     fn create(args: &str) -> Option<Self> {
-        compile_label(args).map(Jump)
+        compile_label(args).map(|label| Jump(Label::from(label)))
     }
 }
 
@@ -24,22 +33,88 @@ impl Command for Jump {
 
     /// Jump To
     ///
-    /// # Panics
-    ///
-    /// See [Program::get_label].
-    fn next(&self, program: &Program, _game_state: &GameState) -> Option<usize> {
-        Some(program.get_label(&self.0))
+    /// See [Program::get_target].
+    fn next(&self, program: &Program, game_state: &GameState) -> Result<usize, RunError> {
+        program.get_target(game_state.i_command)
     }
 
-    fn requires_label(&self) -> Option<&str> {
+    fn requires_label(&self) -> Option<&Label> {
         Some(&self.0)
     }
 
+    fn args(&self) -> Option<String> {
+        Some(self.0.to_string())
+    }
+
+    fn flow(&self) -> Flow {
+        Flow::Jump
+    }
+
     fn factory(&self) -> Box<dyn CommandFactory> {
         Box::new(JumpFactory)
     }
 }
 
+/// `JUMP [addr]`
+///
+/// Jump to the command index stored in tile `addr`. Index `commands.len()` ends the program, like
+/// a label after the last command.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JumpIndirect(pub usize);
+
+impl JumpIndirect {
+    fn create(args: &str) -> Option<Self> {
+        match compile_command_value(args)? {
+            CommandValue::Index(index) => Some(JumpIndirect(index)),
+            CommandValue::Value(_) => None,
+        }
+    }
+
+    /// Target
+    ///
+    /// Returns the command index stored in `value`, valid in a program of `len` commands.
+    pub(crate) fn target(&self, len: usize, value: Option<Value>) -> Result<usize, RunError> {
+        let value = get_from_memory(value)?;
+        match value {
+            Value::Int(idx) if idx >= 0 && idx as usize <= len => Ok(idx as usize),
+            Value::Int(_) => Err(RunError::JumpOutOfRange(value)),
+            Value::Char(_) => Err(RunError::CharIndex(value)),
+        }
+    }
+}
+
+impl Command for JumpIndirect {
+    /// Verify the target read from memory is a valid command index.
+    fn execute(&self, program: &Program, game_state: &mut GameState) -> Result<(), RunError> {
+        let value = game_state.read(self.0);
+        self.target(program.get_commands().len(), value).map(|_| ())
+    }
+
+    fn next(&self, program: &Program, game_state: &GameState) -> Result<usize, RunError> {
+        self.target(program.get_commands().len(), game_state.peek(self.0))
+    }
+
+    fn requires_index(&self) -> Option<usize> {
+        Some(self.0)
+    }
+
+    fn tile(&self) -> Option<usize> {
+        Some(self.0)
+    }
+
+    fn args(&self) -> Option<String> {
+        Some(CommandValue::Index(self.0).to_string())
+    }
+
+    fn flow(&self) -> Flow {
+        Flow::Indirect
+    }
+
+    fn factory(&self) -> Box<dyn CommandFactory> {
+        Box::new(JumpIndirectFactory)
+    }
+}
+
 pub struct JumpFactory;
 
 impl CommandFactory for JumpFactory {
@@ -52,10 +127,32 @@ impl CommandFactory for JumpFactory {
     }
 }
 
+/// Factory of `JUMP [addr]`, an extension of vanilla `JUMP` - only part of non-vanilla
+/// [InstructionSet](crate::code::commands::InstructionSet)s.
+pub struct JumpIndirectFactory;
+
+impl CommandFactory for JumpIndirectFactory {
+    fn command(&self) -> &'static str {
+        "JUMP"
+    }
+
+    fn create(&self, args: &str) -> Option<AnyCommand> {
+        create_with_args!(JumpIndirect, args)
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use alloc::vec;
+
     use crate::code::program::ProgramBuilder;
-    use crate::game::value::Value;
+    use crate::game::problem::OutputMatching;
+    #[cfg(feature = "std")]
+    use crate::{
+        code::{commands::InstructionSet, program::ValidationError},
+        compiler::compile::Compiler,
+        game::problem::ProblemBuilder,
+    };
 
     use super::*;
 
@@ -63,7 +160,7 @@ mod tests {
     #[test]
     fn create_succeeds() {
         let command = Jump::create("a").unwrap();
-        assert_eq!(Jump(String::from("a")), command);
+        assert_eq!(Jump(Label::from("a")), command);
     }
 
     #[test]
@@ -85,6 +182,91 @@ mod tests {
     }
     // endregion
 
+    // region:jump_indirect
+    #[test]
+    fn create_indirect_succeeds() {
+        let command = JumpIndirect::create("[3]").unwrap();
+        assert_eq!(JumpIndirect(3), command);
+    }
+
+    #[test]
+    fn create_indirect_fails() {
+        for args in ["", "3", "a", "[a]", " [3]"] {
+            assert!(JumpIndirect::create(args).is_none());
+        }
+    }
+
+    #[test]
+    fn target_indirect() {
+        let program = ProgramBuilder::new()
+            .add_command(Box::new(JumpIndirect(0)))
+            .add_command(Box::new(JumpIndirect(0)))
+            .build();
+
+        let targets = [
+            (Some(Value::Int(1)), Ok(1)),
+            (Some(Value::Int(2)), Ok(2)),
+            (
+                Some(Value::Int(3)),
+                Err(RunError::JumpOutOfRange(Value::Int(3))),
+            ),
+            (
+                Some(Value::Int(-1)),
+                Err(RunError::JumpOutOfRange(Value::Int(-1))),
+            ),
+            (
+                Some(Value::Char('A')),
+                Err(RunError::CharIndex(Value::Char('A'))),
+            ),
+            (None, Err(RunError::EmptyMemory)),
+        ];
+
+        for (value, expected) in targets {
+            let mut game_state = GameState {
+                input: &vec![],
+                output: &vec![],
+                memory: vec![value],
+                acc: None,
+                i_input: 0,
+                i_output: 0,
+                i_command: 0,
+                speed: 0,
+                halted: false,
+                stack: vec![],
+                stack_max: 0,
+                call_stack: vec![],
+                produced: vec![],
+                output_matching: OutputMatching::Exact,
+                bounds: None,
+                holes: vec![],
+                unbounded_memory: false,
+                memory_stats: Default::default(),
+                check_output: true,
+            };
+
+            let command = JumpIndirect(0);
+            let result = command.execute(&program, &mut game_state);
+            match expected {
+                Ok(idx) => {
+                    assert_eq!(Ok(()), result);
+                    assert_eq!(Ok(idx), command.next(&program, &game_state));
+                }
+                Err(err) => {
+                    assert_eq!(Err(err), result);
+                    assert!(command.next(&program, &game_state).is_err());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn args_indirect() {
+        assert_eq!("[3]", JumpIndirect(3).args().unwrap());
+        assert_eq!(Some(3), JumpIndirect(3).requires_index());
+        assert_eq!("JUMP", JumpIndirect(3).factory().command());
+    }
+    // endregion
+
     // region:factory
     #[test]
     fn command_test() {
@@ -97,6 +279,57 @@ mod tests {
         assert!(command.is_some());
     }
 
+    #[test]
+    fn factory_create_indirect() {
+        let command = JumpIndirectFactory.create("[1]").unwrap();
+        assert_eq!("[1]", command.args().unwrap());
+        assert_eq!("JUMP", command.factory().command());
+        assert!(JumpFactory.create("[1]").is_none());
+        assert!(JumpIndirectFactory.create("a").is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn indirect_is_extended() {
+        assert!(Compiler::default().compile("JUMP [0]\n").is_err());
+        let program = Compiler::extended().compile("JUMP [0]\n").unwrap();
+
+        let problem = |instruction_set| {
+            ProblemBuilder::new()
+                .memory_dim(1)
+                .instruction_set(instruction_set)
+                .enable_command(String::from("JUMP"))
+                .build()
+                .unwrap()
+        };
+        assert_eq!(
+            vec![ValidationError::CommandNotAvailable(String::from(
+                "JUMP [0]"
+            ))],
+            program.validate_all(&problem(InstructionSet::Vanilla))
+        );
+        assert!(program.validate(&problem(InstructionSet::Extended)).is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn indirect_validates_tile() {
+        let program = Compiler::extended().compile("JUMP [1]\n").unwrap();
+        assert_eq!(Some(1), program.get_commands()[0].tile());
+
+        let problem = ProblemBuilder::new()
+            .memory_dim(2)
+            .floor_hole(1)
+            .instruction_set(InstructionSet::Extended)
+            .enable_command(String::from("JUMP"))
+            .build()
+            .unwrap();
+        assert_eq!(
+            vec![ValidationError::FloorHole(1)],
+            program.validate_all(&problem)
+        );
+    }
+
     #[test]
     fn factory_create_fails() {
         let command = JumpFactory.create("");
@@ -126,30 +359,44 @@ mod tests {
             acc: Some(Value::Int(1)),
             i_input: 0,
             i_output: 0,
-            i_command: 5,
+            i_command: 0,
             speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
         };
 
-        let program = ProgramBuilder::new().add_label(String::from("a")).build();
+        let program = ProgramBuilder::new()
+            .add_label(Label::from("a"))
+            .add_command(Box::new(Jump(Label::from("a"))))
+            .build();
 
-        let i_next = Jump(String::from("a")).next(&program, &game_state).unwrap();
+        let i_next = Jump(Label::from("a")).next(&program, &game_state).unwrap();
         assert_eq!(0, i_next);
     }
 
     #[test]
     fn requires_index_test() {
-        assert!(Jump(String::new()).requires_index().is_none());
+        assert!(Jump(Label::default()).requires_index().is_none());
     }
 
     #[test]
     fn requires_label_test() {
-        let command = Jump(String::from("a"));
-        assert_eq!("a", command.requires_label().unwrap());
+        let command = Jump(Label::from("a"));
+        assert_eq!("a", command.requires_label().unwrap().as_str());
     }
 
     #[test]
     fn factory_test() {
-        assert_eq!("JUMP", Jump(String::from("a")).factory().command());
+        assert_eq!("JUMP", Jump(Label::from("a")).factory().command());
     }
     // endregion
 }
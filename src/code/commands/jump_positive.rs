@@ -0,0 +1,281 @@
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+};
+
+use crate::{
+    code::{
+        commands::{compile_label, AnyCommand, Command, CommandFactory, Flow},
+        game_state::GameState,
+        program::{get_acc, Program, RunError},
+        symbol::Label,
+    },
+    create_with_args,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JumpPositive(pub Label);
+
+impl JumpPositive {
+    fn create(args: &str) -> Option<Self> {
+        compile_label(args).map(|label| JumpPositive(Label::from(label)))
+    }
+}
+
+impl Command for JumpPositive {
+    fn execute(&self, _program: &Program, game_state: &mut GameState) -> Result<(), RunError> {
+        get_acc(game_state.acc).map(|_| ())
+    }
+
+    /// Jump To If Positive
+    ///
+    /// Jumps to label if [GameState]`.acc` is greater than `0`, else increments [GameState]`.i_command`.
+    fn next(&self, program: &Program, game_state: &GameState) -> Result<usize, RunError> {
+        if get_acc(game_state.acc)? > 0 {
+            program.get_target(game_state.i_command)
+        } else {
+            Ok(game_state.i_command + 1)
+        }
+    }
+
+    fn requires_label(&self) -> Option<&Label> {
+        Some(&self.0)
+    }
+
+    fn args(&self) -> Option<String> {
+        Some(self.0.to_string())
+    }
+
+    fn flow(&self) -> Flow {
+        Flow::Branch
+    }
+
+    fn factory(&self) -> Box<dyn CommandFactory> {
+        Box::new(JumpPositiveFactory)
+    }
+}
+
+pub struct JumpPositiveFactory;
+
+impl CommandFactory for JumpPositiveFactory {
+    fn command(&self) -> &'static str {
+        "JUMPP"
+    }
+
+    fn create(&self, args: &str) -> Option<AnyCommand> {
+        create_with_args!(JumpPositive, args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use crate::code::program::ProgramBuilder;
+    use crate::game::problem::OutputMatching;
+    use crate::game::value::Value;
+
+    use super::*;
+
+    // region:jumppositive
+    #[test]
+    fn create_succeeds() {
+        let command = JumpPositive::create("a").unwrap();
+        assert_eq!(JumpPositive(Label::from("a")), command);
+    }
+
+    #[test]
+    fn create_fails() {
+        let command = JumpPositive::create("");
+        assert!(command.is_none());
+
+        let command = JumpPositive::create("");
+        assert!(command.is_none());
+
+        let command = JumpPositive::create("1");
+        assert!(command.is_none());
+
+        let command = JumpPositive::create("a1");
+        assert!(command.is_none());
+
+        let command = JumpPositive::create(" ");
+        assert!(command.is_none());
+
+        let command = JumpPositive::create(" a ");
+        assert!(command.is_none());
+    }
+    // endregion
+
+    // region:factory
+    #[test]
+    fn command_test() {
+        assert_eq!("JUMPP", JumpPositiveFactory.command());
+    }
+
+    #[test]
+    fn factory_create_succeeds() {
+        let command = JumpPositiveFactory.create("a");
+        assert!(command.is_some());
+    }
+
+    #[test]
+    fn factory_create_fails() {
+        let command = JumpPositiveFactory.create("");
+        assert!(command.is_none());
+
+        let command = JumpPositiveFactory.create("");
+        assert!(command.is_none());
+
+        let command = JumpPositiveFactory.create("1");
+        assert!(command.is_none());
+
+        let command = JumpPositiveFactory.create("a1");
+        assert!(command.is_none());
+
+        let command = JumpPositiveFactory.create(" ");
+        assert!(command.is_none());
+
+        let command = JumpPositiveFactory.create(" a ");
+        assert!(command.is_none());
+    }
+    // endregion
+
+    // region:command
+    #[test]
+    fn execute_succeeds() {
+        let mut game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![Some(Value::Int(1)), Some(Value::Int(42))],
+            acc: Some(Value::Int(1)),
+            i_input: 0,
+            i_output: 0,
+            i_command: 5,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        let program = ProgramBuilder::new().add_label(Label::from("a")).build();
+
+        JumpPositive(Label::from("a"))
+            .execute(&program, &mut game_state)
+            .unwrap();
+
+        game_state.acc = Some(Value::Char('A'));
+        JumpPositive(Label::from("a"))
+            .execute(&program, &mut game_state)
+            .unwrap();
+    }
+
+    #[test]
+    fn execute_no_acc() {
+        let mut game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![],
+            acc: None,
+            i_input: 0,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        let program = ProgramBuilder::new().add_label(Label::from("a")).build();
+
+        let result = JumpPositive(Label::from("a"))
+            .execute(&program, &mut game_state)
+            .unwrap_err();
+        assert_eq!(RunError::EmptyAcc, result);
+    }
+
+    #[test]
+    fn next_test() {
+        let mut game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![Some(Value::Int(1)), Some(Value::Int(42))],
+            acc: Some(Value::Int(1)),
+            i_input: 0,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        let program = ProgramBuilder::new()
+            .add_label(Label::from("a"))
+            .add_command(Box::new(JumpPositive(Label::from("a"))))
+            .build();
+
+        let i_next = JumpPositive(Label::from("a"))
+            .next(&program, &game_state)
+            .unwrap();
+        assert_eq!(0, i_next);
+
+        game_state.acc = Some(Value::Int(0));
+        let i_next = JumpPositive(Label::from("a"))
+            .next(&program, &game_state)
+            .unwrap();
+        assert_eq!(1, i_next);
+
+        game_state.acc = Some(Value::Int(-1));
+        let i_next = JumpPositive(Label::from("a"))
+            .next(&program, &game_state)
+            .unwrap();
+        assert_eq!(1, i_next);
+
+        game_state.acc = Some(Value::Char('A'));
+        let i_next = JumpPositive(Label::from("a"))
+            .next(&program, &game_state)
+            .unwrap();
+        assert_eq!(1, i_next);
+    }
+
+    #[test]
+    fn requires_index_test() {
+        assert!(JumpPositive(Label::default()).requires_index().is_none());
+    }
+
+    #[test]
+    fn requires_label_test() {
+        let command = JumpPositive(Label::from("a"));
+        assert_eq!("a", command.requires_label().unwrap().as_str());
+    }
+
+    #[test]
+    fn factory_test() {
+        assert_eq!("JUMPP", JumpPositive(Label::from("a")).factory().command());
+    }
+    // endregion
+}
@@ -1,19 +1,24 @@
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+};
+
 use crate::{
     code::{
-        commands::{AnyCommand, Command, CommandFactory},
+        commands::{compile_label, AnyCommand, Command, CommandFactory, Flow},
         game_state::GameState,
         program::{get_acc, Program, RunError},
+        symbol::Label,
     },
-    compiler::compile::compile_label,
     create_with_args,
 };
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct JumpZero(pub String);
+pub struct JumpZero(pub Label);
 
 impl JumpZero {
     fn create(args: &str) -> Option<Self> {
-        compile_label(args).map(JumpZero)
+        compile_label(args).map(|label| JumpZero(Label::from(label)))
     }
 }
 
@@ -25,26 +30,26 @@ impl Command for JumpZero {
     /// Jump To If Zero
     ///
     /// Jumps to label if [GameState]`.acc` equals `0`, else increments [GameState]`.i_command`.
-    ///
-    /// # Panics
-    ///
-    /// Can be caused by:
-    /// - if [GameState]`.acc` is [None] - this is prevented by calling [JumpZero::execute] first
-    /// - see [Program::get_label].
-    fn next(&self, program: &Program, game_state: &GameState) -> Option<usize> {
-        let next_idx = if get_acc(game_state.acc).unwrap() == 0 {
-            program.get_label(&self.0)
+    fn next(&self, program: &Program, game_state: &GameState) -> Result<usize, RunError> {
+        if get_acc(game_state.acc)? == 0 {
+            program.get_target(game_state.i_command)
         } else {
-            game_state.i_command + 1
-        };
-
-        Some(next_idx)
+            Ok(game_state.i_command + 1)
+        }
     }
 
-    fn requires_label(&self) -> Option<&str> {
+    fn requires_label(&self) -> Option<&Label> {
         Some(&self.0)
     }
 
+    fn args(&self) -> Option<String> {
+        Some(self.0.to_string())
+    }
+
+    fn flow(&self) -> Flow {
+        Flow::Branch
+    }
+
     fn factory(&self) -> Box<dyn CommandFactory> {
         Box::new(JumpZeroFactory)
     }
@@ -64,7 +69,10 @@ impl CommandFactory for JumpZeroFactory {
 
 #[cfg(test)]
 mod tests {
+    use alloc::vec;
+
     use crate::code::program::ProgramBuilder;
+    use crate::game::problem::OutputMatching;
     use crate::game::value::Value;
 
     use super::*;
@@ -73,7 +81,7 @@ mod tests {
     #[test]
     fn create_succeeds() {
         let command = JumpZero::create("a").unwrap();
-        assert_eq!(JumpZero(String::from("a")), command);
+        assert_eq!(JumpZero(Label::from("a")), command);
     }
 
     #[test]
@@ -144,16 +152,27 @@ mod tests {
             i_output: 0,
             i_command: 5,
             speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
         };
 
-        let program = ProgramBuilder::new().add_label(String::from("a")).build();
+        let program = ProgramBuilder::new().add_label(Label::from("a")).build();
 
-        JumpZero(String::from("a"))
+        JumpZero(Label::from("a"))
             .execute(&program, &mut game_state)
             .unwrap();
 
         game_state.acc = Some(Value::Char('A'));
-        JumpZero(String::from("a"))
+        JumpZero(Label::from("a"))
             .execute(&program, &mut game_state)
             .unwrap();
     }
@@ -169,11 +188,22 @@ mod tests {
             i_output: 0,
             i_command: 0,
             speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
         };
 
-        let program = ProgramBuilder::new().add_label(String::from("a")).build();
+        let program = ProgramBuilder::new().add_label(Label::from("a")).build();
 
-        let result = JumpZero(String::from("a"))
+        let result = JumpZero(Label::from("a"))
             .execute(&program, &mut game_state)
             .unwrap_err();
         assert_eq!(RunError::EmptyAcc, result);
@@ -188,50 +218,64 @@ mod tests {
             acc: Some(Value::Int(0)),
             i_input: 0,
             i_output: 0,
-            i_command: 5,
+            i_command: 0,
             speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
         };
 
-        let program = ProgramBuilder::new().add_label(String::from("a")).build();
+        let program = ProgramBuilder::new()
+            .add_label(Label::from("a"))
+            .add_command(Box::new(JumpZero(Label::from("a"))))
+            .build();
 
-        let i_next = JumpZero(String::from("a"))
+        let i_next = JumpZero(Label::from("a"))
             .next(&program, &game_state)
             .unwrap();
         assert_eq!(0, i_next);
 
         game_state.acc = Some(Value::Int(1));
-        let i_next = JumpZero(String::from("a"))
+        let i_next = JumpZero(Label::from("a"))
             .next(&program, &game_state)
             .unwrap();
-        assert_eq!(6, i_next);
+        assert_eq!(1, i_next);
 
         game_state.acc = Some(Value::Int(-1));
-        let i_next = JumpZero(String::from("a"))
+        let i_next = JumpZero(Label::from("a"))
             .next(&program, &game_state)
             .unwrap();
-        assert_eq!(6, i_next);
+        assert_eq!(1, i_next);
 
         game_state.acc = Some(Value::Char('A'));
-        let i_next = JumpZero(String::from("a"))
+        let i_next = JumpZero(Label::from("a"))
             .next(&program, &game_state)
             .unwrap();
-        assert_eq!(6, i_next);
+        assert_eq!(1, i_next);
     }
 
     #[test]
     fn requires_index_test() {
-        assert!(JumpZero(String::new()).requires_index().is_none());
+        assert!(JumpZero(Label::default()).requires_index().is_none());
     }
 
     #[test]
     fn requires_label_test() {
-        let command = JumpZero(String::from("a"));
-        assert_eq!("a", command.requires_label().unwrap());
+        let command = JumpZero(Label::from("a"));
+        assert_eq!("a", command.requires_label().unwrap().as_str());
     }
 
     #[test]
     fn factory_test() {
-        assert_eq!("JUMPZ", JumpZero(String::from("a")).factory().command());
+        assert_eq!("JUMPZ", JumpZero(Label::from("a")).factory().command());
     }
     // endregion
 }
@@ -0,0 +1,424 @@
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+};
+
+use crate::{
+    code::{
+        commands::{compile_command_value, AnyCommand, Command, CommandFactory, CommandValue},
+        game_state::GameState,
+        program::{
+            arithmetic, check_bounds, get_acc, get_from_memory, get_index, Program, RunError,
+        },
+    },
+    create_with_args,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mod(pub CommandValue);
+
+impl Mod {
+    fn create(args: &str) -> Option<Self> {
+        compile_command_value(args).map(Mod)
+    }
+}
+
+impl Command for Mod {
+    fn execute(&self, _program: &Program, game_state: &mut GameState) -> Result<(), RunError> {
+        let value = get_acc(game_state.acc)?;
+        let index = get_index(&self.0, game_state)?;
+        let to_mod = get_from_memory(game_state.read(index))?;
+        if to_mod == 0 {
+            return Err(RunError::DivideByZero);
+        }
+        let remainder = arithmetic(value.hrm_mod(to_mod), RunError::Mod)?;
+        let remainder = check_bounds(remainder, game_state.bounds)?;
+        game_state.acc = Some(remainder);
+        Ok(())
+    }
+
+    fn requires_index(&self) -> Option<usize> {
+        match self.0 {
+            CommandValue::Value(_) => None,
+            CommandValue::Index(idx) => Some(idx),
+        }
+    }
+
+    fn tile(&self) -> Option<usize> {
+        Some(self.0.tile())
+    }
+
+    fn args(&self) -> Option<String> {
+        Some(self.0.to_string())
+    }
+
+    fn factory(&self) -> Box<dyn CommandFactory> {
+        Box::new(ModFactory)
+    }
+}
+
+pub struct ModFactory;
+
+impl CommandFactory for ModFactory {
+    fn command(&self) -> &'static str {
+        "MOD"
+    }
+
+    fn create(&self, args: &str) -> Option<AnyCommand> {
+        create_with_args!(Mod, args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use crate::game::problem::OutputMatching;
+    use crate::game::value::{Value, ValueBounds};
+
+    use super::*;
+
+    // region:modulo
+    #[test]
+    fn create_succeeds() {
+        let command = Mod::create("42").unwrap();
+        assert_eq!(Mod(CommandValue::Value(42)), command);
+
+        let command = Mod::create("[42]").unwrap();
+        assert_eq!(Mod(CommandValue::Index(42)), command);
+    }
+
+    #[test]
+    fn create_fails() {
+        let command = Mod::create("");
+        assert!(command.is_none());
+
+        let command = Mod::create("a");
+        assert!(command.is_none());
+
+        let command = Mod::create("a1");
+        assert!(command.is_none());
+
+        let command = Mod::create(" ");
+        assert!(command.is_none());
+
+        let command = Mod::create(" 1 ");
+        assert!(command.is_none());
+    }
+    // endregion
+
+    // region:factory
+    #[test]
+    fn command_test() {
+        assert_eq!("MOD", ModFactory.command());
+    }
+
+    #[test]
+    fn factory_create_succeeds() {
+        let command = ModFactory.create("42");
+        assert!(command.is_some());
+
+        let command = ModFactory.create("[42]");
+        assert!(command.is_some());
+    }
+
+    #[test]
+    fn factory_create_fails() {
+        let command = ModFactory.create("");
+        assert!(command.is_none());
+
+        let command = ModFactory.create("a");
+        assert!(command.is_none());
+
+        let command = ModFactory.create("a1");
+        assert!(command.is_none());
+
+        let command = ModFactory.create(" ");
+        assert!(command.is_none());
+
+        let command = ModFactory.create(" 1 ");
+        assert!(command.is_none());
+    }
+    // endregion
+
+    // region:command
+    #[test]
+    fn execute_succeeds() {
+        let mut game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![Some(Value::Int(1)), Some(Value::Int(42))],
+            acc: Some(Value::Int(85)),
+            i_input: 0,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        Mod(CommandValue::Value(1))
+            .execute(&Default::default(), &mut game_state)
+            .unwrap();
+        assert_eq!(Value::Int(1), game_state.acc.unwrap());
+
+        game_state.acc = Some(Value::Int(-100));
+        Mod(CommandValue::Index(0))
+            .execute(&Default::default(), &mut game_state)
+            .unwrap();
+        assert_eq!(Value::Int(-16), game_state.acc.unwrap());
+    }
+
+    #[test]
+    fn execute_chars() {
+        let mut game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![Some(Value::Char('A')), Some(Value::Int(42))],
+            acc: Some(Value::Int(2)),
+            i_input: 0,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        let result = Mod(CommandValue::Value(0))
+            .execute(&Default::default(), &mut game_state)
+            .unwrap_err();
+        assert_eq!(RunError::Mod, result);
+    }
+
+    #[test]
+    fn execute_divide_by_zero() {
+        let mut game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![Some(Value::Int(0))],
+            acc: Some(Value::Int(42)),
+            i_input: 0,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        let result = Mod(CommandValue::Value(0))
+            .execute(&Default::default(), &mut game_state)
+            .unwrap_err();
+        assert_eq!(RunError::DivideByZero, result);
+    }
+
+    #[test]
+    fn execute_min_by_minus_one() {
+        let mut game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![Some(Value::Int(-1))],
+            acc: Some(Value::Int(i32::MIN)),
+            i_input: 0,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        Mod(CommandValue::Value(0))
+            .execute(&Default::default(), &mut game_state)
+            .unwrap();
+        assert_eq!(Value::Int(0), game_state.acc.unwrap());
+    }
+
+    #[test]
+    fn execute_out_of_bounds() {
+        let mut game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![Some(Value::Int(3))],
+            acc: Some(Value::Int(6)),
+            i_input: 0,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: Some(ValueBounds { min: 1, max: 10 }),
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        let result = Mod(CommandValue::Value(0))
+            .execute(&Default::default(), &mut game_state)
+            .unwrap_err();
+        assert_eq!(RunError::Overflow(Value::Int(0)), result);
+    }
+
+    #[test]
+    fn execute_no_acc() {
+        let mut game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![Some(Value::Int(1)), Some(Value::Int(42))],
+            acc: None,
+            i_input: 0,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        let result = Mod(CommandValue::Value(0))
+            .execute(&Default::default(), &mut game_state)
+            .unwrap_err();
+        assert_eq!(RunError::EmptyAcc, result);
+    }
+
+    #[test]
+    fn execute_bad_index() {
+        let mut game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![Some(Value::Int(5)), Some(Value::Char('A')), None],
+            acc: Some(Value::Int(1)),
+            i_input: 1,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        let result = Mod(CommandValue::Index(0))
+            .execute(&Default::default(), &mut game_state)
+            .unwrap_err();
+        assert_eq!(RunError::IndexOutOfRange(Value::Int(5)), result);
+
+        let result = Mod(CommandValue::Index(1))
+            .execute(&Default::default(), &mut game_state)
+            .unwrap_err();
+        assert_eq!(RunError::CharIndex(Value::Char('A')), result);
+
+        let result = Mod(CommandValue::Index(2))
+            .execute(&Default::default(), &mut game_state)
+            .unwrap_err();
+        assert_eq!(RunError::EmptyMemory, result);
+    }
+
+    #[test]
+    fn next_test() {
+        let game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![],
+            acc: None,
+            i_input: 0,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        assert_eq!(
+            1,
+            Mod(CommandValue::Value(1))
+                .next(&Default::default(), &game_state)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn requires_index_test() {
+        let command = Mod(CommandValue::Value(42));
+        assert!(command.requires_index().is_none());
+
+        let command = Mod(CommandValue::Index(42));
+        assert_eq!(42, command.requires_index().unwrap());
+    }
+
+    #[test]
+    fn requires_label_test() {
+        assert!(Mod(CommandValue::Value(42)).requires_label().is_none());
+        assert!(Mod(CommandValue::Index(42)).requires_label().is_none());
+    }
+
+    #[test]
+    fn factory_test() {
+        assert_eq!("MOD", Mod(CommandValue::Value(42)).factory().command());
+        assert_eq!("MOD", Mod(CommandValue::Index(42)).factory().command());
+    }
+    // endregion
+}
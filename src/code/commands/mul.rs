@@ -0,0 +1,360 @@
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+};
+
+use crate::{
+    code::{
+        commands::{compile_command_value, AnyCommand, Command, CommandFactory, CommandValue},
+        game_state::GameState,
+        program::{
+            arithmetic, check_bounds, get_acc, get_from_memory, get_index, Program, RunError,
+        },
+    },
+    create_with_args,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mul(pub CommandValue);
+
+impl Mul {
+    fn create(args: &str) -> Option<Self> {
+        compile_command_value(args).map(Mul)
+    }
+}
+
+impl Command for Mul {
+    fn execute(&self, _program: &Program, game_state: &mut GameState) -> Result<(), RunError> {
+        let value = get_acc(game_state.acc)?;
+        let index = get_index(&self.0, game_state)?;
+        let to_mul = get_from_memory(game_state.read(index))?;
+        let product = arithmetic(value.hrm_mul(to_mul), RunError::Mul)?;
+        let product = check_bounds(product, game_state.bounds)?;
+        game_state.acc = Some(product);
+        Ok(())
+    }
+
+    fn requires_index(&self) -> Option<usize> {
+        match self.0 {
+            CommandValue::Value(_) => None,
+            CommandValue::Index(idx) => Some(idx),
+        }
+    }
+
+    fn tile(&self) -> Option<usize> {
+        Some(self.0.tile())
+    }
+
+    fn args(&self) -> Option<String> {
+        Some(self.0.to_string())
+    }
+
+    fn factory(&self) -> Box<dyn CommandFactory> {
+        Box::new(MulFactory)
+    }
+}
+
+pub struct MulFactory;
+
+impl CommandFactory for MulFactory {
+    fn command(&self) -> &'static str {
+        "MUL"
+    }
+
+    fn create(&self, args: &str) -> Option<AnyCommand> {
+        create_with_args!(Mul, args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use crate::game::problem::OutputMatching;
+    use crate::game::value::Value;
+
+    use super::*;
+
+    // region:mul
+    #[test]
+    fn create_succeeds() {
+        let command = Mul::create("42").unwrap();
+        assert_eq!(Mul(CommandValue::Value(42)), command);
+
+        let command = Mul::create("[42]").unwrap();
+        assert_eq!(Mul(CommandValue::Index(42)), command);
+    }
+
+    #[test]
+    fn create_fails() {
+        let command = Mul::create("");
+        assert!(command.is_none());
+
+        let command = Mul::create("a");
+        assert!(command.is_none());
+
+        let command = Mul::create("a1");
+        assert!(command.is_none());
+
+        let command = Mul::create(" ");
+        assert!(command.is_none());
+
+        let command = Mul::create(" 1 ");
+        assert!(command.is_none());
+    }
+    // endregion
+
+    // region:factory
+    #[test]
+    fn command_test() {
+        assert_eq!("MUL", MulFactory.command());
+    }
+
+    #[test]
+    fn factory_create_succeeds() {
+        let command = MulFactory.create("42");
+        assert!(command.is_some());
+
+        let command = MulFactory.create("[42]");
+        assert!(command.is_some());
+    }
+
+    #[test]
+    fn factory_create_fails() {
+        let command = MulFactory.create("");
+        assert!(command.is_none());
+
+        let command = MulFactory.create("a");
+        assert!(command.is_none());
+
+        let command = MulFactory.create("a1");
+        assert!(command.is_none());
+
+        let command = MulFactory.create(" ");
+        assert!(command.is_none());
+
+        let command = MulFactory.create(" 1 ");
+        assert!(command.is_none());
+    }
+    // endregion
+
+    // region:command
+    #[test]
+    fn execute_succeeds() {
+        let mut game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![Some(Value::Int(1)), Some(Value::Int(42))],
+            acc: Some(Value::Int(-2)),
+            i_input: 0,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        Mul(CommandValue::Value(0))
+            .execute(&Default::default(), &mut game_state)
+            .unwrap();
+        assert_eq!(Value::Int(-2), game_state.acc.unwrap());
+
+        Mul(CommandValue::Index(0))
+            .execute(&Default::default(), &mut game_state)
+            .unwrap();
+        assert_eq!(Value::Int(-84), game_state.acc.unwrap());
+    }
+
+    #[test]
+    fn execute_chars() {
+        let mut game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![Some(Value::Char('A')), Some(Value::Int(42))],
+            acc: Some(Value::Int(2)),
+            i_input: 0,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        let result = Mul(CommandValue::Value(0))
+            .execute(&Default::default(), &mut game_state)
+            .unwrap_err();
+        assert_eq!(RunError::Mul, result);
+    }
+
+    #[test]
+    fn execute_overflow() {
+        let mut game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![Some(Value::Int(i32::MAX))],
+            acc: Some(Value::Int(2)),
+            i_input: 0,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        let result = Mul(CommandValue::Value(0))
+            .execute(&Default::default(), &mut game_state)
+            .unwrap_err();
+        assert_eq!(RunError::Overflow(Value::Int(i32::MAX)), result);
+    }
+
+    #[test]
+    fn execute_no_acc() {
+        let mut game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![Some(Value::Int(1)), Some(Value::Int(42))],
+            acc: None,
+            i_input: 0,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        let result = Mul(CommandValue::Value(0))
+            .execute(&Default::default(), &mut game_state)
+            .unwrap_err();
+        assert_eq!(RunError::EmptyAcc, result);
+    }
+
+    #[test]
+    fn execute_bad_index() {
+        let mut game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![Some(Value::Int(5)), Some(Value::Char('A')), None],
+            acc: Some(Value::Int(1)),
+            i_input: 1,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        let result = Mul(CommandValue::Index(0))
+            .execute(&Default::default(), &mut game_state)
+            .unwrap_err();
+        assert_eq!(RunError::IndexOutOfRange(Value::Int(5)), result);
+
+        let result = Mul(CommandValue::Index(1))
+            .execute(&Default::default(), &mut game_state)
+            .unwrap_err();
+        assert_eq!(RunError::CharIndex(Value::Char('A')), result);
+
+        let result = Mul(CommandValue::Index(2))
+            .execute(&Default::default(), &mut game_state)
+            .unwrap_err();
+        assert_eq!(RunError::EmptyMemory, result);
+    }
+
+    #[test]
+    fn next_test() {
+        let game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![],
+            acc: None,
+            i_input: 0,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        assert_eq!(
+            1,
+            Mul(CommandValue::Value(1))
+                .next(&Default::default(), &game_state)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn requires_index_test() {
+        let command = Mul(CommandValue::Value(42));
+        assert!(command.requires_index().is_none());
+
+        let command = Mul(CommandValue::Index(42));
+        assert_eq!(42, command.requires_index().unwrap());
+    }
+
+    #[test]
+    fn requires_label_test() {
+        assert!(Mul(CommandValue::Value(42)).requires_label().is_none());
+        assert!(Mul(CommandValue::Index(42)).requires_label().is_none());
+    }
+
+    #[test]
+    fn factory_test() {
+        assert_eq!("MUL", Mul(CommandValue::Value(42)).factory().command());
+        assert_eq!("MUL", Mul(CommandValue::Index(42)).factory().command());
+    }
+    // endregion
+}
@@ -0,0 +1,266 @@
+use alloc::boxed::Box;
+
+use crate::{
+    code::{
+        commands::{AnyCommand, Command, CommandFactory},
+        game_state::GameState,
+        program::{arithmetic, check_bounds, get_acc, Program, RunError},
+    },
+    create_with_args,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Neg;
+
+impl Neg {
+    fn create(args: &str) -> Option<Self> {
+        if args.is_empty() {
+            Some(Self)
+        } else {
+            None
+        }
+    }
+}
+
+impl Command for Neg {
+    fn execute(&self, _program: &Program, game_state: &mut GameState) -> Result<(), RunError> {
+        let value = get_acc(game_state.acc)?;
+        let negated = arithmetic(value.hrm_neg(), RunError::Neg)?;
+        let negated = check_bounds(negated, game_state.bounds)?;
+        game_state.acc = Some(negated);
+        Ok(())
+    }
+
+    fn factory(&self) -> Box<dyn CommandFactory> {
+        Box::new(NegFactory)
+    }
+}
+
+pub struct NegFactory;
+
+impl CommandFactory for NegFactory {
+    fn command(&self) -> &'static str {
+        "NEG"
+    }
+
+    fn create(&self, args: &str) -> Option<AnyCommand> {
+        create_with_args!(Neg, args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use crate::game::problem::OutputMatching;
+    use crate::game::value::Value;
+
+    use super::*;
+
+    // region:neg
+    #[test]
+    fn create_succeeds() {
+        let command = Neg::create("").unwrap();
+        assert_eq!(Neg, command);
+    }
+
+    #[test]
+    fn create_fails() {
+        let command = Neg::create("a");
+        assert!(command.is_none());
+
+        let command = Neg::create("1");
+        assert!(command.is_none());
+
+        let command = Neg::create(" ");
+        assert!(command.is_none());
+    }
+    // endregion
+
+    // region:factory
+    #[test]
+    fn command_test() {
+        assert_eq!("NEG", NegFactory.command());
+    }
+
+    #[test]
+    fn factory_create_succeeds() {
+        let command = NegFactory.create("");
+        assert!(command.is_some());
+    }
+
+    #[test]
+    fn factory_create_fails() {
+        let command = NegFactory.create("a");
+        assert!(command.is_none());
+
+        let command = NegFactory.create("1");
+        assert!(command.is_none());
+    }
+    // endregion
+
+    // region:command
+    #[test]
+    fn execute_succeeds() {
+        let mut game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![],
+            acc: Some(Value::Int(5)),
+            i_input: 0,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        Neg.execute(&Default::default(), &mut game_state).unwrap();
+        assert_eq!(Value::Int(-5), game_state.acc.unwrap());
+
+        Neg.execute(&Default::default(), &mut game_state).unwrap();
+        assert_eq!(Value::Int(5), game_state.acc.unwrap());
+    }
+
+    #[test]
+    fn execute_no_acc() {
+        let mut game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![],
+            acc: None,
+            i_input: 0,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        let result = Neg
+            .execute(&Default::default(), &mut game_state)
+            .unwrap_err();
+        assert_eq!(RunError::EmptyAcc, result);
+    }
+
+    #[test]
+    fn execute_char() {
+        let mut game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![],
+            acc: Some(Value::Char('A')),
+            i_input: 0,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        let result = Neg
+            .execute(&Default::default(), &mut game_state)
+            .unwrap_err();
+        assert_eq!(RunError::Neg, result);
+    }
+
+    #[test]
+    fn execute_overflow() {
+        let mut game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![],
+            acc: Some(Value::Int(i32::MIN)),
+            i_input: 0,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        let result = Neg
+            .execute(&Default::default(), &mut game_state)
+            .unwrap_err();
+        assert_eq!(RunError::Overflow(Value::Int(i32::MAX)), result);
+    }
+
+    #[test]
+    fn next_test() {
+        let game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![],
+            acc: None,
+            i_input: 0,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        assert_eq!(1, Neg.next(&Default::default(), &game_state).unwrap());
+    }
+
+    #[test]
+    fn requires_index_test() {
+        assert!(Neg.requires_index().is_none());
+    }
+
+    #[test]
+    fn requires_label_test() {
+        assert!(Neg.requires_label().is_none());
+    }
+
+    #[test]
+    fn factory_test() {
+        assert_eq!("NEG", Neg.factory().command());
+    }
+    // endregion
+}
@@ -0,0 +1,170 @@
+use alloc::boxed::Box;
+
+use crate::{
+    code::{
+        commands::{AnyCommand, Command, CommandFactory},
+        game_state::GameState,
+        program::{Program, RunError},
+    },
+    create_with_args,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Nop;
+
+impl Nop {
+    fn create(args: &str) -> Option<Self> {
+        if args.is_empty() {
+            Some(Self)
+        } else {
+            None
+        }
+    }
+}
+
+impl Command for Nop {
+    fn execute(&self, _program: &Program, _game_state: &mut GameState) -> Result<(), RunError> {
+        Ok(())
+    }
+
+    fn factory(&self) -> Box<dyn CommandFactory> {
+        Box::new(NopFactory)
+    }
+}
+
+pub struct NopFactory;
+
+impl CommandFactory for NopFactory {
+    fn command(&self) -> &'static str {
+        "NOP"
+    }
+
+    fn create(&self, args: &str) -> Option<AnyCommand> {
+        create_with_args!(Nop, args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use crate::game::problem::OutputMatching;
+    use crate::game::value::Value;
+
+    use super::*;
+
+    // region:nop
+    #[test]
+    fn create_succeeds() {
+        let command = Nop::create("").unwrap();
+        assert_eq!(Nop, command);
+    }
+
+    #[test]
+    fn create_fails() {
+        let command = Nop::create("a");
+        assert!(command.is_none());
+
+        let command = Nop::create("1");
+        assert!(command.is_none());
+
+        let command = Nop::create(" ");
+        assert!(command.is_none());
+    }
+    // endregion
+
+    // region:factory
+    #[test]
+    fn command_test() {
+        assert_eq!("NOP", NopFactory.command());
+    }
+
+    #[test]
+    fn factory_create_succeeds() {
+        let command = NopFactory.create("");
+        assert!(command.is_some());
+    }
+
+    #[test]
+    fn factory_create_fails() {
+        let command = NopFactory.create("a");
+        assert!(command.is_none());
+
+        let command = NopFactory.create("1");
+        assert!(command.is_none());
+    }
+    // endregion
+
+    // region:command
+    #[test]
+    fn execute_succeeds() {
+        let mut game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![Some(Value::Int(1))],
+            acc: None,
+            i_input: 0,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        Nop.execute(&Default::default(), &mut game_state).unwrap();
+        assert_eq!(None, game_state.acc);
+        assert_eq!(vec![Some(Value::Int(1))], game_state.memory);
+    }
+
+    #[test]
+    fn next_test() {
+        let game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![],
+            acc: None,
+            i_input: 0,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        assert_eq!(1, Nop.next(&Default::default(), &game_state).unwrap());
+    }
+
+    #[test]
+    fn requires_index_test() {
+        assert!(Nop.requires_index().is_none());
+    }
+
+    #[test]
+    fn requires_label_test() {
+        assert!(Nop.requires_label().is_none());
+    }
+
+    #[test]
+    fn factory_test() {
+        assert_eq!("NOP", Nop.factory().command());
+    }
+    // endregion
+}
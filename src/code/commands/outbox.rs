@@ -1,3 +1,5 @@
+use alloc::boxed::Box;
+
 use log::{debug, log_enabled, Level};
 
 use crate::{
@@ -8,6 +10,7 @@ use crate::{
         program::{get_acc, RunError},
     },
     create_with_args,
+    game::{problem::OutputMatching, value::Value},
 };
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -31,20 +34,40 @@ impl Command for Outbox {
             debug!("Produced value to outbox: {:?}", value);
         }
 
+        if !game_state.check_output {
+            game_state.produced.push(value);
+            return Ok(());
+        }
+
         if game_state.i_output == game_state.output.len() {
-            return Err(RunError::IncorrectOutput {
-                expected: None,
-                value: Some(value),
+            return Err(RunError::ExtraOutput {
+                index: game_state.i_output,
+                value,
             });
         }
 
-        if value != game_state.output[game_state.i_output] {
-            return Err(RunError::IncorrectOutput {
-                expected: Some(game_state.output[game_state.i_output]),
-                value: Some(value),
-            });
+        match game_state.output_matching {
+            OutputMatching::Exact | OutputMatching::Prefix => {
+                if value != game_state.output[game_state.i_output] {
+                    return Err(RunError::WrongOutput {
+                        index: game_state.i_output,
+                        expected: game_state.output[game_state.i_output],
+                        value,
+                    });
+                }
+            }
+            OutputMatching::Multiset => {
+                let count = |values: &[Value]| values.iter().filter(|v| **v == value).count();
+                if count(&game_state.produced) >= count(game_state.output) {
+                    return Err(RunError::ExtraOutput {
+                        index: game_state.i_output,
+                        value,
+                    });
+                }
+            }
         }
 
+        game_state.produced.push(value);
         game_state.i_output += 1;
         Ok(())
     }
@@ -68,6 +91,9 @@ impl CommandFactory for OutboxFactory {
 
 #[cfg(test)]
 mod tests {
+    use alloc::vec;
+
+    use crate::game::problem::OutputMatching;
     use crate::game::value::Value;
 
     use super::*;
@@ -129,6 +155,17 @@ mod tests {
             i_output: 0,
             i_command: 0,
             speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
         };
 
         Outbox
@@ -148,14 +185,25 @@ mod tests {
             i_output: 0,
             i_command: 0,
             speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
         };
 
         let result = Outbox
             .execute(&Default::default(), &mut game_state)
             .unwrap_err();
-        let expected = RunError::IncorrectOutput {
-            expected: None,
-            value: Some(Value::Int(5)),
+        let expected = RunError::ExtraOutput {
+            index: 0,
+            value: Value::Int(5),
         };
         assert_eq!(expected, result);
     }
@@ -171,18 +219,81 @@ mod tests {
             i_output: 0,
             i_command: 0,
             speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
         };
 
         let result = Outbox
             .execute(&Default::default(), &mut game_state)
             .unwrap_err();
-        let expected = RunError::IncorrectOutput {
-            expected: Some(Value::Char('A')),
-            value: Some(Value::Int(5)),
+        let expected = RunError::WrongOutput {
+            index: 0,
+            expected: Value::Char('A'),
+            value: Value::Int(5),
         };
         assert_eq!(expected, result);
     }
 
+    #[test]
+    fn execute_multiset() {
+        let mut game_state = GameState {
+            input: &vec![],
+            output: &vec![Value::Int(1), Value::Int(2), Value::Int(1)],
+            memory: vec![],
+            acc: Some(Value::Int(1)),
+            i_input: 0,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Multiset,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        for acc in [1, 1, 2] {
+            game_state.acc = Some(Value::Int(acc));
+            Outbox
+                .execute(&Default::default(), &mut game_state)
+                .unwrap();
+        }
+        assert_eq!(3, game_state.i_output);
+        assert_eq!(
+            vec![Value::Int(1), Value::Int(1), Value::Int(2)],
+            game_state.produced
+        );
+
+        game_state.i_output = 1;
+        game_state.produced = vec![Value::Int(1)];
+        game_state.acc = Some(Value::Int(3));
+        let result = Outbox
+            .execute(&Default::default(), &mut game_state)
+            .unwrap_err();
+        assert_eq!(
+            RunError::ExtraOutput {
+                index: 1,
+                value: Value::Int(3)
+            },
+            result
+        );
+    }
+
     #[test]
     fn next_test() {
         let game_state = GameState {
@@ -194,6 +305,17 @@ mod tests {
             i_output: 0,
             i_command: 0,
             speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
         };
 
         assert_eq!(1, Outbox.next(&Default::default(), &game_state).unwrap());
@@ -0,0 +1,202 @@
+use alloc::boxed::Box;
+
+use crate::{
+    code::{
+        commands::{AnyCommand, Command, CommandFactory},
+        game_state::GameState,
+        program::{Program, RunError},
+    },
+    create_with_args,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pop;
+
+impl Pop {
+    fn create(args: &str) -> Option<Self> {
+        if args.is_empty() {
+            Some(Self)
+        } else {
+            None
+        }
+    }
+}
+
+impl Command for Pop {
+    fn execute(&self, _program: &Program, game_state: &mut GameState) -> Result<(), RunError> {
+        let value = game_state.stack.pop().ok_or(RunError::EmptyStack)?;
+        game_state.acc = Some(value);
+        Ok(())
+    }
+
+    fn factory(&self) -> Box<dyn CommandFactory> {
+        Box::new(PopFactory)
+    }
+}
+
+pub struct PopFactory;
+
+impl CommandFactory for PopFactory {
+    fn command(&self) -> &'static str {
+        "POP"
+    }
+
+    fn create(&self, args: &str) -> Option<AnyCommand> {
+        create_with_args!(Pop, args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use crate::game::problem::OutputMatching;
+    use crate::game::value::Value;
+
+    use super::*;
+
+    // region:pop
+    #[test]
+    fn create_succeeds() {
+        let command = Pop::create("").unwrap();
+        assert_eq!(Pop, command);
+    }
+
+    #[test]
+    fn create_fails() {
+        let command = Pop::create("a");
+        assert!(command.is_none());
+
+        let command = Pop::create("1");
+        assert!(command.is_none());
+
+        let command = Pop::create(" ");
+        assert!(command.is_none());
+    }
+    // endregion
+
+    // region:factory
+    #[test]
+    fn command_test() {
+        assert_eq!("POP", PopFactory.command());
+    }
+
+    #[test]
+    fn factory_create_succeeds() {
+        let command = PopFactory.create("");
+        assert!(command.is_some());
+    }
+
+    #[test]
+    fn factory_create_fails() {
+        let command = PopFactory.create("a");
+        assert!(command.is_none());
+
+        let command = PopFactory.create("1");
+        assert!(command.is_none());
+    }
+    // endregion
+
+    // region:command
+    #[test]
+    fn execute_succeeds() {
+        let mut game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![],
+            acc: None,
+            i_input: 0,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![Value::Char('A'), Value::Int(5)],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        Pop.execute(&Default::default(), &mut game_state).unwrap();
+        assert_eq!(vec![Value::Char('A')], game_state.stack);
+        assert_eq!(Some(Value::Int(5)), game_state.acc);
+    }
+
+    #[test]
+    fn execute_empty_stack() {
+        let mut game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![],
+            acc: Some(Value::Int(1)),
+            i_input: 0,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        let result = Pop
+            .execute(&Default::default(), &mut game_state)
+            .unwrap_err();
+        assert_eq!(RunError::EmptyStack, result);
+    }
+
+    #[test]
+    fn next_test() {
+        let game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![],
+            acc: None,
+            i_input: 0,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        assert_eq!(1, Pop.next(&Default::default(), &game_state).unwrap());
+    }
+
+    #[test]
+    fn requires_index_test() {
+        assert!(Pop.requires_index().is_none());
+    }
+
+    #[test]
+    fn requires_label_test() {
+        assert!(Pop.requires_label().is_none());
+    }
+
+    #[test]
+    fn factory_test() {
+        assert_eq!("POP", Pop.factory().command());
+    }
+    // endregion
+}
@@ -0,0 +1,204 @@
+use alloc::boxed::Box;
+
+use crate::{
+    code::{
+        commands::{AnyCommand, Command, CommandFactory},
+        game_state::GameState,
+        program::{get_acc, Program, RunError},
+    },
+    create_with_args,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Push;
+
+impl Push {
+    fn create(args: &str) -> Option<Self> {
+        if args.is_empty() {
+            Some(Self)
+        } else {
+            None
+        }
+    }
+}
+
+impl Command for Push {
+    fn execute(&self, _program: &Program, game_state: &mut GameState) -> Result<(), RunError> {
+        let value = get_acc(game_state.acc)?;
+        game_state.stack.push(value);
+        game_state.stack_max = game_state.stack_max.max(game_state.stack.len());
+        Ok(())
+    }
+
+    fn factory(&self) -> Box<dyn CommandFactory> {
+        Box::new(PushFactory)
+    }
+}
+
+pub struct PushFactory;
+
+impl CommandFactory for PushFactory {
+    fn command(&self) -> &'static str {
+        "PUSH"
+    }
+
+    fn create(&self, args: &str) -> Option<AnyCommand> {
+        create_with_args!(Push, args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use crate::game::problem::OutputMatching;
+    use crate::game::value::Value;
+
+    use super::*;
+
+    // region:push
+    #[test]
+    fn create_succeeds() {
+        let command = Push::create("").unwrap();
+        assert_eq!(Push, command);
+    }
+
+    #[test]
+    fn create_fails() {
+        let command = Push::create("a");
+        assert!(command.is_none());
+
+        let command = Push::create("1");
+        assert!(command.is_none());
+
+        let command = Push::create(" ");
+        assert!(command.is_none());
+    }
+    // endregion
+
+    // region:factory
+    #[test]
+    fn command_test() {
+        assert_eq!("PUSH", PushFactory.command());
+    }
+
+    #[test]
+    fn factory_create_succeeds() {
+        let command = PushFactory.create("");
+        assert!(command.is_some());
+    }
+
+    #[test]
+    fn factory_create_fails() {
+        let command = PushFactory.create("a");
+        assert!(command.is_none());
+
+        let command = PushFactory.create("1");
+        assert!(command.is_none());
+    }
+    // endregion
+
+    // region:command
+    #[test]
+    fn execute_succeeds() {
+        let mut game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![],
+            acc: Some(Value::Int(5)),
+            i_input: 0,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![Value::Char('A')],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        Push.execute(&Default::default(), &mut game_state).unwrap();
+        assert_eq!(vec![Value::Char('A'), Value::Int(5)], game_state.stack);
+        assert_eq!(Some(Value::Int(5)), game_state.acc);
+        assert_eq!(2, game_state.stack_max);
+    }
+
+    #[test]
+    fn execute_no_acc() {
+        let mut game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![],
+            acc: None,
+            i_input: 0,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        let result = Push
+            .execute(&Default::default(), &mut game_state)
+            .unwrap_err();
+        assert_eq!(RunError::EmptyAcc, result);
+    }
+
+    #[test]
+    fn next_test() {
+        let game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![],
+            acc: None,
+            i_input: 0,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        assert_eq!(1, Push.next(&Default::default(), &game_state).unwrap());
+    }
+
+    #[test]
+    fn requires_index_test() {
+        assert!(Push.requires_index().is_none());
+    }
+
+    #[test]
+    fn requires_label_test() {
+        assert!(Push.requires_label().is_none());
+    }
+
+    #[test]
+    fn factory_test() {
+        assert_eq!("PUSH", Push.factory().command());
+    }
+    // endregion
+}
@@ -0,0 +1,176 @@
+use alloc::boxed::Box;
+
+use crate::{
+    code::{
+        commands::{AnyCommand, Command, CommandFactory, Flow},
+        game_state::GameState,
+        program::{Program, RunError},
+    },
+    create_with_args,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ret;
+
+impl Ret {
+    fn create(args: &str) -> Option<Self> {
+        if args.is_empty() {
+            Some(Self)
+        } else {
+            None
+        }
+    }
+}
+
+impl Command for Ret {
+    /// Return
+    ///
+    /// Pop the return address from the call stack into `i_command`.
+    fn execute(&self, _program: &Program, game_state: &mut GameState) -> Result<(), RunError> {
+        game_state.i_command = game_state
+            .call_stack
+            .pop()
+            .ok_or(RunError::EmptyCallStack)?;
+        Ok(())
+    }
+
+    /// Next
+    ///
+    /// Return address was already set by [Ret::execute].
+    fn next(&self, _program: &Program, game_state: &GameState) -> Result<usize, RunError> {
+        Ok(game_state.i_command)
+    }
+
+    fn flow(&self) -> Flow {
+        Flow::Return
+    }
+
+    fn factory(&self) -> Box<dyn CommandFactory> {
+        Box::new(RetFactory)
+    }
+}
+
+pub struct RetFactory;
+
+impl CommandFactory for RetFactory {
+    fn command(&self) -> &'static str {
+        "RET"
+    }
+
+    fn create(&self, args: &str) -> Option<AnyCommand> {
+        create_with_args!(Ret, args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+    use crate::game::problem::OutputMatching;
+
+    // region:ret
+    #[test]
+    fn create_succeeds() {
+        let command = Ret::create("").unwrap();
+        assert_eq!(Ret, command);
+    }
+
+    #[test]
+    fn create_fails() {
+        for args in ["a", "1", " "] {
+            assert!(Ret::create(args).is_none());
+        }
+    }
+    // endregion
+
+    // region:factory
+    #[test]
+    fn command_test() {
+        assert_eq!("RET", RetFactory.command());
+    }
+
+    #[test]
+    fn factory_create_succeeds() {
+        let command = RetFactory.create("");
+        assert!(command.is_some());
+    }
+
+    #[test]
+    fn factory_create_fails() {
+        let command = RetFactory.create("a");
+        assert!(command.is_none());
+    }
+    // endregion
+
+    // region:command
+    #[test]
+    fn execute_succeeds() {
+        let mut game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![],
+            acc: None,
+            i_input: 0,
+            i_output: 0,
+            i_command: 5,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![1, 3],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        Ret.execute(&Default::default(), &mut game_state).unwrap();
+        assert_eq!(vec![1], game_state.call_stack);
+        assert_eq!(3, Ret.next(&Default::default(), &game_state).unwrap());
+    }
+
+    #[test]
+    fn execute_empty_call_stack() {
+        let mut game_state = GameState {
+            input: &vec![],
+            output: &vec![],
+            memory: vec![],
+            acc: None,
+            i_input: 0,
+            i_output: 0,
+            i_command: 5,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
+        };
+
+        let result = Ret
+            .execute(&Default::default(), &mut game_state)
+            .unwrap_err();
+        assert_eq!(RunError::EmptyCallStack, result);
+    }
+
+    #[test]
+    fn requires_label_test() {
+        assert!(Ret.requires_label().is_none());
+    }
+
+    #[test]
+    fn factory_test() {
+        assert_eq!("RET", Ret.factory().command());
+    }
+    // endregion
+}
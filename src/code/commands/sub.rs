@@ -1,10 +1,16 @@
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+};
+
 use crate::{
     code::{
-        commands::{AnyCommand, Command, CommandFactory, CommandValue},
+        commands::{compile_command_value, AnyCommand, Command, CommandFactory, CommandValue},
         game_state::GameState,
-        program::{get_acc, get_from_memory, get_index, Program, RunError},
+        program::{
+            arithmetic, check_bounds, get_acc, get_from_memory, get_index, Program, RunError,
+        },
     },
-    compiler::compile::compile_command_value,
     create_with_args,
 };
 
@@ -20,9 +26,10 @@ impl Sub {
 impl Command for Sub {
     fn execute(&self, _program: &Program, game_state: &mut GameState) -> Result<(), RunError> {
         let value = get_acc(game_state.acc)?;
-        let index = get_index(&self.0, &game_state.memory)?;
-        let to_sub = get_from_memory(game_state.memory[index])?;
-        let diff = value.hrm_sub(to_sub).ok_or(RunError::Sub)?;
+        let index = get_index(&self.0, game_state)?;
+        let to_sub = get_from_memory(game_state.read(index))?;
+        let diff = arithmetic(value.hrm_sub(to_sub), RunError::Sub)?;
+        let diff = check_bounds(diff, game_state.bounds)?;
         game_state.acc = Some(diff);
         Ok(())
     }
@@ -34,6 +41,14 @@ impl Command for Sub {
         }
     }
 
+    fn tile(&self) -> Option<usize> {
+        Some(self.0.tile())
+    }
+
+    fn args(&self) -> Option<String> {
+        Some(self.0.to_string())
+    }
+
     fn factory(&self) -> Box<dyn CommandFactory> {
         Box::new(SubFactory)
     }
@@ -53,6 +68,9 @@ impl CommandFactory for SubFactory {
 
 #[cfg(test)]
 mod tests {
+    use alloc::vec;
+
+    use crate::game::problem::OutputMatching;
     use crate::game::value::Value;
 
     use super::*;
@@ -132,6 +150,17 @@ mod tests {
             i_output: 0,
             i_command: 0,
             speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
         };
 
         Sub(CommandValue::Value(0))
@@ -156,6 +185,17 @@ mod tests {
             i_output: 0,
             i_command: 0,
             speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
         };
 
         let result = Sub(CommandValue::Value(0))
@@ -175,6 +215,17 @@ mod tests {
             i_output: 0,
             i_command: 0,
             speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
         };
 
         let result = Sub(CommandValue::Index(0))
@@ -204,6 +255,17 @@ mod tests {
             i_output: 0,
             i_command: 0,
             speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats: Default::default(),
+            check_output: true,
         };
 
         assert_eq!(
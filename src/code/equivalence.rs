@@ -0,0 +1,142 @@
+use alloc::vec::Vec;
+
+use crate::code::program::{Execution, Program, RunError};
+use crate::game::generator::IOGenerator;
+use crate::game::problem::Problem;
+use crate::game::value::Value;
+
+/// Default step limit of a single run, high enough for any game level
+pub const DEFAULT_MAX_STEPS: u32 = 100_000;
+
+/// Divergence
+///
+/// First input on which two programs behave differently, with the result of each program.
+#[derive(Debug, PartialEq)]
+pub struct Divergence {
+    pub input: Vec<Value>,
+    pub left: Result<Vec<Value>, RunError>,
+    pub right: Result<Vec<Value>, RunError>,
+}
+
+/// Find Divergence
+///
+/// Differential fuzzing - run both programs on the inputs of the problem's IOs followed by the
+/// inputs of `generator`. Outputs are compared, not checked against the problem. Runs failing
+/// with the same [RunError] (e.g. both exceeding `max_steps`) are considered equal. Returns:
+/// - [None] if the programs behaved the same on every input
+/// - [Some(Divergence)] for the first input they behaved differently on
+pub fn find_divergence(
+    problem: &Problem,
+    left: &Program,
+    right: &Program,
+    generator: &IOGenerator,
+    max_steps: u32,
+) -> Option<Divergence> {
+    let inputs = problem
+        .get_ios()
+        .iter()
+        .map(|problem_io| problem_io.input.clone())
+        .chain(generator.generate());
+
+    for input in inputs {
+        let run = |program: &Program| {
+            program
+                .execute(problem, &input, max_steps)
+                .map(|Execution { output, .. }| output)
+        };
+
+        let (left, right) = (run(left), run(right));
+        if left != right {
+            return Some(Divergence { input, left, right });
+        }
+    }
+
+    None
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::compiler::compile::Compiler;
+    use crate::game::generator::ValueRange;
+    use crate::game::problem::{ProblemBuilder, ProblemIO};
+
+    use super::*;
+
+    fn create_problem() -> Problem {
+        ProblemBuilder::new()
+            .add_io(ProblemIO {
+                input: vec![Value::Int(1), Value::Int(2)],
+                output: vec![Value::Int(2), Value::Int(4)],
+            })
+            .memory_dim(1)
+            .enable_all_commands()
+            .build()
+            .unwrap()
+    }
+
+    fn create_generator() -> IOGenerator {
+        IOGenerator {
+            count: 50,
+            min_length: 0,
+            max_length: 6,
+            values: ValueRange::Int { min: -9, max: 9 },
+            seed: 3,
+        }
+    }
+
+    #[test]
+    fn equivalent() {
+        let compiler = Compiler::default();
+        let left = compiler
+            .compile("a:\nINBOX\nCOPYTO 0\nADD 0\nOUTBOX\nJUMP a")
+            .unwrap();
+        let right = compiler
+            .compile("JUMP b\na:\nADD 0\nOUTBOX\nb:\nINBOX\nCOPYTO 0\nJUMP a")
+            .unwrap();
+
+        let divergence = find_divergence(
+            &create_problem(),
+            &left,
+            &right,
+            &create_generator(),
+            DEFAULT_MAX_STEPS,
+        );
+        assert_eq!(None, divergence);
+    }
+
+    #[test]
+    fn diverges() {
+        let compiler = Compiler::default();
+        let left = compiler
+            .compile("a:\nINBOX\nCOPYTO 0\nADD 0\nOUTBOX\nJUMP a")
+            .unwrap();
+        let right = compiler
+            .compile("a:\nINBOX\nJUMPN b\nCOPYTO 0\nADD 0\nOUTBOX\nb:\nJUMP a")
+            .unwrap();
+
+        let divergence = find_divergence(
+            &create_problem(),
+            &left,
+            &right,
+            &create_generator(),
+            DEFAULT_MAX_STEPS,
+        )
+        .unwrap();
+
+        let negative = |value: &Value| matches!(value, Value::Int(value) if *value < 0);
+        assert!(divergence.input.iter().any(negative));
+        assert_ne!(divergence.left, divergence.right);
+    }
+
+    #[test]
+    fn step_limit() {
+        let compiler = Compiler::default();
+        let left = compiler.compile("a:\nJUMP a").unwrap();
+        let right = compiler.compile("a:\nCOPYTO 0\nJUMP a").unwrap();
+
+        let divergence =
+            find_divergence(&create_problem(), &left, &right, &create_generator(), 10).unwrap();
+        assert_eq!(Err(RunError::StepLimit(10)), divergence.left);
+        assert_eq!(Err(RunError::EmptyAcc), divergence.right);
+    }
+}
@@ -0,0 +1,330 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::Write;
+
+use crate::code::{
+    analysis::{analyze, basic_blocks},
+    commands::Flow,
+    program::Program,
+};
+
+/// Flow Graph
+///
+/// Control-flow graph of a program, one [FlowNode] per [crate::code::analysis::BasicBlock], ready
+/// to be rendered, see [FlowGraph::to_dot] & [FlowGraph::to_mermaid].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlowGraph {
+    pub nodes: Vec<FlowNode>,
+    /// Ordered by the node they leave
+    pub edges: Vec<FlowEdge>,
+}
+
+/// Flow Node
+///
+/// Basic block with the labels pointing to it & its commands as source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlowNode {
+    pub labels: Vec<String>,
+    pub commands: Vec<String>,
+    pub status: NodeStatus,
+}
+
+/// Node Status
+///
+/// Structural problem of a [FlowNode], see [analyze].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeStatus {
+    Ok,
+    /// Never executes
+    Unreachable,
+    /// Part of a loop that never ends
+    InfiniteLoop,
+}
+
+/// Flow Edge
+///
+/// Transfer of control between nodes, `to` is [None] for the program end.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlowEdge {
+    pub from: usize,
+    pub to: Option<usize>,
+    /// Jump command taking this edge, e.g. `JUMPZ`, [None] for continuing with the next command
+    pub label: Option<&'static str>,
+    /// Returns from `CALL` & indirect jumps, only known at runtime
+    pub dashed: bool,
+}
+
+impl FlowGraph {
+    /// New
+    ///
+    /// Build the graph of `program`.
+    pub fn new(program: &Program) -> Self {
+        let commands = program.get_commands();
+        let blocks = basic_blocks(program);
+        let report = analyze(program);
+        let mut labels: Vec<(usize, &str)> = program
+            .get_labels()
+            .map(|(label, idx)| (idx, label.as_str()))
+            .collect();
+        labels.sort();
+
+        let mut nodes = Vec::with_capacity(blocks.len());
+        let mut edges = Vec::new();
+        for (i_block, block) in blocks.iter().enumerate() {
+            let status = if report.unreachable.contains(&block.start) {
+                NodeStatus::Unreachable
+            } else if report
+                .infinite_loops
+                .iter()
+                .any(|infinite_loop| infinite_loop.commands.contains(&block.start))
+            {
+                NodeStatus::InfiniteLoop
+            } else {
+                NodeStatus::Ok
+            };
+            nodes.push(FlowNode {
+                labels: labels
+                    .iter()
+                    .filter(|(idx, _)| *idx == block.start)
+                    .map(|(_, label)| label.to_string())
+                    .collect(),
+                commands: commands[block.start..block.end]
+                    .iter()
+                    .map(|command| command.to_source())
+                    .collect(),
+                status,
+            });
+
+            let last = &commands[block.end - 1];
+            let target = program.get_targets()[block.end - 1];
+            for &successor in &block.successors {
+                let jumps = matches!(last.flow(), Flow::Jump | Flow::Branch | Flow::Call)
+                    && target == Some(blocks[successor].start);
+                let (label, dashed) = match last.flow() {
+                    _ if jumps => (Some(last.factory().command()), false),
+                    Flow::Call => (None, true),
+                    Flow::Return | Flow::Indirect => (Some(last.factory().command()), true),
+                    _ => (None, false),
+                };
+                edges.push(FlowEdge {
+                    from: i_block,
+                    to: Some(successor),
+                    label,
+                    dashed,
+                });
+            }
+            if block.ends {
+                edges.push(FlowEdge {
+                    from: i_block,
+                    to: None,
+                    label: None,
+                    dashed: false,
+                });
+            }
+        }
+
+        Self { nodes, edges }
+    }
+
+    /// Reaches the program end, empty programs end right away
+    fn ends(&self) -> bool {
+        self.nodes.is_empty() || self.edges.iter().any(|edge| edge.to.is_none())
+    }
+
+    /// To DOT
+    ///
+    /// Render in Graphviz DOT. Jump edges are labelled with the jump command, unreachable nodes
+    /// are grayed out & nodes in an infinite loop are red.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph program {\n");
+        dot.push_str("    node [shape=box, fontname=\"monospace\"];\n");
+        dot.push_str("    start [shape=point];\n");
+        if self.ends() {
+            dot.push_str("    end [shape=doublecircle, label=\"\"];\n");
+        }
+        let first = if self.nodes.is_empty() { "end" } else { "b0" };
+        writeln!(dot, "    start -> {first};").unwrap();
+
+        let mut edges = self.edges.iter().peekable();
+        for (i_node, node) in self.nodes.iter().enumerate() {
+            let mut text = String::new();
+            node.labels
+                .iter()
+                .for_each(|label| write!(text, "{label}:\\l").unwrap());
+            node.commands
+                .iter()
+                .for_each(|command| write!(text, "    {}\\l", dot_escape(command)).unwrap());
+            let style = match node.status {
+                NodeStatus::Ok => "",
+                NodeStatus::Unreachable => ", style=dashed, color=gray, fontcolor=gray",
+                NodeStatus::InfiniteLoop => ", color=red",
+            };
+            writeln!(dot, "    b{i_node} [label=\"{text}\"{style}];").unwrap();
+
+            while let Some(edge) = edges.next_if(|edge| edge.from == i_node) {
+                let to = edge.to.map_or(String::from("end"), |to| format!("b{to}"));
+                let attributes = match (edge.label, edge.dashed) {
+                    (Some(label), false) => format!(" [label=\"{label}\"]"),
+                    (Some(label), true) => format!(" [label=\"{label}\", style=dashed]"),
+                    (None, true) => String::from(" [style=dashed]"),
+                    (None, false) => String::new(),
+                };
+                writeln!(dot, "    b{i_node} -> {to}{attributes};").unwrap();
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// To Mermaid
+    ///
+    /// Render as a Mermaid flowchart, e.g. for Markdown on GitHub. Styled like [FlowGraph::to_dot].
+    pub fn to_mermaid(&self) -> String {
+        let mut mermaid = String::from("flowchart TD\n");
+        mermaid.push_str("    start((\"start\"))\n");
+        // `end` is a keyword, only allowed as quoted text
+        if self.ends() {
+            mermaid.push_str("    stop(((\"end\")))\n");
+        }
+        let first = if self.nodes.is_empty() { "stop" } else { "b0" };
+        writeln!(mermaid, "    start --> {first}").unwrap();
+
+        let mut edges = self.edges.iter().peekable();
+        for (i_node, node) in self.nodes.iter().enumerate() {
+            let lines: Vec<String> = node
+                .labels
+                .iter()
+                .map(|label| format!("{label}:"))
+                .chain(node.commands.iter().cloned())
+                .map(|line| mermaid_escape(&line))
+                .collect();
+            writeln!(mermaid, "    b{i_node}[\"{}\"]", lines.join("<br/>")).unwrap();
+
+            while let Some(edge) = edges.next_if(|edge| edge.from == i_node) {
+                let to = edge.to.map_or(String::from("stop"), |to| format!("b{to}"));
+                let arrow = if edge.dashed { "-.->" } else { "-->" };
+                let label = edge
+                    .label
+                    .map(|label| format!("|{label}|"))
+                    .unwrap_or_default();
+                writeln!(mermaid, "    b{i_node} {arrow}{label} {to}").unwrap();
+            }
+        }
+
+        for (status, class, style) in [
+            (
+                NodeStatus::Unreachable,
+                "unreachable",
+                "stroke:gray,color:gray,stroke-dasharray:5 5",
+            ),
+            (NodeStatus::InfiniteLoop, "infiniteLoop", "stroke:red"),
+        ] {
+            let nodes: Vec<String> = (0..self.nodes.len())
+                .filter(|&i| self.nodes[i].status == status)
+                .map(|i| format!("b{i}"))
+                .collect();
+            if !nodes.is_empty() {
+                writeln!(mermaid, "    classDef {class} {style}").unwrap();
+                writeln!(mermaid, "    class {} {class}", nodes.join(",")).unwrap();
+            }
+        }
+
+        mermaid
+    }
+}
+
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Mermaid labels are HTML, quotes & brackets are written as entity codes
+fn mermaid_escape(text: &str) -> String {
+    text.replace('"', "#quot;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::compiler::compile::Compiler;
+
+    const CODE: &str = "\
+a:
+    INBOX
+    JUMPZ b
+    DEBUG \"x\" 0
+    OUTBOX
+    JUMP a
+    OUTBOX
+b:
+c:
+    JUMP c
+";
+
+    #[test]
+    fn to_dot_test() {
+        let program = Compiler::default().compile(CODE).unwrap();
+        let expected = concat!(
+            "digraph program {\n",
+            "    node [shape=box, fontname=\"monospace\"];\n",
+            "    start [shape=point];\n",
+            "    start -> b0;\n",
+            "    b0 [label=\"a:\\l    INBOX\\l    JUMPZ b\\l\"];\n",
+            "    b0 -> b1;\n",
+            "    b0 -> b3 [label=\"JUMPZ\"];\n",
+            "    b1 [label=\"    DEBUG \\\"x\\\" 0\\l    OUTBOX\\l    JUMP a\\l\"];\n",
+            "    b1 -> b0 [label=\"JUMP\"];\n",
+            "    b2 [label=\"    OUTBOX\\l\", style=dashed, color=gray, fontcolor=gray];\n",
+            "    b2 -> b3;\n",
+            "    b3 [label=\"b:\\lc:\\l    JUMP c\\l\", color=red];\n",
+            "    b3 -> b3 [label=\"JUMP\"];\n",
+            "}\n",
+        );
+        assert_eq!(expected, program.to_dot());
+
+        let program = Compiler::default().compile("INBOX\nOUTBOX\n").unwrap();
+        assert!(program
+            .to_dot()
+            .contains("    end [shape=doublecircle, label=\"\"];\n"));
+        assert!(program.to_dot().contains("    b0 -> end;\n"));
+        assert!(Program::EMPTY.to_dot().contains("    start -> end;\n"));
+    }
+
+    #[test]
+    fn to_mermaid_test() {
+        let program = Compiler::default().compile(CODE).unwrap();
+        let expected = concat!(
+            "flowchart TD\n",
+            "    start((\"start\"))\n",
+            "    start --> b0\n",
+            "    b0[\"a:<br/>INBOX<br/>JUMPZ b\"]\n",
+            "    b0 --> b1\n",
+            "    b0 -->|JUMPZ| b3\n",
+            "    b1[\"DEBUG #quot;x#quot; 0<br/>OUTBOX<br/>JUMP a\"]\n",
+            "    b1 -->|JUMP| b0\n",
+            "    b2[\"OUTBOX\"]\n",
+            "    b2 --> b3\n",
+            "    b3[\"b:<br/>c:<br/>JUMP c\"]\n",
+            "    b3 -->|JUMP| b3\n",
+            "    classDef unreachable stroke:gray,color:gray,stroke-dasharray:5 5\n",
+            "    class b2 unreachable\n",
+            "    classDef infiniteLoop stroke:red\n",
+            "    class b3 infiniteLoop\n",
+        );
+        assert_eq!(expected, program.to_mermaid());
+
+        let program = Compiler::extended()
+            .compile("CALL f\nHALT\nf:\nRET\n")
+            .unwrap();
+        let mermaid = program.to_mermaid();
+        assert!(mermaid.contains("    stop(((\"end\")))\n"));
+        assert!(mermaid.contains("    b0 -.-> b1\n    b0 -->|CALL| b2\n"));
+        assert!(mermaid.contains("    b1 --> stop\n"));
+        assert!(mermaid.contains("    b2 -.->|RET| b1\n"));
+    }
+}
@@ -1,5 +1,14 @@
+use alloc::collections::BTreeSet;
+use alloc::{vec, vec::Vec};
+
+use serde::{Deserialize, Serialize};
+
 use crate::code::program::Memory;
-use crate::game::value::Value;
+use crate::game::problem::OutputMatching;
+use crate::game::value::{Value, ValueBounds};
+
+/// Maximum number of nested `CALL`s
+pub const MAX_CALL_DEPTH: usize = 256;
 
 pub struct GameState<'a> {
     pub input: &'a Vec<Value>,
@@ -9,11 +18,63 @@ pub struct GameState<'a> {
     pub i_input: usize,
     pub i_output: usize,
     pub i_command: usize,
-    pub speed: u32,
+    pub speed: u64,
+    /// Set by commands that end execution, e.g. `INBOX` with no inputs left
+    pub halted: bool,
+    /// Worker's pocket stack, used by `PUSH` & `POP`
+    pub stack: Vec<Value>,
+    /// Largest stack depth reached so far
+    pub stack_max: usize,
+    /// Return addresses pushed by `CALL`, bounded by [MAX_CALL_DEPTH]
+    pub call_stack: Vec<usize>,
+    /// Values sent to the outbox so far
+    pub produced: Vec<Value>,
+    pub output_matching: OutputMatching,
+    /// Allowed range of int values, [None] if unbounded
+    pub bounds: Option<ValueBounds>,
+    /// Sorted indices of tiles that don't exist, see [crate::game::problem::Problem::get_holes]
+    pub holes: Vec<usize>,
+    /// Memory grows on demand instead of indices being out of range
+    pub unbounded_memory: bool,
+    pub memory_stats: MemoryStats,
+    /// If `false`, produced values are recorded without comparing them to `output`
+    pub check_output: bool,
+}
+
+/// Memory Stats
+///
+/// Floor usage of a run.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemoryStats {
+    /// Tiles read or written
+    pub touched: BTreeSet<usize>,
+    /// Tiles written
+    pub written: BTreeSet<usize>,
+    /// Max number of distinct tiles written in a single IO
+    pub written_max: usize,
+    /// Max number of non-empty tiles at the same time, incl. initial memory
+    pub peak_occupancy: usize,
+}
+
+impl MemoryStats {
+    /// Merge
+    ///
+    /// Combine stats of another IO into these.
+    pub fn merge(&mut self, other: &MemoryStats) {
+        self.touched.extend(&other.touched);
+        self.written.extend(&other.written);
+        self.written_max = self.written_max.max(other.written_max);
+        self.peak_occupancy = self.peak_occupancy.max(other.peak_occupancy);
+    }
 }
 
 impl<'a> GameState<'a> {
     pub fn new(input: &'a Vec<Value>, output: &'a Vec<Value>, memory: Memory) -> Self {
+        let memory_stats = MemoryStats {
+            peak_occupancy: memory.iter().flatten().count(),
+            ..Default::default()
+        };
+
         Self {
             input,
             output,
@@ -23,6 +84,52 @@ impl<'a> GameState<'a> {
             i_output: 0,
             i_command: 0,
             speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+            output_matching: OutputMatching::Exact,
+            bounds: None,
+            holes: vec![],
+            unbounded_memory: false,
+            memory_stats,
+            check_output: true,
+        }
+    }
+
+    /// Read
+    ///
+    /// Returns the value on the tile, [None] if the tile is empty or doesn't exist yet. The tile
+    /// is recorded as touched.
+    pub fn read(&mut self, index: usize) -> Option<Value> {
+        self.memory_stats.touched.insert(index);
+        self.peek(index)
+    }
+
+    /// Peek
+    ///
+    /// Like [GameState::read], without recording the access.
+    pub fn peek(&self, index: usize) -> Option<Value> {
+        self.memory.get(index).copied().flatten()
+    }
+
+    /// Write
+    ///
+    /// Write a value on the tile, growing the memory if needed. Tiles are never emptied, so
+    /// occupancy only grows & filling an empty tile raises its peak by one.
+    pub fn write(&mut self, index: usize, value: Value) {
+        if index >= self.memory.len() {
+            self.memory.resize(index + 1, None);
+        }
+
+        let stats = &mut self.memory_stats;
+        stats.touched.insert(index);
+        if stats.written.insert(index) {
+            stats.written_max = stats.written.len();
+        }
+        if self.memory[index].replace(value).is_none() {
+            stats.peak_occupancy += 1;
         }
     }
 }
@@ -0,0 +1,355 @@
+use alloc::vec::Vec;
+use core::cmp::{Ordering, Reverse};
+
+use crate::code::{
+    program::{Program, RunLimits},
+    search::{alphabet, build, FoundProgram, Letter},
+};
+use crate::game::{generator::Rng, problem::Problem};
+
+/// Evolution Options
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EvolutionOptions {
+    /// Programs in each generation
+    pub population: usize,
+    pub generations: usize,
+    /// Largest program size bred
+    pub max_size: usize,
+    /// Chance of mutating a child, in percent
+    pub mutation: u32,
+    /// Programs competing for each parent, the fittest wins
+    pub tournament: usize,
+    /// Fittest programs carried over to the next generation unchanged
+    pub elitism: usize,
+    pub seed: u64,
+    /// Step limit of a single IO, programs exceeding it fail the IO
+    pub max_steps: u32,
+    /// Use indirect tile arguments, e.g. `COPYFROM [x]`
+    pub indirect: bool,
+}
+
+impl Default for EvolutionOptions {
+    fn default() -> Self {
+        Self {
+            population: 200,
+            generations: 100,
+            max_size: 12,
+            mutation: 30,
+            tournament: 3,
+            elitism: 2,
+            seed: 0,
+            max_steps: 1_000,
+            indirect: false,
+        }
+    }
+}
+
+/// Fitness
+///
+/// How close a program is to solving a problem. Programs are ranked by correct outputs, then
+/// solved IOs, then speed & finally size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fitness {
+    /// Expected outputs produced over all IOs, before the first wrong one
+    pub outputs: usize,
+    /// IOs solved
+    pub ios: usize,
+    /// Speed summed over solved IOs
+    pub speed: u64,
+    pub size: usize,
+}
+
+impl Fitness {
+    /// Evaluate
+    ///
+    /// Run `program` on every IO of `problem`. Programs failing [Program::validate] are unfit.
+    pub fn evaluate(problem: &Problem, program: &Program, max_steps: u32) -> Self {
+        let mut fitness = Fitness {
+            outputs: 0,
+            ios: 0,
+            speed: 0,
+            size: program.size(),
+        };
+        if program.validate(problem).is_err() {
+            return fitness;
+        }
+
+        let limits = RunLimits {
+            max_steps: Some(max_steps),
+            detect_loops: false,
+            max_total_steps: None,
+        };
+        let runs = program.run_each_with_limits(problem, limits);
+        for (problem_io, run) in problem.get_ios().iter().zip(runs) {
+            match run {
+                Ok(score) => {
+                    fitness.outputs += problem_io.output.len();
+                    fitness.ios += 1;
+                    fitness.speed += score.speed;
+                }
+                Err(report) => fitness.outputs += report.outputs_produced,
+            }
+        }
+        fitness
+    }
+
+    /// Solves
+    ///
+    /// Returns `true` if every IO of `problem` is solved.
+    pub fn solves(&self, problem: &Problem) -> bool {
+        self.ios == problem.get_ios().len()
+    }
+
+    fn key(&self) -> (usize, usize, Reverse<u64>, Reverse<usize>) {
+        (
+            self.outputs,
+            self.ios,
+            Reverse(self.speed),
+            Reverse(self.size),
+        )
+    }
+}
+
+impl Ord for Fitness {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl PartialOrd for Fitness {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Evolution
+///
+/// Result of [evolve].
+#[derive(Debug, PartialEq)]
+pub struct Evolution {
+    /// Fittest program of the last generation
+    pub fittest: Program,
+    pub fitness: Fitness,
+    /// The fittest program with its score, if it solves the problem
+    pub solution: Option<FoundProgram>,
+}
+
+/// Evolve
+///
+/// Genetic programming - breed programs of up to [EvolutionOptions::max_size] commands over the
+/// problem's available commands. Starting from random programs, each generation keeps its
+/// [EvolutionOptions::elitism] fittest programs & fills the rest with children of parents picked
+/// by tournament. A child joins the first parent's commands up to a random point with the second
+/// parent's commands from a random point, & is then possibly mutated by replacing, inserting,
+/// removing or swapping commands. Runs are seeded, so evolution is reproducible.
+///
+/// Programs are ranked by [Fitness]. Unlike [search](crate::code::search::search), evolution
+/// scales to larger programs but may miss solutions, or the best ones.
+pub fn evolve(problem: &Problem, options: EvolutionOptions) -> Evolution {
+    let max_size = options.max_size.max(1);
+    let alphabet = alphabet(problem, max_size, options.indirect);
+    if alphabet.is_empty() {
+        let fittest = build(&alphabet, &[]);
+        return Evolution {
+            fitness: Fitness::evaluate(problem, &fittest, options.max_steps),
+            fittest,
+            solution: None,
+        };
+    }
+
+    let mut rng = Rng::new(options.seed);
+    let evaluate = |genome: Vec<usize>| {
+        let fitness = Fitness::evaluate(problem, &build(&alphabet, &genome), options.max_steps);
+        (genome, fitness)
+    };
+
+    let mut population: Vec<(Vec<usize>, Fitness)> = (0..options.population.max(1))
+        .map(|_| {
+            let size = rng.range(1, max_size as i64) as usize;
+            evaluate(random_genome(&alphabet, size, &mut rng))
+        })
+        .collect();
+    population.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+    for _ in 0..options.generations {
+        let mut next: Vec<(Vec<usize>, Fitness)> =
+            population.iter().take(options.elitism).cloned().collect();
+        while next.len() < population.len() {
+            let first = select(&population, options.tournament, &mut rng);
+            let second = select(&population, options.tournament, &mut rng);
+            let mut child = crossover(first, second, max_size, &mut rng);
+            if rng.range(1, 100) <= options.mutation as i64 {
+                mutate(&mut child, &alphabet, max_size, &mut rng);
+            }
+            next.push(evaluate(child));
+        }
+        next.sort_by(|(_, a), (_, b)| b.cmp(a));
+        population = next;
+    }
+
+    let (genome, fitness) = population.swap_remove(0);
+    let fittest = build(&alphabet, &genome);
+    let limits = RunLimits {
+        max_steps: Some(options.max_steps),
+        detect_loops: false,
+        max_total_steps: None,
+    };
+    let solution = fitness
+        .solves(problem)
+        .then(|| fittest.run_with_limits(problem, limits).ok())
+        .flatten()
+        .map(|score| FoundProgram {
+            program: fittest.clone(),
+            score,
+        });
+    Evolution {
+        fittest,
+        fitness,
+        solution,
+    }
+}
+
+fn random_genome(alphabet: &[Letter], size: usize, rng: &mut Rng) -> Vec<usize> {
+    (0..size).map(|_| random_letter(alphabet, rng)).collect()
+}
+
+fn random_letter(alphabet: &[Letter], rng: &mut Rng) -> usize {
+    rng.range(0, alphabet.len() as i64 - 1) as usize
+}
+
+/// Fittest of `tournament` random programs of the sorted `population`
+fn select<'a>(
+    population: &'a [(Vec<usize>, Fitness)],
+    tournament: usize,
+    rng: &mut Rng,
+) -> &'a [usize] {
+    let i = (0..tournament.max(1))
+        .map(|_| rng.range(0, population.len() as i64 - 1) as usize)
+        .min()
+        .unwrap_or(0);
+    &population[i].0
+}
+
+/// One point crossover, `first` up to a random point followed by `second` from a random point
+fn crossover(first: &[usize], second: &[usize], max_size: usize, rng: &mut Rng) -> Vec<usize> {
+    let i = rng.range(0, first.len() as i64) as usize;
+    let j = rng.range(0, second.len() as i64) as usize;
+    let mut child: Vec<usize> = first[..i].iter().chain(&second[j..]).copied().collect();
+    child.truncate(max_size);
+    if child.is_empty() {
+        child = first.to_vec();
+    }
+    child
+}
+
+fn mutate(genome: &mut Vec<usize>, alphabet: &[Letter], max_size: usize, rng: &mut Rng) {
+    let i = rng.range(0, genome.len() as i64 - 1) as usize;
+    match rng.range(0, 3) {
+        1 if genome.len() < max_size => {
+            let j = rng.range(0, genome.len() as i64) as usize;
+            genome.insert(j, random_letter(alphabet, rng));
+        }
+        2 if genome.len() > 1 => {
+            genome.remove(i);
+        }
+        3 => {
+            let j = rng.range(0, genome.len() as i64 - 1) as usize;
+            genome.swap(i, j);
+        }
+        _ => genome[i] = random_letter(alphabet, rng),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use crate::compiler::compile::Compiler;
+    use crate::game::{
+        problem::{ProblemBuilder, ProblemIO},
+        value::Value,
+    };
+    use alloc::{string::String, vec};
+
+    fn problem() -> Problem {
+        ProblemBuilder::new()
+            .add_io(ProblemIO {
+                input: vec![Value::Int(1), Value::Int(-2), Value::Int(3)],
+                output: vec![Value::Int(1), Value::Int(-2), Value::Int(3)],
+            })
+            .add_io(ProblemIO {
+                input: vec![Value::Int(4), Value::Int(0)],
+                output: vec![Value::Int(4), Value::Int(0)],
+            })
+            .memory_dim(2)
+            .enable_command(String::from("INBOX"))
+            .enable_command(String::from("OUTBOX"))
+            .enable_command(String::from("COPYTO"))
+            .enable_command(String::from("COPYFROM"))
+            .enable_command(String::from("JUMP"))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn fitness_test() {
+        let problem = problem();
+        let evaluate = |code: &str| {
+            let program = Compiler::default().compile(code).unwrap();
+            Fitness::evaluate(&problem, &program, 1_000)
+        };
+
+        let solution = evaluate("a:\nINBOX\nOUTBOX\nJUMP a\n");
+        assert!(solution.solves(&problem));
+        assert_eq!(
+            Fitness {
+                outputs: 5,
+                ios: 2,
+                speed: 15,
+                size: 3
+            },
+            solution
+        );
+        let partial = evaluate("INBOX\nOUTBOX\nINBOX\nOUTBOX\n");
+        assert_eq!((4, 1), (partial.outputs, partial.ios));
+        let unfit = evaluate("INBOX\nBUMPUP 0\nOUTBOX\n");
+        assert_eq!((0, 0), (unfit.outputs, unfit.ios));
+
+        let slower = evaluate("a:\nINBOX\nCOPYTO 0\nOUTBOX\nJUMP a\n");
+        assert!(solution > slower && slower > partial && partial > unfit);
+    }
+
+    #[test]
+    fn evolve_test() {
+        let problem = problem();
+        let options = EvolutionOptions {
+            population: 50,
+            generations: 30,
+            max_size: 6,
+            seed: 7,
+            ..EvolutionOptions::default()
+        };
+
+        let evolution = evolve(&problem, options);
+        assert!(evolution.fitness.solves(&problem));
+        let solution = evolution.solution.as_ref().unwrap();
+        assert_eq!(solution.program, evolution.fittest);
+        assert!(solution.program.validate(&problem).is_ok());
+        assert_eq!(evolution, evolve(&problem, options), "evolution is seeded");
+    }
+
+    #[test]
+    fn operators_test() {
+        let problem = problem();
+        let alphabet = alphabet(&problem, 4, false);
+        let mut rng = Rng::new(1);
+        for _ in 0..100 {
+            let mut genome = crossover(&[0, 1, 2], &[3, 4, 5, 6], 4, &mut rng);
+            assert!((1..=4).contains(&genome.len()));
+            mutate(&mut genome, &alphabet, 4, &mut rng);
+            assert!((1..=4).contains(&genome.len()));
+            assert!(genome.iter().all(|&letter| letter < alphabet.len()));
+        }
+    }
+}
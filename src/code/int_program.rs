@@ -0,0 +1,358 @@
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    code::{
+        bytecode::Op,
+        commands::{outbox::Outbox, Command, CommandValue},
+        game_state::GameState,
+        program::{Program, RunError},
+    },
+    game::value::Value,
+};
+
+/// Int Op
+///
+/// [Op] restricted to ints & tiles given directly by index.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum IntOp {
+    Inbox,
+    Outbox,
+    CopyFrom(usize),
+    CopyTo(usize),
+    Add(usize),
+    Sub(usize),
+    BumpUp(usize),
+    BumpDown(usize),
+    Jump(usize),
+    JumpZero(usize),
+    JumpNegative(usize),
+    JumpPositive(usize),
+    Nop,
+}
+
+impl IntOp {
+    fn lower(op: &Op) -> Option<Self> {
+        let direct = |value: &CommandValue| match value {
+            CommandValue::Value(tile) => Some(*tile),
+            CommandValue::Index(_) => None,
+        };
+
+        Some(match op {
+            Op::Inbox => IntOp::Inbox,
+            Op::Outbox => IntOp::Outbox,
+            Op::CopyFrom(command) => IntOp::CopyFrom(direct(&command.0)?),
+            Op::CopyTo(command) => IntOp::CopyTo(direct(&command.0)?),
+            Op::Add(command) => IntOp::Add(direct(&command.0)?),
+            Op::Sub(command) => IntOp::Sub(direct(&command.0)?),
+            Op::BumpUp(command) => IntOp::BumpUp(direct(&command.0)?),
+            Op::BumpDown(command) => IntOp::BumpDown(direct(&command.0)?),
+            Op::Jump(target) => IntOp::Jump(*target),
+            Op::JumpZero(target) => IntOp::JumpZero(*target),
+            Op::JumpNegative(target) => IntOp::JumpNegative(*target),
+            Op::JumpPositive(target) => IntOp::JumpPositive(*target),
+            Op::Nop => IntOp::Nop,
+            _ => return None,
+        })
+    }
+
+    fn tile(&self) -> Option<usize> {
+        match self {
+            IntOp::CopyFrom(tile)
+            | IntOp::CopyTo(tile)
+            | IntOp::Add(tile)
+            | IntOp::Sub(tile)
+            | IntOp::BumpUp(tile)
+            | IntOp::BumpDown(tile) => Some(*tile),
+            _ => None,
+        }
+    }
+}
+
+/// Int Program
+///
+/// Specialized form of a program using only `INBOX`, `OUTBOX`, `COPYFROM`, `COPYTO`, `ADD`, `SUB`,
+/// `BUMPUP`, `BUMPDN`, jumps & `NOP`, with every tile given directly. Once the inputs & memory of
+/// an IO hold only ints, its run needs neither value tag checks nor tile validation on every
+/// step - the common case for arithmetic levels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntProgram {
+    ops: Vec<IntOp>,
+    /// Tiles needed by the program, i.e. the largest tile + 1
+    tiles: usize,
+}
+
+/// Int-only state of a run, see [IntProgram::prepare]
+struct IntState {
+    acc: Option<i32>,
+    memory: Vec<Option<i32>>,
+    input: Vec<i32>,
+    i_input: usize,
+    i_command: usize,
+    /// Tiles read or written, see [MemoryStats](crate::code::game_state::MemoryStats)
+    touched: Vec<bool>,
+    /// Tiles written
+    written: Vec<bool>,
+}
+
+impl IntState {
+    fn read(&mut self, tile: usize) -> Result<i32, RunError> {
+        self.touched[tile] = true;
+        self.memory[tile].ok_or(RunError::EmptyMemory)
+    }
+
+    fn write(&mut self, tile: usize, value: i32) {
+        self.touched[tile] = true;
+        self.written[tile] = true;
+        self.memory[tile] = Some(value);
+    }
+}
+
+impl IntProgram {
+    /// Compile
+    ///
+    /// Returns [None] if any op is not supported.
+    pub fn compile(ops: &[Op]) -> Option<Self> {
+        let ops = ops.iter().map(IntOp::lower).collect::<Option<Vec<_>>>()?;
+        let tiles = ops
+            .iter()
+            .filter_map(IntOp::tile)
+            .max()
+            .map_or(0, |tile| tile + 1);
+
+        Some(Self { ops, tiles })
+    }
+
+    /// Accepts
+    ///
+    /// Whether [IntProgram::run] can continue from `game_state`.
+    pub fn accepts(&self, game_state: &GameState) -> bool {
+        self.prepare(game_state).is_some()
+    }
+
+    fn prepare(&self, game_state: &GameState) -> Option<IntState> {
+        if !game_state.holes.is_empty() || self.tiles > game_state.memory.len() {
+            return None;
+        }
+
+        let int = |value: Value| match value {
+            Value::Int(value) => Some(value),
+            Value::Char(_) => None,
+        };
+        let acc = match game_state.acc {
+            Some(acc) => Some(int(acc)?),
+            None => None,
+        };
+        let memory = game_state
+            .memory
+            .iter()
+            .map(|tile| match tile {
+                Some(value) => int(*value).map(Some),
+                None => Some(None),
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let input = game_state
+            .input
+            .iter()
+            .map(|value| int(*value))
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(IntState {
+            acc,
+            memory,
+            input,
+            i_input: game_state.i_input,
+            i_command: game_state.i_command,
+            touched: vec![false; self.tiles],
+            written: vec![false; self.tiles],
+        })
+    }
+
+    /// Run
+    ///
+    /// Run from `game_state` until the program ends or fails, like [Program::run] without debug
+    /// commands & loop detection. Returns [None] without running if the state is not accepted, see
+    /// [IntProgram::accepts].
+    ///
+    /// `executions` & `reached` are updated per command index, the game state is written back
+    /// even if the run fails.
+    pub fn run(
+        &self,
+        game_state: &mut GameState,
+        max_steps: Option<u32>,
+        executions: &mut [u64],
+        reached: &mut [bool],
+    ) -> Option<Result<(), RunError>> {
+        let mut state = self.prepare(game_state)?;
+        let result = self.run_state(&mut state, game_state, max_steps, executions, reached);
+
+        game_state.acc = state.acc.map(Value::Int);
+        game_state
+            .memory
+            .iter_mut()
+            .zip(&state.memory)
+            .for_each(|(tile, value)| *tile = value.map(Value::Int));
+        game_state.i_input = state.i_input;
+        game_state.i_command = state.i_command;
+        record_memory_stats(game_state, &state);
+
+        Some(result)
+    }
+
+    fn run_state(
+        &self,
+        state: &mut IntState,
+        game_state: &mut GameState,
+        max_steps: Option<u32>,
+        executions: &mut [u64],
+        reached: &mut [bool],
+    ) -> Result<(), RunError> {
+        let (min, max) = match game_state.bounds {
+            Some(bounds) => (bounds.min, bounds.max),
+            None => (i32::MIN, i32::MAX),
+        };
+        let overflow = |value: i32| RunError::Overflow(Value::Int(value));
+        let check_bounds = |value: i32| {
+            if (min..=max).contains(&value) {
+                Ok(value)
+            } else {
+                Err(overflow(value))
+            }
+        };
+
+        let mut steps = 0;
+        while let Some(op) = self.ops.get(state.i_command) {
+            if max_steps == Some(steps) {
+                return Err(RunError::StepLimit(steps));
+            }
+
+            let i_command = state.i_command;
+            reached[i_command] = true;
+            let acc = || state.acc.ok_or(RunError::EmptyAcc);
+            let mut next = i_command + 1;
+            match *op {
+                IntOp::Inbox => {
+                    let Some(&value) = state.input.get(state.i_input) else {
+                        game_state.halted = true;
+                        return Ok(()); // Not counted
+                    };
+                    state.acc = Some(value);
+                    state.i_input += 1;
+                }
+                IntOp::Outbox => {
+                    game_state.acc = state.acc.map(Value::Int);
+                    Outbox.execute(&Program::EMPTY, game_state)?;
+                }
+                IntOp::CopyFrom(i) => state.acc = Some(state.read(i)?),
+                IntOp::CopyTo(i) => state.write(i, acc()?),
+                IntOp::Add(i) => {
+                    let (value, to_add) = (acc()?, state.read(i)?);
+                    let sum = value
+                        .checked_add(to_add)
+                        .ok_or_else(|| overflow(value.saturating_add(to_add)))?;
+                    state.acc = Some(check_bounds(sum)?);
+                }
+                IntOp::Sub(i) => {
+                    let (value, to_sub) = (acc()?, state.read(i)?);
+                    let difference = value
+                        .checked_sub(to_sub)
+                        .ok_or_else(|| overflow(value.saturating_sub(to_sub)))?;
+                    state.acc = Some(check_bounds(difference)?);
+                }
+                IntOp::BumpUp(i) => {
+                    let value = state.read(i)?;
+                    let bumped = value.checked_add(1).ok_or_else(|| overflow(value))?;
+                    let bumped = check_bounds(bumped)?;
+                    state.write(i, bumped);
+                    state.acc = Some(bumped);
+                }
+                IntOp::BumpDown(i) => {
+                    let value = state.read(i)?;
+                    let bumped = value.checked_sub(1).ok_or_else(|| overflow(value))?;
+                    let bumped = check_bounds(bumped)?;
+                    state.write(i, bumped);
+                    state.acc = Some(bumped);
+                }
+                IntOp::Jump(target) => next = target,
+                IntOp::JumpZero(target) if acc()? == 0 => next = target,
+                IntOp::JumpNegative(target) if acc()? < 0 => next = target,
+                IntOp::JumpPositive(target) if acc()? > 0 => next = target,
+                IntOp::JumpZero(_) | IntOp::JumpNegative(_) | IntOp::JumpPositive(_) => {}
+                IntOp::Nop => {}
+            }
+
+            // Built-in commands all cost 1
+            game_state.speed = game_state.speed.saturating_add(1);
+            executions[i_command] = executions[i_command].saturating_add(1);
+            steps += 1;
+            state.i_command = next;
+        }
+
+        Ok(())
+    }
+}
+
+/// Tiles are never emptied, so occupancy peaks at the end of the run
+fn record_memory_stats(game_state: &mut GameState, state: &IntState) {
+    let tiles = |flags: &[bool]| {
+        flags
+            .iter()
+            .enumerate()
+            .filter(|(_, &flag)| flag)
+            .map(|(tile, _)| tile)
+            .collect::<Vec<_>>()
+    };
+
+    let stats = &mut game_state.memory_stats;
+    stats.touched.extend(tiles(&state.touched));
+    stats.written.extend(tiles(&state.written));
+    stats.written_max = stats.written.len();
+    let occupancy = game_state.memory.iter().flatten().count();
+    stats.peak_occupancy = stats.peak_occupancy.max(occupancy);
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::compiler::compile::Compiler;
+
+    #[test]
+    fn compile_test() {
+        let compile = |code: &str| {
+            let program = Compiler::extended().compile(code).unwrap();
+            IntProgram::compile(program.get_bytecode().get_ops())
+        };
+
+        let program = compile("a:\nINBOX\nCOPYTO 3\nBUMPUP 1\nJUMPN a\nOUTBOX\n").unwrap();
+        assert_eq!(4, program.tiles);
+        assert_eq!(IntOp::JumpNegative(0), program.ops[3]);
+
+        assert!(compile("INBOX\nCOPYTO [3]\n").is_none());
+        assert!(compile("INBOX\nDEBUG 0\n").is_none());
+        assert!(compile("INBOX\nMUL 0\n").is_none());
+        assert_eq!(Some(0), compile("").map(|program| program.tiles));
+    }
+
+    #[test]
+    fn accepts_test() {
+        let program = Compiler::default().compile("INBOX\nCOPYTO 1\n").unwrap();
+        let program = IntProgram::compile(program.get_bytecode().get_ops()).unwrap();
+
+        let (input, output) = (vec![Value::Int(1)], vec![]);
+        let memory = vec![None, Some(Value::Int(2))];
+        let mut game_state = GameState::new(&input, &output, memory.clone());
+        assert!(program.accepts(&game_state));
+
+        game_state.holes = vec![0];
+        assert!(!program.accepts(&game_state));
+
+        let game_state = GameState::new(&input, &output, vec![None]);
+        assert!(!program.accepts(&game_state));
+
+        let chars = vec![Value::Char('A')];
+        let game_state = GameState::new(&chars, &output, memory.clone());
+        assert!(!program.accepts(&game_state));
+
+        let game_state = GameState::new(&input, &output, vec![Some(Value::Char('A')); 2]);
+        assert!(!program.accepts(&game_state));
+    }
+}
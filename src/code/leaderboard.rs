@@ -0,0 +1,148 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::code::batch::evaluate;
+use crate::code::program::{Program, ProgramError, Score};
+use crate::game::problem::Problem;
+
+/// Rank By
+///
+/// Primary ranking criterion. Remaining criteria break ties in a fixed order.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RankBy {
+    /// Size, then average speed, then worst-case speed
+    #[default]
+    Size,
+    /// Average speed, then worst-case speed, then size
+    Speed,
+    /// Worst-case speed, then average speed, then size
+    WorstCase,
+}
+
+/// Leaderboard Entry
+///
+/// Result of a single program, `index` being its position in the ranked input.
+#[derive(Debug, PartialEq)]
+pub struct LeaderboardEntry {
+    pub index: usize,
+    /// Competition rank (`1, 1, 3`), [None] if the program failed
+    pub rank: Option<usize>,
+    pub score: Result<Score, ProgramError>,
+}
+
+/// Rank
+///
+/// Validate & run every program on the problem, then order them from best to worst. Programs
+/// with equal scores share a rank & keep their input order. Failing programs come last, in input
+/// order.
+pub fn rank(problem: &Problem, programs: &[Program], rank_by: RankBy) -> Vec<LeaderboardEntry> {
+    let mut entries: Vec<LeaderboardEntry> = programs
+        .iter()
+        .enumerate()
+        .map(|(index, program)| LeaderboardEntry {
+            index,
+            rank: None,
+            score: evaluate(program, problem),
+        })
+        .collect();
+
+    entries.sort_by(|a, b| match (&a.score, &b.score) {
+        (Ok(a), Ok(b)) => compare(a, b, rank_by),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => Ordering::Equal,
+    });
+
+    let mut previous: Option<(usize, &Score)> = None;
+    let mut ranks = Vec::with_capacity(entries.len());
+    for (i, entry) in entries.iter().enumerate() {
+        let rank = entry.score.as_ref().ok().map(|score| match previous {
+            Some((rank, prev)) if compare(prev, score, rank_by).is_eq() => rank,
+            _ => {
+                previous = Some((i + 1, score));
+                i + 1
+            }
+        });
+        ranks.push(rank);
+    }
+
+    entries
+        .into_iter()
+        .zip(ranks)
+        .map(|(entry, rank)| LeaderboardEntry { rank, ..entry })
+        .collect()
+}
+
+fn compare(a: &Score, b: &Score, rank_by: RankBy) -> Ordering {
+    let size = a.size.cmp(&b.size);
+    let speed = a.speed_avg.total_cmp(&b.speed_avg);
+    let worst_case = a.speed_max.cmp(&b.speed_max);
+
+    match rank_by {
+        RankBy::Size => size.then(speed).then(worst_case),
+        RankBy::Speed => speed.then(worst_case).then(size),
+        RankBy::WorstCase => worst_case.then(speed).then(size),
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::code::program::RunError;
+    use crate::compiler::compile::Compiler;
+    use crate::game::problem::{ProblemBuilder, ProblemIO};
+    use crate::game::value::Value;
+
+    use super::*;
+
+    #[test]
+    fn rank_test() {
+        let problem = ProblemBuilder::new()
+            .add_io(ProblemIO {
+                input: vec![Value::Int(1), Value::Int(2)],
+                output: vec![Value::Int(1), Value::Int(2)],
+            })
+            .enable_all_commands()
+            .build()
+            .unwrap();
+
+        let compiler = Compiler::default();
+        let programs: Vec<Program> = [
+            "a:\nINBOX\nOUTBOX\nJUMP a",
+            "INBOX\nOUTBOX\nINBOX\nOUTBOX",
+            "INBOX",
+            "a:\nINBOX\nOUTBOX\nJUMP a",
+        ]
+        .into_iter()
+        .map(|code| compiler.compile(code).unwrap())
+        .collect();
+
+        let ranks = |rank_by| {
+            rank(&problem, &programs, rank_by)
+                .into_iter()
+                .map(|entry| (entry.index, entry.rank))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            vec![(0, Some(1)), (3, Some(1)), (1, Some(3)), (2, None)],
+            ranks(RankBy::Size)
+        );
+        assert_eq!(
+            vec![(1, Some(1)), (0, Some(2)), (3, Some(2)), (2, None)],
+            ranks(RankBy::Speed)
+        );
+
+        let entries = rank(&problem, &programs, RankBy::Size);
+        let Err(ProgramError::Run(report)) = &entries[3].score else {
+            panic!("Expected a run error!");
+        };
+        assert_eq!(
+            RunError::MissingOutput {
+                index: 0,
+                remaining: 2,
+                expected: Value::Int(1),
+            },
+            report.error
+        );
+    }
+}
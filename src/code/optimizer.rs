@@ -0,0 +1,399 @@
+use alloc::{boxed::Box, vec, vec::Vec};
+
+use crate::code::{
+    analysis::unreachable,
+    commands::{nop::Nop, AnyCommand, Flow},
+    optimizer::peephole::Peephole,
+    program::{Program, ProgramBuilder},
+};
+
+pub mod peephole;
+
+/// Rounds of [Optimizer::optimize] before giving up on reaching a fixpoint
+const MAX_ROUNDS: usize = 16;
+
+/// Pass
+///
+/// Behavior preserving transformation of a program: for every input the program runs on without
+/// errors, the optimized program produces the same outputs. Only speed & size may change. Passes
+/// expect a validated program.
+pub trait Pass {
+    /// Name
+    ///
+    /// Returns the pass name, e.g. `dead-code`.
+    fn name(&self) -> &'static str;
+
+    /// Run
+    ///
+    /// Returns [Some(Program)] with the transformed program, [None] if there's nothing to do.
+    fn run(&self, program: &Program) -> Option<Program>;
+}
+
+/// Pass Report
+///
+/// A pass that changed the program, with its size before & after, see [Program::size].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PassReport {
+    pub pass: &'static str,
+    pub size_before: usize,
+    pub size_after: usize,
+}
+
+impl PassReport {
+    /// Delta
+    ///
+    /// Returns the change in size, negative if the program shrank.
+    pub fn delta(&self) -> isize {
+        self.size_after as isize - self.size_before as isize
+    }
+}
+
+/// Optimization
+///
+/// Result of [Optimizer::optimize].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Optimization {
+    pub program: Program,
+    /// Passes that changed the program, in order of application
+    pub passes: Vec<PassReport>,
+}
+
+impl Optimization {
+    /// Delta
+    ///
+    /// Returns the change in size over all passes.
+    pub fn delta(&self) -> isize {
+        self.passes.iter().map(PassReport::delta).sum()
+    }
+}
+
+/// Optimizer
+///
+/// Runs its passes in order, repeating them until none changes the program.
+pub struct Optimizer {
+    pub passes: Vec<Box<dyn Pass>>,
+}
+
+impl Default for Optimizer {
+    /// Optimizer with the built-in passes: [JumpThreading], [Peephole], [NoOps] & [DeadCode].
+    fn default() -> Self {
+        Self {
+            passes: vec![
+                Box::new(JumpThreading),
+                Box::new(Peephole::default()),
+                Box::new(NoOps),
+                Box::new(DeadCode),
+            ],
+        }
+    }
+}
+
+impl Optimizer {
+    /// Add Pass
+    ///
+    /// Run `pass` after the existing passes.
+    pub fn add_pass(mut self, pass: Box<dyn Pass>) -> Self {
+        self.passes.push(pass);
+        self
+    }
+
+    /// Optimize
+    ///
+    /// Run the passes over `program`, which should be validated.
+    pub fn optimize(&self, program: &Program) -> Optimization {
+        let mut program = program.clone();
+        let mut passes = vec![];
+        for _ in 0..MAX_ROUNDS {
+            let mut changed = false;
+            for pass in &self.passes {
+                let Some(optimized) = pass.run(&program) else {
+                    continue;
+                };
+                passes.push(PassReport {
+                    pass: pass.name(),
+                    size_before: program.size(),
+                    size_after: optimized.size(),
+                });
+                program = optimized;
+                changed = true;
+            }
+            if !changed {
+                break;
+            }
+        }
+        Optimization { program, passes }
+    }
+}
+
+/// Dead Code Elimination
+///
+/// Removes commands that can never execute, see [unreachable].
+pub struct DeadCode;
+
+impl Pass for DeadCode {
+    fn name(&self) -> &'static str {
+        "dead-code"
+    }
+
+    fn run(&self, program: &Program) -> Option<Program> {
+        let unreachable = unreachable(program);
+        rewrite(program, |i, _| {
+            if unreachable.binary_search(&i).is_ok() {
+                Edit::Remove
+            } else {
+                Edit::Keep
+            }
+        })
+    }
+}
+
+/// Jump Threading
+///
+/// Points jumps, branches & calls whose target is a `JUMP` straight to where the chain of jumps
+/// ends.
+pub struct JumpThreading;
+
+impl Pass for JumpThreading {
+    fn name(&self) -> &'static str {
+        "jump-threading"
+    }
+
+    fn run(&self, program: &Program) -> Option<Program> {
+        let commands = program.get_commands();
+        let targets = program.get_targets();
+        rewrite(program, |i, command| {
+            if !matches!(command.flow(), Flow::Jump | Flow::Branch | Flow::Call) {
+                return Edit::Keep;
+            }
+
+            let mut visited = vec![i];
+            let mut label = None;
+            let mut target = targets[i];
+            while let Some(t) = target.filter(|t| !visited.contains(t)) {
+                match commands.get(t) {
+                    Some(jump) if jump.flow() == Flow::Jump && targets[t].is_some() => {
+                        visited.push(t);
+                        label = jump.requires_label();
+                        target = targets[t];
+                    }
+                    _ => break,
+                }
+            }
+
+            match label {
+                Some(label) if Some(label) != command.requires_label() => command
+                    .factory()
+                    .create(label.as_str())
+                    .map_or(Edit::Keep, |command| Edit::Replace(vec![command])),
+                _ => Edit::Keep,
+            }
+        })
+    }
+}
+
+/// No-Op Removal
+///
+/// Removes commands without any effect: `NOP` & a `JUMP` to the next command. Sequences of
+/// commands are simplified by [Peephole].
+pub struct NoOps;
+
+impl Pass for NoOps {
+    fn name(&self) -> &'static str {
+        "no-ops"
+    }
+
+    fn run(&self, program: &Program) -> Option<Program> {
+        let targets = program.get_targets();
+        rewrite(program, |i, command| {
+            let jumps_next = command.flow() == Flow::Jump && targets[i] == Some(i + 1);
+            if command.as_any().is::<Nop>() || jumps_next {
+                Edit::Remove
+            } else {
+                Edit::Keep
+            }
+        })
+    }
+}
+
+/// Change of a single command by [rewrite]
+enum Edit {
+    Keep,
+    Remove,
+    /// Replace with any number of commands
+    Replace(Vec<AnyCommand>),
+}
+
+/// Rebuild the program with each command edited by `edit`, [None] if nothing changes. Labels &
+/// comments of removed commands move to the next kept command, replaced commands keep them.
+/// Programs with indirect jumps are only rewritten command for command, as anything else would
+/// move the indices they jump to.
+fn rewrite(program: &Program, mut edit: impl FnMut(usize, &AnyCommand) -> Edit) -> Option<Program> {
+    let commands = program.get_commands();
+    let indirect = commands
+        .iter()
+        .any(|command| command.flow() == Flow::Indirect);
+    let edits: Vec<Edit> = commands
+        .iter()
+        .enumerate()
+        .map(|(i, command)| edit(i, command))
+        .collect();
+    let resizes = edits.iter().any(|edit| match edit {
+        Edit::Keep => false,
+        Edit::Remove => true,
+        Edit::Replace(replacement) => replacement.len() != 1,
+    });
+    if edits.iter().all(|edit| matches!(edit, Edit::Keep)) || (indirect && resizes) {
+        return None;
+    }
+
+    let mut labels: Vec<_> = program.get_labels().collect();
+    labels.sort_by_key(|&(_, i)| i);
+    let mut labels = labels.into_iter().peekable();
+    let mut comments = program.get_comments().iter().peekable();
+
+    let mut builder = ProgramBuilder::new();
+    for (i, edit) in edits
+        .into_iter()
+        .enumerate()
+        .chain([(commands.len(), Edit::Remove)])
+    {
+        while let Some((label, _)) = labels.next_if(|&(_, idx)| idx <= i) {
+            builder.add_label_ref(label.clone());
+        }
+        while let Some(&(_, comment)) = comments.next_if(|(idx, _)| *idx <= i) {
+            builder.add_comment_ref(comment);
+        }
+
+        let replacement = match edit {
+            Edit::Keep => vec![commands[i].clone()],
+            Edit::Remove => continue,
+            Edit::Replace(replacement) => replacement,
+        };
+        if let Some(line) = program.get_line(i) {
+            builder.source_line_ref(line);
+        }
+        replacement
+            .into_iter()
+            .for_each(|command| builder.add_command_ref(command));
+    }
+    for define in program.get_defines() {
+        builder.add_define_ref(define.clone());
+    }
+    Some(builder.build())
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::{
+        compiler::compile::Compiler,
+        game::{
+            problem::{Problem, ProblemBuilder, ProblemIO},
+            value::Value,
+        },
+    };
+
+    fn problem() -> Problem {
+        ProblemBuilder::new()
+            .add_io(ProblemIO {
+                input: vec![Value::Int(3), Value::Int(0), Value::Int(-2)],
+                output: vec![Value::Int(3), Value::Int(0), Value::Int(-2)],
+            })
+            .memory_dim(4)
+            .instruction_set(crate::code::commands::InstructionSet::Extended)
+            .enable_all_commands()
+            .build()
+            .unwrap()
+    }
+
+    fn compile(code: &str) -> Program {
+        Compiler::extended().compile(code).unwrap()
+    }
+
+    fn sources(program: &Program) -> Vec<String> {
+        program
+            .get_commands()
+            .iter()
+            .map(|command| command.to_source())
+            .collect()
+    }
+
+    #[test]
+    fn dead_code_test() {
+        let program = compile("a:\nINBOX\nOUTBOX\nJUMP a\nOUTBOX\nNEG\nb:\nHALT\n");
+        let optimized = DeadCode.run(&program).unwrap();
+        assert_eq!(vec!["INBOX", "OUTBOX", "JUMP a"], sources(&optimized));
+        assert_eq!(Some(3), optimized.get_label("b"));
+        assert_eq!(Some(3), optimized.get_line(2));
+        assert_eq!(None, DeadCode.run(&optimized));
+
+        let indirect = compile("JUMP [0]\nOUTBOX\n");
+        assert_eq!(None, DeadCode.run(&indirect));
+    }
+
+    #[test]
+    fn jump_threading_test() {
+        let program = compile("a:\nINBOX\nJUMPZ b\nOUTBOX\nb:\nJUMP c\nc:\nJUMP a\n");
+        let optimized = JumpThreading.run(&program).unwrap();
+        assert_eq!(
+            vec!["INBOX", "JUMPZ a", "OUTBOX", "JUMP a", "JUMP a"],
+            sources(&optimized)
+        );
+        assert_eq!(Some(0), optimized.get_targets()[1]);
+        assert_eq!(None, JumpThreading.run(&optimized));
+
+        let cycle = compile("a:\nJUMP b\nb:\nJUMP a\n");
+        let optimized = JumpThreading.run(&cycle).unwrap();
+        assert_eq!(vec!["JUMP a", "JUMP b"], sources(&optimized));
+        assert_eq!(None, JumpThreading.run(&optimized));
+    }
+
+    #[test]
+    fn no_ops_test() {
+        let program =
+            compile("a:\nINBOX\nNOP\nCOPYTO 0\nb:\nCOPYFROM 0\nJUMP c\nc:\nOUTBOX\nJUMP a\n");
+        let optimized = NoOps.run(&program).unwrap();
+        assert_eq!(
+            vec!["INBOX", "COPYTO 0", "COPYFROM 0", "OUTBOX", "JUMP a"],
+            sources(&optimized)
+        );
+        assert_eq!(Some(2), optimized.get_label("b"));
+        assert_eq!(Some(3), optimized.get_label("c"));
+        assert_eq!(None, NoOps.run(&compile("COPYTO 0\nCOPYFROM 0\n")));
+    }
+
+    #[test]
+    fn optimize_test() {
+        let problem = problem();
+        let program = compile(
+            "start:\nINBOX\nJUMPZ zero\nNOP\nOUTBOX\nJUMP loop\nNEG\nzero:\nOUTBOX\nJUMP loop\n\
+             loop:\nJUMP start\n",
+        );
+        let optimization = Optimizer::default().optimize(&program);
+        assert_eq!(
+            vec![
+                "INBOX",
+                "JUMPZ zero",
+                "OUTBOX",
+                "JUMP start",
+                "OUTBOX",
+                "JUMP start"
+            ],
+            sources(&optimization.program)
+        );
+        assert_eq!(-3, optimization.delta());
+        assert_eq!(
+            vec!["jump-threading", "no-ops", "dead-code"],
+            optimization
+                .passes
+                .iter()
+                .map(|report| report.pass)
+                .collect::<Vec<_>>()
+        );
+
+        let score = program.run(&problem).unwrap();
+        let optimized = optimization.program.run(&problem).unwrap();
+        assert!(optimized.speed_max < score.speed_max);
+        assert_eq!(6, optimization.program.size());
+    }
+}
@@ -0,0 +1,274 @@
+use alloc::{
+    boxed::Box,
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::mem;
+
+use crate::code::{
+    commands::{AnyCommand, CommandFactory, Flow, InstructionSet},
+    optimizer::{rewrite, Edit, Pass},
+    program::Program,
+};
+
+/// Peephole
+///
+/// Replaces short sequences of commands matching a [PeepholeRule], e.g. `COPYTO x`, `COPYFROM x`
+/// with `COPYTO x`. Rules are tried in order at every command, matches don't overlap. A sequence
+/// is only replaced if nothing jumps into it, i.e. past its first command.
+pub struct Peephole {
+    pub rules: Vec<PeepholeRule>,
+}
+
+impl Default for Peephole {
+    /// Peephole with the built-in rules, each shrinking the program by a command:
+    /// - `COPYTO x`, `COPYFROM x` to `COPYTO x`
+    /// - `COPYTO x`, `COPYTO x` to `COPYTO x`
+    /// - `COPYFROM x`, `COPYTO x` to `COPYFROM x`
+    /// - `JUMPZ l`, `JUMP l` to `JUMP l`, likewise for `JUMPN` & `JUMPP`
+    /// - `NEG`, `NEG` to nothing
+    fn default() -> Self {
+        let rules = [
+            (&["COPYTO {x}", "COPYFROM {x}"][..], &["COPYTO {x}"][..]),
+            (&["COPYTO {x}", "COPYTO {x}"], &["COPYTO {x}"]),
+            (&["COPYFROM {x}", "COPYTO {x}"], &["COPYFROM {x}"]),
+            (&["JUMPZ {l}", "JUMP {l}"], &["JUMP {l}"]),
+            (&["JUMPN {l}", "JUMP {l}"], &["JUMP {l}"]),
+            (&["JUMPP {l}", "JUMP {l}"], &["JUMP {l}"]),
+            (&["NEG", "NEG"], &[]),
+        ];
+        Self {
+            rules: rules
+                .into_iter()
+                .map(|(pattern, replacement)| PeepholeRule::new(pattern, replacement))
+                .collect(),
+        }
+    }
+}
+
+impl Peephole {
+    /// Add Rule
+    ///
+    /// Try `rule` after the existing rules.
+    pub fn add_rule(mut self, rule: PeepholeRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+}
+
+impl Pass for Peephole {
+    fn name(&self) -> &'static str {
+        "peephole"
+    }
+
+    fn run(&self, program: &Program) -> Option<Program> {
+        let commands = program.get_commands();
+        // Commands continued at from anywhere but the previous command
+        let mut entries = vec![false; commands.len() + 1];
+        program.get_labels().for_each(|(_, i)| entries[i] = true);
+        for (i, command) in commands.iter().enumerate() {
+            if command.flow() == Flow::Call {
+                entries[i + 1] = true;
+            }
+        }
+
+        let factories = InstructionSet::Extended.factories();
+        let mut edits: Vec<Edit> = commands.iter().map(|_| Edit::Keep).collect();
+        let mut i = 0;
+        while i < commands.len() {
+            let replaced = self.rules.iter().find_map(|rule| {
+                let window = commands.get(i..i + rule.pattern.len())?;
+                if entries[i + 1..i + window.len()].contains(&true) {
+                    return None;
+                }
+                Some((window.len(), rule.apply(window, &factories)?))
+            });
+            match replaced {
+                Some((len, replacement)) => {
+                    edits[i] = Edit::Replace(replacement);
+                    (i + 1..i + len).for_each(|j| edits[j] = Edit::Remove);
+                    i += len;
+                }
+                None => i += 1,
+            }
+        }
+
+        rewrite(program, |i, _| mem::replace(&mut edits[i], Edit::Keep))
+    }
+}
+
+/// Peephole Rule
+///
+/// Replacement of a sequence of commands, both written as source. Arguments may contain a
+/// `{name}` placeholder: `{x}` matches a tile or label, `[{x}]` an indirect tile. A name must
+/// match the same value wherever it's used. The replacement must not change outputs, see [Pass].
+///
+/// ```
+/// use hrm::code::optimizer::peephole::{Peephole, PeepholeRule};
+///
+/// // Bumping a tile up & back down only loads it
+/// let rule = PeepholeRule::new(&["BUMPUP {x}", "BUMPDN {x}"], &["COPYFROM {x}"]);
+/// let peephole = Peephole::default().add_rule(rule);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeepholeRule {
+    pattern: Vec<(String, Option<String>)>,
+    replacement: Vec<(String, Option<String>)>,
+}
+
+impl PeepholeRule {
+    /// New
+    ///
+    /// Rule replacing commands matching `pattern` with `replacement`, e.g. `["JUMPZ {l}",
+    /// "JUMP {l}"]` with `["JUMP {l}"]`. Empty patterns never match.
+    pub fn new(pattern: &[&str], replacement: &[&str]) -> Self {
+        let parse = |lines: &[&str]| {
+            lines
+                .iter()
+                .map(|line| match line.trim().split_once(' ') {
+                    Some((command, args)) => (command.to_string(), Some(args.trim().to_string())),
+                    None => (line.trim().to_string(), None),
+                })
+                .collect()
+        };
+        Self {
+            pattern: parse(pattern),
+            replacement: parse(replacement),
+        }
+    }
+
+    /// Returns the replacement of `window` if it matches, [None] if it doesn't or the replacement
+    /// can't be created
+    fn apply(
+        &self,
+        window: &[AnyCommand],
+        factories: &[Box<dyn CommandFactory>],
+    ) -> Option<Vec<AnyCommand>> {
+        if self.pattern.is_empty() {
+            return None;
+        }
+
+        let mut bindings = BTreeMap::new();
+        for ((command, pattern), actual) in self.pattern.iter().zip(window) {
+            if actual.factory().command() != command {
+                return None;
+            }
+            match (pattern, actual.args()) {
+                (None, None) => {}
+                (Some(pattern), Some(args)) => bind(pattern, &args, &mut bindings)?,
+                _ => return None,
+            }
+        }
+
+        self.replacement
+            .iter()
+            .map(|(command, args)| {
+                let args = match args {
+                    Some(args) => substitute(args, &bindings)?,
+                    None => String::new(),
+                };
+                factories
+                    .iter()
+                    .filter(|factory| factory.command() == command)
+                    .find_map(|factory| factory.create(&args))
+            })
+            .collect()
+    }
+}
+
+/// Match `args` against a pattern with at most one `{name}` placeholder, recording its value
+fn bind(pattern: &str, args: &str, bindings: &mut BTreeMap<String, String>) -> Option<()> {
+    let Some((prefix, rest)) = pattern.split_once('{') else {
+        return (pattern == args).then_some(());
+    };
+    let (name, suffix) = rest.split_once('}')?;
+    let value = args.strip_prefix(prefix)?.strip_suffix(suffix)?;
+    if value.is_empty() || value.contains(['[', ']', ' ']) {
+        return None;
+    }
+
+    match bindings.get(name) {
+        Some(bound) => (bound == value).then_some(()),
+        None => {
+            bindings.insert(name.to_string(), value.to_string());
+            Some(())
+        }
+    }
+}
+
+/// Replace the placeholder in `args` with its bound value, [None] if it's unbound
+fn substitute(args: &str, bindings: &BTreeMap<String, String>) -> Option<String> {
+    let Some((prefix, rest)) = args.split_once('{') else {
+        return Some(args.to_string());
+    };
+    let (name, suffix) = rest.split_once('}')?;
+    Some(format!("{prefix}{}{suffix}", bindings.get(name)?))
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::compiler::compile::Compiler;
+
+    /// Optimized commands as source, separated by `; `
+    fn optimize(peephole: &Peephole, code: &str) -> Option<String> {
+        let program = Compiler::extended().compile(code).unwrap();
+        peephole.run(&program).map(|program| {
+            let commands: Vec<String> = program
+                .get_commands()
+                .iter()
+                .map(|command| command.to_source())
+                .collect();
+            commands.join("; ")
+        })
+    }
+
+    #[test]
+    fn peephole_test() {
+        let peephole = Peephole::default();
+        assert_eq!(
+            Some(String::from("INBOX; COPYTO 0; JUMP a; OUTBOX")),
+            optimize(
+                &peephole,
+                "a:\nINBOX\nCOPYTO 0\nCOPYFROM 0\nNEG\nNEG\nJUMPZ a\nJUMP a\nOUTBOX\n"
+            )
+        );
+        assert_eq!(
+            Some(String::from("COPYFROM 1; COPYTO 1; COPYFROM 2")),
+            optimize(&peephole, "COPYFROM 1\nCOPYTO 1\nCOPYTO 1\nCOPYFROM 2\n")
+        );
+
+        for unchanged in [
+            "COPYTO [0]\nCOPYFROM [0]\n",
+            "COPYTO 0\nCOPYFROM 1\n",
+            "COPYTO 0\na:\nCOPYFROM 0\nJUMP a\n",
+            "a:\nJUMPZ a\nJUMP b\nb:\n",
+            "CALL f\nNEG\nf:\nNEG\nRET\n",
+        ] {
+            assert_eq!(None, optimize(&peephole, unchanged), "{unchanged}");
+        }
+    }
+
+    #[test]
+    fn peephole_rule_test() {
+        let peephole = Peephole { rules: vec![] }.add_rule(PeepholeRule::new(
+            &["BUMPUP {x}", "BUMPDN {x}"],
+            &["COPYFROM {x}"],
+        ));
+        assert_eq!(
+            Some(String::from("COPYFROM 3")),
+            optimize(&peephole, "BUMPUP 3\nBUMPDN 3\n")
+        );
+        assert_eq!(None, optimize(&peephole, "BUMPUP 3\nBUMPDN 4\n"));
+        assert_eq!(None, optimize(&peephole, "BUMPUP [3]\nBUMPDN [3]\n"));
+
+        let mut bindings = BTreeMap::new();
+        assert_eq!(Some(()), bind("[{x}]", "[3]", &mut bindings));
+        assert_eq!(None, bind("{x}", "[3]", &mut bindings));
+        assert_eq!(Some(String::from("3")), substitute("{x}", &bindings));
+        assert_eq!(None, substitute("{y}", &bindings));
+    }
+}
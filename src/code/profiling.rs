@@ -0,0 +1,157 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Number of timing buckets, bucket `i` counts executions that took `[2^i, 2^(i + 1))`
+/// nanoseconds, the last one also everything slower
+pub const TIMING_BUCKETS: usize = 16;
+
+/// Command Timing
+///
+/// Wall-clock time spent executing a single command. Timings vary between runs, so timings are
+/// equal if their counts are, keeping scores of identical runs equal.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct CommandTiming {
+    /// Timed executions
+    pub count: u64,
+    /// Total time of all executions
+    pub nanos: u64,
+    /// Executions per duration, see [TIMING_BUCKETS]
+    pub buckets: [u64; TIMING_BUCKETS],
+}
+
+impl CommandTiming {
+    /// Record
+    ///
+    /// Add an execution that took `elapsed`.
+    pub fn record(&mut self, elapsed: Duration) {
+        let nanos = u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX);
+        let bucket = (nanos.max(1).ilog2() as usize).min(TIMING_BUCKETS - 1);
+
+        self.count = self.count.saturating_add(1);
+        self.nanos = self.nanos.saturating_add(nanos);
+        self.buckets[bucket] = self.buckets[bucket].saturating_add(1);
+    }
+
+    /// Merge
+    ///
+    /// Combine timings of another run of the same command into these.
+    pub fn merge(&mut self, other: &CommandTiming) {
+        self.count = self.count.saturating_add(other.count);
+        self.nanos = self.nanos.saturating_add(other.nanos);
+        self.buckets
+            .iter_mut()
+            .zip(&other.buckets)
+            .for_each(|(total, count)| *total = total.saturating_add(*count));
+    }
+
+    /// Mean Nanos
+    ///
+    /// Average time of an execution, `0` if never executed.
+    pub fn mean_nanos(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.nanos as f64 / self.count as f64
+        }
+    }
+}
+
+impl PartialEq for CommandTiming {
+    fn eq(&self, other: &Self) -> bool {
+        self.count == other.count
+    }
+}
+
+/// Profile
+///
+/// [CommandTiming] of every command, by command index. Only collected with the `profiling`
+/// feature, which times each step of the generic execution loop.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    pub commands: Vec<CommandTiming>,
+}
+
+impl Profile {
+    /// Profile of a program with `len` commands
+    pub fn new(len: usize) -> Self {
+        Self {
+            commands: vec![CommandTiming::default(); len],
+        }
+    }
+
+    /// Record
+    ///
+    /// Add an execution of command `i_command` that took `elapsed`.
+    pub fn record(&mut self, i_command: usize, elapsed: Duration) {
+        if let Some(timing) = self.commands.get_mut(i_command) {
+            timing.record(elapsed);
+        }
+    }
+
+    /// Merge
+    ///
+    /// Combine the profile of another run of the same program into this one.
+    pub fn merge(&mut self, other: &Profile) {
+        if self.commands.len() < other.commands.len() {
+            self.commands
+                .resize(other.commands.len(), CommandTiming::default());
+        }
+        self.commands
+            .iter_mut()
+            .zip(&other.commands)
+            .for_each(|(total, timing)| total.merge(timing));
+    }
+
+    /// Total Nanos
+    ///
+    /// Time spent executing all commands.
+    pub fn total_nanos(&self) -> u64 {
+        self.commands
+            .iter()
+            .fold(0u64, |total, timing| total.saturating_add(timing.nanos))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_test() {
+        let mut timing = CommandTiming::default();
+        timing.record(Duration::from_nanos(0));
+        timing.record(Duration::from_nanos(5));
+        timing.record(Duration::from_secs(1));
+
+        assert_eq!(3, timing.count);
+        assert_eq!(1_000_000_005, timing.nanos);
+        assert_eq!(1, timing.buckets[0]);
+        assert_eq!(1, timing.buckets[2]);
+        assert_eq!(1, timing.buckets[TIMING_BUCKETS - 1]);
+        assert_eq!(1_000_000_005.0 / 3.0, timing.mean_nanos());
+        assert_eq!(0.0, CommandTiming::default().mean_nanos());
+
+        let mut other = CommandTiming::default();
+        (0..3).for_each(|_| other.record(Duration::from_nanos(1)));
+        assert_eq!(timing, other);
+    }
+
+    #[test]
+    fn merge_test() {
+        let mut profile = Profile::new(1);
+        profile.record(0, Duration::from_nanos(4));
+        profile.record(3, Duration::from_nanos(4)); // Ignored
+
+        let mut other = Profile::new(2);
+        other.record(0, Duration::from_nanos(2));
+        other.record(1, Duration::from_nanos(8));
+        profile.merge(&other);
+
+        assert_eq!(2, profile.commands.len());
+        assert_eq!(2, profile.commands[0].count);
+        assert_eq!(1, profile.commands[0].buckets[1]);
+        assert_eq!(1, profile.commands[0].buckets[2]);
+        assert_eq!(14, profile.total_nanos());
+    }
+}
@@ -1,137 +1,767 @@
-use std::collections::HashMap;
+use alloc::collections::BTreeMap;
+use alloc::{
+    borrow::Cow,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
 
 use log::{debug, log_enabled, trace, Level};
+use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "profiling")]
+use crate::code::profiling::Profile;
 use crate::{
     code::{
-        commands::{AnyCommand, CommandValue},
-        game_state::GameState,
+        bytecode::{Bytecode, Op},
+        commands::{debug::DebugEvent, AnyCommand, Command, CommandValue},
+        flow_graph::FlowGraph,
+        game_state::{GameState, MemoryStats},
+        state_set::{fingerprint, StateSet},
+        symbol::{Label, SymbolTable},
     },
     game::{
-        problem::{Problem, ProblemIO},
-        value::Value,
+        problem::{OutputMatching, Problem},
+        value::{ArithmeticError, Value, ValueBounds},
     },
 };
 
 pub type Memory = Vec<Option<Value>>;
 
-#[derive(Debug, PartialEq)]
+/// Number of executions per command keyword, e.g. `COPYFROM`. Keywords of a run borrow the
+/// static ones of the commands, deserialized keywords are owned.
+pub type CommandHistogram = BTreeMap<Cow<'static, str>, u64>;
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+#[non_exhaustive]
 pub enum ProgramError {
+    #[error(transparent)]
     Validation(ValidationError),
-    Run(RunError),
+    #[error(transparent)]
+    Run(FailureReport),
+}
+
+impl ProgramError {
+    /// Code
+    ///
+    /// Returns the stable error code of the underlying error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ProgramError::Validation(err) => err.code(),
+            ProgramError::Run(report) => report.error.code(),
+        }
+    }
 }
 
-#[derive(Debug, PartialEq)]
+/// Validation Error
+///
+/// Error codes `E02xx`, see [ValidationError::code].
+#[derive(Debug, PartialEq, thiserror::Error)]
+#[non_exhaustive]
 pub enum ValidationError {
+    #[error("Command `{0}` is not available in this problem")]
     CommandNotAvailable(String),
+    #[error("Tile {0} does not exist")]
     CommandIndex(usize),
-    MissingLabel(String),
+    #[error("Label `{0}` is not defined")]
+    MissingLabel(Label),
+    /// Label defined more than once, the last definition is used
+    #[error("Label `{0}` is defined more than once")]
+    DuplicateLabel(Label),
+    #[error("Label points to command {0}, past the end of the program")]
     LabelIndex(usize),
+    /// Command references a tile that doesn't exist, see [Problem::get_holes]
+    #[error("Tile {0} is a hole")]
+    FloorHole(usize),
+    /// Command used more often than the problem allows
+    #[error("Command `{command}` is used {used} times, at most {limit} allowed")]
+    CommandLimitExceeded {
+        command: String,
+        limit: usize,
+        used: usize,
+    },
 }
 
-#[derive(Debug, PartialEq)]
+impl ValidationError {
+    /// Code
+    ///
+    /// Returns the stable error code, e.g. `E0201`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ValidationError::CommandNotAvailable(_) => "E0201",
+            ValidationError::CommandIndex(_) => "E0202",
+            ValidationError::MissingLabel(_) => "E0203",
+            ValidationError::DuplicateLabel(_) => "E0204",
+            ValidationError::LabelIndex(_) => "E0205",
+            ValidationError::FloorHole(_) => "E0206",
+            ValidationError::CommandLimitExceeded { .. } => "E0207",
+        }
+    }
+}
+
+/// Run Error
+///
+/// Error codes `E03xx`, see [RunError::code].
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[non_exhaustive]
 pub enum RunError {
+    #[error("Nothing in hands")]
     EmptyAcc,
+    #[error("Tile is empty")]
     EmptyMemory,
-    IncorrectOutput {
-        expected: Option<Value>,
-        value: Option<Value>,
+    /// Value sent to the outbox at output `index` differs from the expected one
+    #[error("Output {index}: expected {expected}, got {value}")]
+    WrongOutput {
+        index: usize,
+        expected: Value,
+        value: Value,
+    },
+    /// Value sent to the outbox when no more outputs (of that value for
+    /// [OutputMatching::Multiset]) are expected
+    #[error("Output {index}: no output expected, got {value}")]
+    ExtraOutput { index: usize, value: Value },
+    /// Program ended with `remaining` outputs missing, starting at output `index`
+    #[error("Output {index}: program ended with {remaining} output(s) missing, expected {expected} next")]
+    MissingOutput {
+        index: usize,
+        remaining: usize,
+        expected: Value,
     },
+    #[error("Tile index must be a number, got {0}")]
     CharIndex(Value),
+    #[error("Tile {0} does not exist")]
     IndexOutOfRange(Value),
+    #[error("Command {0} does not exist")]
+    JumpOutOfRange(Value),
+    #[error("Value {0} is out of bounds")]
+    Overflow(Value),
+    /// Access to a tile that doesn't exist, see [Problem::get_holes]
+    #[error("Tile {0} is a hole")]
+    FloorHole(usize),
+    #[error("Cannot add a letter & a number")]
     Add,
+    #[error("Cannot subtract a letter & a number")]
     Sub,
+    #[error("Cannot multiply letters")]
+    Mul,
+    #[error("Cannot divide letters")]
+    Div,
+    #[error("Cannot take the remainder of letters")]
+    Mod,
+    #[error("Cannot negate a letter")]
+    Neg,
+    #[error("Division by zero")]
+    DivideByZero,
+    #[error("Stack is empty")]
+    EmptyStack,
+    #[error("Return without a matching call")]
+    EmptyCallStack,
+    #[error("Call depth exceeds {depth}")]
+    CallStackOverflow { depth: usize },
+    /// More steps executed than allowed, see [Program::execute]
+    #[error("Program did not end within {0} steps")]
+    StepLimit(u32),
+    /// Jump to a label that doesn't exist, see [Program::validate]
+    #[error("Label `{0}` is not defined")]
+    UndefinedLabel(Label),
+    /// Run reached a state it was in before, see [RunLimits::detect_loops]
+    #[error("Program repeats a state & never ends")]
+    InfiniteLoop,
+    /// More steps executed over all IOs than allowed, see [RunLimits::max_total_steps]
+    #[error("Program did not finish every IO within {0} steps")]
+    TotalStepLimit(u64),
+}
+
+impl RunError {
+    /// Code
+    ///
+    /// Returns the stable error code, e.g. `E0301`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            RunError::EmptyAcc => "E0301",
+            RunError::EmptyMemory => "E0302",
+            RunError::WrongOutput { .. } => "E0303",
+            RunError::ExtraOutput { .. } => "E0304",
+            RunError::MissingOutput { .. } => "E0305",
+            RunError::CharIndex(_) => "E0306",
+            RunError::IndexOutOfRange(_) => "E0307",
+            RunError::JumpOutOfRange(_) => "E0308",
+            RunError::Overflow(_) => "E0309",
+            RunError::FloorHole(_) => "E0310",
+            RunError::Add => "E0311",
+            RunError::Sub => "E0312",
+            RunError::Mul => "E0313",
+            RunError::Div => "E0314",
+            RunError::Mod => "E0315",
+            RunError::Neg => "E0316",
+            RunError::DivideByZero => "E0317",
+            RunError::EmptyStack => "E0318",
+            RunError::EmptyCallStack => "E0319",
+            RunError::CallStackOverflow { .. } => "E0320",
+            RunError::StepLimit(_) => "E0321",
+            RunError::UndefinedLabel(_) => "E0322",
+            RunError::InfiniteLoop => "E0323",
+            RunError::TotalStepLimit(_) => "E0324",
+        }
+    }
 }
 
-#[derive(Debug, PartialEq)]
+/// Score
+///
+/// Result of a successful run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Score {
     pub size: usize,
-    pub speed_min: u32,
-    pub speed_max: u32,
+    /// Weighted cycles, see [Command::cost]
+    pub speed_min: u64,
+    pub speed_max: u64,
     pub speed_avg: f64,
+    /// Executed commands, regardless of their cost
+    pub steps_min: u64,
+    pub steps_max: u64,
+    pub steps_avg: f64,
+    /// Largest stack depth reached in any IO
+    pub stack_max: usize,
+    /// Speed of each IO, in the problem's IO order
+    pub speeds: Vec<u64>,
+    /// Index of the first IO with speed [Score::speed_min]
+    pub i_speed_min: usize,
+    /// Index of the first IO with speed [Score::speed_max]
+    pub i_speed_max: usize,
+    /// Steps of each IO, in the problem's IO order
+    pub steps: Vec<u64>,
+    /// Executed commands of each IO, in the problem's IO order
+    pub histograms: Vec<CommandHistogram>,
+    /// Whether each command was reached in any IO, by command index
+    pub coverage: Vec<bool>,
+    /// Executions summed over all IOs, by command index
+    pub executions: Vec<u64>,
+    /// Floor usage merged over all IOs
+    pub memory: MemoryStats,
+    /// Timings merged over all IOs
+    #[cfg(feature = "profiling")]
+    pub profile: Profile,
+}
+
+impl Score {
+    /// Histogram
+    ///
+    /// Returns executed commands summed over all IOs.
+    pub fn histogram(&self) -> CommandHistogram {
+        let mut total = CommandHistogram::new();
+        for histogram in &self.histograms {
+            for (command, &count) in histogram {
+                *total.entry(command.clone()).or_default() += count;
+            }
+        }
+        total
+    }
+
+    /// Evaluate
+    ///
+    /// Check the score against the problem's size & speed challenges.
+    pub fn evaluate(&self, problem: &Problem) -> ChallengeResult {
+        self.meets(&ScoreTarget::from(problem))
+    }
+
+    /// Meets
+    ///
+    /// Check the score against given size & speed targets. Speed is compared on average.
+    pub fn meets(&self, target: &ScoreTarget) -> ChallengeResult {
+        ChallengeResult {
+            size: target.size.map(|size| self.size <= size),
+            speed: target.speed.map(|speed| self.speed_avg <= speed as f64),
+        }
+    }
 }
 
-#[derive(Debug, Default)]
+/// Score Target
+///
+/// Size & speed challenges, [None] if there is no such challenge.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ScoreTarget {
+    pub size: Option<usize>,
+    pub speed: Option<u64>,
+}
+
+impl From<&Problem> for ScoreTarget {
+    fn from(value: &Problem) -> Self {
+        Self {
+            size: value.get_size_target(),
+            speed: value.get_speed_target(),
+        }
+    }
+}
+
+/// Challenge Result
+///
+/// Whether each challenge was met, [None] if there is no such challenge.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ChallengeResult {
+    pub size: Option<bool>,
+    pub speed: Option<bool>,
+}
+
+impl ChallengeResult {
+    /// Passed
+    ///
+    /// Returns `true` if every existing challenge was met.
+    pub fn passed(&self) -> bool {
+        self.size != Some(false) && self.speed != Some(false)
+    }
+}
+
+/// Coverage
+///
+/// Command indices reached & never reached during a run, see [Program::coverage].
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Coverage {
+    pub executed: Vec<usize>,
+    pub unexecuted: Vec<usize>,
+}
+
+impl Coverage {
+    /// Unexecuted Lines
+    ///
+    /// Returns source lines (0-based) of never reached commands, see [Program::get_line].
+    pub fn unexecuted_lines(&self, program: &Program) -> Vec<usize> {
+        let mut lines: Vec<usize> = self
+            .unexecuted
+            .iter()
+            .filter_map(|&i| program.get_line(i))
+            .collect();
+        lines.dedup();
+        lines
+    }
+}
+
+/// Profile Entry
+///
+/// Execution count of a single command, see [Program::profile].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfileEntry {
+    pub i_command: usize,
+    /// Source line (0-based), see [Program::get_line]
+    pub line: Option<usize>,
+    pub source: String,
+    pub count: u64,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct Program {
-    // todo: add comments & defines - verify them
     commands: Vec<AnyCommand>,
-    labels: HashMap<String, usize>,
+    /// Defined labels, interned per program
+    symbols: SymbolTable,
+    /// Label definitions by symbol of `symbols`, kept for validation, diagnostics & printing -
+    /// never read while running
+    labels: Vec<usize>,
+    comments: Vec<(usize, u32)>,
+    /// Drawings of comments & tile labels, kept for exporting, see [Define]
+    defines: Vec<Define>,
+    /// Jump targets resolved from `labels` in [ProgramBuilder::build]
+    targets: Vec<Option<usize>>,
+    /// Source line (0-based) of each command, empty if not compiled from source
+    lines: Vec<usize>,
+    /// Labels defined more than once, see [ValidationError::DuplicateLabel]
+    duplicate_labels: Vec<Label>,
+    bytecode: Bytecode,
 }
 
 impl Program {
+    /// Program without commands
+    pub(crate) const EMPTY: Program = Program {
+        commands: Vec::new(),
+        symbols: SymbolTable::EMPTY,
+        labels: Vec::new(),
+        comments: Vec::new(),
+        defines: Vec::new(),
+        targets: Vec::new(),
+        lines: Vec::new(),
+        duplicate_labels: Vec::new(),
+        bytecode: Bytecode::EMPTY,
+    };
+
+    pub fn get_commands(&self) -> &Vec<AnyCommand> {
+        &self.commands
+    }
+
+    /// Get Labels
+    ///
+    /// Get label definitions as pairs of (label, index of the following command), in name order.
+    pub fn get_labels(&self) -> impl Iterator<Item = (&Label, usize)> {
+        self.symbols
+            .iter()
+            .map(|(symbol, label)| (label, self.labels[symbol.index()]))
+    }
+
+    /// Get Line
+    ///
+    /// Returns the source line (0-based) the command was compiled from, [None] if the program was
+    /// not compiled from source.
+    pub fn get_line(&self, i_command: usize) -> Option<usize> {
+        self.lines.get(i_command).copied()
+    }
+
+    /// Coverage
+    ///
+    /// Split commands into those reached in at least one IO of the run & those never reached.
+    /// Debug commands are ignored.
+    pub fn coverage(&self, score: &Score) -> Coverage {
+        let (executed, unexecuted) = (0..self.commands.len())
+            .filter(|&i| !self.commands[i].is_debug())
+            .partition(|&i| score.coverage.get(i).copied().unwrap_or(false));
+        Coverage {
+            executed,
+            unexecuted,
+        }
+    }
+
+    /// Profile
+    ///
+    /// Returns executed commands ordered from the hottest, i.e. most executed over all IOs of the
+    /// run. Ties are ordered by command index.
+    pub fn profile(&self, score: &Score) -> Vec<ProfileEntry> {
+        let mut entries: Vec<ProfileEntry> = score
+            .executions
+            .iter()
+            .enumerate()
+            .filter(|(_, &count)| count > 0)
+            .map(|(i_command, &count)| ProfileEntry {
+                i_command,
+                line: self.get_line(i_command),
+                source: self.commands[i_command].to_source(),
+                count,
+            })
+            .collect();
+        entries.sort_by(|a, b| b.count.cmp(&a.count).then(a.i_command.cmp(&b.i_command)));
+        entries
+    }
+
+    /// To DOT
+    ///
+    /// Returns the control-flow graph in Graphviz DOT, see [FlowGraph::to_dot].
+    pub fn to_dot(&self) -> String {
+        FlowGraph::new(self).to_dot()
+    }
+
+    /// To Mermaid
+    ///
+    /// Returns the control-flow graph as a Mermaid flowchart, see [FlowGraph::to_mermaid].
+    pub fn to_mermaid(&self) -> String {
+        FlowGraph::new(self).to_mermaid()
+    }
+
+    /// Get Comments
+    ///
+    /// Get comments as pairs of (index of the following command, comment id).
+    pub fn get_comments(&self) -> &Vec<(usize, u32)> {
+        &self.comments
+    }
+
+    /// Get Defines
+    ///
+    /// Get drawings of comments & tile labels, in source order.
+    pub fn get_defines(&self) -> &Vec<Define> {
+        &self.defines
+    }
+
     /// Get Label
     ///
-    /// Get label's index.
+    /// Get label's index, [None] if the label does not exist. Running a program never looks up
+    /// labels, jumps use targets resolved at build time, see [Program::get_target].
+    pub fn get_label(&self, label: &str) -> Option<usize> {
+        self.symbols
+            .lookup(label)
+            .map(|symbol| self.labels[symbol.index()])
+    }
+
+    /// Get Target
+    ///
+    /// Get the resolved jump target of the command at index `i_command`. Returns
+    /// [RunError::UndefinedLabel] if the command does not jump or its label does not exist, which
+    /// [Program::validate] rules out for jump commands.
+    pub fn get_target(&self, i_command: usize) -> Result<usize, RunError> {
+        self.targets
+            .get(i_command)
+            .copied()
+            .flatten()
+            .ok_or_else(|| {
+                let label = self
+                    .commands
+                    .get(i_command)
+                    .and_then(|command| command.requires_label())
+                    .cloned()
+                    .unwrap_or_default();
+                RunError::UndefinedLabel(label)
+            })
+    }
+
+    /// Get Bytecode
     ///
-    /// # Panics
+    /// Get the [Bytecode] the program is executed with.
+    pub fn get_bytecode(&self) -> &Bytecode {
+        &self.bytecode
+    }
+
+    /// Get Targets
     ///
-    /// Panics if the label does not exist. Will NEVER panic if the program
-    /// is validated with [Program::validate].
-    pub fn get_label(&self, label: &str) -> usize {
-        *self.labels.get(label).unwrap() // safe if program is validated
+    /// Get resolved jump targets by command index, [None] for commands without a (resolved) label.
+    pub fn get_targets(&self) -> &Vec<Option<usize>> {
+        &self.targets
     }
 
     /// Validate
     ///
-    /// Validate [Program] for the given [Problem].
+    /// Validate [Program] for the given [Problem]. Returns the first issue found, see
+    /// [Program::validate_all].
     pub fn validate(&self, problem: &Problem) -> Result<(), ProgramError> {
+        match self.validate_all(problem).into_iter().next() {
+            Some(err) => Err(ProgramError::Validation(err)),
+            None => Ok(()),
+        }
+    }
+
+    /// Validate All
+    ///
+    /// Validate [Program] for the given [Problem], returning every issue found - in command
+    /// order, followed by exceeded command limits & label issues. Empty if the program is valid.
+    pub fn validate_all(&self, problem: &Problem) -> Vec<ValidationError> {
+        self.validation_issues(problem)
+            .into_iter()
+            .map(|(_, err)| err)
+            .collect()
+    }
+
+    /// Validation Issues
+    ///
+    /// See [Program::validate_all], paired with the index of the offending command if there is one.
+    pub(crate) fn validation_issues(
+        &self,
+        problem: &Problem,
+    ) -> Vec<(Option<usize>, ValidationError)> {
         debug!("Validating problem");
+        let mut errors = vec![];
+
+        // A command is supported if a factory of the instruction set creates it, args included
+        let factories = problem.get_instruction_set().factories();
+        let supported = |command: &AnyCommand| {
+            let keyword = command.factory().command();
+            let args = command.args().unwrap_or_default();
+            factories
+                .iter()
+                .filter(|factory| factory.command() == keyword)
+                .any(|factory| factory.create(&args).is_some())
+        };
 
         // Validate commands
-        for command in &self.commands {
-            trace!("Validating command: {:?}", command);
-            // todo
+        for (i, command) in self.commands.iter().enumerate() {
+            trace!("Validating command: {}", command.to_source());
             let command_type = command.factory().command();
-            if !problem.is_command_available(command_type) {
-                return Err(ProgramError::Validation(
+            if !command.is_debug() && !problem.is_command_available(command_type) {
+                errors.push((
+                    Some(i),
                     ValidationError::CommandNotAvailable(command_type.to_string()),
                 ));
+            } else if !command.is_debug() && !supported(command) {
+                // Extended form of an available command, e.g. `JUMP [addr]` in a vanilla problem
+                errors.push((
+                    Some(i),
+                    ValidationError::CommandNotAvailable(command.to_source()),
+                ));
+            }
+
+            if let Some(idx) = command.requires_index().or(command.tile()) {
+                if !problem.has_unbounded_memory() && idx >= problem.get_memory().len() {
+                    errors.push((Some(i), ValidationError::CommandIndex(idx)));
+                }
             }
 
-            if let Some(idx) = command.requires_index() {
-                if idx >= problem.get_memory().len() {
-                    return Err(ProgramError::Validation(ValidationError::CommandIndex(idx)));
+            if let Some(idx) = command.tile() {
+                if problem.is_hole(idx) {
+                    errors.push((Some(i), ValidationError::FloorHole(idx)));
                 }
             }
 
+            // Labels were resolved once by the builder, so no label lookups are needed
             if let Some(label) = command.requires_label() {
-                if !self.labels.contains_key(label) {
-                    return Err(ProgramError::Validation(ValidationError::MissingLabel(
-                        label.to_string(),
-                    )));
+                if self.targets.get(i).copied().flatten().is_none() {
+                    errors.push((Some(i), ValidationError::MissingLabel(label.clone())));
                 }
             }
         }
 
+        // Validate command limits
+        let mut limits: Vec<(&String, &usize)> = problem.get_command_limits().iter().collect();
+        limits.sort();
+        for (command, &limit) in limits {
+            let used = self
+                .commands
+                .iter()
+                .filter(|c| !c.is_debug() && c.factory().command() == command)
+                .count();
+            if used > limit {
+                errors.push((
+                    None,
+                    ValidationError::CommandLimitExceeded {
+                        command: command.clone(),
+                        limit,
+                        used,
+                    },
+                ));
+            }
+        }
+
         // Validate labels
-        for (label, &idx) in &self.labels {
+        errors.extend(
+            self.duplicate_labels
+                .iter()
+                .map(|label| (None, ValidationError::DuplicateLabel(label.clone()))),
+        );
+        let mut labels: Vec<(usize, &str)> = self
+            .get_labels()
+            .map(|(label, idx)| (idx, label.as_str()))
+            .collect();
+        labels.sort();
+        for (idx, label) in labels {
             trace!("Validating label: {} => {}", label, idx);
             if idx > self.commands.len() {
-                return Err(ProgramError::Validation(ValidationError::LabelIndex(idx)));
+                errors.push((None, ValidationError::LabelIndex(idx)));
             }
         }
 
-        debug!("Successfully validated program");
+        if errors.is_empty() {
+            debug!("Successfully validated program");
+        }
 
-        Ok(())
+        errors
     }
 
     /// Run code
     ///
-    /// Run [Program] for given [Problem].
+    /// Run [Program] for given [Problem]. Debug commands are logged with [log::debug]. On failure,
+    /// returns a [FailureReport] with the failing IO & the game state at the moment of failure.
+    ///
+    /// Labels are not guaranteed to exist without running [Program::validate], jumping to a
+    /// missing label fails with [RunError::UndefinedLabel].
+    pub fn run(&self, problem: &Problem) -> Result<Score, FailureReport> {
+        self.run_with_debug(problem, &mut |event| debug!("{event}"))
+    }
+
+    /// Run with limits
+    ///
+    /// Run [Program] for given [Problem], failing IOs that exceed the [RunLimits]. See
+    /// [Program::run].
+    pub fn run_with_limits(
+        &self,
+        problem: &Problem,
+        limits: RunLimits,
+    ) -> Result<Score, FailureReport> {
+        self.run_checked(problem, limits, &mut |event| debug!("{event}"))
+    }
+
+    /// Run code with debug callback
+    ///
+    /// Run [Program] for given [Problem], passing every [DebugEvent] to `on_debug`. See
+    /// [Program::run].
+    pub fn run_with_debug(
+        &self,
+        problem: &Problem,
+        on_debug: &mut dyn FnMut(&DebugEvent),
+    ) -> Result<Score, FailureReport> {
+        self.run_checked(problem, RunLimits::default(), on_debug)
+    }
+
+    /// Run each
     ///
-    /// # Panics
+    /// Run every IO of [Problem] on its own, unlike [Program::run] which stops at the first
+    /// failing IO. Returns the [IOScore] or [FailureReport] of each IO, in order.
+    pub fn run_each(&self, problem: &Problem) -> Vec<Result<IOScore, FailureReport>> {
+        self.run_each_with_limits(problem, RunLimits::default())
+    }
+
+    /// Run each with limits
     ///
-    /// Labels are not guaranteed to exist without running [Program::validate], which can cause
-    /// program to panic when unwrapping.
-    pub fn run(&self, problem: &Problem) -> Result<Score, RunError> {
+    /// See [Program::run_each], failing IOs that exceed the [RunLimits].
+    pub fn run_each_with_limits(
+        &self,
+        problem: &Problem,
+        limits: RunLimits,
+    ) -> Vec<Result<IOScore, FailureReport>> {
+        let mut buffer = Memory::with_capacity(problem.get_memory().len());
+        let mut used = 0;
+        problem
+            .get_ios()
+            .iter()
+            .enumerate()
+            .map(|(i, problem_io)| {
+                self.run_io(
+                    problem,
+                    &problem_io.input,
+                    Some(&problem_io.output),
+                    limits,
+                    &mut used,
+                    &mut |event| debug!("{event}"),
+                    &mut buffer,
+                )
+                .map(|io_run| IOScore {
+                    speed: io_run.speed,
+                    steps: self.histogram(&io_run.counters.executions).values().sum(),
+                })
+                .map_err(|report| FailureReport { i_io: i, ..report })
+            })
+            .collect()
+    }
+
+    fn run_checked(
+        &self,
+        problem: &Problem,
+        limits: RunLimits,
+        on_debug: &mut dyn FnMut(&DebugEvent),
+    ) -> Result<Score, FailureReport> {
         if log_enabled!(Level::Debug) {
             debug!("Running program");
         }
 
-        let (mut speed_min, mut speed_max, mut speed_avg) = (u32::MAX, 0, 0);
-        for problem_io in problem.get_ios() {
-            let speed = self.run_io(problem_io, problem.get_memory().clone())?;
+        let (mut speed_min, mut speed_max, mut speed_avg) = (u64::MAX, 0u64, 0u64);
+        let (mut i_speed_min, mut i_speed_max) = (0, 0);
+        let mut stack_max = 0;
+        let mut speeds = Vec::with_capacity(problem.get_ios().len());
+        let mut steps: Vec<u64> = Vec::with_capacity(problem.get_ios().len());
+        let mut histograms = Vec::with_capacity(problem.get_ios().len());
+        let mut coverage = vec![false; self.commands.len()];
+        let mut executions = vec![0u64; self.commands.len()];
+        let mut memory = MemoryStats::default();
+        #[cfg(feature = "profiling")]
+        let mut profile = Profile::new(self.commands.len());
+        let mut buffer = Memory::with_capacity(problem.get_memory().len());
+        let mut used = 0;
+        for (i, problem_io) in problem.get_ios().iter().enumerate() {
+            let io_run = self
+                .run_io(
+                    problem,
+                    &problem_io.input,
+                    Some(&problem_io.output),
+                    limits,
+                    &mut used,
+                    on_debug,
+                    &mut buffer,
+                )
+                .map_err(|report| FailureReport { i_io: i, ..report })?;
+            let speed = io_run.speed;
+            stack_max = stack_max.max(io_run.stack_max);
+            coverage
+                .iter_mut()
+                .zip(&io_run.counters.reached)
+                .for_each(|(covered, reached)| *covered |= reached);
+            executions
+                .iter_mut()
+                .zip(&io_run.counters.executions)
+                .for_each(|(total, count)| *total = total.saturating_add(*count));
+            memory.merge(&io_run.memory_stats);
+            #[cfg(feature = "profiling")]
+            profile.merge(&io_run.counters.profile);
+            let histogram = self.histogram(&io_run.counters.executions);
 
             if log_enabled!(Level::Debug) {
                 debug!("Program ended, speed = {speed}");
@@ -139,63 +769,470 @@ impl Program {
 
             if speed > speed_max {
                 speed_max = speed;
+                i_speed_max = i;
             }
 
             if speed < speed_min {
                 speed_min = speed;
+                i_speed_min = i;
             }
 
-            speed_avg += speed;
+            speed_avg = speed_avg.saturating_add(speed);
+            speeds.push(speed);
+            steps.push(histogram.values().sum());
+            histograms.push(histogram);
         }
 
         if log_enabled!(Level::Debug) {
             debug!("Successfully finished problem for all IOs");
         }
 
+        let ios = problem.get_ios().len() as f64;
         Ok(Score {
-            size: self.commands.len(),
+            size: self.size(),
             speed_min,
             speed_max,
-            speed_avg: (speed_avg as f64) / (problem.get_ios().len() as f64),
+            speed_avg: (speed_avg as f64) / ios,
+            steps_min: steps.iter().copied().min().unwrap_or(u64::MAX),
+            steps_max: steps.iter().copied().max().unwrap_or(0),
+            steps_avg: steps
+                .iter()
+                .fold(0u64, |sum, &steps| sum.saturating_add(steps)) as f64
+                / ios,
+            stack_max,
+            speeds,
+            i_speed_min,
+            i_speed_max,
+            steps,
+            histograms,
+            coverage,
+            executions,
+            memory,
+            #[cfg(feature = "profiling")]
+            profile,
+        })
+    }
+
+    fn histogram(&self, executions: &[u64]) -> CommandHistogram {
+        let mut histogram = CommandHistogram::new();
+        for (command, &count) in self.commands.iter().zip(executions) {
+            if count > 0 {
+                *histogram
+                    .entry(Cow::Borrowed(command.factory().command()))
+                    .or_default() += count;
+            }
+        }
+        histogram
+    }
+
+    /// Size
+    ///
+    /// Number of commands as shown by the game, i.e. after expanding pseudo instructions &
+    /// excluding labels, comments & debug commands.
+    pub fn size(&self) -> usize {
+        self.size_histogram().values().sum::<u64>() as usize
+    }
+
+    /// Size Histogram
+    ///
+    /// Breakdown of [Program::size] by command keyword.
+    pub fn size_histogram(&self) -> CommandHistogram {
+        let mut histogram = CommandHistogram::new();
+        for command in self.commands.iter().filter(|command| !command.is_debug()) {
+            *histogram
+                .entry(Cow::Borrowed(command.factory().command()))
+                .or_default() += 1;
+        }
+        histogram
+    }
+
+    /// Execute
+    ///
+    /// Run [Program] on a single input without checking its outputs. Returns:
+    /// - [Ok(Execution)] with the produced outputs & speed
+    /// - [Err(RunError)] if the run fails, e.g. [RunError::StepLimit] after `max_steps` steps
+    pub fn execute(
+        &self,
+        problem: &Problem,
+        input: &[Value],
+        max_steps: u32,
+    ) -> Result<Execution, RunError> {
+        let limits = RunLimits {
+            max_steps: Some(max_steps),
+            ..Default::default()
+        };
+        self.execute_with_limits(problem, input, limits)
+    }
+
+    /// Execute with limits
+    ///
+    /// See [Program::execute]. Without [RunLimits::max_steps], a program that never ends is only
+    /// stopped by [RunLimits::detect_loops], if it repeats a state.
+    pub fn execute_with_limits(
+        &self,
+        problem: &Problem,
+        input: &[Value],
+        limits: RunLimits,
+    ) -> Result<Execution, RunError> {
+        let input = input.to_vec();
+        let io_run = self
+            .run_io(
+                problem,
+                &input,
+                None,
+                limits,
+                &mut 0,
+                &mut |_| {},
+                &mut Memory::new(),
+            )
+            .map_err(|report| report.error)?;
+        Ok(Execution {
+            output: io_run.produced,
+            speed: io_run.speed,
         })
     }
 
-    fn run_io(&self, problem_io: &ProblemIO, memory: Memory) -> Result<u32, RunError> {
+    /// Run IO
+    ///
+    /// Run a single IO, checking produced values against `output` unless it's [None]. `memory` is
+    /// reset in place from the problem's memory, so its allocation is reused across IOs, & handed
+    /// back once the run succeeds. `used` counts steps of the run's IOs so far, see
+    /// [RunLimits::max_total_steps].
+    #[allow(clippy::too_many_arguments)]
+    fn run_io(
+        &self,
+        problem: &Problem,
+        input: &Vec<Value>,
+        output: Option<&Vec<Value>>,
+        limits: RunLimits,
+        used: &mut u64,
+        on_debug: &mut dyn FnMut(&DebugEvent),
+        memory: &mut Memory,
+    ) -> Result<IORun, FailureReport> {
         if log_enabled!(Level::Debug) {
             debug!("Running program for new IO");
         }
-        let mut game_state = GameState::new(&problem_io.input, &problem_io.output, memory);
+        memory.clone_from(problem.get_memory());
+        let unchecked = vec![];
+        let mut game_state =
+            GameState::new(input, output.unwrap_or(&unchecked), core::mem::take(memory));
+        game_state.output_matching = problem.get_output_matching();
+        game_state.bounds = problem.get_value_bounds();
+        game_state.holes = problem.get_holes().to_vec();
+        game_state.unbounded_memory = problem.has_unbounded_memory();
+        game_state.check_output = output.is_some();
+        let mut counters = RunCounters::new(self.commands.len());
+
+        let (io_limits, budgeted) = limits.for_io(*used);
+        let result = self.run_state(problem, &mut game_state, io_limits, on_debug, &mut counters);
+        *used = counters
+            .executions
+            .iter()
+            .fold(*used, |used, &count| used.saturating_add(count));
+        let result = match (result, limits.max_total_steps) {
+            (Err(RunError::StepLimit(_)), Some(total)) if budgeted => {
+                Err(RunError::TotalStepLimit(total))
+            }
+            (result, _) => result,
+        };
+
+        match result {
+            Ok(()) => {
+                *memory = game_state.memory;
+                Ok(IORun {
+                    speed: game_state.speed,
+                    stack_max: game_state.stack_max,
+                    counters,
+                    memory_stats: game_state.memory_stats,
+                    produced: game_state.produced,
+                })
+            }
+            Err(error) => Err(FailureReport {
+                error,
+                i_io: 0,
+                input: input.clone(),
+                i_command: game_state.i_command,
+                acc: game_state.acc,
+                memory: game_state.memory,
+                inputs_consumed: game_state.i_input,
+                outputs_produced: game_state.produced.len(),
+            }),
+        }
+    }
+
+    fn run_state(
+        &self,
+        problem: &Problem,
+        game_state: &mut GameState,
+        limits: RunLimits,
+        on_debug: &mut dyn FnMut(&DebugEvent),
+        counters: &mut RunCounters,
+    ) -> Result<(), RunError> {
+        // Traces, loop detection & profiling need the generic loop
+        let int_program = self.bytecode.get_int_program().filter(|_| {
+            !limits.detect_loops && !log_enabled!(Level::Trace) && !cfg!(feature = "profiling")
+        });
+        let fast = int_program.and_then(|int_program| {
+            let (executions, reached) = (&mut counters.executions, &mut counters.reached);
+            int_program.run(game_state, limits.max_steps, executions, reached)
+        });
+        match fast {
+            Some(result) => result?,
+            None => self.run_commands(problem, game_state, limits, on_debug, counters)?,
+        }
+
+        self.check_finished(game_state)
+    }
+
+    fn run_commands(
+        &self,
+        problem: &Problem,
+        game_state: &mut GameState,
+        limits: RunLimits,
+        on_debug: &mut dyn FnMut(&DebugEvent),
+        counters: &mut RunCounters,
+    ) -> Result<(), RunError> {
+        let mut steps = 0;
+        let mut states = StateSet::new();
 
         while game_state.i_command < self.commands.len() {
-            game_state.speed += 1;
-            let command = &self.commands[game_state.i_command];
-            trace!("Running command {}: {:?}", game_state.i_command, command);
+            let command = self.commands[game_state.i_command].as_ref();
+            let op = self.bytecode.get_op(game_state.i_command);
+            if matches!(op, Op::Debug | Op::Dynamic) {
+                if let Some(event) = command.debug(game_state) {
+                    on_debug(&event);
+                    game_state.i_command += 1;
+                    continue;
+                }
+            }
+
+            if let Some(max_steps) = limits.max_steps {
+                if steps == max_steps {
+                    return Err(RunError::StepLimit(max_steps));
+                }
+            }
+
+            trace!(
+                "Running command {}: {}{}",
+                game_state.i_command,
+                command.to_source(),
+                tile_alias(problem, command)
+            );
+
+            let i_command = game_state.i_command;
+            counters.reached[i_command] = true;
+            #[cfg(feature = "profiling")]
+            let start = std::time::Instant::now();
+            op.execute(self, command, game_state)?;
+            if game_state.halted {
+                break; // Ended on Inbox or Halt - not counted
+            }
+            game_state.speed = game_state.speed.saturating_add(op.cost(command) as u64);
+            counters.executions[i_command] = counters.executions[i_command].saturating_add(1);
+            steps += 1;
+            game_state.i_command = op.next(self, command, game_state)?;
+            #[cfg(feature = "profiling")]
+            counters.profile.record(i_command, start.elapsed());
 
-            command.execute(self, &mut game_state)?;
-            game_state.i_command = command
-                .next(self, &game_state)
-                .unwrap_or_else(|| usize::MAX);
+            // A run that never ends keeps jumping back, so only states at jump targets are kept
+            if limits.detect_loops
+                && game_state.i_command <= i_command
+                && !states.insert(fingerprint(game_state))
+            {
+                return Err(RunError::InfiniteLoop);
+            }
         }
 
-        if game_state.i_output == game_state.output.len() {
-            let speed_delta = if game_state.i_command == self.commands.len() {
-                debug!("No more commands to execute");
-                0 // No more commands to be executed
+        Ok(())
+    }
+
+    /// Check Finished
+    ///
+    /// Once the run ended, fails with [RunError::MissingOutput] unless every output was produced.
+    pub(crate) fn check_finished(&self, game_state: &GameState) -> Result<(), RunError> {
+        let output_matched = match game_state.output_matching {
+            _ if !game_state.check_output => true,
+            OutputMatching::Exact | OutputMatching::Multiset => {
+                game_state.i_output == game_state.output.len()
+            }
+            OutputMatching::Prefix => true,
+        };
+
+        if output_matched {
+            if game_state.halted {
+                debug!("Execution halted");
             } else {
-                debug!("No more inputs to consume");
-                1 // Ended on Inbox - remove from count
-            };
+                debug!("No more commands to execute");
+            }
 
-            Ok(game_state.speed - speed_delta)
+            Ok(())
         } else {
-            Err(RunError::IncorrectOutput {
-                expected: Some(game_state.output[game_state.i_output]),
-                value: None,
+            Err(RunError::MissingOutput {
+                index: game_state.i_output,
+                remaining: game_state.output.len() - game_state.i_output,
+                expected: game_state.output[game_state.i_output],
             })
         }
     }
 }
 
+/// Run Limits
+///
+/// Guards against programs that never end. By default, runs are unlimited.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RunLimits {
+    /// Fail with [RunError::StepLimit] after this many steps
+    pub max_steps: Option<u32>,
+    /// Fail with [RunError::InfiniteLoop] once a state repeats. States are remembered by their
+    /// [fingerprint] only at backward jumps, keeping the overhead low on long runs.
+    pub detect_loops: bool,
+    /// Fail with [RunError::TotalStepLimit] after this many steps over all IOs of a run
+    pub max_total_steps: Option<u64>,
+}
+
+impl RunLimits {
+    /// Limits of the next IO once the previous IOs took `used` steps. Returns whether the
+    /// remaining total steps are the tighter step limit.
+    fn for_io(self, used: u64) -> (RunLimits, bool) {
+        let Some(total) = self.max_total_steps else {
+            return (self, false);
+        };
+        let remaining = u32::try_from(total.saturating_sub(used)).unwrap_or(u32::MAX);
+        if self
+            .max_steps
+            .is_some_and(|max_steps| max_steps <= remaining)
+        {
+            return (self, false);
+        }
+
+        let limits = RunLimits {
+            max_steps: Some(remaining),
+            ..self
+        };
+        (limits, true)
+    }
+}
+
+/// Execution
+///
+/// Outputs & speed of a single unchecked run, see [Program::execute].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Execution {
+    pub output: Vec<Value>,
+    pub speed: u64,
+}
+
+/// Define Kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DefineKind {
+    Comment,
+    Label,
+}
+
+/// Define
+///
+/// Drawing exported by the game, of comment `index` (see `COMMENT n`) or of the label on tile
+/// `index`. Never interpreted, only kept so programs paste back into the game unchanged.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Define {
+    pub kind: DefineKind,
+    pub index: u32,
+    /// Base64 blob lines following the `DEFINE COMMENT|LABEL n` header, the last ending with `;`
+    pub blob: Vec<String>,
+}
+
+/// IO Score
+///
+/// Speed & steps of a single passing IO, see [Program::run_each].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct IOScore {
+    pub speed: u64,
+    pub steps: u64,
+}
+
+/// Failure Report
+///
+/// Failing IO & game state at the moment a run failed, see [Program::run].
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("IO {i_io}, command {i_command}: {error}")]
+pub struct FailureReport {
+    pub error: RunError,
+    /// Index of the failing IO in [Problem::get_ios]
+    pub i_io: usize,
+    pub input: Vec<Value>,
+    /// Index of the failing command, equals the number of commands if the program ended with
+    /// outputs missing
+    pub i_command: usize,
+    pub acc: Option<Value>,
+    pub memory: Memory,
+    pub inputs_consumed: usize,
+    pub outputs_produced: usize,
+}
+
+/// Result of running a single IO
+struct IORun {
+    speed: u64,
+    stack_max: usize,
+    counters: RunCounters,
+    memory_stats: MemoryStats,
+    produced: Vec<Value>,
+}
+
+/// Per-command counters of a single IO
+struct RunCounters {
+    /// Counted executions by command index
+    executions: Vec<u64>,
+    /// Whether each command was reached, incl. the one that halted
+    reached: Vec<bool>,
+    #[cfg(feature = "profiling")]
+    profile: Profile,
+}
+
+impl RunCounters {
+    fn new(len: usize) -> Self {
+        Self {
+            executions: vec![0; len],
+            reached: vec![false; len],
+            #[cfg(feature = "profiling")]
+            profile: Profile::new(len),
+        }
+    }
+}
+
+/// Tile label of the command's tile, formatted for traces, e.g. ` (ZERO)`.
+fn tile_alias(problem: &Problem, command: &dyn Command) -> String {
+    command
+        .requires_index()
+        .and_then(|idx| problem.get_tile_label(idx))
+        .map(|label| format!(" ({label})"))
+        .unwrap_or_default()
+}
+
+/// Check Bounds
+///
+/// Returns the value if it is within bounds, else [RunError::Overflow].
+pub fn check_bounds(value: Value, bounds: Option<ValueBounds>) -> Result<Value, RunError> {
+    match bounds {
+        Some(bounds) if !bounds.contains(value) => Err(RunError::Overflow(value)),
+        _ => Ok(value),
+    }
+}
+
+/// Arithmetic
+///
+/// Returns the result of a [Value] operation, `operands` if it isn't defined for the operands or
+/// [RunError::Overflow] if the result doesn't fit in an `i32`.
+pub fn arithmetic(
+    result: Result<Value, ArithmeticError>,
+    operands: RunError,
+) -> Result<Value, RunError> {
+    result.map_err(|err| match err {
+        ArithmeticError::Operands => operands,
+        ArithmeticError::Overflow(value) => RunError::Overflow(value),
+    })
+}
+
 // todo: test
 pub fn get_acc(acc: Option<Value>) -> Result<Value, RunError> {
     match acc {
@@ -213,28 +1250,43 @@ pub fn get_from_memory(memory: Option<Value>) -> Result<Value, RunError> {
 }
 
 // todo: test
-pub fn get_index(command_value: &CommandValue, memory: &Memory) -> Result<usize, RunError> {
-    match command_value {
-        CommandValue::Value(value) => Ok(*value),
+pub fn get_index(
+    command_value: &CommandValue,
+    game_state: &mut GameState,
+) -> Result<usize, RunError> {
+    let index = match command_value {
+        CommandValue::Value(value) => *value,
         CommandValue::Index(index) => {
-            let index_value = get_from_memory(memory[*index])?;
+            let index_value = get_from_memory(game_state.read(*index))?;
             match index_value {
                 Value::Int(idx) => {
-                    if idx < 0 || idx as usize >= memory.len() {
-                        Err(RunError::IndexOutOfRange(index_value))
-                    } else {
-                        Ok(idx as usize)
+                    let out_of_range =
+                        !game_state.unbounded_memory && idx as usize >= game_state.memory.len();
+                    if idx < 0 || out_of_range {
+                        return Err(RunError::IndexOutOfRange(index_value));
                     }
+                    idx as usize
                 }
-                Value::Char(_) => Err(RunError::CharIndex(index_value)),
+                Value::Char(_) => return Err(RunError::CharIndex(index_value)),
             }
         }
+    };
+
+    if game_state.holes.binary_search(&index).is_ok() {
+        Err(RunError::FloorHole(index))
+    } else {
+        Ok(index)
     }
 }
 
 pub struct ProgramBuilder {
     commands: Vec<AnyCommand>,
-    labels: HashMap<String, usize>,
+    /// Label definitions in order, interned once the program is built
+    labels: Vec<(Label, usize)>,
+    comments: Vec<(usize, u32)>,
+    defines: Vec<Define>,
+    line: Option<usize>,
+    lines: Vec<usize>,
 }
 
 impl Default for ProgramBuilder {
@@ -247,11 +1299,25 @@ impl ProgramBuilder {
     pub fn new() -> Self {
         Self {
             commands: vec![],
-            labels: HashMap::new(),
+            labels: vec![],
+            comments: vec![],
+            defines: vec![],
+            line: None,
+            lines: vec![],
         }
     }
 
+    /// Source Line
+    ///
+    /// Set the source line (0-based) of the commands added next, see [Program::get_line].
+    pub fn source_line_ref(&mut self, line: usize) {
+        self.line = Some(line);
+    }
+
     pub fn add_command_ref(&mut self, command: AnyCommand) {
+        if let Some(line) = self.line {
+            self.lines.push(line);
+        }
         self.commands.push(command);
     }
 
@@ -260,34 +1326,116 @@ impl ProgramBuilder {
         self
     }
 
-    pub fn add_label_ref(&mut self, label: String) {
-        self.labels.insert(label, self.commands.len());
+    pub fn add_label_ref(&mut self, label: Label) {
+        self.labels.push((label, self.commands.len()));
     }
 
-    pub fn add_label(mut self, label: String) -> Self {
+    pub fn add_label(mut self, label: Label) -> Self {
         self.add_label_ref(label);
         self
     }
 
+    pub fn add_comment_ref(&mut self, comment: u32) {
+        self.comments.push((self.commands.len(), comment));
+    }
+
+    pub fn add_comment(mut self, comment: u32) -> Self {
+        self.add_comment_ref(comment);
+        self
+    }
+
+    pub fn add_define_ref(&mut self, define: Define) {
+        self.defines.push(define);
+    }
+
+    pub fn add_define(mut self, define: Define) -> Self {
+        self.add_define_ref(define);
+        self
+    }
+
+    /// Build
+    ///
+    /// Build [Program], interning its labels & resolving the labels of jump commands to command
+    /// indices. Labels defined more than once point to their last definition.
     pub fn build(self) -> Program {
+        let symbols = SymbolTable::new(self.labels.iter().map(|(label, _)| label.clone()));
+        let mut labels = vec![None; symbols.len()];
+        let mut duplicate_labels = vec![];
+        for (label, idx) in self.labels {
+            if let Some(symbol) = symbols.lookup(label.as_str()) {
+                if labels[symbol.index()].replace(idx).is_some() {
+                    duplicate_labels.push(label);
+                }
+            }
+        }
+        // Every symbol is a defined label
+        let labels: Vec<usize> = labels.into_iter().flatten().collect();
+
+        let targets: Vec<Option<usize>> = self
+            .commands
+            .iter()
+            .map(|command| {
+                command
+                    .requires_label()
+                    .and_then(|label| symbols.lookup(label.as_str()))
+                    .map(|symbol| labels[symbol.index()])
+            })
+            .collect();
+        let lines = if self.lines.len() == self.commands.len() {
+            self.lines
+        } else {
+            vec![]
+        };
+
+        let bytecode = Bytecode::compile(&self.commands, &targets);
+
         Program {
             commands: self.commands,
-            labels: self.labels,
+            symbols,
+            labels,
+            comments: self.comments,
+            defines: self.defines,
+            targets,
+            lines,
+            duplicate_labels,
+            bytecode,
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use alloc::boxed::Box;
+
     use crate::code::commands::add::Add;
+    use crate::code::commands::call::Call;
     use crate::code::commands::copy_from::CopyFrom;
     use crate::code::commands::copy_to::CopyTo;
+    use crate::code::commands::debug::DebugPrint;
+    use crate::code::commands::halt::Halt;
+    use crate::code::commands::inbox::Inbox;
     use crate::code::commands::jump::Jump;
+    use crate::code::commands::neg::Neg;
+    use crate::code::commands::nop::{Nop, NopFactory};
+    use crate::code::commands::outbox::Outbox;
+    use crate::code::commands::pop::Pop;
+    use crate::code::commands::push::Push;
+    use crate::code::commands::ret::Ret;
     use crate::code::commands::sub::Sub;
+    use crate::code::commands::{Command, CommandFactory, InstructionSet};
+    #[cfg(feature = "std")]
+    use crate::compiler::compile::Compiler;
     use crate::game::problem::{ProblemBuilder, ProblemIO};
 
     use super::*;
 
+    fn histogram<const N: usize>(counts: [(&'static str, u64); N]) -> CommandHistogram {
+        counts
+            .into_iter()
+            .map(|(command, count)| (Cow::Borrowed(command), count))
+            .collect()
+    }
+
     #[test]
     fn validate_succeeds() {
         let problem = ProblemBuilder::new()
@@ -297,20 +1445,51 @@ mod tests {
                 output: vec![],
             })
             .enable_all_commands()
-            .build();
+            .build()
+            .unwrap();
 
         let program = ProgramBuilder::new()
-            .add_label(String::from("a"))
+            .add_label(Label::from("a"))
             .add_command(Box::new(CopyFrom(CommandValue::Value(0))))
-            .add_label(String::from("b"))
+            .add_label(Label::from("b"))
             .add_command(Box::new(CopyTo(CommandValue::Index(4))))
-            .add_label(String::from("c"))
-            .add_command(Box::new(Jump(String::from("a"))))
+            .add_label(Label::from("c"))
+            .add_command(Box::new(Jump(Label::from("a"))))
             .build();
 
         program.validate(&problem).unwrap();
     }
 
+    #[test]
+    fn build_resolves_targets() {
+        let program = ProgramBuilder::new()
+            .add_command(Box::new(CopyFrom(CommandValue::Value(0))))
+            .add_label(Label::from("a"))
+            .add_command(Box::new(Jump(Label::from("b"))))
+            .add_label(Label::from("b"))
+            .add_command(Box::new(Jump(Label::from("a"))))
+            .build();
+
+        assert_eq!(vec![None, Some(2), Some(1)], program.targets);
+        assert_eq!(Ok(2), program.get_target(1));
+        assert_eq!(Ok(1), program.get_target(2));
+    }
+
+    #[test]
+    fn clone_eq() {
+        let program = ProgramBuilder::new()
+            .add_label(Label::from("a"))
+            .add_command(Box::new(Inbox::new()))
+            .add_command(Box::new(Jump(Label::from("a"))))
+            .build();
+
+        let mut cloned = program.clone();
+        assert_eq!(program, cloned);
+
+        cloned.commands[0] = Box::new(Outbox);
+        assert_ne!(program, cloned);
+    }
+
     #[test]
     fn validate_fails() {
         let dim = 5;
@@ -322,34 +1501,64 @@ mod tests {
             })
             .enable_all_commands()
             .disable_command("SUB")
-            .build();
+            .build()
+            .unwrap();
 
+        let symbols = SymbolTable::new([Label::from("a")]);
         let validate_results = [
             (
                 Program {
                     commands: vec![Box::new(Add(CommandValue::Index(dim + 1)))],
+                    symbols: Default::default(),
                     labels: Default::default(),
+                    comments: vec![],
+                    defines: vec![],
+                    targets: vec![],
+                    lines: vec![],
+                    duplicate_labels: vec![],
+                    bytecode: Default::default(),
                 },
                 ProgramError::Validation(ValidationError::CommandIndex(dim + 1)),
             ),
             (
                 Program {
-                    commands: vec![Box::new(Jump(String::from("a")))],
+                    commands: vec![Box::new(Jump(Label::from("a")))],
+                    symbols: Default::default(),
                     labels: Default::default(),
+                    comments: vec![],
+                    defines: vec![],
+                    targets: vec![],
+                    lines: vec![],
+                    duplicate_labels: vec![],
+                    bytecode: Default::default(),
                 },
-                ProgramError::Validation(ValidationError::MissingLabel(String::from("a"))),
+                ProgramError::Validation(ValidationError::MissingLabel(Label::from("a"))),
             ),
             (
                 Program {
                     commands: vec![],
-                    labels: HashMap::from([(String::from("a"), dim + 1)]),
+                    symbols: symbols.clone(),
+                    labels: vec![dim + 1],
+                    comments: vec![],
+                    defines: vec![],
+                    targets: vec![],
+                    lines: vec![],
+                    duplicate_labels: vec![],
+                    bytecode: Default::default(),
                 },
                 ProgramError::Validation(ValidationError::LabelIndex(dim + 1)),
             ),
             (
                 Program {
                     commands: vec![Box::new(Sub(CommandValue::Value(0)))],
-                    labels: HashMap::from([(String::from("a"), dim + 1)]),
+                    symbols: symbols.clone(),
+                    labels: vec![dim + 1],
+                    comments: vec![],
+                    defines: vec![],
+                    targets: vec![],
+                    lines: vec![],
+                    duplicate_labels: vec![],
+                    bytecode: Default::default(),
                 },
                 ProgramError::Validation(ValidationError::CommandNotAvailable(String::from("SUB"))),
             ),
@@ -363,4 +1572,1081 @@ mod tests {
             assert_eq!(validate_result.1, err);
         }
     }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn validate_floor_hole() {
+        let problem = ProblemBuilder::new()
+            .memory_dim(3)
+            .floor_hole(1)
+            .enable_all_commands()
+            .build()
+            .unwrap();
+
+        for code in ["COPYTO 1", "ADD [1]"] {
+            let program = Compiler::default().compile(code).unwrap();
+            assert_eq!(
+                ProgramError::Validation(ValidationError::FloorHole(1)),
+                program.validate(&problem).unwrap_err()
+            );
+        }
+
+        let program = Compiler::default()
+            .compile("COPYTO 2\nCOPYTO [0]\nADD 0")
+            .unwrap();
+        assert!(program.validate(&problem).is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn validate_command_limit() {
+        let problem = ProblemBuilder::new()
+            .enable_all_commands()
+            .command_limit(String::from("JUMP"), 1)
+            .build()
+            .unwrap();
+
+        let code = "a:\nINBOX\nJUMP a\nDEBUG\nJUMP a\n";
+        let program = Compiler::default().compile(code).unwrap();
+        assert_eq!(
+            ProgramError::Validation(ValidationError::CommandLimitExceeded {
+                command: String::from("JUMP"),
+                limit: 1,
+                used: 2,
+            }),
+            program.validate(&problem).unwrap_err()
+        );
+
+        let program = Compiler::default().compile("a:\nINBOX\nJUMP a\n").unwrap();
+        assert!(program.validate(&problem).is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn run_fails() {
+        let problem = ProblemBuilder::new()
+            .add_io(ProblemIO {
+                input: vec![Value::Int(1)],
+                output: vec![Value::Int(1)],
+            })
+            .add_io(ProblemIO {
+                input: vec![Value::Int(2), Value::Int(3)],
+                output: vec![Value::Int(2), Value::Int(3)],
+            })
+            .memory_dim(2)
+            .build()
+            .unwrap();
+
+        let code = "\
+    INBOX
+    OUTBOX
+    INBOX
+    COPYTO 0
+    ADD 1
+";
+        let program = Compiler::default().compile(code).unwrap();
+        assert_eq!(
+            FailureReport {
+                error: RunError::EmptyMemory,
+                i_io: 1,
+                input: vec![Value::Int(2), Value::Int(3)],
+                i_command: 4,
+                acc: Some(Value::Int(3)),
+                memory: vec![Some(Value::Int(3)), None],
+                inputs_consumed: 2,
+                outputs_produced: 1,
+            },
+            program.run(&problem).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn error_codes() {
+        let err = ProgramError::Validation(ValidationError::MissingLabel(Label::from("a")));
+        assert_eq!("E0203", err.code());
+        assert_eq!("Label `a` is not defined", err.to_string());
+
+        let err = RunError::WrongOutput {
+            index: 2,
+            expected: Value::Int(1),
+            value: Value::Char('A'),
+        };
+        assert_eq!("E0303", err.code());
+        assert_eq!("Output 2: expected 1, got A", err.to_string());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn run_resets_memory() {
+        let problem = ProblemBuilder::new()
+            .add_io(ProblemIO {
+                input: vec![],
+                output: vec![Value::Int(1)],
+            })
+            .add_io(ProblemIO {
+                input: vec![],
+                output: vec![Value::Int(1)],
+            })
+            .memory_dim(1)
+            .add_memory_slot(0, Value::Int(0))
+            .enable_all_commands()
+            .build()
+            .unwrap();
+
+        let program = Compiler::default().compile("BUMPUP 0\nOUTBOX").unwrap();
+        assert_eq!(vec![2, 2], program.run(&problem).unwrap().speeds);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn execute_detects_loops() {
+        let problem = ProblemBuilder::new()
+            .memory_dim(1)
+            .add_memory_slot(0, Value::Int(0))
+            .enable_all_commands()
+            .build()
+            .unwrap();
+        let limits = RunLimits {
+            max_steps: None,
+            detect_loops: true,
+            max_total_steps: None,
+        };
+
+        let program = Compiler::default()
+            .compile("INBOX\na:\nCOPYTO 0\nBUMPDN 0\nJUMPN b\nJUMP a\nb:\nOUTBOX")
+            .unwrap();
+        let execution = program
+            .execute_with_limits(&problem, &[Value::Int(3)], limits)
+            .unwrap();
+        assert_eq!(vec![Value::Int(-1)], execution.output);
+
+        let program = Compiler::default()
+            .compile("INBOX\na:\nCOPYTO 0\nCOPYFROM 0\nJUMP a")
+            .unwrap();
+        assert_eq!(
+            Err(RunError::InfiniteLoop),
+            program.execute_with_limits(&problem, &[Value::Int(3)], limits)
+        );
+        assert_eq!(
+            Err(RunError::StepLimit(100)),
+            program.execute(&problem, &[Value::Int(3)], 100)
+        );
+
+        let program = Compiler::default().compile("a:\nJUMP a").unwrap();
+        assert_eq!(
+            Err(RunError::InfiniteLoop),
+            program.execute_with_limits(&problem, &[], limits)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn run_with_limits_test() {
+        let problem = ProblemBuilder::new()
+            .add_io(ProblemIO {
+                input: vec![Value::Int(1)],
+                output: vec![Value::Int(1)],
+            })
+            .add_io(ProblemIO {
+                input: vec![Value::Int(0)],
+                output: vec![Value::Int(0)],
+            })
+            .enable_all_commands()
+            .build()
+            .unwrap();
+        let program = Compiler::default()
+            .compile("INBOX\na:\nJUMPZ a\nOUTBOX")
+            .unwrap();
+
+        let report = program
+            .run_with_limits(
+                &problem,
+                RunLimits {
+                    max_steps: None,
+                    detect_loops: true,
+                    max_total_steps: None,
+                },
+            )
+            .unwrap_err();
+        assert_eq!(RunError::InfiniteLoop, report.error);
+        assert_eq!(1, report.i_io);
+        assert_eq!(1, report.i_command);
+        assert_eq!("E0323", report.error.code());
+
+        let report = program
+            .run_with_limits(
+                &problem,
+                RunLimits {
+                    max_steps: Some(10),
+                    detect_loops: false,
+                    max_total_steps: None,
+                },
+            )
+            .unwrap_err();
+        assert_eq!(RunError::StepLimit(10), report.error);
+
+        let limits = RunLimits {
+            max_steps: Some(100),
+            detect_loops: false,
+            max_total_steps: Some(15),
+        };
+        let report = program.run_with_limits(&problem, limits).unwrap_err();
+        assert_eq!(RunError::TotalStepLimit(15), report.error);
+        assert_eq!(1, report.i_io);
+        assert_eq!("E0324", report.error.code());
+        let results = program.run_each_with_limits(&problem, limits);
+        assert_eq!(Ok(IOScore { speed: 3, steps: 3 }), results[0]);
+        assert_eq!(
+            RunError::TotalStepLimit(15),
+            results[1].as_ref().unwrap_err().error
+        );
+    }
+
+    #[test]
+    fn build_many_labels() {
+        let problem = ProblemBuilder::new()
+            .add_io(ProblemIO {
+                input: vec![],
+                output: vec![],
+            })
+            .enable_all_commands()
+            .build()
+            .unwrap();
+        // Labels `aaa`, `baa`, ...
+        let label = |i: usize| -> Label {
+            (0..3)
+                .map(|digit| (b'a' + (i / 26usize.pow(digit) % 26) as u8) as char)
+                .collect::<String>()
+                .into()
+        };
+        let labels = 10_000;
+        let mut builder = ProgramBuilder::new();
+        for i in 0..labels {
+            builder.add_label_ref(label(i));
+            builder.add_command_ref(Box::new(Jump(label(labels - 1 - i))));
+        }
+        builder.add_label_ref(label(0));
+        let program = builder.build();
+
+        assert_eq!(labels, program.get_labels().count());
+        assert_eq!(Some(labels), program.get_label("aaa"));
+        assert_eq!(Some(27), program.get_label("bba"));
+        for i in 0..labels - 1 {
+            assert_eq!(Ok(labels - 1 - i), program.get_target(i));
+        }
+        assert_eq!(Ok(labels), program.get_target(labels - 1));
+        assert_eq!(
+            Err(ProgramError::Validation(ValidationError::DuplicateLabel(
+                Label::from("aaa")
+            ))),
+            program.validate(&problem)
+        );
+    }
+
+    #[test]
+    fn run_undefined_label() {
+        let problem = ProblemBuilder::new()
+            .add_io(ProblemIO {
+                input: vec![Value::Int(1)],
+                output: vec![],
+            })
+            .build()
+            .unwrap();
+
+        let program = ProgramBuilder::new()
+            .add_command(Box::new(Inbox::new()))
+            .add_command(Box::new(Jump(Label::from("a"))))
+            .build();
+        assert_eq!(
+            RunError::UndefinedLabel(Label::from("a")),
+            program.run(&problem).unwrap_err().error
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn validate_all_test() {
+        let problem = ProblemBuilder::new()
+            .memory_dim(2)
+            .add_io(ProblemIO {
+                input: vec![],
+                output: vec![],
+            })
+            .enable_all_commands()
+            .disable_command("SUB")
+            .build()
+            .unwrap();
+
+        let program = ProgramBuilder::new()
+            .add_label(Label::from("a"))
+            .add_command(Box::new(Sub(CommandValue::Value(0))))
+            .add_command(Box::new(CopyTo(CommandValue::Index(5))))
+            .add_command(Box::new(Jump(Label::from("b"))))
+            .add_label(Label::from("a"))
+            .add_command(Box::new(Jump(Label::from("c"))))
+            .build();
+        assert_eq!(
+            vec![
+                ValidationError::CommandNotAvailable(String::from("SUB")),
+                ValidationError::CommandIndex(5),
+                ValidationError::MissingLabel(Label::from("b")),
+                ValidationError::MissingLabel(Label::from("c")),
+                ValidationError::DuplicateLabel(Label::from("a")),
+            ],
+            program.validate_all(&problem)
+        );
+        assert_eq!(
+            ProgramError::Validation(ValidationError::CommandNotAvailable(String::from("SUB"))),
+            program.validate(&problem).unwrap_err()
+        );
+
+        let program = Compiler::default().compile("a:\nINBOX\nJUMP a").unwrap();
+        assert!(program.validate_all(&problem).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn size_test() {
+        let code = "\
+-- HUMAN RESOURCE MACHINE PROGRAM --
+a:
+    INBOX
+    COMMENT 0
+    MOVE 0 1
+    DEBUG \"x\" 0
+    OUTBOX
+    JUMP a
+";
+        let program = Compiler::default().compile(code).unwrap();
+
+        assert_eq!(5, program.size());
+        assert_eq!(
+            histogram([
+                ("INBOX", 1),
+                ("COPYFROM", 1),
+                ("COPYTO", 1),
+                ("OUTBOX", 1),
+                ("JUMP", 1),
+            ]),
+            program.size_histogram()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn coverage_test() {
+        let problem = ProblemBuilder::new()
+            .add_io(ProblemIO {
+                input: vec![Value::Int(1)],
+                output: vec![Value::Int(1)],
+            })
+            .memory_dim(1)
+            .enable_all_commands()
+            .build()
+            .unwrap();
+
+        let code = "\
+a:
+    INBOX
+    JUMPZ b
+    OUTBOX
+    JUMP a
+b:
+    COPYTO 0
+    OUTBOX
+";
+        let program = Compiler::default().compile(code).unwrap();
+        let score = program.run(&problem).unwrap();
+
+        let coverage = program.coverage(&score);
+        assert_eq!(vec![0, 1, 2, 3], coverage.executed);
+        assert_eq!(vec![4, 5], coverage.unexecuted);
+        assert_eq!(vec![6, 7], coverage.unexecuted_lines(&program));
+        assert_eq!(Some(1), program.get_line(0));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn profile_test() {
+        let problem = ProblemBuilder::new()
+            .add_io(ProblemIO {
+                input: vec![Value::Int(1), Value::Int(2)],
+                output: vec![Value::Int(1), Value::Int(2)],
+            })
+            .add_io(ProblemIO {
+                input: vec![Value::Int(3)],
+                output: vec![Value::Int(3)],
+            })
+            .enable_all_commands()
+            .build()
+            .unwrap();
+
+        let code = "a:\nINBOX\nOUTBOX\nJUMP a\nOUTBOX\n";
+        let program = Compiler::default().compile(code).unwrap();
+        let score = program.run(&problem).unwrap();
+
+        assert_eq!(vec![3, 3, 3, 0], score.executions);
+        assert_eq!(
+            ProfileEntry {
+                i_command: 0,
+                line: Some(1),
+                source: String::from("INBOX"),
+                count: 3,
+            },
+            program.profile(&score)[0]
+        );
+        assert_eq!(3, program.profile(&score).len());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn memory_stats_test() {
+        let problem = ProblemBuilder::new()
+            .add_io(ProblemIO {
+                input: vec![Value::Int(1), Value::Int(2)],
+                output: vec![Value::Int(3)],
+            })
+            .add_io(ProblemIO {
+                input: vec![Value::Int(4), Value::Int(4)],
+                output: vec![Value::Int(8)],
+            })
+            .memory_dim(4)
+            .add_memory_slot(3, Value::Int(0))
+            .enable_all_commands()
+            .build()
+            .unwrap();
+
+        let code = "INBOX\nCOPYTO 0\nINBOX\nCOPYTO 1\nCOPYFROM 0\nADD 1\nCOPYTO 0\nOUTBOX\n";
+        let program = Compiler::default().compile(code).unwrap();
+        let score = program.run(&problem).unwrap();
+
+        assert_eq!(
+            MemoryStats {
+                touched: [0, 1].into(),
+                written: [0, 1].into(),
+                written_max: 2,
+                peak_occupancy: 3,
+            },
+            score.memory
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn serialize_score() {
+        let problem = ProblemBuilder::new()
+            .add_io(ProblemIO {
+                input: vec![Value::Int(1)],
+                output: vec![Value::Int(1)],
+            })
+            .size_target(2)
+            .enable_all_commands()
+            .build()
+            .unwrap();
+
+        let program = Compiler::default().compile("INBOX\nOUTBOX").unwrap();
+        let score = program.run(&problem).unwrap();
+
+        let mut json = serde_json::to_value(&score).unwrap();
+        assert_eq!(2, json["size"]);
+        assert_eq!(1, json["histograms"][0]["OUTBOX"]);
+        assert_eq!(0, json["memory"]["peak_occupancy"]);
+        assert_eq!(score, serde_json::from_value(json.clone()).unwrap());
+        json["histograms"][0]["EXTENSION"] = 3.into();
+        let deserialized: Score = serde_json::from_value(json).unwrap();
+        assert_eq!(Some(&3), deserialized.histograms[0].get("EXTENSION"));
+
+        let result = score.evaluate(&problem);
+        let json = serde_json::to_string(&result).unwrap();
+        assert_eq!(result, serde_json::from_str(&json).unwrap());
+    }
+
+    #[test]
+    fn evaluate() {
+        let score = Score {
+            size: 5,
+            speed_min: 10,
+            speed_max: 30,
+            speed_avg: 20.0,
+            steps_min: 10,
+            steps_max: 30,
+            steps_avg: 20.0,
+            stack_max: 0,
+            speeds: vec![10, 30],
+            i_speed_min: 0,
+            i_speed_max: 1,
+            steps: vec![10, 30],
+            histograms: vec![],
+            coverage: vec![],
+            executions: vec![],
+            memory: Default::default(),
+            #[cfg(feature = "profiling")]
+            profile: Default::default(),
+        };
+
+        let evaluation = score.evaluate(&ProblemBuilder::new().build().unwrap());
+        assert_eq!(
+            ChallengeResult {
+                size: None,
+                speed: None
+            },
+            evaluation
+        );
+        assert!(evaluation.passed());
+
+        let problem = ProblemBuilder::new()
+            .size_target(5)
+            .speed_target(19)
+            .build()
+            .unwrap();
+        assert_eq!(
+            ChallengeResult {
+                size: Some(true),
+                speed: Some(false)
+            },
+            score.evaluate(&problem)
+        );
+
+        let result = score.meets(&ScoreTarget {
+            size: Some(4),
+            speed: Some(20),
+        });
+        assert_eq!(Some(false), result.size);
+        assert_eq!(Some(true), result.speed);
+        assert!(!result.passed());
+
+        let result = score.meets(&ScoreTarget {
+            size: None,
+            speed: Some(20),
+        });
+        assert!(result.passed());
+    }
+
+    #[test]
+    fn run_nop_consumes_cycle() {
+        let problem = ProblemBuilder::new()
+            .add_io(ProblemIO {
+                input: vec![Value::Int(1)],
+                output: vec![Value::Int(1)],
+            })
+            .instruction_set(InstructionSet::Extended)
+            .enable_all_commands()
+            .build()
+            .unwrap();
+
+        let program = ProgramBuilder::new()
+            .add_command(Box::new(Inbox::new()))
+            .add_command(Box::new(Nop))
+            .add_command(Box::new(Outbox))
+            .build();
+
+        program.validate(&problem).unwrap();
+        let score = program.run(&problem).unwrap();
+        assert_eq!(3, score.size);
+        assert_eq!(3, score.speed_max);
+    }
+
+    #[test]
+    fn run_multiple_ios() {
+        let problem = ProblemBuilder::new()
+            .add_io(ProblemIO {
+                input: vec![Value::Int(1), Value::Int(2)],
+                output: vec![Value::Int(1), Value::Int(2)],
+            })
+            .add_io(ProblemIO {
+                input: vec![Value::Int(3)],
+                output: vec![Value::Int(3)],
+            })
+            .enable_all_commands()
+            .build()
+            .unwrap();
+
+        let program = ProgramBuilder::new()
+            .add_label(Label::from("a"))
+            .add_command(Box::new(Inbox::new()))
+            .add_command(Box::new(Outbox))
+            .add_command(Box::new(Jump(Label::from("a"))))
+            .build();
+
+        program.validate(&problem).unwrap();
+        let score = program.run(&problem).unwrap();
+        assert_eq!(3, score.speed_min);
+        assert_eq!(6, score.speed_max);
+        assert_eq!(vec![6, 3], score.speeds);
+        assert_eq!(1, score.i_speed_min);
+        assert_eq!(0, score.i_speed_max);
+        assert_eq!(
+            vec![
+                histogram([("INBOX", 2), ("OUTBOX", 2), ("JUMP", 2)]),
+                histogram([("INBOX", 1), ("OUTBOX", 1), ("JUMP", 1)]),
+            ],
+            score.histograms
+        );
+        assert_eq!(
+            histogram([("INBOX", 3), ("OUTBOX", 3), ("JUMP", 3)]),
+            score.histogram()
+        );
+    }
+
+    #[test]
+    fn run_each_test() {
+        let problem = ProblemBuilder::new()
+            .add_io(ProblemIO {
+                input: vec![Value::Int(1), Value::Int(2)],
+                output: vec![Value::Int(1), Value::Int(2)],
+            })
+            .add_io(ProblemIO {
+                input: vec![Value::Int(3)],
+                output: vec![Value::Int(4)],
+            })
+            .add_io(ProblemIO {
+                input: vec![Value::Int(5)],
+                output: vec![Value::Int(5)],
+            })
+            .enable_all_commands()
+            .build()
+            .unwrap();
+
+        let program = ProgramBuilder::new()
+            .add_label(Label::from("a"))
+            .add_command(Box::new(Inbox::new()))
+            .add_command(Box::new(Outbox))
+            .add_command(Box::new(Jump(Label::from("a"))))
+            .build();
+
+        let results = program.run_each(&problem);
+        assert_eq!(3, results.len());
+        assert_eq!(Ok(IOScore { speed: 6, steps: 6 }), results[0]);
+        let report = results[1].as_ref().unwrap_err();
+        assert_eq!(1, report.i_io);
+        assert_eq!(Err(report.clone()), program.run(&problem));
+        assert_eq!(Ok(IOScore { speed: 3, steps: 3 }), results[2]);
+
+        let limits = RunLimits {
+            max_steps: Some(4),
+            detect_loops: false,
+            max_total_steps: None,
+        };
+        let results = program.run_each_with_limits(&problem, limits);
+        let report = results[0].as_ref().unwrap_err();
+        assert_eq!(RunError::StepLimit(4), report.error);
+        assert_eq!(1, report.outputs_produced);
+        assert_eq!(Ok(IOScore { speed: 3, steps: 3 }), results[2]);
+    }
+
+    #[test]
+    fn run_output_matching() {
+        let program = ProgramBuilder::new()
+            .add_label(Label::from("a"))
+            .add_command(Box::new(Inbox::new()))
+            .add_command(Box::new(Outbox))
+            .add_command(Box::new(Jump(Label::from("a"))))
+            .build();
+
+        let problem = |output_matching| {
+            ProblemBuilder::new()
+                .add_io(ProblemIO {
+                    input: vec![Value::Int(1), Value::Int(2)],
+                    output: vec![Value::Int(2), Value::Int(1)],
+                })
+                .output_matching(output_matching)
+                .enable_all_commands()
+                .build()
+                .unwrap()
+        };
+        assert!(program.run(&problem(OutputMatching::Exact)).is_err());
+        assert!(program.run(&problem(OutputMatching::Multiset)).is_ok());
+
+        let problem = ProblemBuilder::new()
+            .add_io(ProblemIO {
+                input: vec![Value::Int(1)],
+                output: vec![Value::Int(1), Value::Int(2)],
+            })
+            .output_matching(OutputMatching::Prefix)
+            .enable_all_commands()
+            .build()
+            .unwrap();
+        assert!(program.run(&problem).is_ok());
+    }
+
+    #[test]
+    fn run_halt() {
+        let problem = ProblemBuilder::new()
+            .add_io(ProblemIO {
+                input: vec![Value::Int(1), Value::Int(2)],
+                output: vec![Value::Int(1)],
+            })
+            .instruction_set(InstructionSet::Extended)
+            .enable_all_commands()
+            .build()
+            .unwrap();
+
+        let program = ProgramBuilder::new()
+            .add_command(Box::new(Inbox::new()))
+            .add_command(Box::new(Outbox))
+            .add_command(Box::new(Halt))
+            .add_command(Box::new(Outbox))
+            .build();
+
+        program.validate(&problem).unwrap();
+        let score = program.run(&problem).unwrap();
+        assert_eq!(2, score.speed_max);
+    }
+
+    #[test]
+    fn run_stack() {
+        let problem = ProblemBuilder::new()
+            .add_io(ProblemIO {
+                input: vec![Value::Int(1), Value::Int(2)],
+                output: vec![Value::Int(2), Value::Int(1)],
+            })
+            .instruction_set(InstructionSet::Extended)
+            .enable_all_commands()
+            .build()
+            .unwrap();
+
+        let program = ProgramBuilder::new()
+            .add_command(Box::new(Inbox::new()))
+            .add_command(Box::new(Push))
+            .add_command(Box::new(Inbox::new()))
+            .add_command(Box::new(Push))
+            .add_command(Box::new(Pop))
+            .add_command(Box::new(Outbox))
+            .add_command(Box::new(Pop))
+            .add_command(Box::new(Outbox))
+            .build();
+
+        program.validate(&problem).unwrap();
+        let score = program.run(&problem).unwrap();
+        assert_eq!(2, score.stack_max);
+        assert_eq!(8, score.speed_max);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn run_unbounded_memory() {
+        let builder = || {
+            ProblemBuilder::new()
+                .add_io(ProblemIO {
+                    input: vec![Value::Int(300), Value::Int(7)],
+                    output: vec![Value::Int(7)],
+                })
+                .memory_dim(1)
+                .enable_all_commands()
+        };
+        let code = "INBOX\nCOPYTO 500\nINBOX\nCOPYTO [500]\nCOPYFROM 300\nOUTBOX\n";
+        let program = Compiler::default().compile(code).unwrap();
+
+        let problem = builder().build().unwrap();
+        assert_eq!(
+            ProgramError::Validation(ValidationError::CommandIndex(500)),
+            program.validate(&problem).unwrap_err()
+        );
+
+        let problem = builder().unbounded_memory(true).build().unwrap();
+        program.validate(&problem).unwrap();
+        let score = program.run(&problem).unwrap();
+        assert_eq!(6, score.speed_max);
+    }
+
+    #[test]
+    fn run_call() {
+        let problem = ProblemBuilder::new()
+            .add_io(ProblemIO {
+                input: vec![Value::Int(1), Value::Int(2)],
+                output: vec![Value::Int(-1), Value::Int(-2)],
+            })
+            .instruction_set(InstructionSet::Extended)
+            .enable_all_commands()
+            .build()
+            .unwrap();
+
+        let program = ProgramBuilder::new()
+            .add_label(Label::from("a"))
+            .add_command(Box::new(Inbox::new()))
+            .add_command(Box::new(Call(Label::from("b"))))
+            .add_command(Box::new(Outbox))
+            .add_command(Box::new(Jump(Label::from("a"))))
+            .add_label(Label::from("b"))
+            .add_command(Box::new(Neg))
+            .add_command(Box::new(Ret))
+            .build();
+
+        program.validate(&problem).unwrap();
+        let score = program.run(&problem).unwrap();
+        assert_eq!(12, score.speed_max);
+    }
+
+    #[test]
+    fn run_debug() {
+        let problem = ProblemBuilder::new()
+            .add_io(ProblemIO {
+                input: vec![Value::Int(1)],
+                output: vec![Value::Int(1)],
+            })
+            .memory_dim(1)
+            .enable_command(String::from("INBOX"))
+            .enable_command(String::from("OUTBOX"))
+            .build()
+            .unwrap();
+
+        let program = ProgramBuilder::new()
+            .add_command(Box::new(Inbox::new()))
+            .add_command(Box::new(DebugPrint {
+                message: String::from("a"),
+                slots: vec![0],
+            }))
+            .add_command(Box::new(Outbox))
+            .build();
+
+        program.validate(&problem).unwrap();
+
+        let mut events = vec![];
+        let score = program
+            .run_with_debug(&problem, &mut |event| events.push(event.clone()))
+            .unwrap();
+
+        assert_eq!(2, score.size);
+        assert_eq!(2, score.speed_max);
+        assert_eq!(
+            vec![DebugEvent {
+                i_command: 1,
+                message: String::from("a"),
+                acc: Some(Value::Int(1)),
+                memory: vec![(0, None)],
+            }],
+            events
+        );
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Slow;
+
+    impl Command for Slow {
+        fn execute(&self, _program: &Program, _game_state: &mut GameState) -> Result<(), RunError> {
+            Ok(())
+        }
+
+        fn cost(&self) -> u32 {
+            3
+        }
+
+        fn factory(&self) -> Box<dyn CommandFactory> {
+            Box::new(NopFactory)
+        }
+    }
+
+    #[test]
+    fn run_cost() {
+        let problem = ProblemBuilder::new()
+            .add_io(ProblemIO {
+                input: vec![Value::Int(1)],
+                output: vec![Value::Int(1)],
+            })
+            .instruction_set(InstructionSet::Extended)
+            .enable_all_commands()
+            .build()
+            .unwrap();
+
+        let program = ProgramBuilder::new()
+            .add_command(Box::new(Inbox::new()))
+            .add_command(Box::new(Slow))
+            .add_command(Box::new(Outbox))
+            .build();
+
+        program.validate(&problem).unwrap();
+        let score = program.run(&problem).unwrap();
+        assert_eq!(5, score.speed_max);
+        assert_eq!(3, score.steps_max);
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Glacial;
+
+    impl Command for Glacial {
+        fn execute(&self, _program: &Program, _game_state: &mut GameState) -> Result<(), RunError> {
+            Ok(())
+        }
+
+        fn cost(&self) -> u32 {
+            u32::MAX
+        }
+
+        fn factory(&self) -> Box<dyn CommandFactory> {
+            Box::new(NopFactory)
+        }
+    }
+
+    #[test]
+    fn run_cost_exceeds_u32() {
+        let io = || ProblemIO {
+            input: vec![Value::Int(1)],
+            output: vec![Value::Int(1)],
+        };
+        let problem = ProblemBuilder::new()
+            .add_io(io())
+            .add_io(io())
+            .instruction_set(InstructionSet::Extended)
+            .enable_all_commands()
+            .build()
+            .unwrap();
+
+        let program = ProgramBuilder::new()
+            .add_command(Box::new(Inbox::new()))
+            .add_command(Box::new(Glacial))
+            .add_command(Box::new(Glacial))
+            .add_command(Box::new(Outbox))
+            .build();
+
+        let score = program.run(&problem).unwrap();
+        let speed = 2 * u32::MAX as u64 + 2;
+        assert_eq!(speed, score.speed_max);
+        assert_eq!(speed as f64, score.speed_avg);
+        assert_eq!(4, score.steps_max);
+    }
+
+    #[cfg(feature = "profiling")]
+    #[test]
+    #[cfg(feature = "std")]
+    fn run_profile() {
+        let problem = ProblemBuilder::new()
+            .add_io(ProblemIO {
+                input: vec![Value::Int(1), Value::Int(2)],
+                output: vec![Value::Int(1), Value::Int(2)],
+            })
+            .add_io(ProblemIO {
+                input: vec![Value::Int(3)],
+                output: vec![Value::Int(3)],
+            })
+            .enable_all_commands()
+            .build()
+            .unwrap();
+        let program = Compiler::default()
+            .compile("a:\nINBOX\nOUTBOX\nJUMP a\n")
+            .unwrap();
+
+        let score = program.run(&problem).unwrap();
+        let counts: Vec<u64> = score
+            .profile
+            .commands
+            .iter()
+            .map(|timing| timing.count)
+            .collect();
+        assert_eq!(score.executions, counts);
+        assert!(score
+            .profile
+            .commands
+            .iter()
+            .all(|timing| { timing.buckets.iter().sum::<u64>() == timing.count }));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn int_program_matches_generic() {
+        let problem = |input: Vec<i32>, output: Vec<i32>, bounds: Option<ValueBounds>| {
+            ProblemBuilder::new()
+                .add_io(ProblemIO {
+                    input: input.into_iter().map(Value::Int).collect(),
+                    output: output.into_iter().map(Value::Int).collect(),
+                })
+                .memory_dim(4)
+                .add_memory_slot(3, Value::Int(0))
+                .value_bounds(bounds)
+                .enable_all_commands()
+                .build()
+                .unwrap()
+        };
+        let game = Some(ValueBounds::GAME);
+        let problems = [
+            problem(vec![3, -2, 0], vec![3, -3, 0], game),
+            problem(vec![5, 4], vec![5, 4], game),
+            problem(vec![999, 1], vec![999, 999], game),
+            problem(vec![], vec![1], game),
+            // i32 overflow
+            problem(vec![i32::MAX, 1, i32::MIN], vec![i32::MAX, 1], None),
+        ];
+        let sources = [
+            // Count down from each input
+            "a:\nINBOX\nJUMPN b\nOUTBOX\nJUMP a\nb:\nCOPYTO 0\nBUMPDN 0\nOUTBOX\nJUMP a\n",
+            "a:\nINBOX\nCOPYTO 1\nADD 1\nSUB 1\nJUMPZ c\nOUTBOX\nJUMP a\nc:\nBUMPUP 3\nOUTBOX\nJUMP a\n",
+            "INBOX\nCOPYTO 0\nINBOX\nADD 0\nOUTBOX\n",
+            "a:\nCOPYFROM 2\nOUTBOX\n",
+            "a:\nJUMPN a\n",
+        ];
+
+        let generic = RunLimits {
+            detect_loops: true,
+            ..Default::default()
+        };
+        for source in sources {
+            let program = Compiler::default().compile(source).unwrap();
+            assert!(program.get_bytecode().get_int_program().is_some());
+
+            for problem in &problems {
+                assert_eq!(
+                    program.run_with_limits(problem, generic),
+                    program.run(problem),
+                    "{source}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn int_program_memory_stats_match_generic() {
+        let problem = ProblemBuilder::new()
+            .memory_dim(4)
+            .add_memory_slot(3, Value::Int(999))
+            .value_bounds(Some(ValueBounds::GAME))
+            .enable_all_commands()
+            .build()
+            .unwrap();
+        let (input, output) = (vec![Value::Int(1), Value::Int(2)], vec![]);
+        let sources = [
+            "INBOX\nCOPYTO 0\nCOPYFROM 1\nCOPYTO 2\n",
+            // Fail on the empty acc, before touching the tile
+            "COPYTO 0\n",
+            "ADD 1\n",
+            // Fail after reading the tile, before writing it
+            "INBOX\nCOPYTO 0\nBUMPUP 3\nCOPYTO 2\n",
+            "INBOX\nADD 3\nCOPYTO 1\n",
+            // Out of steps
+            "a:\nCOPYFROM 3\nCOPYTO 1\nJUMP a\n",
+            "a:\nBUMPDN 3\nJUMP a\n",
+        ];
+
+        let limits = RunLimits {
+            max_steps: Some(10),
+            ..Default::default()
+        };
+        for source in sources {
+            let program = Compiler::default().compile(source).unwrap();
+            let int_program = program.get_bytecode().get_int_program().unwrap();
+            let run = |fast: bool| {
+                let mut game_state = GameState::new(&input, &output, problem.get_memory().clone());
+                game_state.bounds = problem.get_value_bounds();
+                let mut counters = RunCounters::new(program.get_commands().len());
+                let result = if fast {
+                    let (executions, reached) = (&mut counters.executions, &mut counters.reached);
+                    int_program
+                        .run(&mut game_state, limits.max_steps, executions, reached)
+                        .unwrap()
+                } else {
+                    program.run_commands(
+                        &problem,
+                        &mut game_state,
+                        limits,
+                        &mut |_| {},
+                        &mut counters,
+                    )
+                };
+                (result, game_state.memory_stats)
+            };
+
+            let (result, stats) = run(false);
+            assert!(result.is_err(), "{source}");
+            assert_eq!((result, stats), run(true), "{source}");
+        }
+    }
 }
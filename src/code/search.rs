@@ -0,0 +1,414 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use crate::code::{
+    commands::{nop::Nop, AnyCommand, Flow},
+    optimizer::Optimizer,
+    program::{Program, ProgramBuilder, RunLimits, Score},
+    symbol::Label,
+};
+use crate::game::problem::Problem;
+
+/// Commands failing whenever they're the first one executed, on empty hands or an empty stack
+const FAILS_FIRST: [&str; 14] = [
+    "OUTBOX", "COPYTO", "ADD", "SUB", "MUL", "DIV", "MOD", "NEG", "JUMPZ", "JUMPN", "JUMPP",
+    "PUSH", "POP", "RET",
+];
+
+/// Search Limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchLimits {
+    /// Largest program size enumerated
+    pub max_size: usize,
+    /// Step limit of a single IO, candidates exceeding it fail
+    pub max_steps: u32,
+    /// Number of candidates run before giving up, [None] to enumerate everything
+    pub max_candidates: Option<u64>,
+    /// Enumerate indirect tile arguments, e.g. `COPYFROM [x]`
+    pub indirect: bool,
+}
+
+impl Default for SearchLimits {
+    fn default() -> Self {
+        Self {
+            max_size: 4,
+            max_steps: 1_000,
+            max_candidates: Some(1_000_000),
+            indirect: false,
+        }
+    }
+}
+
+/// Found Program
+///
+/// Program solving every IO of the searched problem, with its score.
+#[derive(Debug, PartialEq)]
+pub struct FoundProgram {
+    pub program: Program,
+    pub score: Score,
+}
+
+/// Search Result
+///
+/// Best programs of [search].
+#[derive(Debug, PartialEq)]
+pub struct SearchResult {
+    /// Smallest solution, the fastest of that size
+    pub smallest: Option<FoundProgram>,
+    /// Fastest solution on average, the smallest of that speed
+    pub fastest: Option<FoundProgram>,
+    /// Number of candidates run
+    pub candidates: u64,
+    /// Whether every program up to [SearchLimits::max_size] was considered, else the search ran
+    /// out of [SearchLimits::max_candidates]
+    pub complete: bool,
+}
+
+/// Search
+///
+/// Superoptimizer - enumerate programs of up to [SearchLimits::max_size] commands over the
+/// problem's available commands, smallest first, & run each on the problem's IOs. Only
+/// [FoundProgram]s solving every IO are kept.
+///
+/// The space is pruned of programs known to fail or to have an equivalent no larger & no slower
+/// one, which is enumerated instead:
+/// - tiles without initial value are interchangeable, so they're first used in ascending order
+/// - a program doesn't start with a command failing on empty hands or stack, e.g. `OUTBOX`
+/// - jumps never go to the next command & `JUMP` never to itself, `NOP` is never used
+/// - a program produces output only if it has an `OUTBOX`
+/// - solutions the [Optimizer] would change are skipped
+pub fn search(problem: &Problem, limits: SearchLimits) -> SearchResult {
+    let mut searcher = Searcher {
+        problem,
+        limits,
+        needs_outbox: problem.get_ios().iter().any(|io| !io.output.is_empty()),
+        optimizer: Optimizer::default(),
+        result: SearchResult {
+            smallest: None,
+            fastest: None,
+            candidates: 0,
+            complete: true,
+        },
+    };
+
+    for size in 1..=limits.max_size {
+        let alphabet = alphabet(problem, size, limits.indirect);
+        let mut program = Vec::with_capacity(size);
+        if !searcher.enumerate(&alphabet, size, &mut program, 0) {
+            searcher.result.complete = false;
+            break;
+        }
+    }
+    searcher.result
+}
+
+/// Command available to the search, with its argument
+pub(crate) struct Letter {
+    pub(crate) command: AnyCommand,
+    pub(crate) keyword: &'static str,
+    /// Index of the tile among fresh tiles, if it's one without initial value
+    pub(crate) fresh: Option<usize>,
+    /// Index of the jumped to command
+    pub(crate) target: Option<usize>,
+}
+
+struct Searcher<'a> {
+    problem: &'a Problem,
+    limits: SearchLimits,
+    needs_outbox: bool,
+    optimizer: Optimizer,
+    result: SearchResult,
+}
+
+impl Searcher<'_> {
+    /// Enumerate programs of `size` commands starting with `program`, `fresh` being the number
+    /// of fresh tiles used. Returns `false` once out of candidates.
+    fn enumerate(
+        &mut self,
+        alphabet: &[Letter],
+        size: usize,
+        program: &mut Vec<usize>,
+        fresh: usize,
+    ) -> bool {
+        let i = program.len();
+        if i == size {
+            return self.try_candidate(alphabet, program);
+        }
+
+        for (i_letter, letter) in alphabet.iter().enumerate() {
+            if i == 0 && FAILS_FIRST.contains(&letter.keyword) {
+                continue;
+            }
+            if letter.fresh.is_some_and(|tile| tile > fresh) {
+                continue;
+            }
+            match (letter.command.flow(), letter.target) {
+                (_, Some(target)) if target == i + 1 => continue,
+                (Flow::Jump, Some(target)) if target == i => continue,
+                _ => {}
+            }
+            let used = program
+                .iter()
+                .filter(|&&j| alphabet[j].keyword == letter.keyword)
+                .count();
+            if self
+                .problem
+                .get_command_limit(letter.keyword)
+                .is_some_and(|limit| used >= limit)
+            {
+                continue;
+            }
+
+            program.push(i_letter);
+            let fresh = match letter.fresh {
+                Some(tile) if tile == fresh => fresh + 1,
+                _ => fresh,
+            };
+            let more = self.enumerate(alphabet, size, program, fresh);
+            program.pop();
+            if !more {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Run a complete candidate, keeping it if it's a new best. Returns `false` once out of
+    /// candidates.
+    fn try_candidate(&mut self, alphabet: &[Letter], program: &[usize]) -> bool {
+        if self.needs_outbox && !program.iter().any(|&i| alphabet[i].keyword == "OUTBOX") {
+            return true;
+        }
+        if let Some(max) = self.limits.max_candidates {
+            if self.result.candidates >= max {
+                return false;
+            }
+        }
+        self.result.candidates += 1;
+
+        let program = build(alphabet, program);
+        let limits = RunLimits {
+            max_steps: Some(self.limits.max_steps),
+            detect_loops: false,
+            max_total_steps: None,
+        };
+        let Ok(score) = program.run_with_limits(self.problem, limits) else {
+            return true;
+        };
+        if program.validate(self.problem).is_err()
+            || self
+                .optimizer
+                .passes
+                .iter()
+                .any(|pass| pass.run(&program).is_some())
+        {
+            return true;
+        }
+
+        let result = &mut self.result;
+        let smaller = result.smallest.as_ref().is_none_or(|best| {
+            (score.size, score.speed_avg) < (best.score.size, best.score.speed_avg)
+        });
+        let faster = result.fastest.as_ref().is_none_or(|best| {
+            (score.speed_avg, score.size) < (best.score.speed_avg, best.score.size)
+        });
+        match (smaller, faster) {
+            (true, true) => {
+                result.fastest = Some(FoundProgram {
+                    program: program.clone(),
+                    score: score.clone(),
+                });
+                result.smallest = Some(FoundProgram { program, score });
+            }
+            (true, false) => result.smallest = Some(FoundProgram { program, score }),
+            (false, true) => result.fastest = Some(FoundProgram { program, score }),
+            (false, false) => {}
+        }
+        true
+    }
+}
+
+/// Commands available to programs of `size` commands, including indirect tile arguments if
+/// `indirect`
+pub(crate) fn alphabet(problem: &Problem, size: usize, indirect: bool) -> Vec<Letter> {
+    let memory = problem.get_memory();
+    let initialized: Vec<usize> = (0..memory.len())
+        .filter(|&tile| memory[tile].is_some() && !problem.is_hole(tile))
+        .collect();
+    let dim = if problem.has_unbounded_memory() {
+        usize::MAX
+    } else {
+        memory.len()
+    };
+    // A program of `size` commands uses at most `size` fresh tiles
+    let fresh: Vec<usize> = (0..dim)
+        .filter(|&tile| memory.get(tile).is_none_or(Option::is_none) && !problem.is_hole(tile))
+        .take(size)
+        .collect();
+    let tiles = initialized
+        .iter()
+        .map(|&tile| (tile, None))
+        .chain(fresh.iter().enumerate().map(|(i, &tile)| (tile, Some(i))));
+
+    let mut alphabet = vec![];
+    for factory in problem.get_instruction_set().factories() {
+        let keyword = factory.command();
+        if !problem.is_command_available(keyword) {
+            continue;
+        }
+        let letter = |command: AnyCommand, fresh, target| Letter {
+            command,
+            keyword,
+            fresh,
+            target,
+        };
+
+        if let Some(command) = factory.create("") {
+            if !command.as_any().is::<Nop>() && !command.is_debug() {
+                alphabet.push(letter(command, None, None));
+            }
+        } else if factory.create("a").is_some() {
+            for target in 0..=size {
+                if let Some(command) = factory.create(label(target).as_str()) {
+                    alphabet.push(letter(command, None, Some(target)));
+                }
+            }
+        } else {
+            for (tile, fresh) in tiles.clone() {
+                let args = [
+                    Some(tile.to_string()),
+                    indirect.then(|| format!("[{tile}]")),
+                ];
+                for args in args.into_iter().flatten() {
+                    if let Some(command) = factory.create(&args) {
+                        alphabet.push(letter(command, fresh, None));
+                    }
+                }
+            }
+        }
+    }
+    alphabet
+}
+
+/// Build the program of `letters`, labelling every jump target. Targets past the end are
+/// labelled at the end.
+pub(crate) fn build(alphabet: &[Letter], letters: &[usize]) -> Program {
+    let mut targets: Vec<usize> = letters.iter().filter_map(|&i| alphabet[i].target).collect();
+    targets.sort_unstable();
+    targets.dedup();
+
+    let mut builder = ProgramBuilder::new();
+    for i in 0..=letters.len() {
+        targets
+            .iter()
+            .filter(|&&target| target == i || (i == letters.len() && target > i))
+            .for_each(|&target| builder.add_label_ref(Label::from(label(target))));
+        if let Some(&letter) = letters.get(i) {
+            builder.add_command_ref(alphabet[letter].command.clone());
+        }
+    }
+    builder.build()
+}
+
+/// Label of command `i`, in base 26 letters: `a`, ..., `z`, `ba`, ...
+fn label(mut i: usize) -> String {
+    let mut label = vec![b'a' + (i % 26) as u8];
+    while i >= 26 {
+        i /= 26;
+        label.push(b'a' + (i % 26) as u8);
+    }
+    label.reverse();
+    String::from_utf8(label).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{
+        problem::{ProblemBuilder, ProblemIO},
+        value::Value,
+    };
+
+    fn sources(found: &Option<FoundProgram>) -> Vec<String> {
+        found
+            .as_ref()
+            .unwrap()
+            .program
+            .get_commands()
+            .iter()
+            .map(|command| command.to_source())
+            .collect()
+    }
+
+    #[test]
+    fn search_test() {
+        let problem = ProblemBuilder::new()
+            .add_io(ProblemIO {
+                input: vec![Value::Int(1), Value::Int(2)],
+                output: vec![Value::Int(1), Value::Int(2)],
+            })
+            .enable_command(String::from("INBOX"))
+            .enable_command(String::from("OUTBOX"))
+            .enable_command(String::from("JUMP"))
+            .build()
+            .unwrap();
+
+        let result = search(&problem, SearchLimits::default());
+        assert!(result.complete);
+        assert_eq!(vec!["INBOX", "OUTBOX", "JUMP a"], sources(&result.smallest));
+        assert_eq!(
+            vec!["INBOX", "OUTBOX", "INBOX", "OUTBOX"],
+            sources(&result.fastest)
+        );
+        assert_eq!(4.0, result.fastest.unwrap().score.speed_avg);
+
+        let limited = SearchLimits {
+            max_candidates: Some(2),
+            ..SearchLimits::default()
+        };
+        let result = search(&problem, limited);
+        assert!(!result.complete);
+        assert_eq!(2, result.candidates);
+    }
+
+    #[test]
+    fn search_memory() {
+        // Output the sum of each pair of inputs
+        let problem = ProblemBuilder::new()
+            .add_io(ProblemIO {
+                input: vec![Value::Int(1), Value::Int(2), Value::Int(3), Value::Int(-4)],
+                output: vec![Value::Int(3), Value::Int(-1)],
+            })
+            .memory_dim(1)
+            .enable_command(String::from("INBOX"))
+            .enable_command(String::from("OUTBOX"))
+            .enable_command(String::from("COPYTO"))
+            .enable_command(String::from("ADD"))
+            .enable_command(String::from("JUMP"))
+            .build()
+            .unwrap();
+
+        let limits = SearchLimits {
+            max_size: 6,
+            max_candidates: None,
+            ..SearchLimits::default()
+        };
+        let result = search(&problem, limits);
+        assert!(result.complete);
+        assert_eq!(
+            vec!["INBOX", "COPYTO 0", "INBOX", "ADD 0", "OUTBOX", "JUMP a"],
+            sources(&result.smallest)
+        );
+    }
+
+    #[test]
+    fn label_test() {
+        assert_eq!("a", label(0));
+        assert_eq!("z", label(25));
+        assert_eq!("ba", label(26));
+    }
+}
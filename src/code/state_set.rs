@@ -0,0 +1,218 @@
+use alloc::{vec, vec::Vec};
+use core::hash::{Hash, Hasher};
+
+use crate::code::game_state::GameState;
+
+/// Marks an empty slot, fingerprints equal to it are remapped
+const EMPTY: u64 = 0;
+
+/// State Set
+///
+/// Open-addressing set of state fingerprints with linear probing, used to detect a run revisiting
+/// a state. Fingerprints are 64-bit hashes, so distinct states collide with negligible
+/// probability, in exchange for storing 8 bytes per state regardless of memory size.
+#[derive(Debug, Default, Clone)]
+pub struct StateSet {
+    slots: Vec<u64>,
+    len: usize,
+}
+
+impl StateSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Insert
+    ///
+    /// Returns `true` if the fingerprint was not in the set yet.
+    pub fn insert(&mut self, fingerprint: u64) -> bool {
+        if 2 * (self.len + 1) > self.slots.len() {
+            self.grow();
+        }
+
+        let fingerprint = if fingerprint == EMPTY { 1 } else { fingerprint };
+        if Self::insert_slot(&mut self.slots, fingerprint) {
+            self.len += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Clear
+    ///
+    /// Remove all fingerprints, keeping the allocation.
+    pub fn clear(&mut self) {
+        self.slots.fill(EMPTY);
+        self.len = 0;
+    }
+
+    fn grow(&mut self) {
+        let capacity = (2 * self.slots.len()).max(64);
+        let slots = core::mem::replace(&mut self.slots, vec![EMPTY; capacity]);
+        for fingerprint in slots.into_iter().filter(|&slot| slot != EMPTY) {
+            Self::insert_slot(&mut self.slots, fingerprint);
+        }
+    }
+
+    /// Capacity is a power of two, so the low bits of the fingerprint pick the starting slot.
+    fn insert_slot(slots: &mut [u64], fingerprint: u64) -> bool {
+        let mask = slots.len() - 1;
+        let mut i = fingerprint as usize & mask;
+        loop {
+            match slots[i] {
+                EMPTY => {
+                    slots[i] = fingerprint;
+                    return true;
+                }
+                slot if slot == fingerprint => return false,
+                _ => i = (i + 1) & mask,
+            }
+        }
+    }
+}
+
+/// Fingerprint
+///
+/// Hash of everything that determines how a run continues: the next command, acc, memory, read
+/// inputs, produced outputs & both stacks.
+pub fn fingerprint(game_state: &GameState) -> u64 {
+    let mut hasher = FingerprintHasher::default();
+    game_state.i_command.hash(&mut hasher);
+    game_state.acc.hash(&mut hasher);
+    game_state.memory.hash(&mut hasher);
+    game_state.i_input.hash(&mut hasher);
+    game_state.i_output.hash(&mut hasher);
+    game_state.stack.hash(&mut hasher);
+    game_state.call_stack.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Fingerprint Hasher
+///
+/// Multiply-rotate hasher, much cheaper than the default SipHash on the few small integers a
+/// state consists of. Not resistant to crafted collisions.
+#[derive(Default)]
+struct FingerprintHasher(u64);
+
+impl FingerprintHasher {
+    const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+    fn add(&mut self, word: u64) {
+        self.0 = (self.0.rotate_left(5) ^ word).wrapping_mul(Self::SEED);
+    }
+}
+
+impl Hasher for FingerprintHasher {
+    /// Mixes the high bits into the low bits used by [StateSet] to pick a slot.
+    fn finish(&self) -> u64 {
+        let mut hash = self.0;
+        hash ^= hash >> 33;
+        hash = hash.wrapping_mul(0xff51_afd7_ed55_8ccd);
+        hash ^= hash >> 33;
+        hash
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(8) {
+            let mut word = [0; 8];
+            word[..chunk.len()].copy_from_slice(chunk);
+            self.add(u64::from_le_bytes(word));
+        }
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.add(i as u64);
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.add(i as u64);
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.add(i);
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.add(i as u64);
+    }
+
+    fn write_isize(&mut self, i: isize) {
+        self.add(i as u64);
+    }
+
+    fn write_i32(&mut self, i: i32) {
+        self.add(i as u64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::value::Value;
+
+    #[test]
+    fn insert_test() {
+        let mut states = StateSet::new();
+        assert!(states.is_empty());
+
+        assert!(states.insert(5));
+        assert!(states.insert(EMPTY));
+        assert!(!states.insert(5));
+        assert!(!states.insert(EMPTY));
+        assert_eq!(2, states.len());
+
+        // Colliding starting slots
+        assert!(states.insert(5 + 64));
+        assert!(!states.insert(5 + 64));
+        assert_eq!(3, states.len());
+    }
+
+    #[test]
+    fn insert_grows() {
+        let mut states = StateSet::new();
+        for fingerprint in 0..1000u64 {
+            assert!(states.insert(fingerprint * 1024));
+        }
+        for fingerprint in 0..1000u64 {
+            assert!(!states.insert(fingerprint * 1024));
+        }
+        assert_eq!(1000, states.len());
+
+        states.clear();
+        assert!(states.is_empty());
+        assert!(states.insert(1024));
+    }
+
+    #[test]
+    fn fingerprint_test() {
+        let (input, output) = (vec![Value::Int(1)], vec![]);
+        let mut game_state = GameState::new(&input, &output, vec![None, Some(Value::Int(2))]);
+        let initial = fingerprint(&game_state);
+
+        game_state.speed += 3;
+        assert_eq!(initial, fingerprint(&game_state));
+
+        for change in [
+            |state: &mut GameState| state.i_command = 1,
+            |state: &mut GameState| state.acc = Some(Value::Int(0)),
+            |state: &mut GameState| state.memory[0] = Some(Value::Char('A')),
+            |state: &mut GameState| state.i_input = 1,
+            |state: &mut GameState| state.i_output = 1,
+            |state: &mut GameState| state.stack.push(Value::Int(0)),
+            |state: &mut GameState| state.call_stack.push(0),
+        ] {
+            let mut game_state = GameState::new(&input, &output, vec![None, Some(Value::Int(2))]);
+            change(&mut game_state);
+            assert_ne!(initial, fingerprint(&game_state));
+        }
+    }
+}
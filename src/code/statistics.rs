@@ -0,0 +1,179 @@
+use alloc::vec::Vec;
+
+use crate::code::program::{Program, RunError};
+use crate::game::generator::IOGenerator;
+use crate::game::problem::Problem;
+use crate::game::value::Value;
+
+/// Speed Stats
+///
+/// Distribution of speeds over sampled inputs. All values are `0` if there are no samples.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SpeedStats {
+    pub samples: usize,
+    pub mean: f64,
+    pub min: u64,
+    pub median: u64,
+    /// 95th percentile (nearest rank)
+    pub p95: u64,
+    pub max: u64,
+}
+
+impl SpeedStats {
+    pub fn from_speeds(speeds: &[u64]) -> Self {
+        if speeds.is_empty() {
+            return Self::default();
+        }
+
+        let mut sorted = speeds.to_vec();
+        sorted.sort_unstable();
+        let percentile = |p: f64| sorted[((p * sorted.len() as f64).ceil() as usize).max(1) - 1];
+
+        Self {
+            samples: sorted.len(),
+            mean: sorted.iter().map(|&speed| speed as f64).sum::<f64>() / sorted.len() as f64,
+            min: sorted[0],
+            median: percentile(0.5),
+            p95: percentile(0.95),
+            max: sorted[sorted.len() - 1],
+        }
+    }
+}
+
+/// Sample Error
+///
+/// Input on which sampling failed.
+#[derive(Debug, PartialEq)]
+pub struct SampleError {
+    pub input: Vec<Value>,
+    pub error: RunError,
+}
+
+/// Sample Speeds
+///
+/// Run the program on every input of `generator` & aggregate the speeds, like the game's average
+/// over random inboxes. If the problem has a reference, outputs are checked against it. Returns:
+/// - [Ok(SpeedStats)] if every run succeeded
+/// - [Err(SampleError)] for the first failing input, incl. exceeding `max_steps`
+pub fn sample_speeds(
+    problem: &Problem,
+    program: &Program,
+    generator: &IOGenerator,
+    max_steps: u32,
+) -> Result<SpeedStats, SampleError> {
+    let mut speeds = Vec::with_capacity(generator.count);
+    for input in generator.generate() {
+        let result =
+            program
+                .execute(problem, &input, max_steps)
+                .and_then(|execution| match problem.get_reference() {
+                    Some(reference) => {
+                        check_output(&reference.output(&input), &execution.output)?;
+                        Ok(execution.speed)
+                    }
+                    None => Ok(execution.speed),
+                });
+
+        match result {
+            Ok(speed) => speeds.push(speed),
+            Err(error) => return Err(SampleError { input, error }),
+        }
+    }
+
+    Ok(SpeedStats::from_speeds(&speeds))
+}
+
+fn check_output(expected: &[Value], output: &[Value]) -> Result<(), RunError> {
+    let mismatch =
+        (0..expected.len().max(output.len())).find(|&i| expected.get(i) != output.get(i));
+
+    match mismatch.map(|i| (i, expected.get(i), output.get(i))) {
+        Some((index, Some(&expected), Some(&value))) => Err(RunError::WrongOutput {
+            index,
+            expected,
+            value,
+        }),
+        Some((index, None, Some(&value))) => Err(RunError::ExtraOutput { index, value }),
+        Some((index, Some(&first), None)) => Err(RunError::MissingOutput {
+            index,
+            remaining: expected.len() - index,
+            expected: first,
+        }),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compiler::compile::Compiler;
+    use crate::game::generator::ValueRange;
+    use crate::game::problem::ProblemBuilder;
+
+    use super::*;
+
+    fn create_generator() -> IOGenerator {
+        IOGenerator {
+            count: 20,
+            min_length: 1,
+            max_length: 10,
+            values: ValueRange::Int { min: 0, max: 9 },
+            seed: 11,
+        }
+    }
+
+    #[test]
+    fn from_speeds_test() {
+        let stats = SpeedStats::from_speeds(&[5, 1, 4, 2, 3]);
+        assert_eq!(
+            SpeedStats {
+                samples: 5,
+                mean: 3.0,
+                min: 1,
+                median: 3,
+                p95: 5,
+                max: 5,
+            },
+            stats
+        );
+
+        assert_eq!(SpeedStats::default(), SpeedStats::from_speeds(&[]));
+    }
+
+    #[test]
+    fn sample_speeds_test() {
+        let problem = ProblemBuilder::new()
+            .reference(|input| input.to_vec())
+            .enable_all_commands()
+            .build()
+            .unwrap();
+        let program = Compiler::default()
+            .compile("a:\nINBOX\nOUTBOX\nJUMP a")
+            .unwrap();
+
+        let stats = sample_speeds(&problem, &program, &create_generator(), 1000).unwrap();
+        assert_eq!(20, stats.samples);
+        assert!(stats.min >= 3 && stats.max <= 30);
+        assert!(stats.min <= stats.median && stats.median <= stats.p95 && stats.p95 <= stats.max);
+    }
+
+    #[test]
+    fn sample_speeds_fails() {
+        let problem = ProblemBuilder::new()
+            .reference(|input| input.to_vec())
+            .enable_all_commands()
+            .build()
+            .unwrap();
+        let program = Compiler::default().compile("INBOX\nOUTBOX").unwrap();
+
+        let error = sample_speeds(&problem, &program, &create_generator(), 1000).unwrap_err();
+        assert!(error.input.len() > 1);
+        assert_eq!(
+            RunError::MissingOutput {
+                index: 1,
+                remaining: error.input.len() - 1,
+                expected: error.input[1],
+            },
+            error.error
+        );
+    }
+}
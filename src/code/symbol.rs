@@ -0,0 +1,191 @@
+use alloc::{string::String, sync::Arc, vec::Vec};
+use core::{borrow::Borrow, fmt};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Label
+///
+/// Name of a label, shared between the compiler, commands & errors - cloning only bumps a
+/// reference count instead of copying the string. Names are freed with the last user.
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Label(Arc<str>);
+
+impl Label {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for Label {
+    fn from(value: &str) -> Self {
+        Label(Arc::from(value))
+    }
+}
+
+impl From<String> for Label {
+    fn from(value: String) -> Self {
+        Label(Arc::from(value))
+    }
+}
+
+impl Borrow<str> for Label {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl PartialEq<str> for Label {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Label {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl fmt::Debug for Label {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for Label {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for Label {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Label {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Label::from)
+    }
+}
+
+/// Symbol
+///
+/// Id of a label in a program's [SymbolTable] - a `u32` that is cheap to copy, compare & hash.
+/// Symbols are only meaningful within their table, where they order like their names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    /// Index
+    ///
+    /// Returns the position of the symbol in its table, e.g. to key tables by symbol.
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Symbol Table
+///
+/// Interned labels of a single program, each program owns its table & frees it with the program.
+/// Symbols are assigned in name order, so that sorting by symbol sorts by name.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SymbolTable {
+    /// Name of each symbol, sorted & without duplicates
+    names: Vec<Label>,
+}
+
+impl SymbolTable {
+    pub const EMPTY: SymbolTable = SymbolTable { names: Vec::new() };
+
+    /// New
+    ///
+    /// Intern `labels`, duplicates get a single symbol.
+    pub fn new(labels: impl IntoIterator<Item = Label>) -> Self {
+        let mut names: Vec<Label> = labels.into_iter().collect();
+        names.sort_unstable();
+        names.dedup();
+        Self { names }
+    }
+
+    /// Lookup
+    ///
+    /// Returns the symbol of `name`, [None] if it is not in the table.
+    pub fn lookup(&self, name: &str) -> Option<Symbol> {
+        self.names
+            .binary_search_by(|label| label.as_str().cmp(name))
+            .ok()
+            .map(|i| Symbol(i as u32))
+    }
+
+    /// Name
+    ///
+    /// Returns the label of a symbol of this table.
+    pub fn name(&self, symbol: Symbol) -> &Label {
+        &self.names[symbol.index()]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Iter
+    ///
+    /// Returns symbols with their labels, in name order.
+    pub fn iter(&self) -> impl Iterator<Item = (Symbol, &Label)> {
+        self.names
+            .iter()
+            .enumerate()
+            .map(|(i, label)| (Symbol(i as u32), label))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{format, vec};
+
+    use super::*;
+
+    #[test]
+    fn table_test() {
+        let table = SymbolTable::new(["b", "a", "c", "a"].map(Label::from));
+        assert_eq!(3, table.len());
+        let a = table.lookup("a").unwrap();
+        let b = table.lookup("b").unwrap();
+        assert!(a < b);
+        assert_eq!("a", table.name(a).as_str());
+        assert_eq!(None, table.lookup("d"));
+        assert_eq!(
+            vec!["a", "b", "c"],
+            table
+                .iter()
+                .map(|(_, label)| label.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert!(SymbolTable::EMPTY.is_empty());
+    }
+
+    #[test]
+    fn label_test() {
+        let label = Label::from("label_test");
+        assert_eq!("label_test", format!("{label}"));
+        assert_eq!("\"label_test\"", format!("{label:?}"));
+        assert_eq!(label, "label_test");
+        assert_eq!(label, Label::from(String::from("label_test")));
+        assert_eq!("", Label::default().as_str());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn serde_label() {
+        let label = Label::from("serde_label");
+        let serialized = serde_json::to_string(&label).unwrap();
+        assert_eq!("\"serde_label\"", serialized);
+        assert_eq!(label, serde_json::from_str::<Label>(&serialized).unwrap());
+    }
+}
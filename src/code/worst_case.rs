@@ -0,0 +1,148 @@
+use alloc::vec::Vec;
+
+use crate::code::program::Program;
+use crate::game::generator::{IOGenerator, Rng};
+use crate::game::problem::Problem;
+use crate::game::value::Value;
+
+/// Search Options
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SearchOptions {
+    /// Number of mutations tried after the initial inputs
+    pub iterations: usize,
+    pub seed: u64,
+    /// Step limit of a single run, inputs exceeding it are skipped
+    pub max_steps: u32,
+}
+
+/// Worst Case
+///
+/// Slowest input found & its speed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorstCase {
+    pub input: Vec<Value>,
+    pub speed: u64,
+}
+
+/// Find Worst Case
+///
+/// Hill-climbing search for the input maximizing the program's speed. Starts from the slowest
+/// input of `generator`, then repeatedly mutates it (replacing, inserting or removing a value
+/// within the generator's value & length ranges), keeping mutations that are not faster. Inputs
+/// on which the program fails are skipped. Returns [None] if the program failed on every input.
+pub fn find_worst_case(
+    problem: &Problem,
+    program: &Program,
+    generator: &IOGenerator,
+    options: SearchOptions,
+) -> Option<WorstCase> {
+    let speed = |input: &[Value]| {
+        program
+            .execute(problem, input, options.max_steps)
+            .ok()
+            .map(|execution| execution.speed)
+    };
+
+    let mut best = generator
+        .generate()
+        .into_iter()
+        .filter_map(|input| speed(&input).map(|speed| WorstCase { input, speed }))
+        .max_by_key(|worst_case| worst_case.speed)?;
+
+    let mut rng = Rng::new(options.seed);
+    for _ in 0..options.iterations {
+        let input = mutate(&best.input, generator, &mut rng);
+        if let Some(speed) = speed(&input) {
+            if speed >= best.speed {
+                best = WorstCase { input, speed };
+            }
+        }
+    }
+
+    Some(best)
+}
+
+fn mutate(input: &[Value], generator: &IOGenerator, rng: &mut Rng) -> Vec<Value> {
+    let mut input = input.to_vec();
+    let can_insert = input.len() < generator.max_length;
+    let can_remove = input.len() > generator.min_length;
+
+    match rng.range(0, 2) {
+        1 if can_insert => {
+            let i = rng.range(0, input.len() as i64) as usize;
+            input.insert(i, generator.values.sample(rng));
+        }
+        2 if can_remove => {
+            let i = rng.range(0, input.len() as i64 - 1) as usize;
+            input.remove(i);
+        }
+        _ if !input.is_empty() => {
+            let i = rng.range(0, input.len() as i64 - 1) as usize;
+            input[i] = generator.values.sample(rng);
+        }
+        _ if can_insert => input.push(generator.values.sample(rng)),
+        _ => {}
+    }
+
+    input
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::compiler::compile::Compiler;
+    use crate::game::generator::ValueRange;
+    use crate::game::problem::ProblemBuilder;
+
+    use super::*;
+
+    #[test]
+    fn find_worst_case_test() {
+        let problem = ProblemBuilder::new()
+            .memory_dim(1)
+            .enable_all_commands()
+            .build()
+            .unwrap();
+        // Counts down each input, so the slowest input is the longest with the largest values
+        let code = "\
+a:
+    INBOX
+b:
+    JUMPZ a
+    COPYTO 0
+    BUMPDN 0
+    JUMP b
+";
+        let program = Compiler::default().compile(code).unwrap();
+        let generator = IOGenerator {
+            count: 3,
+            min_length: 1,
+            max_length: 3,
+            values: ValueRange::Int { min: 0, max: 5 },
+            seed: 1,
+        };
+        let options = SearchOptions {
+            iterations: 500,
+            seed: 2,
+            max_steps: 10_000,
+        };
+
+        let initial = generator
+            .generate()
+            .iter()
+            .filter_map(|input| program.execute(&problem, input, 1000).ok())
+            .map(|execution| execution.speed)
+            .max()
+            .unwrap();
+
+        let worst_case = find_worst_case(&problem, &program, &generator, options).unwrap();
+        assert!(worst_case.speed >= initial);
+        assert_eq!(vec![Value::Int(5); 3], worst_case.input);
+        assert_eq!(
+            Some(worst_case.speed),
+            program
+                .execute(&problem, &worst_case.input, 1000)
+                .ok()
+                .map(|e| e.speed)
+        );
+    }
+}
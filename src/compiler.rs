@@ -1 +1,5 @@
 pub mod compile;
+pub mod export;
+pub mod navigation;
+pub mod pseudo;
+pub mod tokenize;
@@ -1,25 +1,59 @@
+use std::sync::LazyLock;
+
 use regex::Regex;
 
 use crate::{
     code::{
-        commands::{AnyCommand, CommandFactory, CommandValue},
-        program::{Program, ProgramBuilder},
+        commands::{debug::DebugPrintFactory, AnyCommand, CommandFactory, InstructionSet},
+        program::{Define, DefineKind, Program, ProgramBuilder},
+        symbol::Label,
     },
     commands,
+    compiler::pseudo::PseudoInstruction,
+    pseudo_instructions,
 };
 
-const COMMAND_REGEX: &str = r"^([A-Z]+)(?:\s+(.*)|(\s*))$"; // Used with trimmed string
+pub use crate::code::commands::{compile_command_value, compile_label};
 
-#[derive(Debug, PartialEq)]
+pub(crate) const COMMAND_REGEX: &str = r"^([A-Z]+)(?:\s+(.*)|(\s*))$"; // Used with trimmed string
+
+/// Patterns are compiled once & shared, parsing a line only borrows slices of it
+pub(crate) static COMMAND: LazyLock<Regex> = LazyLock::new(|| Regex::new(COMMAND_REGEX).unwrap());
+static COMMENT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^COMMENT\s+(\d+)$").unwrap());
+static DEFINE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^DEFINE\s+(COMMENT|LABEL)\s+(\d+)$").unwrap());
+static NEW_LABEL: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^([a-z]+):$").unwrap());
+
+/// Parse Error
+///
+/// Error codes `E01xx`, see [ParseError::code].
+#[derive(Debug, PartialEq, thiserror::Error)]
+#[non_exhaustive]
 pub enum ParseError {
+    #[error("Illegal line: {0}")]
     IllegalLine(String),
+    #[error("Label `{0}` is not defined")]
+    MissingLabel(Label),
+}
+
+impl ParseError {
+    /// Code
+    ///
+    /// Returns the stable error code, e.g. `E0101`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParseError::IllegalLine(_) => "E0101",
+            ParseError::MissingLabel(_) => "E0102",
+        }
+    }
 }
 
 #[derive(Debug)]
-pub enum ParsedLine {
+pub enum ParsedLine<'a> {
     Comment(u32),
-    Label(String),
+    Label(&'a str),
     Command(AnyCommand),
+    Commands(Vec<AnyCommand>),
     Empty,
     CommentedCode,
     Define(DefineInstruction),
@@ -33,38 +67,123 @@ pub enum DefineInstruction {
 
 pub struct Compiler {
     pub commands: Vec<Box<dyn CommandFactory>>,
+    pub pseudo_instructions: Vec<Box<dyn PseudoInstruction>>,
+    /// Strict (game) mode - debug commands are stripped from compiled programs
+    pub strict: bool,
 }
 
 impl Default for Compiler {
     fn default() -> Self {
+        let mut commands: Vec<Box<dyn CommandFactory>> = commands!();
+        commands.push(Box::new(DebugPrintFactory));
+
         Self {
-            commands: commands!(),
+            commands,
+            pseudo_instructions: pseudo_instructions!(),
+            strict: false,
         }
     }
 }
 
 impl Compiler {
+    /// New
+    ///
+    /// Create a [Compiler] that recognizes commands of the given [InstructionSet].
+    pub fn new(instruction_set: &InstructionSet) -> Self {
+        let mut commands = instruction_set.factories();
+        commands.push(Box::new(DebugPrintFactory));
+
+        Self {
+            commands,
+            pseudo_instructions: pseudo_instructions!(),
+            strict: false,
+        }
+    }
+
+    /// Strict
+    ///
+    /// Enable or disable strict (game) mode, in which debug commands are stripped.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Extended
+    ///
+    /// Create a [Compiler] for [InstructionSet::Extended].
+    pub fn extended() -> Self {
+        Self::new(&InstructionSet::Extended)
+    }
+
     /// Compile
     ///
     /// Compile HRM code consisting of instructions (e.g. [Command]) separated by new lines.
+    /// Drawings exported by the game (`DEFINE` blobs) are kept, see [Program::get_defines].
     /// Returns:
-    /// - [Ok(Program)] if code was successfully parsed
+    /// - [Ok(Program)] if code was successfully parsed & all jump labels exist
     /// - [Err(ParseError)] else
     pub fn compile(&self, code: &str) -> Result<Program, ParseError> {
         let mut builder = ProgramBuilder::new();
+        let mut define: Option<Define> = None;
+
+        for (i, line) in code.lines().enumerate() {
+            builder.source_line_ref(i);
+            // Blob lines of a define, up to & including the one ending with `;`
+            if let Some(mut current) = define.take() {
+                let line = line.trim();
+                if !line.is_empty() {
+                    current.blob.push(line.to_string());
+                }
+                if line.ends_with(';') {
+                    builder.add_define_ref(current);
+                } else {
+                    define = Some(current);
+                }
+                continue;
+            }
 
-        for line in code.lines() {
             match self.compile_instruction(line)? {
-                ParsedLine::Label(label) => builder.add_label_ref(label),
+                ParsedLine::Label(label) => builder.add_label_ref(Label::from(label)),
+                ParsedLine::Command(command) if self.strict && command.is_debug() => {}
                 ParsedLine::Command(command) => builder.add_command_ref(command),
+                ParsedLine::Commands(commands) => commands
+                    .into_iter()
+                    .for_each(|command| builder.add_command_ref(command)),
+                ParsedLine::Comment(comment) => builder.add_comment_ref(comment),
+                ParsedLine::Define(instruction) => {
+                    let (kind, index) = match instruction {
+                        DefineInstruction::COMMENT(index) => (DefineKind::Comment, index),
+                        DefineInstruction::LABEL(index) => (DefineKind::Label, index),
+                    };
+                    define = Some(Define {
+                        kind,
+                        index,
+                        blob: vec![],
+                    });
+                }
                 _ => {}
             }
         }
+        if let Some(define) = define {
+            builder.add_define_ref(define); // Unterminated blob
+        }
+
+        let program = builder.build();
+
+        let missing_label = program
+            .get_commands()
+            .iter()
+            .zip(program.get_targets())
+            .filter(|(_, target)| target.is_none())
+            .find_map(|(command, _)| command.requires_label());
+        if let Some(label) = missing_label {
+            return Err(ParseError::MissingLabel(label.clone()));
+        }
 
-        Ok(builder.build())
+        Ok(program)
     }
 
-    fn compile_instruction(&self, instruction: &str) -> Result<ParsedLine, ParseError> {
+    fn compile_instruction<'a>(&self, instruction: &'a str) -> Result<ParsedLine<'a>, ParseError> {
         let instruction = instruction.trim();
 
         if instruction.is_empty() {
@@ -91,6 +210,10 @@ impl Compiler {
             return Ok(ParsedLine::Command(command));
         }
 
+        if let Some(commands) = self.compile_pseudo_instruction(instruction) {
+            return Ok(ParsedLine::Commands(commands));
+        }
+
         Err(ParseError::IllegalLine(instruction.to_string()))
     }
 
@@ -101,9 +224,8 @@ impl Compiler {
     /// - [None] else
     ///
     /// Expects instruction to be trimmed.
-    fn compile_command(&self, instruction: &str) -> Option<AnyCommand> {
-        let regex = Regex::new(COMMAND_REGEX).unwrap();
-        if let Some(captures) = regex.captures(instruction) {
+    pub(crate) fn compile_command(&self, instruction: &str) -> Option<AnyCommand> {
+        if let Some(captures) = COMMAND.captures(instruction) {
             let (_, [command, args]) = captures.extract();
 
             return self
@@ -116,6 +238,28 @@ impl Compiler {
 
         None
     }
+
+    /// Compile Pseudo Instruction
+    ///
+    /// Tries to compile an instruction as a [PseudoInstruction]. Returns:
+    /// - [Ok(Vec<AnyCommand>)] with the expanded commands if args are correct
+    /// - [None] else
+    ///
+    /// Expects instruction to be trimmed.
+    fn compile_pseudo_instruction(&self, instruction: &str) -> Option<Vec<AnyCommand>> {
+        if let Some(captures) = COMMAND.captures(instruction) {
+            let (_, [command, args]) = captures.extract();
+
+            return self
+                .pseudo_instructions
+                .iter()
+                .filter(|pseudo| pseudo.command() == command)
+                .filter_map(|pseudo| pseudo.expand(args))
+                .next();
+        }
+
+        None
+    }
 }
 
 /// Compile Comment
@@ -125,11 +269,10 @@ impl Compiler {
 /// - [None] else
 ///
 /// Expects instruction to be trimmed.
-fn compile_comment(instruction: &str) -> Option<u32> {
-    let regex = Regex::new(r"^COMMENT\s+(\d+)$").unwrap();
-    if let Some(captures) = regex.captures(instruction) {
+pub(crate) fn compile_comment(instruction: &str) -> Option<u32> {
+    if let Some(captures) = COMMENT.captures(instruction) {
         let (_, [arg]) = captures.extract();
-        return Some(arg.parse().unwrap());
+        return arg.parse().ok();
     }
 
     None
@@ -142,15 +285,14 @@ fn compile_comment(instruction: &str) -> Option<u32> {
 /// - [None] else
 ///
 /// Expects instruction to be trimmed.
-fn compile_define(instruction: &str) -> Option<DefineInstruction> {
-    let regex = Regex::new(r"^DEFINE\s+(COMMENT|LABEL)\s+(\d+)$").unwrap();
-    if let Some(captures) = regex.captures(instruction) {
+pub(crate) fn compile_define(instruction: &str) -> Option<DefineInstruction> {
+    if let Some(captures) = DEFINE.captures(instruction) {
         let (_, [define_type, index]) = captures.extract();
-        let index = index.parse().unwrap();
+        let index = index.parse().ok()?;
         return match define_type {
             "COMMENT" => Some(DefineInstruction::COMMENT(index)),
             "LABEL" => Some(DefineInstruction::LABEL(index)),
-            &_ => panic!("This cannot occur!"),
+            &_ => None,
         };
     }
 
@@ -160,51 +302,15 @@ fn compile_define(instruction: &str) -> Option<DefineInstruction> {
 /// Compile New Label
 ///
 /// Tries to compile an instruction as a new label. Returns:
-/// - [Ok(String)] if instruction consists of lowercase a-z followed by a colon
+/// - [Ok(&str)] with the label, borrowed from the instruction, if instruction consists of
+///   lowercase a-z followed by a colon
 /// - [None] else
 ///
 /// Expects instruction to be trimmed.
-fn compile_new_label(instruction: &str) -> Option<String> {
-    let regex = Regex::new(r"^([a-z]+):$").unwrap();
-    if let Some(captures) = regex.captures(instruction) {
+pub(crate) fn compile_new_label(instruction: &str) -> Option<&str> {
+    if let Some(captures) = NEW_LABEL.captures(instruction) {
         let (_, [label]) = captures.extract();
-        return Some(label.to_string());
-    }
-
-    None
-}
-
-/// Compile Command Value
-///
-/// Returns [Ok(Value)] if input matches one of:
-/// - <code>\d+</code>
-/// - <code>\[\d+\]</code>
-///
-/// Returns [None] otherwise.
-pub fn compile_command_value(value: &str) -> Option<CommandValue> {
-    let regex = Regex::new(r"^(\[\d+]|\d+)$").unwrap();
-    if let Some(captures) = regex.captures(value) {
-        let (_, [value]) = captures.extract();
-        return if value.starts_with('[') {
-            let value = value[1..(value.len() - 1)].parse().unwrap();
-            Some(CommandValue::Index(value))
-        } else {
-            let value = value.parse().unwrap();
-            Some(CommandValue::Value(value))
-        };
-    }
-
-    None
-}
-
-/// Compile Label
-///
-/// Returns [Ok(String)] if input matches <code>\[a-z\]+</code>, else returns [None].
-pub fn compile_label(label: &str) -> Option<String> {
-    let regex = Regex::new(r"^([a-z]+)$").unwrap();
-    if let Some(captures) = regex.captures(label) {
-        let (_, [label]) = captures.extract();
-        return Some(label.to_string());
+        return Some(label);
     }
 
     None
@@ -212,7 +318,10 @@ pub fn compile_label(label: &str) -> Option<String> {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeSet;
+
     use super::*;
+    use crate::code::commands::CommandValue;
 
     #[test]
     fn valid_commands_no_args() {
@@ -249,6 +358,111 @@ mod tests {
         }
     }
 
+    #[test]
+    fn compile_succeeds() {
+        let code = "a:\n    INBOX\n    OUTBOX\n    JUMP a\n";
+        let program = Compiler::default().compile(code).unwrap();
+        assert_eq!(3, program.get_commands().len());
+        assert_eq!(Ok(0), program.get_target(2));
+    }
+
+    #[test]
+    fn compile_missing_label() {
+        let code = "a:\n    INBOX\n    JUMPZ b\n";
+        let err = Compiler::default().compile(code).unwrap_err();
+        assert_eq!(ParseError::MissingLabel(Label::from("b")), err);
+    }
+
+    #[test]
+    fn compile_extended() {
+        let code = "a:\nINBOX\nJUMPP a\nMUL 0\nDIV [1]\nMOD 2\nNEG\nNOP\nOUTBOX";
+        assert!(Compiler::default().compile(code).is_err());
+        assert_eq!(
+            8,
+            Compiler::extended()
+                .compile(code)
+                .unwrap()
+                .get_commands()
+                .len()
+        );
+    }
+
+    #[test]
+    fn compile_custom() {
+        let instruction_set =
+            InstructionSet::Custom(BTreeSet::from([String::from("INBOX"), String::from("NEG")]));
+        let compiler = Compiler::new(&instruction_set);
+
+        assert!(compiler.compile("INBOX\nNEG").is_ok());
+        assert!(compiler.compile("INBOX\nOUTBOX").is_err());
+    }
+
+    #[test]
+    fn compile_pseudo_instructions() {
+        let code = "a:\n    ZERO 0\n    MOVE [1] 2\n    JUMP a\n";
+        let program = Compiler::default().compile(code).unwrap();
+
+        let commands: Vec<&str> = program
+            .get_commands()
+            .iter()
+            .map(|command| command.factory().command())
+            .collect();
+        assert_eq!(
+            vec!["COPYFROM", "SUB", "COPYTO", "COPYFROM", "COPYTO", "JUMP"],
+            commands
+        );
+        assert_eq!(Ok(0), program.get_target(5));
+    }
+
+    #[test]
+    fn compile_debug() {
+        let code = "INBOX\nDEBUG \"after inbox\" 0\nOUTBOX";
+
+        let program = Compiler::default().compile(code).unwrap();
+        assert_eq!(3, program.get_commands().len());
+        assert!(program.get_commands()[1].is_debug());
+
+        let program = Compiler::default().strict(true).compile(code).unwrap();
+        assert_eq!(2, program.get_commands().len());
+    }
+
+    #[test]
+    fn compile_defines() {
+        let code = "\
+-- HUMAN RESOURCE MACHINE PROGRAM --
+
+    INBOX
+    COMMENT  0
+    OUTBOX
+
+
+DEFINE COMMENT 0
+eJwzYmBgEGHyd
+Ab;
+
+DEFINE LABEL 3
+eJxTYGAQ;
+";
+        let program = Compiler::default().compile(code).unwrap();
+
+        assert_eq!(2, program.get_commands().len());
+        assert_eq!(
+            &vec![
+                Define {
+                    kind: DefineKind::Comment,
+                    index: 0,
+                    blob: vec![String::from("eJwzYmBgEGHyd"), String::from("Ab;")],
+                },
+                Define {
+                    kind: DefineKind::Label,
+                    index: 3,
+                    blob: vec![String::from("eJxTYGAQ;")],
+                },
+            ],
+            program.get_defines()
+        );
+    }
+
     #[test]
     fn compile_comment_succeeds() {
         let line = "COMMENT 123";
@@ -257,8 +471,9 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::useless_vec)]
     fn compile_comment_fails() {
-        for arg in vec!["", "1a", "b", "C", "aBc", "0 1"] {
+        for arg in vec!["", "1a", "b", "C", "aBc", "0 1", "99999999999"] {
             let line = format!("COMMENT {}", arg);
             let comment = compile_comment(&line);
             assert!(comment.is_none());
@@ -285,6 +500,7 @@ mod tests {
         for line in ["a:", "abc:"] {
             let label = compile_new_label(line).unwrap();
             assert_eq!(&line[..line.len() - 1], label);
+            assert_eq!(line.as_ptr(), label.as_ptr()); // Borrowed, not copied
         }
     }
 
@@ -343,7 +559,18 @@ mod tests {
         let compiler = Compiler::default();
 
         for cmd in ["COPYFROM", "COPYTO", "ADD", "SUB", "BUMPUP", "BUMPDN"] {
-            for arg in ["", "1a", "abc", "D", "[", "[]", "[1a]", "[A]"] {
+            for arg in [
+                "",
+                "1a",
+                "abc",
+                "D",
+                "[",
+                "[]",
+                "[1a]",
+                "[A]",
+                "99999999999999999999999",
+                "[99999999999999999999999]",
+            ] {
                 let line = format!("{} {}", cmd, arg);
                 let command = compiler.compile_command(&line);
                 assert!(command.is_none());
@@ -396,6 +623,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::useless_vec)]
     fn compile_label_succeeds() {
         for label in vec!["a", "bc", "def"] {
             let parsed_label = compile_label(label).unwrap();
@@ -404,6 +632,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::useless_vec)]
     fn compile_label_fails() {
         for label in vec!["A", "aBc", "1", "a1", "ab:", ""] {
             let label = compile_label(label);
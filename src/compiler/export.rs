@@ -0,0 +1,153 @@
+use std::fmt::Write;
+
+use crate::code::{
+    commands::{Flow, InstructionSet},
+    program::{DefineKind, Program},
+};
+
+/// First line of every program copied from the game
+pub const GAME_HEADER: &str = "-- HUMAN RESOURCE MACHINE PROGRAM --";
+
+/// Export Error
+///
+/// Error codes `E07xx`, see [ExportError::code].
+#[derive(Debug, PartialEq, thiserror::Error)]
+#[non_exhaustive]
+pub enum ExportError {
+    /// Extension commands & indirect jumps, the game has neither
+    #[error("Command `{0}` does not exist in the game")]
+    UnsupportedCommand(String),
+}
+
+impl ExportError {
+    /// Code
+    ///
+    /// Returns the stable error code, e.g. `E0701`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ExportError::UnsupportedCommand(_) => "E0701",
+        }
+    }
+}
+
+/// Export Game
+///
+/// Write [Program] the way the game copies it to the clipboard, so it can be pasted back in:
+/// - the [GAME_HEADER] line & a blank line
+/// - labels unindented, commands & comments indented by 4 spaces with mnemonics padded to 8
+/// - two blank lines, then every [Program::get_defines] blob, each followed by a blank line
+///
+/// Debug commands are left out. Compiling the export gives back the same program, including its
+/// defines. Fails if the program uses commands the game doesn't have.
+pub fn export_game(program: &Program) -> Result<String, ExportError> {
+    let mut labels: Vec<(usize, &str)> = program
+        .get_labels()
+        .map(|(label, idx)| (idx, label.as_str()))
+        .collect();
+    labels.sort();
+    let mut labels = labels.into_iter().peekable();
+    let mut comments = program.get_comments().iter().peekable();
+
+    let mut out = format!("{GAME_HEADER}\n\n");
+    let commands = program.get_commands();
+    for i in 0..=commands.len() {
+        while let Some((_, label)) = labels.next_if(|(idx, _)| *idx <= i) {
+            writeln!(out, "{label}:").unwrap();
+        }
+        while let Some((_, comment)) = comments.next_if(|(idx, _)| *idx <= i) {
+            writeln!(out, "    {:<8} {comment}", "COMMENT").unwrap();
+        }
+
+        let Some(command) = commands.get(i).filter(|command| !command.is_debug()) else {
+            continue;
+        };
+        let source = command.to_source();
+        if !InstructionSet::Vanilla.contains(command.factory().command())
+            || command.flow() == Flow::Indirect
+        {
+            return Err(ExportError::UnsupportedCommand(source));
+        }
+        match source.split_once(' ') {
+            Some((mnemonic, args)) => writeln!(out, "    {mnemonic:<8} {args}").unwrap(),
+            None => writeln!(out, "    {source:<8}").unwrap(),
+        }
+    }
+
+    out.push_str("\n\n");
+    for define in program.get_defines() {
+        let kind = match define.kind {
+            DefineKind::Comment => "COMMENT",
+            DefineKind::Label => "LABEL",
+        };
+        writeln!(out, "DEFINE {kind} {}", define.index).unwrap();
+        define
+            .blob
+            .iter()
+            .for_each(|line| writeln!(out, "{line}").unwrap());
+        out.push('\n');
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::compile::Compiler;
+
+    // Mnemonics without args are padded with trailing spaces, like in the game
+    const EXPORT: &str = concat!(
+        "-- HUMAN RESOURCE MACHINE PROGRAM --\n",
+        "\n",
+        "a:\n",
+        "    INBOX   \n",
+        "    COPYTO   0\n",
+        "    COMMENT  0\n",
+        "    JUMPZ    b\n",
+        "    COPYFROM [0]\n",
+        "    OUTBOX  \n",
+        "    JUMP     a\n",
+        "b:\n",
+        "\n",
+        "\n",
+        "DEFINE COMMENT 0\n",
+        "eJwzYmBgEGHyd\n",
+        "Ab;\n",
+        "\n",
+        "DEFINE LABEL 0\n",
+        "eJxTYGAQ;\n",
+        "\n",
+    );
+
+    #[test]
+    fn export_game_round_trip() {
+        let program = Compiler::default().compile(EXPORT).unwrap();
+        let export = export_game(&program).unwrap();
+        assert_eq!(EXPORT, export);
+
+        let compiled = Compiler::default().compile(&export).unwrap();
+        assert_eq!(program.get_commands(), compiled.get_commands());
+        assert_eq!(program.get_defines(), compiled.get_defines());
+    }
+
+    #[test]
+    fn export_game_canonical() {
+        let code = "a:\nINBOX\nDEBUG \"x\" 0\n  OUTBOX\nJUMP a";
+        let program = Compiler::default().compile(code).unwrap();
+
+        assert_eq!(
+            format!("{GAME_HEADER}\n\na:\n    INBOX   \n    OUTBOX  \n    JUMP     a\n\n\n"),
+            export_game(&program).unwrap()
+        );
+    }
+
+    #[test]
+    fn export_game_fails() {
+        for (code, command) in [("MUL 2\n", "MUL 2"), ("JUMP [1]\n", "JUMP [1]")] {
+            let program = Compiler::extended().compile(code).unwrap();
+            let err = export_game(&program).unwrap_err();
+            assert_eq!(ExportError::UnsupportedCommand(command.to_string()), err);
+            assert_eq!("E0701", err.code());
+        }
+    }
+}
@@ -0,0 +1,268 @@
+use std::ops::Range;
+
+use crate::{
+    code::commands::compile_label,
+    compiler::{
+        compile::Compiler,
+        tokenize::{Token, TokenKind},
+    },
+};
+
+/// Rename Error
+///
+/// Error codes `E10xx`, see [RenameError::code].
+#[derive(Debug, PartialEq, thiserror::Error)]
+#[non_exhaustive]
+pub enum RenameError {
+    #[error("Only labels can be renamed")]
+    NotALabel,
+    /// Labels are lowercase ASCII letters
+    #[error("`{0}` is not a valid label")]
+    InvalidName(String),
+    #[error("Label `{0}` already exists")]
+    Exists(String),
+}
+
+impl RenameError {
+    /// Code
+    ///
+    /// Returns the stable error code, e.g. `E1001`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            RenameError::NotALabel => "E1001",
+            RenameError::InvalidName(_) => "E1002",
+            RenameError::Exists(_) => "E1003",
+        }
+    }
+}
+
+/// Token At
+///
+/// Returns the token at byte `offset` of `source`. A token ending at `offset` counts, so a cursor
+/// right after a word finds it.
+pub fn token_at(compiler: &Compiler, source: &str, offset: usize) -> Option<Token> {
+    compiler
+        .tokenize(source)
+        .into_iter()
+        .find(|token| token.span.start <= offset && offset <= token.span.end)
+}
+
+/// Label At
+///
+/// Returns the name of the label defined or referenced at byte `offset` of `source`.
+pub fn label_at<'a>(compiler: &Compiler, source: &'a str, offset: usize) -> Option<&'a str> {
+    let token = token_at(compiler, source, offset)?;
+    match token.kind {
+        TokenKind::LabelDefinition | TokenKind::LabelReference => {
+            Some(&source[label_name(source, &token)])
+        }
+        _ => None,
+    }
+}
+
+/// Label Definition
+///
+/// Returns the byte range of the name where `label` is defined, the first one if it's defined
+/// more than once.
+pub fn label_definition(compiler: &Compiler, source: &str, label: &str) -> Option<Range<usize>> {
+    compiler
+        .tokenize(source)
+        .iter()
+        .filter(|token| token.kind == TokenKind::LabelDefinition)
+        .map(|token| label_name(source, token))
+        .find(|name| &source[name.clone()] == label)
+}
+
+/// Label Occurrences
+///
+/// Returns byte ranges of every definition of & reference to `label`, in source order.
+pub fn label_occurrences(compiler: &Compiler, source: &str, label: &str) -> Vec<Range<usize>> {
+    compiler
+        .tokenize(source)
+        .iter()
+        .filter(|token| {
+            matches!(
+                token.kind,
+                TokenKind::LabelDefinition | TokenKind::LabelReference
+            )
+        })
+        .map(|token| label_name(source, token))
+        .filter(|name| &source[name.clone()] == label)
+        .collect()
+}
+
+/// Rename Label
+///
+/// Returns the byte ranges to replace with `new_name` to rename the label at byte `offset`, see
+/// [label_occurrences]. Fails if there's no label at `offset`, or `new_name` is invalid or taken.
+pub fn rename_label(
+    compiler: &Compiler,
+    source: &str,
+    offset: usize,
+    new_name: &str,
+) -> Result<Vec<Range<usize>>, RenameError> {
+    let label = label_at(compiler, source, offset).ok_or(RenameError::NotALabel)?;
+    if compile_label(new_name).is_none() {
+        return Err(RenameError::InvalidName(new_name.to_string()));
+    }
+    if new_name != label && label_definition(compiler, source, new_name).is_some() {
+        return Err(RenameError::Exists(new_name.to_string()));
+    }
+
+    Ok(label_occurrences(compiler, source, label))
+}
+
+/// Mnemonic Doc
+///
+/// Returns a Markdown description of a command or pseudo instruction keyword, e.g. for editor
+/// hovers.
+pub fn mnemonic_doc(mnemonic: &str) -> Option<&'static str> {
+    let doc = match mnemonic {
+        "INBOX" => {
+            "`INBOX` - pick up the next value from the inbox. The program ends once the \
+            inbox is empty."
+        }
+        "OUTBOX" => "`OUTBOX` - put the value in hands into the outbox.",
+        "COPYFROM" => {
+            "`COPYFROM x` - copy tile `x` into hands. `[x]` uses the tile whose index \
+            is on tile `x`."
+        }
+        "COPYTO" => {
+            "`COPYTO x` - copy the value in hands onto tile `x`. `[x]` uses the tile \
+            whose index is on tile `x`."
+        }
+        "ADD" => "`ADD x` - add tile `x` to the value in hands.",
+        "SUB" => {
+            "`SUB x` - subtract tile `x` from the value in hands. Letters subtract to their \
+            distance in the alphabet."
+        }
+        "BUMPUP" => "`BUMPUP x` - add 1 to tile `x` & copy the result into hands.",
+        "BUMPDN" => "`BUMPDN x` - subtract 1 from tile `x` & copy the result into hands.",
+        "JUMP" => "`JUMP a` - continue at label `a`.",
+        "JUMPZ" => "`JUMPZ a` - continue at label `a` if the value in hands is zero.",
+        "JUMPN" => "`JUMPN a` - continue at label `a` if the value in hands is negative.",
+        "JUMPP" => {
+            "`JUMPP a` - continue at label `a` if the value in hands is positive. \
+            Extension."
+        }
+        "MUL" => "`MUL x` - multiply the value in hands by tile `x`. Extension.",
+        "DIV" => {
+            "`DIV x` - divide the value in hands by tile `x`, rounding towards zero. \
+            Extension."
+        }
+        "MOD" => "`MOD x` - remainder of dividing the value in hands by tile `x`. Extension.",
+        "NEG" => "`NEG` - negate the value in hands. Extension.",
+        "NOP" => "`NOP` - do nothing for a step. Extension.",
+        "HALT" => "`HALT` - end the program. Extension.",
+        "PUSH" => "`PUSH` - push a copy of the value in hands onto the stack. Extension.",
+        "POP" => "`POP` - pop the top of the stack into hands. Extension.",
+        "CALL" => "`CALL a` - continue at label `a`, `RET` returns to the next command. Extension.",
+        "RET" => "`RET` - return to the command after the last `CALL`. Extension.",
+        "DEBUG" => {
+            "`DEBUG \"text\" x...` - print the text & the given tiles. Never executed, \
+            costs nothing."
+        }
+        "MOVE" => {
+            "`MOVE a b` - copy tile `a` to tile `b`. Pseudo instruction for `COPYFROM a`, \
+            `COPYTO b`."
+        }
+        "ZERO" => {
+            "`ZERO x` - set tile `x` to 0. Pseudo instruction for `COPYFROM x`, `SUB x`, \
+            `COPYTO x`."
+        }
+        _ => return None,
+    };
+    Some(doc)
+}
+
+/// Byte range of the name in a label token, without the `:` of definitions
+fn label_name(source: &str, token: &Token) -> Range<usize> {
+    let name = token.text(source).trim_end_matches(':').trim_end();
+    token.span.start..(token.span.start + name.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODE: &str = "\
+a:
+    INBOX
+    JUMPZ b
+    JUMP a
+b:
+    OUTBOX
+    JUMP a
+";
+
+    #[test]
+    fn label_navigation() {
+        let compiler = Compiler::default();
+        let reference = CODE.find("JUMP a").unwrap() + 5;
+
+        assert_eq!(Some("a"), label_at(&compiler, CODE, reference));
+        assert_eq!(Some("a"), label_at(&compiler, CODE, reference + 1));
+        assert_eq!(
+            Some("b"),
+            label_at(&compiler, CODE, CODE.find("b:").unwrap())
+        );
+        assert_eq!(None, label_at(&compiler, CODE, CODE.find("INBOX").unwrap()));
+        assert_eq!(Some(0..1), label_definition(&compiler, CODE, "a"));
+        assert_eq!(None, label_definition(&compiler, CODE, "c"));
+
+        let occurrences: Vec<&str> = label_occurrences(&compiler, CODE, "a")
+            .into_iter()
+            .map(|range| {
+                assert_eq!("a", &CODE[range.clone()]);
+                &CODE[..range.start]
+            })
+            .map(|before| before.lines().last().unwrap_or_default())
+            .collect();
+        assert_eq!(vec!["", "    JUMP ", "    JUMP "], occurrences);
+    }
+
+    #[test]
+    fn rename_label_test() {
+        let compiler = Compiler::default();
+        let ranges = rename_label(&compiler, CODE, 0, "loop").unwrap();
+        let mut renamed = CODE.to_string();
+        for range in ranges.into_iter().rev() {
+            renamed.replace_range(range, "loop");
+        }
+        assert!(compiler.compile(&renamed).is_ok());
+        assert_eq!(
+            "loop:\n    INBOX\n    JUMPZ b\n    JUMP loop\n",
+            &renamed[..renamed.find("b:").unwrap()]
+        );
+
+        let inbox = CODE.find("INBOX").unwrap();
+        for (offset, name, err) in [
+            (inbox, "c", RenameError::NotALabel),
+            (0, "Loop", RenameError::InvalidName(String::from("Loop"))),
+            (0, "b", RenameError::Exists(String::from("b"))),
+        ] {
+            assert_eq!(Err(err), rename_label(&compiler, CODE, offset, name));
+        }
+        assert_eq!("E1003", RenameError::Exists(String::new()).code());
+    }
+
+    #[test]
+    fn mnemonic_doc_test() {
+        let compiler = Compiler::extended();
+        for factory in &compiler.commands {
+            assert!(
+                mnemonic_doc(factory.command()).is_some(),
+                "{}",
+                factory.command()
+            );
+        }
+        for pseudo in &compiler.pseudo_instructions {
+            assert!(
+                mnemonic_doc(pseudo.command()).is_some(),
+                "{}",
+                pseudo.command()
+            );
+        }
+        assert_eq!(None, mnemonic_doc("inbox"));
+    }
+}
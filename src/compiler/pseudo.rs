@@ -0,0 +1,112 @@
+use crate::code::commands::{
+    compile_command_value, copy_from::CopyFrom, copy_to::CopyTo, sub::Sub, AnyCommand,
+};
+
+/// Pseudo Instruction
+///
+/// Compiler-level instruction that is expanded into a sequence of vanilla commands, so the
+/// compiled program still validates against unmodified problems.
+pub trait PseudoInstruction {
+    /// Command
+    ///
+    /// Returns pseudo instruction keyword.
+    fn command(&self) -> &'static str;
+
+    /// Expand
+    ///
+    /// Returns [Some(Vec<AnyCommand>)] with the lowered commands if args are valid, else [None].
+    fn expand(&self, args: &str) -> Option<Vec<AnyCommand>>;
+}
+
+#[macro_export]
+macro_rules! pseudo_instructions {
+    () => {
+        vec![
+            Box::new($crate::compiler::pseudo::Move),
+            Box::new($crate::compiler::pseudo::Zero),
+        ]
+    };
+}
+
+/// `ZERO x` - set tile `x` to `0`.
+///
+/// Expands to `COPYFROM x`, `SUB x`, `COPYTO x`. Tile `x` must not be empty.
+pub struct Zero;
+
+impl PseudoInstruction for Zero {
+    fn command(&self) -> &'static str {
+        "ZERO"
+    }
+
+    fn expand(&self, args: &str) -> Option<Vec<AnyCommand>> {
+        let value = compile_command_value(args)?;
+        Some(vec![
+            Box::new(CopyFrom(value)),
+            Box::new(Sub(value)),
+            Box::new(CopyTo(value)),
+        ])
+    }
+}
+
+/// `MOVE a b` - copy tile `a` to tile `b`.
+///
+/// Expands to `COPYFROM a`, `COPYTO b`.
+pub struct Move;
+
+impl PseudoInstruction for Move {
+    fn command(&self) -> &'static str {
+        "MOVE"
+    }
+
+    fn expand(&self, args: &str) -> Option<Vec<AnyCommand>> {
+        let mut args = args.split_whitespace();
+        let (from, to) = (args.next()?, args.next()?);
+        if args.next().is_some() {
+            return None;
+        }
+
+        let (from, to) = (compile_command_value(from)?, compile_command_value(to)?);
+        Some(vec![Box::new(CopyFrom(from)), Box::new(CopyTo(to))])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_succeeds() {
+        let commands = Zero.expand("[3]").unwrap();
+        let expected = ["COPYFROM [3]", "SUB [3]", "COPYTO [3]"];
+
+        assert_eq!(expected.len(), commands.len());
+        for (expected, command) in expected.into_iter().zip(commands) {
+            assert_eq!(expected, command.to_source());
+        }
+    }
+
+    #[test]
+    fn zero_fails() {
+        for args in ["", "a", "1 2", "[a]"] {
+            assert!(Zero.expand(args).is_none());
+        }
+    }
+
+    #[test]
+    fn move_succeeds() {
+        let commands = Move.expand("1   [2]").unwrap();
+        let expected = ["COPYFROM 1", "COPYTO [2]"];
+
+        assert_eq!(expected.len(), commands.len());
+        for (expected, command) in expected.into_iter().zip(commands) {
+            assert_eq!(expected, command.to_source());
+        }
+    }
+
+    #[test]
+    fn move_fails() {
+        for args in ["", "1", "1 2 3", "a 1", "1 b"] {
+            assert!(Move.expand(args).is_none());
+        }
+    }
+}
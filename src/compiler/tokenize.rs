@@ -0,0 +1,243 @@
+use std::ops::Range;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::compiler::compile::{
+    compile_command_value, compile_comment, compile_define, compile_label, compile_new_label,
+    Compiler, COMMAND,
+};
+
+/// Argument of a custom command, a quoted string or a word
+static ARG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#""[^"]*"|\S+"#).unwrap());
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TokenKind {
+    /// Command keyword, e.g. <code>COPYFROM</code>
+    Mnemonic,
+    /// Memory argument, e.g. <code>4</code> or <code>\[4\]</code>
+    Address,
+    /// New label, e.g. <code>a:</code>
+    LabelDefinition,
+    /// Label argument, e.g. <code>a</code> in <code>JUMP a</code>
+    LabelReference,
+    /// Quoted text, e.g. the message of <code>DEBUG "msg"</code>
+    Text,
+    /// <code>COMMENT 0</code> or commented code, e.g. <code>-- HRM --</code>
+    Comment,
+    /// <code>DEFINE COMMENT|LABEL n</code> header followed by its blob lines
+    Define,
+    /// Anything the compiler would reject
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    /// Byte range in the source code
+    pub span: Range<usize>,
+}
+
+impl Token {
+    fn new(kind: TokenKind, span: Range<usize>) -> Self {
+        Self { kind, span }
+    }
+
+    /// Text
+    ///
+    /// Returns the part of `code` covered by this token.
+    pub fn text<'a>(&self, code: &'a str) -> &'a str {
+        &code[self.span.clone()]
+    }
+}
+
+impl Compiler {
+    /// Tokenize
+    ///
+    /// Split HRM code into classified [Token]s with byte ranges. Whitespace is not tokenized.
+    /// Unlike [Compiler::compile], tokenizing never fails - unrecognized text is returned as
+    /// [TokenKind::Unknown].
+    pub fn tokenize(&self, code: &str) -> Vec<Token> {
+        let mut tokens = vec![];
+        let mut in_define = false;
+        let mut offset = 0;
+
+        for line in code.split_inclusive('\n') {
+            let start = offset + line.len() - line.trim_start().len();
+            let instruction = line.trim();
+            let span = start..(start + instruction.len());
+            offset += line.len();
+
+            if instruction.is_empty() {
+                continue;
+            }
+
+            if in_define {
+                in_define = !instruction.ends_with(';');
+                tokens.push(Token::new(TokenKind::Define, span));
+                continue;
+            }
+
+            if (instruction.starts_with("--") && instruction.ends_with("--"))
+                || compile_comment(instruction).is_some()
+            {
+                tokens.push(Token::new(TokenKind::Comment, span));
+            } else if compile_define(instruction).is_some() {
+                in_define = true;
+                tokens.push(Token::new(TokenKind::Define, span));
+            } else if compile_new_label(instruction).is_some() {
+                tokens.push(Token::new(TokenKind::LabelDefinition, span));
+            } else if let Some(captures) = COMMAND.captures(instruction) {
+                let command = captures.get(1).unwrap();
+                let kind = if self.is_command(command.as_str()) {
+                    TokenKind::Mnemonic
+                } else {
+                    TokenKind::Unknown
+                };
+                tokens.push(Token::new(kind, shift(command.range(), start)));
+
+                if let Some(args) = captures.get(2) {
+                    for arg in ARG.find_iter(args.as_str()) {
+                        tokens.push(Token::new(
+                            classify_arg(arg.as_str()),
+                            shift(arg.range(), start + args.start()),
+                        ));
+                    }
+                }
+            } else {
+                tokens.push(Token::new(TokenKind::Unknown, span));
+            }
+        }
+
+        tokens
+    }
+
+    fn is_command(&self, command: &str) -> bool {
+        self.commands
+            .iter()
+            .any(|factory| factory.command() == command)
+            || self
+                .pseudo_instructions
+                .iter()
+                .any(|pseudo| pseudo.command() == command)
+    }
+}
+
+fn classify_arg(arg: &str) -> TokenKind {
+    if arg.len() > 1 && arg.starts_with('"') && arg.ends_with('"') {
+        TokenKind::Text
+    } else if compile_command_value(arg).is_some() {
+        TokenKind::Address
+    } else if compile_label(arg).is_some() {
+        TokenKind::LabelReference
+    } else {
+        TokenKind::Unknown
+    }
+}
+
+fn shift(range: Range<usize>, offset: usize) -> Range<usize> {
+    (range.start + offset)..(range.end + offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokenize_program() {
+        let code = "\
+-- HUMAN RESOURCE MACHINE PROGRAM --
+
+a:
+    INBOX
+    COPYTO [4]
+    JUMPZ a
+    COMMENT 0
+DEFINE COMMENT 0
+eJwzYmBgEGHyd;
+";
+        let tokens = Compiler::default().tokenize(code);
+
+        let expected = [
+            (TokenKind::Comment, "-- HUMAN RESOURCE MACHINE PROGRAM --"),
+            (TokenKind::LabelDefinition, "a:"),
+            (TokenKind::Mnemonic, "INBOX"),
+            (TokenKind::Mnemonic, "COPYTO"),
+            (TokenKind::Address, "[4]"),
+            (TokenKind::Mnemonic, "JUMPZ"),
+            (TokenKind::LabelReference, "a"),
+            (TokenKind::Comment, "COMMENT 0"),
+            (TokenKind::Define, "DEFINE COMMENT 0"),
+            (TokenKind::Define, "eJwzYmBgEGHyd;"),
+        ];
+
+        assert_eq!(expected.len(), tokens.len());
+        for ((kind, text), token) in expected.into_iter().zip(tokens) {
+            assert_eq!(kind, token.kind);
+            assert_eq!(text, token.text(code));
+        }
+    }
+
+    #[test]
+    fn tokenize_unknown() {
+        let code = "FOO 1\nADD abc1\nabc";
+        let tokens = Compiler::default().tokenize(code);
+
+        let expected = [
+            (TokenKind::Unknown, 0..3),
+            (TokenKind::Address, 4..5),
+            (TokenKind::Mnemonic, 6..9),
+            (TokenKind::Unknown, 10..14),
+            (TokenKind::Unknown, 15..18),
+        ];
+
+        assert_eq!(expected.len(), tokens.len());
+        for ((kind, span), token) in expected.into_iter().zip(tokens) {
+            assert_eq!(Token::new(kind, span), token);
+        }
+    }
+
+    #[test]
+    fn tokenize_pseudo_instruction() {
+        let code = "MOVE 1  [2]";
+        let tokens = Compiler::default().tokenize(code);
+
+        assert_eq!(
+            vec![
+                Token::new(TokenKind::Mnemonic, 0..4),
+                Token::new(TokenKind::Address, 5..6),
+                Token::new(TokenKind::Address, 8..11),
+            ],
+            tokens
+        );
+    }
+
+    #[test]
+    fn tokenize_debug() {
+        let code = "DEBUG \"a b\" 1";
+        let tokens = Compiler::default().tokenize(code);
+
+        assert_eq!(
+            vec![
+                Token::new(TokenKind::Mnemonic, 0..5),
+                Token::new(TokenKind::Text, 6..11),
+                Token::new(TokenKind::Address, 12..13),
+            ],
+            tokens
+        );
+    }
+
+    #[test]
+    fn tokenize_crlf() {
+        let code = "INBOX\r\n  OUTBOX\r\n";
+        let tokens = Compiler::default().tokenize(code);
+
+        assert_eq!(
+            vec![
+                Token::new(TokenKind::Mnemonic, 0..5),
+                Token::new(TokenKind::Mnemonic, 9..15),
+            ],
+            tokens
+        );
+    }
+}
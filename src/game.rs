@@ -1,2 +1,4 @@
+pub mod generator;
 pub mod problem;
+pub mod problem_set;
 pub mod value;
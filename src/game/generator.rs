@@ -0,0 +1,159 @@
+use alloc::vec::Vec;
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::value::Value;
+
+/// IO Generator
+///
+/// Spec for deterministically generating random problem inputs. The same spec (incl. `seed`)
+/// always generates the same inputs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IOGenerator {
+    /// Number of inputs
+    pub count: usize,
+    pub min_length: usize,
+    pub max_length: usize,
+    pub values: ValueRange,
+    pub seed: u64,
+}
+
+/// Value Range
+///
+/// Inclusive range of generated values.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ValueRange {
+    Int { min: i32, max: i32 },
+    Char { min: char, max: char },
+}
+
+impl IOGenerator {
+    /// Generate
+    ///
+    /// Returns `count` inputs with lengths & values in the given ranges.
+    pub fn generate(&self) -> Vec<Vec<Value>> {
+        let mut rng = Rng::new(self.seed);
+        (0..self.count)
+            .map(|_| {
+                let length = rng.range(self.min_length as i64, self.max_length as i64) as usize;
+                (0..length).map(|_| self.values.sample(&mut rng)).collect()
+            })
+            .collect()
+    }
+}
+
+impl ValueRange {
+    /// Sample
+    ///
+    /// Returns a random value in the range.
+    pub fn sample(&self, rng: &mut Rng) -> Value {
+        match *self {
+            ValueRange::Int { min, max } => Value::Int(rng.range(min as i64, max as i64) as i32),
+            ValueRange::Char { min, max } => {
+                let value = rng.range(min as i64, max as i64) as u32;
+                Value::Char(char::from_u32(value).unwrap_or(min))
+            }
+        }
+    }
+}
+
+/// Rng
+///
+/// Small seeded pseudo random number generator (SplitMix64). Not suitable for cryptography.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Range
+    ///
+    /// Returns a number in `min..=max`. Returns `min` if the range is empty.
+    pub fn range(&mut self, min: i64, max: i64) -> i64 {
+        if max <= min {
+            return min;
+        }
+
+        let span = (max - min) as u64 + 1;
+        min + (self.next_u64() % span) as i64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_deterministic() {
+        let generator = IOGenerator {
+            count: 5,
+            min_length: 0,
+            max_length: 8,
+            values: ValueRange::Int { min: -9, max: 9 },
+            seed: 42,
+        };
+
+        let inputs = generator.generate();
+        assert_eq!(inputs, generator.generate());
+        assert_ne!(
+            inputs,
+            IOGenerator {
+                seed: 43,
+                ..generator.clone()
+            }
+            .generate()
+        );
+
+        assert_eq!(5, inputs.len());
+        for input in inputs {
+            assert!(input.len() <= 8);
+            for value in input {
+                match value {
+                    Value::Int(value) => assert!((-9..=9).contains(&value)),
+                    Value::Char(_) => panic!("Expected int"),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn generate_chars() {
+        let generator = IOGenerator {
+            count: 3,
+            min_length: 4,
+            max_length: 4,
+            values: ValueRange::Char { min: 'A', max: 'E' },
+            seed: 7,
+        };
+
+        for input in generator.generate() {
+            assert_eq!(4, input.len());
+            for value in input {
+                match value {
+                    Value::Char(value) => assert!(('A'..='E').contains(&value)),
+                    Value::Int(_) => panic!("Expected char"),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rng_range() {
+        let mut rng = Rng::new(0);
+        assert_eq!(3, rng.range(3, 3));
+        assert_eq!(3, rng.range(3, -1));
+        for _ in 0..100 {
+            assert!((-2..=2).contains(&rng.range(-2, 2)));
+        }
+    }
+}
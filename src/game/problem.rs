@@ -1,7 +1,18 @@
-use std::collections::{HashMap, HashSet};
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::sync::Arc;
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt::{Debug, Formatter};
 
-use crate::code::commands::ALL_COMMANDS;
-use crate::game::value::Value;
+use serde::{Deserialize, Serialize};
+
+use crate::code::commands::{InstructionSet, ALL_COMMANDS, EXTENDED_COMMANDS};
+use crate::game::generator::Rng;
+use crate::game::value::{Value, ValueBounds};
 
 #[derive(Debug)]
 pub struct Problem {
@@ -9,7 +20,18 @@ pub struct Problem {
     pub description: String,
     ios: Vec<ProblemIO>,
     memory: Vec<Option<Value>>,
-    available_commands: HashSet<String>,
+    instruction_set: InstructionSet,
+    available_commands: BTreeSet<String>,
+    reference: Option<Reference>,
+    size_target: Option<usize>,
+    speed_target: Option<u64>,
+    tile_labels: BTreeMap<usize, String>,
+    output_matching: OutputMatching,
+    value_bounds: Option<ValueBounds>,
+    metadata: Metadata,
+    command_limits: BTreeMap<String, usize>,
+    holes: Vec<usize>,
+    unbounded_memory: bool,
 }
 
 impl Problem {
@@ -18,14 +40,26 @@ impl Problem {
         description: String,
         ios: Vec<ProblemIO>,
         memory: Vec<Option<Value>>,
-        available_commands: HashSet<String>,
+        instruction_set: InstructionSet,
+        available_commands: BTreeSet<String>,
     ) -> Self {
         Self {
             title,
             description,
             ios,
             memory,
+            instruction_set,
             available_commands,
+            reference: None,
+            size_target: None,
+            speed_target: None,
+            tile_labels: BTreeMap::new(),
+            output_matching: OutputMatching::Exact,
+            value_bounds: Some(ValueBounds::GAME),
+            metadata: Metadata::default(),
+            command_limits: BTreeMap::new(),
+            holes: vec![],
+            unbounded_memory: false,
         }
     }
 
@@ -37,18 +71,220 @@ impl Problem {
         &self.memory
     }
 
+    pub fn get_instruction_set(&self) -> &InstructionSet {
+        &self.instruction_set
+    }
+
+    pub fn get_available_commands(&self) -> &BTreeSet<String> {
+        &self.available_commands
+    }
+
     pub fn is_command_available(&self, command: &str) -> bool {
         self.available_commands.contains(command)
     }
+
+    /// Size Target
+    ///
+    /// Size challenge - max number of commands.
+    pub fn get_size_target(&self) -> Option<usize> {
+        self.size_target
+    }
+
+    /// Speed Target
+    ///
+    /// Speed challenge - max average number of executed commands.
+    pub fn get_speed_target(&self) -> Option<u64> {
+        self.speed_target
+    }
+
+    /// Tile Label
+    ///
+    /// Returns the label written on a floor tile, e.g. `ZERO`.
+    pub fn get_tile_label(&self, idx: usize) -> Option<&str> {
+        self.tile_labels.get(&idx).map(String::as_str)
+    }
+
+    pub fn get_tile_labels(&self) -> &BTreeMap<usize, String> {
+        &self.tile_labels
+    }
+
+    /// Command Limit
+    ///
+    /// Returns the max number of times a command may be used in a program, [None] if unlimited.
+    pub fn get_command_limit(&self, command: &str) -> Option<usize> {
+        self.command_limits.get(command).copied()
+    }
+
+    pub fn get_command_limits(&self) -> &BTreeMap<String, usize> {
+        &self.command_limits
+    }
+
+    /// Holes
+    ///
+    /// Returns sorted indices of floor tiles that don't exist. Holes can neither be referenced
+    /// nor accessed indirectly.
+    pub fn get_holes(&self) -> &[usize] {
+        &self.holes
+    }
+
+    pub fn is_hole(&self, idx: usize) -> bool {
+        self.holes.binary_search(&idx).is_ok()
+    }
+
+    /// Unbounded Memory
+    ///
+    /// If `true`, memory grows on demand & tiles past the memory dim are valid. Not a game
+    /// feature, meant for experiments.
+    pub fn has_unbounded_memory(&self) -> bool {
+        self.unbounded_memory
+    }
+
+    pub fn get_output_matching(&self) -> OutputMatching {
+        self.output_matching
+    }
+
+    pub fn get_metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    pub fn get_difficulty(&self) -> Option<u32> {
+        self.metadata.difficulty
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.metadata.has_tag(tag)
+    }
+
+    /// Value Bounds
+    ///
+    /// Allowed range of int values, [None] if unbounded.
+    pub fn get_value_bounds(&self) -> Option<ValueBounds> {
+        self.value_bounds
+    }
+
+    pub fn get_reference(&self) -> Option<&Reference> {
+        self.reference.as_ref()
+    }
+
+    /// Add Input
+    ///
+    /// Add an IO whose output is computed by the [Reference]. Returns `false` & ignores the
+    /// input if the problem has no reference.
+    pub fn add_input(&mut self, input: Vec<Value>) -> bool {
+        match &self.reference {
+            Some(reference) => {
+                let problem_io = reference.io(input);
+                self.ios.push(problem_io);
+                true
+            }
+            None => false,
+        }
+    }
 }
 
+/// Metadata
+///
+/// Optional information about a problem that does not affect running it.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Metadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<u32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl Metadata {
+    pub fn is_empty(&self) -> bool {
+        *self == Metadata::default()
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+}
+
+/// Output Matching
+///
+/// How produced outputs are compared to the expected outputs.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OutputMatching {
+    /// Same values in the same order
+    #[default]
+    Exact,
+    /// Same values in any order
+    Multiset,
+    /// Values in the same order, but the program may stop early
+    Prefix,
+}
+
+impl OutputMatching {
+    pub fn is_exact(&self) -> bool {
+        *self == OutputMatching::Exact
+    }
+}
+
+/// Reference
+///
+/// Reference implementation of a problem, used to compute expected outputs from inputs.
+#[derive(Clone)]
+pub struct Reference(Arc<ReferenceFn>);
+
+type ReferenceFn = dyn Fn(&[Value]) -> Vec<Value> + Send + Sync;
+
+impl Reference {
+    pub fn new(reference: impl Fn(&[Value]) -> Vec<Value> + Send + Sync + 'static) -> Self {
+        Self(Arc::new(reference))
+    }
+
+    /// Output
+    ///
+    /// Returns the expected output for given input.
+    pub fn output(&self, input: &[Value]) -> Vec<Value> {
+        (self.0)(input)
+    }
+
+    /// IO
+    ///
+    /// Returns a [ProblemIO] with the expected output for given input.
+    pub fn io(&self, input: Vec<Value>) -> ProblemIO {
+        let output = self.output(&input);
+        ProblemIO { input, output }
+    }
+}
+
+impl Debug for Reference {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str("Reference")
+    }
+}
+
+type IOGeneratorFn = dyn Fn(&mut Rng) -> ProblemIO;
+
 pub struct ProblemBuilder {
     title: String,
     description: String,
     ios: Vec<ProblemIO>,
-    memory: HashMap<usize, Value>,
+    memory: BTreeMap<usize, Value>,
     memory_dim: Option<usize>,
-    available_commands: HashSet<String>,
+    instruction_set: InstructionSet,
+    available_commands: BTreeSet<String>,
+    inputs: Vec<Vec<Value>>,
+    reference: Option<Reference>,
+    io_generators: Vec<(usize, Box<IOGeneratorFn>)>,
+    seed: u64,
+    size_target: Option<usize>,
+    speed_target: Option<u64>,
+    tile_labels: BTreeMap<usize, String>,
+    output_matching: OutputMatching,
+    value_bounds: Option<ValueBounds>,
+    metadata: Metadata,
+    command_limits: BTreeMap<String, usize>,
+    holes: BTreeSet<usize>,
+    unbounded_memory: bool,
+    explicit_commands: BTreeSet<String>,
+    unknown_commands: Vec<String>,
 }
 
 impl Default for ProblemBuilder {
@@ -65,7 +301,23 @@ impl ProblemBuilder {
             ios: vec![],
             memory: Default::default(),
             memory_dim: None,
+            instruction_set: InstructionSet::Vanilla,
             available_commands: Default::default(),
+            inputs: vec![],
+            reference: None,
+            io_generators: vec![],
+            seed: 0,
+            size_target: None,
+            speed_target: None,
+            tile_labels: BTreeMap::new(),
+            output_matching: OutputMatching::Exact,
+            value_bounds: Some(ValueBounds::GAME),
+            metadata: Metadata::default(),
+            command_limits: BTreeMap::new(),
+            holes: BTreeSet::new(),
+            unbounded_memory: false,
+            explicit_commands: Default::default(),
+            unknown_commands: vec![],
         }
     }
 
@@ -79,11 +331,91 @@ impl ProblemBuilder {
         self
     }
 
+    pub fn metadata(mut self, metadata: Metadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
     pub fn add_io(mut self, problem_io: ProblemIO) -> Self {
         self.ios.push(problem_io);
         self
     }
 
+    /// Add Input
+    ///
+    /// Add an IO whose output is computed by the [ProblemBuilder::reference] when building.
+    pub fn add_input(mut self, input: Vec<Value>) -> Self {
+        self.inputs.push(input);
+        self
+    }
+
+    /// Reference
+    ///
+    /// Set the reference implementation used to compute outputs of [ProblemBuilder::add_input].
+    pub fn reference(
+        mut self,
+        reference: impl Fn(&[Value]) -> Vec<Value> + Send + Sync + 'static,
+    ) -> Self {
+        self.reference = Some(Reference::new(reference));
+        self
+    }
+
+    /// Add IO Generator
+    ///
+    /// Add `count` IOs produced by `generator` when building. All generators share one [Rng]
+    /// seeded with [ProblemBuilder::seed], so the generated IOs are deterministic.
+    pub fn add_io_generator(
+        mut self,
+        count: usize,
+        generator: impl Fn(&mut Rng) -> ProblemIO + 'static,
+    ) -> Self {
+        self.io_generators.push((count, Box::new(generator)));
+        self
+    }
+
+    /// Seed
+    ///
+    /// Set the seed of the [Rng] used by [ProblemBuilder::add_io_generator], defaults to `0`.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn size_target(mut self, size_target: usize) -> Self {
+        self.size_target = Some(size_target);
+        self
+    }
+
+    pub fn speed_target(mut self, speed_target: u64) -> Self {
+        self.speed_target = Some(speed_target);
+        self
+    }
+
+    /// Output Matching
+    ///
+    /// Set how outputs are compared, defaults to [OutputMatching::Exact].
+    pub fn output_matching(mut self, output_matching: OutputMatching) -> Self {
+        self.output_matching = output_matching;
+        self
+    }
+
+    /// Value Bounds
+    ///
+    /// Set the allowed range of int values, defaults to [ValueBounds::GAME]. [None] disables
+    /// overflow checks. IO & floor values outside the bounds fail [ProblemBuilder::build].
+    pub fn value_bounds(mut self, value_bounds: Option<ValueBounds>) -> Self {
+        self.value_bounds = value_bounds;
+        self
+    }
+
+    /// Tile Label
+    ///
+    /// Write a label on a floor tile.
+    pub fn tile_label(mut self, idx: usize, label: String) -> Self {
+        self.tile_labels.insert(idx, label);
+        self
+    }
+
     pub fn memory_dim(mut self, dim: usize) -> Self {
         self.memory_dim = Some(dim);
         self
@@ -94,43 +426,228 @@ impl ProblemBuilder {
         self
     }
 
+    /// Floor Hole
+    ///
+    /// Remove a tile from the floor. The tile must be inside the memory dim & empty.
+    pub fn floor_hole(mut self, idx: usize) -> Self {
+        self.holes.insert(idx);
+        self
+    }
+
+    /// Unbounded Memory
+    ///
+    /// Let memory grow on demand, see [Problem::has_unbounded_memory].
+    pub fn unbounded_memory(mut self, unbounded_memory: bool) -> Self {
+        self.unbounded_memory = unbounded_memory;
+        self
+    }
+
+    /// Instruction Set
+    ///
+    /// Set the commands that exist at all, defaults to [InstructionSet::Vanilla]. Commands enabled
+    /// by [ProblemBuilder::enable_all_commands] outside the instruction set are dropped by
+    /// [ProblemBuilder::build].
+    pub fn instruction_set(mut self, instruction_set: InstructionSet) -> Self {
+        self.instruction_set = instruction_set;
+        self
+    }
+
+    /// Enable All Commands
+    ///
+    /// Enable every command of the instruction set.
     pub fn enable_all_commands(mut self) -> Self {
-        self.available_commands =
-            HashSet::from_iter(ALL_COMMANDS.iter().map(|command| command.to_string()));
+        self.available_commands = BTreeSet::from_iter(
+            ALL_COMMANDS
+                .iter()
+                .chain(EXTENDED_COMMANDS.iter())
+                .map(|command| command.to_string()),
+        );
         self
     }
 
+    /// Enable Command
+    ///
+    /// Enable a vanilla or an extended command. Unknown commands & commands outside the
+    /// instruction set are reported by [ProblemBuilder::build].
     pub fn enable_command(mut self, command: String) -> Self {
-        if ALL_COMMANDS.contains(&command.as_str()) {
+        if ALL_COMMANDS.contains(&command.as_str()) || EXTENDED_COMMANDS.contains(&command.as_str())
+        {
+            self.explicit_commands.insert(command.clone());
             self.available_commands.insert(command);
+        } else {
+            self.unknown_commands.push(command);
+        }
+        self
+    }
+
+    /// Command Limit
+    ///
+    /// Allow a command to be used at most `limit` times, e.g. at most 1 `JUMP`. Unknown commands
+    /// are reported by [ProblemBuilder::build].
+    pub fn command_limit(mut self, command: String, limit: usize) -> Self {
+        if ALL_COMMANDS.contains(&command.as_str()) || EXTENDED_COMMANDS.contains(&command.as_str())
+        {
+            self.command_limits.insert(command, limit);
+        } else {
+            self.unknown_commands.push(command);
         }
         self
     }
 
     pub fn disable_command(mut self, command: &str) -> Self {
+        self.explicit_commands.remove(command);
         self.available_commands.remove(command);
         self
     }
 
-    pub fn build(self) -> Problem {
+    /// Build
+    ///
+    /// Returns:
+    /// - [Ok(Problem)] if the builder is consistent
+    /// - [Err(Vec<ProblemBuildError>)] with every inconsistency found, e.g. memory slots outside
+    ///   the memory dim or unknown commands
+    pub fn build(mut self) -> Result<Problem, Vec<ProblemBuildError>> {
+        let mut errors = vec![];
+
         let mut memory = match self.memory_dim {
             Some(memory_dim) => vec![None; memory_dim],
             None => vec![],
         };
 
         for (i, value) in self.memory {
-            if i < memory.len() {
-                memory[i] = Some(value);
+            match memory.get_mut(i) {
+                Some(slot) => *slot = Some(value),
+                None => errors.push(ProblemBuildError::MemoryIndex(i)),
             }
         }
 
-        Problem::new(
+        self.tile_labels
+            .keys()
+            .copied()
+            .filter(|idx| *idx >= memory.len())
+            .for_each(|idx| errors.push(ProblemBuildError::TileLabelIndex(idx)));
+
+        let holes: Vec<usize> = self.holes.into_iter().collect();
+        holes
+            .iter()
+            .filter(|&&idx| {
+                !matches!(memory.get(idx), Some(None)) || self.tile_labels.contains_key(&idx)
+            })
+            .for_each(|&idx| errors.push(ProblemBuildError::HoleIndex(idx)));
+
+        self.unknown_commands
+            .into_iter()
+            .for_each(|command| errors.push(ProblemBuildError::UnknownCommand(command)));
+
+        let instruction_set = self.instruction_set;
+        self.explicit_commands
+            .into_iter()
+            .filter(|command| !instruction_set.contains(command))
+            .for_each(|command| {
+                errors.push(ProblemBuildError::CommandNotInInstructionSet(command))
+            });
+
+        self.available_commands
+            .retain(|command| instruction_set.contains(command));
+
+        let mut ios = self.ios;
+        match &self.reference {
+            Some(reference) => ios.extend(self.inputs.into_iter().map(|input| reference.io(input))),
+            None if !self.inputs.is_empty() => errors.push(ProblemBuildError::MissingReference),
+            None => {}
+        }
+
+        let mut rng = Rng::new(self.seed);
+        for (count, generator) in &self.io_generators {
+            ios.extend((0..*count).map(|_| generator(&mut rng)));
+        }
+
+        if let Some(bounds) = self.value_bounds {
+            let mut out_of_bounds: Vec<Value> = vec![];
+            ios.iter()
+                .flat_map(|io| io.input.iter().chain(&io.output))
+                .chain(memory.iter().flatten())
+                .filter(|&&value| !bounds.contains(value))
+                .for_each(|&value| {
+                    if !out_of_bounds.contains(&value) {
+                        out_of_bounds.push(value);
+                    }
+                });
+            errors.extend(
+                out_of_bounds
+                    .into_iter()
+                    .map(ProblemBuildError::ValueOutOfBounds),
+            );
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        let mut problem = Problem::new(
             self.title,
             self.description,
-            self.ios,
+            ios,
             memory,
+            instruction_set,
             self.available_commands,
-        )
+        );
+        problem.reference = self.reference;
+        problem.size_target = self.size_target;
+        problem.speed_target = self.speed_target;
+        problem.tile_labels = self.tile_labels;
+        problem.output_matching = self.output_matching;
+        problem.value_bounds = self.value_bounds;
+        problem.metadata = self.metadata;
+        problem.command_limits = self.command_limits;
+        problem.holes = holes;
+        problem.unbounded_memory = self.unbounded_memory;
+        Ok(problem)
+    }
+}
+
+/// Problem Build Error
+///
+/// Error codes `E04xx`, see [ProblemBuildError::code].
+#[derive(Debug, PartialEq, thiserror::Error)]
+#[non_exhaustive]
+pub enum ProblemBuildError {
+    /// Memory slot outside the memory dim
+    #[error("Memory slot {0} is outside the floor")]
+    MemoryIndex(usize),
+    /// Tile label outside the memory dim
+    #[error("Tile label {0} is outside the floor")]
+    TileLabelIndex(usize),
+    /// Floor hole outside the memory dim or on an occupied or labelled tile
+    #[error("Hole {0} is outside the floor or on an occupied or labelled tile")]
+    HoleIndex(usize),
+    #[error("Unknown command `{0}`")]
+    UnknownCommand(String),
+    /// Command enabled with [ProblemBuilder::enable_command] that is not in the instruction set
+    #[error("Command `{0}` is not in the instruction set")]
+    CommandNotInInstructionSet(String),
+    /// Inputs added with [ProblemBuilder::add_input] without a reference
+    #[error("Inputs added without a reference solution")]
+    MissingReference,
+    /// IO or floor value outside the [ProblemBuilder::value_bounds]
+    #[error("Value {0} is out of bounds")]
+    ValueOutOfBounds(Value),
+}
+
+impl ProblemBuildError {
+    /// Code
+    ///
+    /// Returns the stable error code, e.g. `E0401`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ProblemBuildError::MemoryIndex(_) => "E0401",
+            ProblemBuildError::TileLabelIndex(_) => "E0402",
+            ProblemBuildError::HoleIndex(_) => "E0403",
+            ProblemBuildError::UnknownCommand(_) => "E0404",
+            ProblemBuildError::CommandNotInInstructionSet(_) => "E0405",
+            ProblemBuildError::MissingReference => "E0406",
+            ProblemBuildError::ValueOutOfBounds(_) => "E0407",
+        }
     }
 }
 
@@ -154,7 +671,8 @@ mod tests {
             })
             .memory_dim(0)
             .enable_all_commands()
-            .build();
+            .build()
+            .unwrap();
 
         assert_eq!(ALL_COMMANDS.len(), problem.available_commands.len());
         for command in ALL_COMMANDS {
@@ -172,14 +690,230 @@ mod tests {
             })
             .memory_dim(0)
             .enable_command(available_command.clone())
-            .build();
+            .build()
+            .unwrap();
 
         assert!(problem.is_command_available(&available_command));
 
         ALL_COMMANDS
             .iter()
             .filter(|command| **command != available_command)
-            .for_each(|command| assert!(!problem.is_command_available(*command)));
+            .for_each(|command| assert!(!problem.is_command_available(command)));
+    }
+
+    #[test]
+    fn enable_extended_command_test() {
+        let problem = ProblemBuilder::new().enable_all_commands().build().unwrap();
+        for command in EXTENDED_COMMANDS {
+            assert!(!problem.is_command_available(command));
+        }
+
+        let errors = ProblemBuilder::new()
+            .enable_command(String::from("MUL"))
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            vec![ProblemBuildError::CommandNotInInstructionSet(String::from(
+                "MUL"
+            ))],
+            errors
+        );
+
+        let problem = ProblemBuilder::new()
+            .enable_command(String::from("MUL"))
+            .instruction_set(InstructionSet::Extended)
+            .build()
+            .unwrap();
+        assert!(problem.is_command_available("MUL"));
+    }
+
+    #[test]
+    fn instruction_set_test() {
+        let problem = ProblemBuilder::new()
+            .instruction_set(InstructionSet::Extended)
+            .enable_all_commands()
+            .build()
+            .unwrap();
+        assert_eq!(
+            ALL_COMMANDS.len() + EXTENDED_COMMANDS.len(),
+            problem.available_commands.len()
+        );
+
+        let problem = ProblemBuilder::new()
+            .instruction_set(InstructionSet::Custom(BTreeSet::from([
+                String::from("INBOX"),
+                String::from("MUL"),
+            ])))
+            .enable_all_commands()
+            .disable_command("INBOX")
+            .build()
+            .unwrap();
+        assert_eq!(
+            BTreeSet::from([String::from("MUL")]),
+            problem.available_commands
+        );
+    }
+
+    #[test]
+    fn reference_test() {
+        let mut problem = ProblemBuilder::new()
+            .add_io(ProblemIO {
+                input: vec![Value::Int(1)],
+                output: vec![Value::Int(1)],
+            })
+            .add_input(vec![Value::Int(2), Value::Int(-3)])
+            .reference(|input| {
+                input
+                    .iter()
+                    .map(|value| value.hrm_add(*value).unwrap())
+                    .collect()
+            })
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            &vec![
+                ProblemIO {
+                    input: vec![Value::Int(1)],
+                    output: vec![Value::Int(1)],
+                },
+                ProblemIO {
+                    input: vec![Value::Int(2), Value::Int(-3)],
+                    output: vec![Value::Int(4), Value::Int(-6)],
+                },
+            ],
+            problem.get_ios()
+        );
+
+        assert!(problem.add_input(vec![Value::Int(5)]));
+        assert_eq!(vec![Value::Int(10)], problem.get_ios()[2].output);
+    }
+
+    #[test]
+    fn io_generator_test() {
+        let create = |seed| {
+            ProblemBuilder::new()
+                .seed(seed)
+                .add_io_generator(50, |rng| {
+                    let value = Value::Int(rng.range(-9, 9) as i32);
+                    ProblemIO {
+                        input: vec![value],
+                        output: vec![value.hrm_add(value).unwrap()],
+                    }
+                })
+                .build()
+                .unwrap()
+        };
+
+        let problem = create(7);
+        assert_eq!(50, problem.get_ios().len());
+        for problem_io in problem.get_ios() {
+            let Value::Int(value) = problem_io.input[0] else {
+                panic!("expected an int");
+            };
+            assert!((-9..=9).contains(&value));
+            assert_eq!(vec![Value::Int(2 * value)], problem_io.output);
+        }
+
+        assert_eq!(problem.get_ios(), create(7).get_ios());
+        assert_ne!(problem.get_ios(), create(8).get_ios());
+    }
+
+    #[test]
+    fn command_limit_test() {
+        let problem = ProblemBuilder::new()
+            .command_limit(String::from("JUMP"), 1)
+            .build()
+            .unwrap();
+
+        assert_eq!(Some(1), problem.get_command_limit("JUMP"));
+        assert_eq!(None, problem.get_command_limit("JUMPZ"));
+
+        let errors = ProblemBuilder::new()
+            .command_limit(String::from("FOO"), 1)
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            vec![ProblemBuildError::UnknownCommand(String::from("FOO"))],
+            errors
+        );
+    }
+
+    #[test]
+    fn tile_label_test() {
+        let problem = ProblemBuilder::new()
+            .memory_dim(3)
+            .tile_label(2, String::from("ZERO"))
+            .build()
+            .unwrap();
+
+        assert_eq!(Some("ZERO"), problem.get_tile_label(2));
+        assert_eq!(None, problem.get_tile_label(1));
+        assert_eq!(1, problem.get_tile_labels().len());
+    }
+
+    #[test]
+    fn reference_missing_test() {
+        let mut problem = ProblemBuilder::new().build().unwrap();
+        assert!(problem.get_reference().is_none());
+        assert!(!problem.add_input(vec![Value::Int(1)]));
+        assert!(problem.get_ios().is_empty());
+    }
+
+    #[test]
+    fn build_fails() {
+        let errors = ProblemBuilder::new()
+            .memory_dim(2)
+            .add_memory_slot(4, Value::Int(0))
+            .add_memory_slot(1, Value::Int(0))
+            .add_memory_slot(2, Value::Int(0))
+            .tile_label(3, String::from("OUT"))
+            .tile_label(0, String::from("IN"))
+            .floor_hole(1)
+            .floor_hole(0)
+            .floor_hole(5)
+            .enable_command(String::from("FOO"))
+            .enable_command(String::from("NEG"))
+            .add_input(vec![Value::Int(1)])
+            .build()
+            .unwrap_err();
+
+        assert_eq!(
+            vec![
+                ProblemBuildError::MemoryIndex(2),
+                ProblemBuildError::MemoryIndex(4),
+                ProblemBuildError::TileLabelIndex(3),
+                ProblemBuildError::HoleIndex(0),
+                ProblemBuildError::HoleIndex(1),
+                ProblemBuildError::HoleIndex(5),
+                ProblemBuildError::UnknownCommand(String::from("FOO")),
+                ProblemBuildError::CommandNotInInstructionSet(String::from("NEG")),
+                ProblemBuildError::MissingReference,
+            ],
+            errors
+        );
+    }
+
+    #[test]
+    fn build_value_bounds() {
+        let builder = || {
+            ProblemBuilder::new()
+                .memory_dim(1)
+                .add_memory_slot(0, Value::Int(-1000))
+                .add_io(ProblemIO {
+                    input: vec![Value::Int(1000), Value::Int(1)],
+                    output: vec![Value::Int(1000)],
+                })
+        };
+
+        assert_eq!(
+            vec![
+                ProblemBuildError::ValueOutOfBounds(Value::Int(1000)),
+                ProblemBuildError::ValueOutOfBounds(Value::Int(-1000)),
+            ],
+            builder().build().unwrap_err()
+        );
+        assert!(builder().value_bounds(None).build().is_ok());
     }
 
     #[test]
@@ -193,14 +927,15 @@ mod tests {
             .memory_dim(0)
             .enable_all_commands()
             .disable_command(unavailable_command)
-            .build();
+            .build()
+            .unwrap();
 
         assert!(!problem.is_command_available(unavailable_command));
 
         ALL_COMMANDS
             .iter()
             .filter(|command| **command != unavailable_command)
-            .for_each(|command| assert!(problem.is_command_available(*command)));
+            .for_each(|command| assert!(problem.is_command_available(command)));
     }
     // endregion
 }
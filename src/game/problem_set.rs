@@ -0,0 +1,128 @@
+use alloc::{string::String, vec::Vec};
+
+use crate::game::problem::Problem;
+
+/// Problem Set
+///
+/// An ordered collection of problems, e.g. a practice playlist.
+#[derive(Debug, Default)]
+pub struct ProblemSet {
+    pub title: String,
+    problems: Vec<Problem>,
+}
+
+impl ProblemSet {
+    pub fn new(title: String, problems: Vec<Problem>) -> Self {
+        Self { title, problems }
+    }
+
+    pub fn add(&mut self, problem: Problem) {
+        self.problems.push(problem);
+    }
+
+    pub fn get_problems(&self) -> &Vec<Problem> {
+        &self.problems
+    }
+
+    pub fn len(&self) -> usize {
+        self.problems.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.problems.is_empty()
+    }
+
+    /// Filter
+    ///
+    /// Returns problems matching the predicate, in set order, e.g.
+    /// `set.filter(|p| p.has_tag("sorting"))`.
+    pub fn filter<F>(&self, predicate: F) -> impl Iterator<Item = &Problem>
+    where
+        F: Fn(&Problem) -> bool,
+    {
+        self.problems
+            .iter()
+            .filter(move |problem| predicate(problem))
+    }
+
+    /// Tags
+    ///
+    /// Returns all distinct tags used in the set, sorted.
+    pub fn tags(&self) -> Vec<&str> {
+        let mut tags: Vec<&str> = self
+            .problems
+            .iter()
+            .flat_map(|problem| problem.get_metadata().tags.iter().map(String::as_str))
+            .collect();
+        tags.sort_unstable();
+        tags.dedup();
+        tags
+    }
+}
+
+impl FromIterator<Problem> for ProblemSet {
+    fn from_iter<T: IntoIterator<Item = Problem>>(iter: T) -> Self {
+        Self::new(String::new(), iter.into_iter().collect())
+    }
+}
+
+impl IntoIterator for ProblemSet {
+    type Item = Problem;
+    type IntoIter = alloc::vec::IntoIter<Problem>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.problems.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::ToString, vec};
+
+    use crate::game::problem::{Metadata, ProblemBuilder, ProblemIO};
+
+    use super::*;
+
+    #[test]
+    fn filter_test() {
+        let set: ProblemSet = [
+            create_problem("A", Some(1), &["sorting"]),
+            create_problem("B", Some(3), &["math", "sorting"]),
+            create_problem("C", None, &[]),
+        ]
+        .into_iter()
+        .collect();
+
+        let titles: Vec<&str> = set
+            .filter(|p| p.has_tag("sorting"))
+            .map(|p| p.title.as_str())
+            .collect();
+        assert_eq!(vec!["A", "B"], titles);
+
+        let titles: Vec<&str> = set
+            .filter(|p| p.get_difficulty().is_some_and(|difficulty| difficulty <= 2))
+            .map(|p| p.title.as_str())
+            .collect();
+        assert_eq!(vec!["A"], titles);
+
+        assert_eq!(vec!["math", "sorting"], set.tags());
+        assert_eq!(3, set.len());
+    }
+
+    fn create_problem(title: &str, difficulty: Option<u32>, tags: &[&str]) -> Problem {
+        ProblemBuilder::new()
+            .title(String::from(title))
+            .add_io(ProblemIO {
+                input: vec![],
+                output: vec![],
+            })
+            .memory_dim(0)
+            .metadata(Metadata {
+                author: None,
+                difficulty,
+                tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            })
+            .build()
+            .unwrap()
+    }
+}
@@ -1,29 +1,146 @@
-use std::cmp::Ordering;
-use std::fmt::{Display, Formatter, Write};
-use std::ops::{Add, Sub};
+use alloc::string::{String, ToString};
+use core::cmp::Ordering;
+use core::fmt::{Display, Formatter, Write};
+use core::ops::{Add, Sub};
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum Value {
     Int(i32),
     Char(char),
 }
 
+/// Arithmetic Error
+///
+/// Failed arithmetic on [Value]s, e.g. [Value::hrm_add].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArithmeticError {
+    /// Operation isn't defined for the operands, e.g. adding letters
+    Operands,
+    /// Result doesn't fit in an `i32`, holds the result saturated to `i32`
+    Overflow(Value),
+}
+
 impl Value {
-    pub fn hrm_add(self, rhs: Self) -> Option<Self> {
+    /// HRM Add
+    ///
+    /// Sum of two [Value::Int]s.
+    pub fn hrm_add(self, rhs: Self) -> Result<Self, ArithmeticError> {
+        match (self, rhs) {
+            (Value::Int(lhs), Value::Int(rhs)) => {
+                lhs.checked_add(rhs)
+                    .map(Value::Int)
+                    .ok_or(ArithmeticError::Overflow(Value::Int(
+                        lhs.saturating_add(rhs),
+                    )))
+            }
+            _ => Err(ArithmeticError::Operands),
+        }
+    }
+
+    /// HRM Sub
+    ///
+    /// Difference of two [Value::Int]s, or the distance between two [Value::Char]s.
+    pub fn hrm_sub(self, rhs: Self) -> Result<Self, ArithmeticError> {
+        match (self, rhs) {
+            (Value::Int(lhs), Value::Int(rhs)) => {
+                lhs.checked_sub(rhs)
+                    .map(Value::Int)
+                    .ok_or(ArithmeticError::Overflow(Value::Int(
+                        lhs.saturating_sub(rhs),
+                    )))
+            }
+            (Value::Char(lhs), Value::Char(rhs)) => Ok(Value::Int(lhs as i32 - rhs as i32)),
+            _ => Err(ArithmeticError::Operands),
+        }
+    }
+
+    /// HRM Mul
+    ///
+    /// Product of two [Value::Int]s.
+    pub fn hrm_mul(self, rhs: Self) -> Result<Self, ArithmeticError> {
         match (self, rhs) {
-            (Value::Int(lhs), Value::Int(rhs)) => Some(Value::Int(lhs + rhs)),
-            _ => None,
+            (Value::Int(lhs), Value::Int(rhs)) => {
+                lhs.checked_mul(rhs)
+                    .map(Value::Int)
+                    .ok_or(ArithmeticError::Overflow(Value::Int(
+                        lhs.saturating_mul(rhs),
+                    )))
+            }
+            _ => Err(ArithmeticError::Operands),
         }
     }
 
-    pub fn hrm_sub(self, rhs: Self) -> Option<Self> {
+    /// HRM Neg
+    ///
+    /// Negation of a [Value::Int].
+    pub fn hrm_neg(self) -> Result<Self, ArithmeticError> {
+        match self {
+            Value::Int(value) => {
+                value
+                    .checked_neg()
+                    .map(Value::Int)
+                    .ok_or(ArithmeticError::Overflow(Value::Int(
+                        value.saturating_neg(),
+                    )))
+            }
+            Value::Char(_) => Err(ArithmeticError::Operands),
+        }
+    }
+
+    /// HRM Div
+    ///
+    /// Truncating division of two [Value::Int]s. Chars & division by zero are
+    /// [ArithmeticError::Operands], `i32::MIN / -1` overflows.
+    pub fn hrm_div(self, rhs: Self) -> Result<Self, ArithmeticError> {
+        match (self, rhs) {
+            (Value::Int(_), Value::Int(0)) => Err(ArithmeticError::Operands),
+            (Value::Int(lhs), Value::Int(rhs)) => {
+                lhs.checked_div(rhs)
+                    .map(Value::Int)
+                    .ok_or(ArithmeticError::Overflow(Value::Int(
+                        lhs.saturating_div(rhs),
+                    )))
+            }
+            _ => Err(ArithmeticError::Operands),
+        }
+    }
+
+    /// HRM Mod
+    ///
+    /// Remainder of [Value::hrm_div], taking the sign of `self`. Chars & division by zero are
+    /// [ArithmeticError::Operands], `i32::MIN % -1` is 0.
+    pub fn hrm_mod(self, rhs: Self) -> Result<Self, ArithmeticError> {
         match (self, rhs) {
-            (Value::Int(lhs), Value::Int(rhs)) => Some(Value::Int(lhs - rhs)),
-            (Value::Char(lhs), Value::Char(rhs)) => Some(Value::Int(lhs as i32 - rhs as i32)),
-            _ => None,
+            (Value::Int(_), Value::Int(0)) => Err(ArithmeticError::Operands),
+            (Value::Int(lhs), Value::Int(rhs)) => Ok(Value::Int(lhs.wrapping_rem(rhs))),
+            _ => Err(ArithmeticError::Operands),
+        }
+    }
+}
+
+/// Value Bounds
+///
+/// Inclusive range of int values, chars are always in bounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValueBounds {
+    pub min: i32,
+    pub max: i32,
+}
+
+impl ValueBounds {
+    /// The game's bounds - values outside `-999..=999` overflow.
+    pub const GAME: Self = Self {
+        min: -999,
+        max: 999,
+    };
+
+    pub fn contains(&self, value: Value) -> bool {
+        match value {
+            Value::Int(value) => (self.min..=self.max).contains(&value),
+            Value::Char(_) => true,
         }
     }
 }
@@ -50,7 +167,8 @@ impl Add for Value {
     type Output = Value;
 
     fn add(self, rhs: Self) -> Self::Output {
-        self.hrm_add(rhs).expect("Cannot add INT & CHAR")
+        self.hrm_add(rhs)
+            .expect("Cannot add INT & CHAR or overflow")
     }
 }
 
@@ -58,7 +176,8 @@ impl Sub for Value {
     type Output = Value;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        self.hrm_sub(rhs).expect("Cannot sub INT & CHAR")
+        self.hrm_sub(rhs)
+            .expect("Cannot sub INT & CHAR or overflow")
     }
 }
 
@@ -72,7 +191,7 @@ impl From<Value> for String {
 }
 
 impl Display for Value {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
             Value::Int(val) => f.write_str(val.to_string().as_str()),
             Value::Char(val) => f.write_char(*val),
@@ -85,6 +204,16 @@ mod tests {
     use super::*;
 
     #[test]
+    fn value_bounds_contains() {
+        assert!(ValueBounds::GAME.contains(Value::Int(999)));
+        assert!(ValueBounds::GAME.contains(Value::Int(-999)));
+        assert!(!ValueBounds::GAME.contains(Value::Int(1000)));
+        assert!(!ValueBounds::GAME.contains(Value::Int(-1000)));
+        assert!(ValueBounds::GAME.contains(Value::Char('Z')));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
     fn foo() {
         let value = Value::Int(5);
         let serialized = serde_json::to_string(&value).unwrap();
@@ -96,6 +225,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "std")]
     fn bar() {
         let value = "[1, 2, \"1\", \"B\"]";
         let deserialized: Vec<Value> = serde_json::from_str(value).unwrap();
@@ -121,7 +251,7 @@ mod tests {
     fn add_chars() {
         let a = Value::Char('A');
         let b = Value::Char('B');
-        assert_eq!(None, a.hrm_add(b));
+        assert_eq!(Err(ArithmeticError::Operands), a.hrm_add(b));
     }
 
     #[test]
@@ -134,11 +264,11 @@ mod tests {
     fn add_mixed() {
         let a = Value::Int(0);
         let b = Value::Char('0');
-        assert_eq!(None, a.hrm_add(b));
+        assert_eq!(Err(ArithmeticError::Operands), a.hrm_add(b));
 
         let a = Value::Int(0);
         let b = Value::Char('0');
-        assert_eq!(None, b.hrm_add(a));
+        assert_eq!(Err(ArithmeticError::Operands), b.hrm_add(a));
     }
 
     #[test]
@@ -146,6 +276,18 @@ mod tests {
     fn add_mixed_trait() {
         let _ = Value::Int(0) + Value::Char('0');
     }
+
+    #[test]
+    fn add_overflow() {
+        assert_eq!(
+            Err(ArithmeticError::Overflow(Value::Int(i32::MAX))),
+            Value::Int(i32::MAX).hrm_add(Value::Int(1))
+        );
+        assert_eq!(
+            Err(ArithmeticError::Overflow(Value::Int(i32::MIN))),
+            Value::Int(i32::MIN).hrm_add(Value::Int(-1))
+        );
+    }
     // endregion
 
     // region:sub
@@ -181,11 +323,11 @@ mod tests {
     fn sub_mixed() {
         let a = Value::Int(0);
         let b = Value::Char('0');
-        assert_eq!(None, a.hrm_sub(b));
+        assert_eq!(Err(ArithmeticError::Operands), a.hrm_sub(b));
 
         let a = Value::Int(0);
         let b = Value::Char('0');
-        assert_eq!(None, b.hrm_sub(a));
+        assert_eq!(Err(ArithmeticError::Operands), b.hrm_sub(a));
     }
 
     #[test]
@@ -193,6 +335,136 @@ mod tests {
     fn sub_mixed_trait() {
         let _ = Value::Int(0) + Value::Char('0');
     }
+
+    #[test]
+    fn sub_overflow() {
+        assert_eq!(
+            Err(ArithmeticError::Overflow(Value::Int(i32::MIN))),
+            Value::Int(i32::MIN).hrm_sub(Value::Int(1))
+        );
+    }
+    // endregion
+
+    // region:mul
+    #[test]
+    fn mul_ints() {
+        let a = Value::Int(-5);
+        let b = Value::Int(10);
+        assert_eq!(Value::Int(-50), a.hrm_mul(b).unwrap());
+    }
+
+    #[test]
+    fn mul_chars() {
+        let a = Value::Char('A');
+        let b = Value::Char('B');
+        assert_eq!(Err(ArithmeticError::Operands), a.hrm_mul(b));
+    }
+
+    #[test]
+    fn mul_mixed() {
+        let a = Value::Int(2);
+        let b = Value::Char('0');
+        assert_eq!(Err(ArithmeticError::Operands), a.hrm_mul(b));
+        assert_eq!(Err(ArithmeticError::Operands), b.hrm_mul(a));
+    }
+
+    #[test]
+    fn mul_overflow() {
+        let a = Value::Int(i32::MAX);
+        assert_eq!(
+            Err(ArithmeticError::Overflow(Value::Int(i32::MAX))),
+            a.hrm_mul(Value::Int(2))
+        );
+        assert_eq!(
+            Err(ArithmeticError::Overflow(Value::Int(i32::MIN))),
+            a.hrm_mul(Value::Int(-2))
+        );
+    }
+    // endregion
+
+    // region:neg
+    #[test]
+    fn neg_int() {
+        assert_eq!(Value::Int(-5), Value::Int(5).hrm_neg().unwrap());
+        assert_eq!(Value::Int(0), Value::Int(0).hrm_neg().unwrap());
+    }
+
+    #[test]
+    fn neg_char() {
+        assert_eq!(Err(ArithmeticError::Operands), Value::Char('A').hrm_neg());
+    }
+
+    #[test]
+    fn neg_overflow() {
+        assert_eq!(
+            Err(ArithmeticError::Overflow(Value::Int(i32::MAX))),
+            Value::Int(i32::MIN).hrm_neg()
+        );
+    }
+    // endregion
+
+    // region:div
+    #[test]
+    fn div_ints() {
+        assert_eq!(Value::Int(3), Value::Int(7).hrm_div(Value::Int(2)).unwrap());
+        assert_eq!(
+            Value::Int(-3),
+            Value::Int(-7).hrm_div(Value::Int(2)).unwrap()
+        );
+        assert_eq!(
+            Err(ArithmeticError::Operands),
+            Value::Int(7).hrm_div(Value::Int(0))
+        );
+    }
+
+    #[test]
+    fn div_chars() {
+        assert_eq!(
+            Err(ArithmeticError::Operands),
+            Value::Char('A').hrm_div(Value::Char('B'))
+        );
+        assert_eq!(
+            Err(ArithmeticError::Operands),
+            Value::Int(1).hrm_div(Value::Char('B'))
+        );
+    }
+
+    #[test]
+    fn div_overflow() {
+        assert_eq!(
+            Err(ArithmeticError::Overflow(Value::Int(i32::MAX))),
+            Value::Int(i32::MIN).hrm_div(Value::Int(-1))
+        );
+        assert_eq!(
+            Ok(Value::Int(0)),
+            Value::Int(i32::MIN).hrm_mod(Value::Int(-1))
+        );
+    }
+
+    #[test]
+    fn mod_ints() {
+        assert_eq!(Value::Int(1), Value::Int(7).hrm_mod(Value::Int(2)).unwrap());
+        assert_eq!(
+            Value::Int(-1),
+            Value::Int(-7).hrm_mod(Value::Int(2)).unwrap()
+        );
+        assert_eq!(
+            Err(ArithmeticError::Operands),
+            Value::Int(7).hrm_mod(Value::Int(0))
+        );
+    }
+
+    #[test]
+    fn mod_chars() {
+        assert_eq!(
+            Err(ArithmeticError::Operands),
+            Value::Char('A').hrm_mod(Value::Char('B'))
+        );
+        assert_eq!(
+            Err(ArithmeticError::Operands),
+            Value::Char('A').hrm_mod(Value::Int(1))
+        );
+    }
     // endregion
 
     // region:cmp
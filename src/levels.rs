@@ -0,0 +1,122 @@
+//! Levels
+//!
+//! Official levels of the game as [ProblemDefinition]s, embedded in the binary. Only 36 levels
+//! ship, because cutscene years (5, 15, 18, 27, 33 & 42) have no level, see [ids].
+
+use crate::model::problem_definition::ProblemDefinition;
+
+macro_rules! level {
+    ($year:literal) => {
+        (
+            concat!("year_", $year),
+            include_str!(concat!("levels/year_", $year, ".json")),
+        )
+    };
+}
+
+/// Official levels, keyed by year. Cutscene years (5, 15, 18, 27, 33 & 42) have no level.
+const LEVELS: [(&str, &str); 36] = [
+    level!(1),
+    level!(2),
+    level!(3),
+    level!(4),
+    level!(6),
+    level!(7),
+    level!(8),
+    level!(9),
+    level!(10),
+    level!(11),
+    level!(12),
+    level!(13),
+    level!(14),
+    level!(16),
+    level!(17),
+    level!(19),
+    level!(20),
+    level!(21),
+    level!(22),
+    level!(23),
+    level!(24),
+    level!(25),
+    level!(26),
+    level!(28),
+    level!(29),
+    level!(30),
+    level!(31),
+    level!(32),
+    level!(34),
+    level!(35),
+    level!(36),
+    level!(37),
+    level!(38),
+    level!(39),
+    level!(40),
+    level!(41),
+];
+
+/// Ids
+///
+/// Returns ids of all official levels in game order, e.g. `year_20`.
+pub fn ids() -> impl Iterator<Item = &'static str> {
+    LEVELS.iter().map(|(id, _)| *id)
+}
+
+/// Get
+///
+/// Returns [Some(ProblemDefinition)] of the official level with given id (e.g. `year_20`), else
+/// [None].
+pub fn get(id: &str) -> Option<ProblemDefinition> {
+    LEVELS
+        .iter()
+        .find(|(level_id, _)| *level_id == id)
+        .map(|(_, json)| serde_json::from_str(json).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compiler::compile::Compiler;
+    use crate::game::problem::Problem;
+
+    use super::*;
+
+    #[test]
+    fn get_all_levels() {
+        for id in ids() {
+            let definition = get(id).unwrap();
+            definition.validate().unwrap();
+            assert!(!definition.ios.is_empty(), "{id}");
+            assert!(definition.size_target.is_some(), "{id}");
+            assert!(definition.speed_target.is_some(), "{id}");
+
+            let problem = Problem::try_from(definition).unwrap();
+            assert!(problem.is_command_available("INBOX"), "{id}");
+        }
+    }
+
+    #[test]
+    fn get_missing_level() {
+        assert!(get("year_5").is_none());
+        assert!(get("year_43").is_none());
+    }
+
+    #[test]
+    fn level_titles() {
+        assert_eq!(36, ids().count());
+        assert_eq!("Sorting Floor", get("year_41").unwrap().title);
+    }
+
+    #[test]
+    fn solve_busy_mail_room() {
+        let problem = Problem::try_from(get("year_2").unwrap()).unwrap();
+
+        let program = Compiler::default()
+            .compile("a:\nINBOX\nOUTBOX\nJUMP a\n")
+            .unwrap();
+        program.validate(&problem).unwrap();
+        let score = program.run(&problem).unwrap();
+
+        let evaluation = score.evaluate(&problem);
+        assert_eq!(Some(true), evaluation.size);
+        assert_eq!(Some(true), evaluation.speed);
+    }
+}
@@ -1,4 +1,23 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[doc(hidden)]
+pub extern crate alloc;
+
+#[cfg(feature = "bench")]
+pub mod bench;
 pub mod code;
+#[cfg(feature = "std")]
 pub mod compiler;
 pub mod game;
+#[cfg(feature = "levels")]
+pub mod levels;
+#[cfg(feature = "lsp")]
+pub mod lsp;
+#[cfg(feature = "std")]
 pub mod model;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "tui")]
+pub mod tui;
+#[cfg(feature = "wasm")]
+pub mod wasm;
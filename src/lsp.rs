@@ -0,0 +1,469 @@
+//! Language Server
+//!
+//! Language Server Protocol support for editors, run by the `hrm-lsp` binary over stdio. Built on
+//! the [Compiler::tokenize] tokens & [Diagnostic]s:
+//! - compile errors & analysis warnings are published when a document is opened or saved
+//! - go to label definition, label rename & hover docs for command mnemonics
+//!
+//! Documents are compiled with [Compiler::extended], since there's no problem to validate against.
+
+use std::{collections::HashMap, error::Error, ops::Range};
+
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, RequestId, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument,
+        Notification as _, PublishDiagnostics,
+    },
+    request::{GotoDefinition, HoverRequest, Rename, Request as _},
+    DiagnosticRelatedInformation, DiagnosticSeverity, GotoDefinitionParams, GotoDefinitionResponse,
+    Hover, HoverContents, HoverParams, HoverProviderCapability, Location, MarkupContent,
+    MarkupKind, NumberOrString, OneOf, Position, PublishDiagnosticsParams, Range as LspRange,
+    RenameParams, SaveOptions, ServerCapabilities, TextDocumentSyncCapability,
+    TextDocumentSyncKind, TextDocumentSyncOptions, TextEdit, Uri, WorkspaceEdit,
+};
+use serde::de::DeserializeOwned;
+
+use crate::{
+    compiler::{
+        compile::Compiler,
+        navigation::{label_at, label_definition, mnemonic_doc, rename_label, token_at},
+        tokenize::TokenKind,
+    },
+    model::diagnostic::{Diagnostic, Severity, Span},
+};
+
+/// Run Stdio
+///
+/// Serve a single client over stdin & stdout until it shuts down.
+pub fn run_stdio() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let (connection, io_threads) = Connection::stdio();
+    let capabilities = serde_json::to_value(Server::capabilities())?;
+    connection.initialize(capabilities)?;
+    serve(connection, Server::new())?;
+    io_threads.join()?;
+    Ok(())
+}
+
+fn serve(connection: Connection, mut server: Server) -> Result<(), Box<dyn Error + Send + Sync>> {
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    break;
+                }
+                connection
+                    .sender
+                    .send(server.handle_request(request).into())?;
+            }
+            Message::Notification(notification) => {
+                if let Some(publish) = server.handle_notification(notification) {
+                    connection.sender.send(publish.into())?;
+                }
+            }
+            Message::Response(_) => {}
+        }
+    }
+    Ok(())
+}
+
+/// Server
+///
+/// Open documents & the requests handled for them, independent of the transport.
+pub struct Server {
+    compiler: Compiler,
+    documents: HashMap<Uri, String>,
+}
+
+impl Default for Server {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Server {
+    pub fn new() -> Self {
+        Self {
+            compiler: Compiler::extended(),
+            documents: HashMap::new(),
+        }
+    }
+
+    /// Capabilities
+    ///
+    /// Features announced to the client on initialization. Documents are synced in full.
+    pub fn capabilities() -> ServerCapabilities {
+        ServerCapabilities {
+            text_document_sync: Some(TextDocumentSyncCapability::Options(
+                TextDocumentSyncOptions {
+                    open_close: Some(true),
+                    change: Some(TextDocumentSyncKind::FULL),
+                    save: Some(SaveOptions::default().into()),
+                    ..TextDocumentSyncOptions::default()
+                },
+            )),
+            definition_provider: Some(OneOf::Left(true)),
+            rename_provider: Some(OneOf::Left(true)),
+            hover_provider: Some(HoverProviderCapability::Simple(true)),
+            ..ServerCapabilities::default()
+        }
+    }
+
+    /// Handle Request
+    ///
+    /// Answer go to definition, rename & hover requests. Unknown methods are answered with
+    /// [ErrorCode::MethodNotFound].
+    pub fn handle_request(&self, request: Request) -> Response {
+        let id = request.id.clone();
+        match request.method.as_str() {
+            GotoDefinition::METHOD => respond(id, request, |params| self.definition(params)),
+            Rename::METHOD => respond(id, request, |params| self.rename(params)),
+            HoverRequest::METHOD => respond(id, request, |params| self.hover(params)),
+            method => Response::new_err(
+                id,
+                ErrorCode::MethodNotFound as i32,
+                format!("Unsupported request `{method}`"),
+            ),
+        }
+    }
+
+    /// Handle Notification
+    ///
+    /// Track open documents, returns the diagnostics to publish, if any.
+    pub fn handle_notification(&mut self, notification: Notification) -> Option<Notification> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: lsp_types::DidOpenTextDocumentParams = extract(notification)?;
+                let uri = params.text_document.uri;
+                self.documents
+                    .insert(uri.clone(), params.text_document.text);
+                Some(self.publish_diagnostics(uri))
+            }
+            DidChangeTextDocument::METHOD => {
+                let params: lsp_types::DidChangeTextDocumentParams = extract(notification)?;
+                // Full sync, the last change holds the whole document
+                let text = params.content_changes.into_iter().last()?.text;
+                self.documents.insert(params.text_document.uri, text);
+                None
+            }
+            DidSaveTextDocument::METHOD => {
+                let params: lsp_types::DidSaveTextDocumentParams = extract(notification)?;
+                Some(self.publish_diagnostics(params.text_document.uri))
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: lsp_types::DidCloseTextDocumentParams = extract(notification)?;
+                let uri = params.text_document.uri;
+                self.documents.remove(&uri);
+                Some(publish(PublishDiagnosticsParams::new(uri, vec![], None)))
+            }
+            _ => None,
+        }
+    }
+
+    /// Diagnostics
+    ///
+    /// Returns the compile error or the analysis warnings of document `uri`.
+    pub fn diagnostics(&self, uri: &Uri) -> Vec<lsp_types::Diagnostic> {
+        let Some(source) = self.documents.get(uri) else {
+            return vec![];
+        };
+        let diagnostics = match self.compiler.compile(source) {
+            Ok(program) => Diagnostic::from_analysis(&self.compiler, source, &program),
+            Err(err) => vec![Diagnostic::from_parse_error(&self.compiler, source, &err)],
+        };
+
+        diagnostics
+            .into_iter()
+            .map(|diagnostic| lsp_diagnostic(uri, source, diagnostic))
+            .collect()
+    }
+
+    fn publish_diagnostics(&self, uri: Uri) -> Notification {
+        let diagnostics = self.diagnostics(&uri);
+        publish(PublishDiagnosticsParams::new(uri, diagnostics, None))
+    }
+
+    /// Document & byte offset of a position in it
+    fn locate(&self, uri: &Uri, position: Position) -> Result<(&str, usize), String> {
+        let source = self
+            .documents
+            .get(uri)
+            .ok_or_else(|| format!("Document `{}` is not open", uri.as_str()))?;
+        Ok((source, to_offset(source, position)))
+    }
+
+    fn definition(
+        &self,
+        params: GotoDefinitionParams,
+    ) -> Result<Option<GotoDefinitionResponse>, String> {
+        let document = params.text_document_position_params;
+        let (source, offset) = self.locate(&document.text_document.uri, document.position)?;
+        let definition = label_at(&self.compiler, source, offset)
+            .and_then(|label| label_definition(&self.compiler, source, label));
+
+        Ok(definition.map(|definition| {
+            GotoDefinitionResponse::Scalar(Location::new(
+                document.text_document.uri,
+                to_range(source, definition),
+            ))
+        }))
+    }
+
+    fn rename(&self, params: RenameParams) -> Result<Option<WorkspaceEdit>, String> {
+        let document = params.text_document_position;
+        let (source, offset) = self.locate(&document.text_document.uri, document.position)?;
+        let edits = rename_label(&self.compiler, source, offset, &params.new_name)
+            .map_err(|err| format!("{}: {err}", err.code()))?
+            .into_iter()
+            .map(|range| TextEdit::new(to_range(source, range), params.new_name.clone()))
+            .collect();
+
+        // `Uri` is hashed by its text only, its cached parts don't change the key
+        #[allow(clippy::mutable_key_type)]
+        let changes = HashMap::from([(document.text_document.uri, edits)]);
+        Ok(Some(WorkspaceEdit::new(changes)))
+    }
+
+    fn hover(&self, params: HoverParams) -> Result<Option<Hover>, String> {
+        let document = params.text_document_position_params;
+        let (source, offset) = self.locate(&document.text_document.uri, document.position)?;
+        let hover = token_at(&self.compiler, source, offset)
+            .filter(|token| token.kind == TokenKind::Mnemonic)
+            .and_then(|token| {
+                let doc = mnemonic_doc(token.text(source))?;
+                Some(Hover {
+                    contents: HoverContents::Markup(MarkupContent {
+                        kind: MarkupKind::Markdown,
+                        value: doc.to_string(),
+                    }),
+                    range: Some(to_range(source, token.span)),
+                })
+            });
+
+        Ok(hover)
+    }
+}
+
+/// Answer `request` with the result of `handler`, its errors are [ErrorCode::RequestFailed]
+fn respond<P: DeserializeOwned, R: serde::Serialize>(
+    id: RequestId,
+    request: Request,
+    handler: impl FnOnce(P) -> Result<R, String>,
+) -> Response {
+    let params = match serde_json::from_value(request.params) {
+        Ok(params) => params,
+        Err(err) => return Response::new_err(id, ErrorCode::InvalidParams as i32, err.to_string()),
+    };
+    match handler(params) {
+        Ok(result) => Response::new_ok(id, result),
+        Err(message) => Response::new_err(id, ErrorCode::RequestFailed as i32, message),
+    }
+}
+
+/// Params of `notification`, [None] if malformed
+fn extract<P: DeserializeOwned>(notification: Notification) -> Option<P> {
+    serde_json::from_value(notification.params).ok()
+}
+
+fn publish(params: PublishDiagnosticsParams) -> Notification {
+    Notification::new(PublishDiagnostics::METHOD.to_string(), params)
+}
+
+fn lsp_diagnostic(uri: &Uri, source: &str, diagnostic: Diagnostic) -> lsp_types::Diagnostic {
+    let span_range = |span: Option<Span>| span.map_or(0..0, |span| span.start..span.end);
+    let related = diagnostic
+        .related
+        .into_iter()
+        .map(|related| DiagnosticRelatedInformation {
+            location: Location::new(uri.clone(), to_range(source, span_range(related.span))),
+            message: related.message,
+        })
+        .collect::<Vec<_>>();
+
+    lsp_types::Diagnostic {
+        range: to_range(source, span_range(diagnostic.span)),
+        severity: Some(match diagnostic.severity {
+            Severity::Error => DiagnosticSeverity::ERROR,
+            Severity::Warning => DiagnosticSeverity::WARNING,
+        }),
+        code: Some(NumberOrString::String(diagnostic.code)),
+        source: Some(String::from("hrm")),
+        message: diagnostic.message,
+        related_information: (!related.is_empty()).then_some(related),
+        ..lsp_types::Diagnostic::default()
+    }
+}
+
+/// Byte offset of an LSP position, whose characters are UTF-16 code units. Positions past the end
+/// of a line are clamped to it.
+fn to_offset(source: &str, position: Position) -> usize {
+    let mut offset = 0;
+    for (i, line) in source.split_inclusive('\n').enumerate() {
+        if i == position.line as usize {
+            let mut units = 0;
+            for (i_char, c) in line.trim_end_matches(['\r', '\n']).char_indices() {
+                if units >= position.character as usize {
+                    return offset + i_char;
+                }
+                units += c.len_utf16();
+            }
+            return offset + line.trim_end_matches(['\r', '\n']).len();
+        }
+        offset += line.len();
+    }
+    source.len()
+}
+
+fn to_position(source: &str, offset: usize) -> Position {
+    let before = &source[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    Position::new(
+        before.matches('\n').count() as u32,
+        before[line_start..].encode_utf16().count() as u32,
+    )
+}
+
+fn to_range(source: &str, range: Range<usize>) -> LspRange {
+    LspRange::new(
+        to_position(source, range.start),
+        to_position(source, range.end),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::{
+        DidOpenTextDocumentParams, TextDocumentIdentifier, TextDocumentItem,
+        TextDocumentPositionParams,
+    };
+    use serde_json::json;
+
+    use super::*;
+
+    const CODE: &str = "\
+-- ü --
+a:
+    INBOX
+    JUMPZ b
+    JUMP a
+b:
+    JUMP b
+";
+
+    fn uri() -> Uri {
+        "file:///solution.hrm".parse().unwrap()
+    }
+
+    fn server() -> (Server, Notification) {
+        let mut server = Server::new();
+        let open = DidOpenTextDocumentParams {
+            text_document: TextDocumentItem::new(uri(), String::from("hrm"), 1, CODE.to_string()),
+        };
+        let published = server
+            .handle_notification(Notification::new(
+                DidOpenTextDocument::METHOD.to_string(),
+                open,
+            ))
+            .unwrap();
+        (server, published)
+    }
+
+    fn request(server: &Server, method: &str, line: u32, character: u32) -> Response {
+        let mut params = serde_json::to_value(TextDocumentPositionParams::new(
+            TextDocumentIdentifier::new(uri()),
+            Position::new(line, character),
+        ))
+        .unwrap();
+        params["newName"] = json!("loop");
+        server.handle_request(Request::new(RequestId::from(1), method.to_string(), params))
+    }
+
+    #[test]
+    fn diagnostics_test() {
+        let (mut server, published) = server();
+        let params: PublishDiagnosticsParams = extract(published).unwrap();
+        assert_eq!(1, params.diagnostics.len());
+        let diagnostic = &params.diagnostics[0];
+        assert_eq!(
+            Some(NumberOrString::String(String::from("W0602"))),
+            diagnostic.code
+        );
+        assert_eq!(
+            LspRange::new(Position::new(6, 4), Position::new(6, 10)),
+            diagnostic.range
+        );
+        let related = &diagnostic.related_information.as_ref().unwrap()[0];
+        assert_eq!(Position::new(5, 0), related.location.range.start);
+
+        let change = json!({
+            "textDocument": {"uri": uri(), "version": 2},
+            "contentChanges": [{"text": "JUMP c\n"}],
+        });
+        let notification = Notification::new(DidChangeTextDocument::METHOD.to_string(), change);
+        assert!(server.handle_notification(notification).is_none());
+        let save = json!({"textDocument": {"uri": uri()}});
+        let notification = Notification::new(DidSaveTextDocument::METHOD.to_string(), save);
+        let params: PublishDiagnosticsParams =
+            extract(server.handle_notification(notification).unwrap()).unwrap();
+        assert_eq!(
+            Some(DiagnosticSeverity::ERROR),
+            params.diagnostics[0].severity
+        );
+    }
+
+    #[test]
+    fn definition_test() {
+        let (server, _) = server();
+        let response = request(&server, GotoDefinition::METHOD, 4, 10);
+        let expected = GotoDefinitionResponse::Scalar(Location::new(
+            uri(),
+            LspRange::new(Position::new(1, 0), Position::new(1, 1)),
+        ));
+        assert_eq!(
+            Some(serde_json::to_value(expected).unwrap()),
+            response.result
+        );
+
+        let response = request(&server, GotoDefinition::METHOD, 2, 6);
+        assert_eq!(Some(serde_json::Value::Null), response.result);
+    }
+
+    #[test]
+    fn rename_test() {
+        let (server, _) = server();
+        let response = request(&server, Rename::METHOD, 1, 0);
+        let edit: WorkspaceEdit = serde_json::from_value(response.result.unwrap()).unwrap();
+        let edits = &edit.changes.unwrap()[&uri()];
+        let lines: Vec<u32> = edits.iter().map(|edit| edit.range.start.line).collect();
+        assert_eq!(vec![1, 4], lines);
+
+        let response = request(&server, Rename::METHOD, 2, 6);
+        assert!(response.error.unwrap().message.starts_with("E1001"));
+    }
+
+    #[test]
+    fn hover_test() {
+        let (server, _) = server();
+        let response = request(&server, HoverRequest::METHOD, 3, 5);
+        let hover: Hover = serde_json::from_value(response.result.unwrap()).unwrap();
+        let HoverContents::Markup(content) = hover.contents else {
+            unreachable!()
+        };
+        assert!(content.value.starts_with("`JUMPZ a`"));
+
+        let response = request(&server, "textDocument/completion", 0, 0);
+        assert_eq!(
+            ErrorCode::MethodNotFound as i32,
+            response.error.unwrap().code
+        );
+    }
+
+    #[test]
+    fn position_test() {
+        let source = "ab\nü𝄞x\n";
+        assert_eq!(9, to_offset(source, Position::new(1, 3)));
+        assert_eq!(Position::new(1, 3), to_position(source, 9));
+        assert_eq!(10, to_offset(source, Position::new(1, 99)));
+        assert_eq!(source.len(), to_offset(source, Position::new(5, 0)));
+    }
+}
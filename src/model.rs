@@ -1 +1,18 @@
+#[cfg(feature = "binary")]
+pub mod binary;
+pub mod command_definition;
+pub mod diagnostic;
+pub mod junit;
+pub mod level_data;
+pub mod lint;
 pub mod problem_definition;
+pub mod problem_set_definition;
+pub mod program_definition;
+pub mod report;
+#[cfg(feature = "levels")]
+pub mod repository;
+pub mod session;
+pub mod solution_definition;
+pub mod stream;
+pub mod svg;
+pub mod trace;
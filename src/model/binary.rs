@@ -0,0 +1,385 @@
+//! Binary
+//!
+//! Compact binary format of compiled programs, for caching large corpora of solutions. A file is
+//! a header - [BINARY_MAGIC] & [BINARY_VERSION] as little endian `u16` - followed by programs,
+//! each a little endian `u32` length & a [postcard] record. Records are length-prefixed, so a
+//! memory-mapped corpus can be skipped through without decoding, see [CorpusReader].
+//!
+//! Commands are stored by opcode & arguments, decoded without the source compiler.
+
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    code::{
+        commands::{debug::DebugPrintFactory, CommandFactory},
+        program::{Define, Program, ProgramBuilder},
+        symbol::Label,
+    },
+    commands, extended_commands,
+};
+
+/// Magic bytes at the start of every binary file
+pub const BINARY_MAGIC: [u8; 4] = *b"HRMB";
+
+/// Version of the binary format, bumped on any change to the records or [OPCODES]
+pub const BINARY_VERSION: u16 = 1;
+
+/// Command keywords by opcode, the order is part of the format
+const OPCODES: [&str; 23] = [
+    "INBOX", "OUTBOX", "COPYFROM", "COPYTO", "ADD", "SUB", "BUMPUP", "BUMPDN", "JUMP", "JUMPZ",
+    "JUMPN", "MUL", "DIV", "MOD", "NEG", "NOP", "JUMPP", "HALT", "PUSH", "POP", "CALL", "RET",
+    "DEBUG",
+];
+
+const HEADER_LEN: usize = BINARY_MAGIC.len() + 2;
+
+/// Binary Error
+///
+/// Error codes `E12xx`, see [BinaryError::code].
+#[derive(Debug, PartialEq, thiserror::Error)]
+#[non_exhaustive]
+pub enum BinaryError {
+    /// Only built-in commands have an opcode
+    #[error("Command `{0}` can't be stored in the binary format")]
+    UnsupportedCommand(String),
+    #[error("Not a binary program file")]
+    InvalidHeader,
+    #[error("Unsupported binary format version {0}, expected {BINARY_VERSION}")]
+    UnsupportedVersion(u16),
+    /// Truncated or corrupted record
+    #[error("Malformed record: {0}")]
+    Malformed(String),
+    #[error("Invalid command `{0}`")]
+    InvalidCommand(String),
+    /// [Program::from_binary] of a file with more or less than one program
+    #[error("Expected a single program, found {0}")]
+    ProgramCount(usize),
+}
+
+impl BinaryError {
+    /// Code
+    ///
+    /// Returns the stable error code, e.g. `E1201`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            BinaryError::UnsupportedCommand(_) => "E1201",
+            BinaryError::InvalidHeader => "E1202",
+            BinaryError::UnsupportedVersion(_) => "E1203",
+            BinaryError::Malformed(_) => "E1204",
+            BinaryError::InvalidCommand(_) => "E1205",
+            BinaryError::ProgramCount(_) => "E1206",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Record<'a> {
+    #[serde(borrow)]
+    commands: Vec<CommandRecord<'a>>,
+    /// Labels & the command index they point to
+    #[serde(borrow)]
+    labels: Vec<(Cow<'a, str>, u32)>,
+    comments: Vec<(u32, u32)>,
+    defines: Vec<Define>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CommandRecord<'a> {
+    opcode: u8,
+    /// Arguments as source, see [crate::code::commands::Command::args]
+    #[serde(borrow)]
+    args: Option<Cow<'a, str>>,
+}
+
+impl Program {
+    /// To Binary
+    ///
+    /// Encode as a binary file holding only this program. Fails on custom commands.
+    pub fn to_binary(&self) -> Result<Vec<u8>, BinaryError> {
+        write_corpus([self])
+    }
+
+    /// From Binary
+    ///
+    /// Decode a binary file holding a single program, see [Program::to_binary].
+    pub fn from_binary(bytes: &[u8]) -> Result<Self, BinaryError> {
+        let mut programs = CorpusReader::new(bytes)?.collect::<Result<Vec<_>, _>>()?;
+        match programs.len() {
+            1 => Ok(programs.remove(0)),
+            count => Err(BinaryError::ProgramCount(count)),
+        }
+    }
+}
+
+/// Write Corpus
+///
+/// Encode `programs` as a single binary file, read it with [CorpusReader]. Fails on custom
+/// commands.
+pub fn write_corpus<'p>(
+    programs: impl IntoIterator<Item = &'p Program>,
+) -> Result<Vec<u8>, BinaryError> {
+    let mut bytes = BINARY_MAGIC.to_vec();
+    bytes.extend(BINARY_VERSION.to_le_bytes());
+
+    for program in programs {
+        let record = encode(program)?;
+        let record = postcard::to_allocvec(&record)
+            .map_err(|err| BinaryError::Malformed(err.to_string()))?;
+        bytes.extend((record.len() as u32).to_le_bytes());
+        bytes.extend(record);
+    }
+
+    Ok(bytes)
+}
+
+/// Corpus Reader
+///
+/// Iterator decoding the programs of a binary file, e.g. a memory-mapped one, one at a time.
+/// Stops after the first error.
+pub struct CorpusReader<'a> {
+    /// Records not read yet
+    bytes: &'a [u8],
+    /// Factories by opcode
+    factories: Vec<Box<dyn CommandFactory>>,
+    failed: bool,
+}
+
+impl<'a> CorpusReader<'a> {
+    /// New
+    ///
+    /// Check the header of `bytes`, records are decoded while iterating.
+    pub fn new(bytes: &'a [u8]) -> Result<Self, BinaryError> {
+        if bytes.len() < HEADER_LEN || bytes[..BINARY_MAGIC.len()] != BINARY_MAGIC {
+            return Err(BinaryError::InvalidHeader);
+        }
+        let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+        if version != BINARY_VERSION {
+            return Err(BinaryError::UnsupportedVersion(version));
+        }
+
+        let mut factories: Vec<Box<dyn CommandFactory>> = commands!();
+        factories.extend::<Vec<Box<dyn CommandFactory>>>(extended_commands!());
+        factories.push(Box::new(DebugPrintFactory));
+        let factories = OPCODES
+            .iter()
+            .map(|&keyword| {
+                let i = factories
+                    .iter()
+                    .position(|factory| factory.command() == keyword)
+                    .expect("every opcode is a built-in command");
+                factories.swap_remove(i)
+            })
+            .collect();
+
+        Ok(Self {
+            bytes: &bytes[HEADER_LEN..],
+            factories,
+            failed: false,
+        })
+    }
+
+    /// Skip Program
+    ///
+    /// Skip the next program without decoding it.
+    pub fn skip_program(&mut self) -> Result<(), BinaryError> {
+        self.next_record().map(|_| ())
+    }
+
+    fn next_record(&mut self) -> Result<&'a [u8], BinaryError> {
+        let truncated = || BinaryError::Malformed(String::from("truncated record"));
+        let (len, rest) = self.bytes.split_first_chunk::<4>().ok_or_else(truncated)?;
+        let len = u32::from_le_bytes(*len) as usize;
+        if rest.len() < len {
+            return Err(truncated());
+        }
+        let (record, rest) = rest.split_at(len);
+        self.bytes = rest;
+        Ok(record)
+    }
+
+    fn decode(&self, record: &[u8]) -> Result<Program, BinaryError> {
+        let record: Record =
+            postcard::from_bytes(record).map_err(|err| BinaryError::Malformed(err.to_string()))?;
+
+        let mut labels = record.labels;
+        labels.sort_by_key(|(_, idx)| *idx);
+        let mut labels = labels.into_iter().peekable();
+        let mut comments = record.comments.into_iter().peekable();
+        let mut builder = ProgramBuilder::new();
+
+        for (i, command) in record.commands.into_iter().enumerate() {
+            let i = i as u32;
+            while let Some((label, _)) = labels.next_if(|(_, idx)| *idx == i) {
+                builder.add_label_ref(Label::from(&*label));
+            }
+            while let Some((_, comment)) = comments.next_if(|(idx, _)| *idx == i) {
+                builder.add_comment_ref(comment);
+            }
+
+            let args = command.args.as_deref().unwrap_or_default();
+            let factory = self
+                .factories
+                .get(command.opcode as usize)
+                .ok_or_else(|| BinaryError::InvalidCommand(format!("opcode {}", command.opcode)))?;
+            let command = factory.create(args).ok_or_else(|| {
+                BinaryError::InvalidCommand(format!("{} {args}", factory.command()))
+            })?;
+            builder.add_command_ref(command);
+        }
+
+        labels.for_each(|(label, _)| builder.add_label_ref(Label::from(&*label)));
+        comments.for_each(|(_, comment)| builder.add_comment_ref(comment));
+        record
+            .defines
+            .into_iter()
+            .for_each(|define| builder.add_define_ref(define));
+
+        Ok(builder.build())
+    }
+}
+
+impl Iterator for CorpusReader<'_> {
+    type Item = Result<Program, BinaryError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.bytes.is_empty() {
+            return None;
+        }
+        let program = self.next_record().and_then(|record| self.decode(record));
+        self.failed = program.is_err();
+        Some(program)
+    }
+}
+
+fn encode(program: &Program) -> Result<Record<'_>, BinaryError> {
+    let commands = program
+        .get_commands()
+        .iter()
+        .map(|command| {
+            let keyword = command.factory().command();
+            let opcode = OPCODES
+                .iter()
+                .position(|&opcode| opcode == keyword)
+                .ok_or_else(|| BinaryError::UnsupportedCommand(keyword.to_string()))?;
+            Ok(CommandRecord {
+                opcode: opcode as u8,
+                args: command.args().map(Cow::Owned),
+            })
+        })
+        .collect::<Result<_, BinaryError>>()?;
+
+    Ok(Record {
+        commands,
+        labels: program
+            .get_labels()
+            .map(|(label, idx)| (Cow::Borrowed(label.as_str()), idx as u32))
+            .collect(),
+        comments: program
+            .get_comments()
+            .iter()
+            .map(|&(idx, comment)| (idx as u32, comment))
+            .collect(),
+        defines: program.get_defines().clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        code::commands::{ALL_COMMANDS, EXTENDED_COMMANDS},
+        compiler::compile::Compiler,
+        model::program_definition::ProgramDefinition,
+    };
+
+    const CODE: &str = "\
+a:
+    INBOX
+    COMMENT 0
+    COPYTO [4]
+    DEBUG \"x y\" 0 1
+b:
+    JUMPZ a
+    CALL f
+    HALT
+f:
+    MUL 2
+    RET
+DEFINE COMMENT 0
+eJxTYGAQ;
+";
+
+    #[test]
+    fn opcodes() {
+        let mut keywords: Vec<&str> = ALL_COMMANDS.into_iter().chain(EXTENDED_COMMANDS).collect();
+        keywords.push("DEBUG");
+        assert_eq!(keywords, OPCODES);
+    }
+
+    #[test]
+    fn binary_program() {
+        let program = Compiler::extended().compile(CODE).unwrap();
+        let bytes = program.to_binary().unwrap();
+        assert_eq!(b"HRMB\x01\x00", &bytes[..HEADER_LEN]);
+        assert!(bytes.len() < serde_json::to_vec(&program).unwrap().len());
+
+        let decoded = Program::from_binary(&bytes).unwrap();
+        assert_eq!(
+            ProgramDefinition::from(&program),
+            ProgramDefinition::from(&decoded)
+        );
+        assert_eq!(program.get_targets(), decoded.get_targets());
+        assert_eq!(program.get_bytecode(), decoded.get_bytecode());
+    }
+
+    #[test]
+    fn corpus() {
+        let compiler = Compiler::extended();
+        let programs: Vec<Program> = ["INBOX\nOUTBOX\n", "", "a:\nJUMP a\n"]
+            .iter()
+            .map(|code| compiler.compile(code).unwrap())
+            .collect();
+        let bytes = write_corpus(&programs).unwrap();
+
+        let mut reader = CorpusReader::new(&bytes).unwrap();
+        reader.skip_program().unwrap();
+        let rest: Vec<Program> = reader.map(Result::unwrap).collect();
+        assert_eq!(2, rest.len());
+        assert!(rest[0].get_commands().is_empty());
+        assert_eq!(Some(0), rest[1].get_label("a"));
+        assert_eq!(
+            Err(BinaryError::ProgramCount(3)),
+            Program::from_binary(&bytes).map(|_| ())
+        );
+        assert_eq!(
+            Err(BinaryError::ProgramCount(0)),
+            Program::from_binary(b"HRMB\x01\x00").map(|_| ())
+        );
+    }
+
+    #[test]
+    fn binary_errors() {
+        let program = Compiler::default().compile("INBOX\nOUTBOX\n").unwrap();
+        let bytes = program.to_binary().unwrap();
+
+        let decode = |bytes: &[u8]| Program::from_binary(bytes).map(|_| ()).unwrap_err().code();
+        assert_eq!("E1202", decode(b"HRM"));
+        assert_eq!("E1202", decode(b"{\"commands\": []}"));
+        assert_eq!("E1203", decode(b"HRMB\x02\x00"));
+        assert_eq!("E1204", decode(&bytes[..bytes.len() - 1]));
+
+        // First command's opcode, after the record length & command count
+        let mut corrupted = bytes.clone();
+        corrupted[HEADER_LEN + 5] = 99;
+        assert_eq!(
+            Err(BinaryError::InvalidCommand(String::from("opcode 99"))),
+            Program::from_binary(&corrupted).map(|_| ())
+        );
+
+        let mut reader = CorpusReader::new(&corrupted).unwrap();
+        assert!(reader.next().unwrap().is_err());
+        assert!(reader.next().is_none());
+    }
+}
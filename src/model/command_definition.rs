@@ -0,0 +1,53 @@
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::code::commands::{AnyCommand, Command};
+use crate::compiler::compile::Compiler;
+
+/// Commands are serialized as their source line, e.g. `"COPYFROM [4]"`.
+impl Serialize for dyn Command {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_source())
+    }
+}
+
+/// Commands are compiled with [Compiler::extended], so extension commands are restored as well.
+impl<'de> Deserialize<'de> for AnyCommand {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let source = String::deserialize(deserializer)?;
+        Compiler::extended()
+            .compile_command(&source)
+            .ok_or_else(|| de::Error::custom(format!("illegal command: {source}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_commands() {
+        for source in [
+            "INBOX",
+            "COPYFROM [4]",
+            "JUMPZ a",
+            "MUL 2",
+            "CALL b",
+            "JUMP [1]",
+            "DEBUG \"x\" 0",
+        ] {
+            let command = Compiler::extended().compile_command(source).unwrap();
+            let serialized = serde_json::to_string(&command).unwrap();
+            assert_eq!(format!("{source:?}"), serialized);
+
+            let deserialized: AnyCommand = serde_json::from_str(&serialized).unwrap();
+            assert_eq!(source, serde_json::to_value(&deserialized).unwrap());
+        }
+    }
+
+    #[test]
+    fn deserialize_command_fails() {
+        for json in ["\"FOO\"", "\"ADD a\"", "1"] {
+            assert!(serde_json::from_str::<AnyCommand>(json).is_err());
+        }
+    }
+}
@@ -0,0 +1,498 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    code::{
+        analysis::analyze,
+        program::{FailureReport, Program, RunError, ValidationError},
+    },
+    compiler::{
+        compile::{Compiler, ParseError},
+        tokenize::{Token, TokenKind},
+    },
+    game::problem::Problem,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// Warning code of commands that can never execute, see [crate::code::analysis::unreachable]
+pub const UNREACHABLE: &str = "W0601";
+/// Warning code of loops that never end, see [crate::code::analysis::infinite_loops]
+pub const INFINITE_LOOP: &str = "W0602";
+
+/// Message Style
+///
+/// Tone of rendered run error messages, see [run_error_message].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageStyle {
+    /// Short, neutral messages for editors & tooling
+    #[default]
+    Plain,
+    /// Messages mirroring the game's tone, e.g. "Empty value! You can't OUTBOX with empty hands!"
+    Game,
+}
+
+/// Span
+///
+/// Location in the source code.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Span {
+    /// Source line (0-based)
+    pub line: usize,
+    /// Byte offset of the first character in the source code
+    pub start: usize,
+    /// Byte offset after the last character in the source code
+    pub end: usize,
+}
+
+impl Span {
+    /// Line
+    ///
+    /// Returns the [Span] of the trimmed source line (0-based), [None] if it doesn't exist.
+    pub fn line(source: &str, line: usize) -> Option<Self> {
+        let mut offset = 0;
+        for (i, text) in source.split_inclusive('\n').enumerate() {
+            if i == line {
+                let start = offset + text.len() - text.trim_start().len();
+                return Some(Self {
+                    line,
+                    start,
+                    end: start + text.trim().len(),
+                });
+            }
+            offset += text.len();
+        }
+
+        None
+    }
+
+    fn token(source: &str, token: &Token) -> Self {
+        Self {
+            line: source[..token.span.start].matches('\n').count(),
+            start: token.span.start,
+            end: token.span.end,
+        }
+    }
+}
+
+/// Related Information
+///
+/// Secondary location or note attached to a [Diagnostic].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Related {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub span: Option<Span>,
+    pub message: String,
+}
+
+/// Diagnostic
+///
+/// Serializable compile, validation, analysis or run issue for editors & web frontends. `code` is
+/// a stable identifier - the error code of the underlying error, e.g. `E0102` (see
+/// [ParseError::code]), or a warning code, e.g. [UNREACHABLE].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub code: String,
+    pub severity: Severity,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub span: Option<Span>,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub related: Vec<Related>,
+}
+
+impl Diagnostic {
+    fn new(code: &str, severity: Severity, span: Option<Span>, message: String) -> Self {
+        Self {
+            code: code.to_string(),
+            severity,
+            span,
+            message,
+            related: vec![],
+        }
+    }
+
+    /// From Parse Error
+    ///
+    /// Returns the [Diagnostic] of a failed [Compiler::compile] of `source`.
+    pub fn from_parse_error(compiler: &Compiler, source: &str, err: &ParseError) -> Self {
+        match err {
+            ParseError::IllegalLine(line) => {
+                let span = source
+                    .lines()
+                    .position(|text| text.trim() == line)
+                    .and_then(|i| Span::line(source, i));
+                Self::new(err.code(), Severity::Error, span, err.to_string())
+            }
+            ParseError::MissingLabel(label) => {
+                let span = find_token(compiler, source, TokenKind::LabelReference, label.as_str());
+                Self::new(err.code(), Severity::Error, span, err.to_string())
+            }
+        }
+    }
+
+    /// From Validation
+    ///
+    /// Returns a [Diagnostic] for every issue found by [Program::validate_all].
+    pub fn from_validation(
+        compiler: &Compiler,
+        source: &str,
+        program: &Program,
+        problem: &Problem,
+    ) -> Vec<Self> {
+        program
+            .validation_issues(problem)
+            .into_iter()
+            .map(|(i_command, err)| {
+                let span = match (&err, i_command) {
+                    (_, Some(i_command)) => command_span(source, program, i_command),
+                    (ValidationError::DuplicateLabel(label), None) => {
+                        let definition = format!("{label}:");
+                        find_tokens(compiler, source, TokenKind::LabelDefinition, &definition)
+                            .last()
+                            .cloned()
+                    }
+                    _ => None,
+                };
+                Self::new(err.code(), Severity::Error, span, err.to_string())
+            })
+            .collect()
+    }
+
+    /// From Analysis
+    ///
+    /// Returns a warning for every finding of [analyze].
+    pub fn from_analysis(compiler: &Compiler, source: &str, program: &Program) -> Vec<Self> {
+        let report = analyze(program);
+
+        let unreachable = report.unreachable.iter().map(|&i_command| {
+            Self::new(
+                UNREACHABLE,
+                Severity::Warning,
+                command_span(source, program, i_command),
+                String::from("Command can never execute"),
+            )
+        });
+
+        let infinite_loops = report.infinite_loops.iter().map(|infinite_loop| {
+            let mut diagnostic = Self::new(
+                INFINITE_LOOP,
+                Severity::Warning,
+                infinite_loop
+                    .commands
+                    .iter()
+                    .min()
+                    .and_then(|&i_command| command_span(source, program, i_command)),
+                format!(
+                    "Loop `{}` never reads the inbox nor writes to the outbox and never ends",
+                    infinite_loop.label
+                ),
+            );
+            let definition = format!("{}:", infinite_loop.label);
+            diagnostic.related.push(Related {
+                span: find_token(compiler, source, TokenKind::LabelDefinition, &definition),
+                message: format!("Label `{}` defined here", infinite_loop.label),
+            });
+            diagnostic
+        });
+
+        unreachable.chain(infinite_loops).collect()
+    }
+
+    /// From Failure
+    ///
+    /// Returns the [Diagnostic] of a failed [Program::run], with the message
+    /// rendered in the given [MessageStyle].
+    pub fn from_failure(
+        source: &str,
+        program: &Program,
+        report: &FailureReport,
+        style: MessageStyle,
+    ) -> Self {
+        let command = program
+            .get_commands()
+            .get(report.i_command)
+            .map(|command| command.factory().command());
+        let mut diagnostic = Self::new(
+            report.error.code(),
+            Severity::Error,
+            command_span(source, program, report.i_command),
+            run_error_message(&report.error, command, style),
+        );
+        diagnostic.related.push(Related {
+            span: None,
+            message: format!(
+                "IO {}: {} input(s) consumed, {} output(s) produced",
+                report.i_io, report.inputs_consumed, report.outputs_produced
+            ),
+        });
+        diagnostic
+    }
+}
+
+/// Diagnose
+///
+/// Compile `source`, validate it & run it for `problem`, collecting all [Diagnostic]s. Analysis
+/// warnings are reported for compiled programs, the program is only run if it is valid.
+pub fn diagnose(compiler: &Compiler, source: &str, problem: &Problem) -> Vec<Diagnostic> {
+    diagnose_with_style(compiler, source, problem, MessageStyle::Plain)
+}
+
+/// Diagnose With Style
+///
+/// See [diagnose], run errors are rendered in the given [MessageStyle].
+pub fn diagnose_with_style(
+    compiler: &Compiler,
+    source: &str,
+    problem: &Problem,
+    style: MessageStyle,
+) -> Vec<Diagnostic> {
+    let program = match compiler.compile(source) {
+        Ok(program) => program,
+        Err(err) => return vec![Diagnostic::from_parse_error(compiler, source, &err)],
+    };
+
+    let mut diagnostics = Diagnostic::from_validation(compiler, source, &program, problem);
+    let valid = diagnostics.is_empty();
+    diagnostics.extend(Diagnostic::from_analysis(compiler, source, &program));
+
+    if valid {
+        if let Err(report) = program.run(problem) {
+            diagnostics.push(Diagnostic::from_failure(source, &program, &report, style));
+        }
+    }
+
+    diagnostics
+}
+
+pub(crate) fn command_span(source: &str, program: &Program, i_command: usize) -> Option<Span> {
+    program
+        .get_line(i_command)
+        .and_then(|line| Span::line(source, line))
+}
+
+fn find_tokens(compiler: &Compiler, source: &str, kind: TokenKind, text: &str) -> Vec<Span> {
+    compiler
+        .tokenize(source)
+        .iter()
+        .filter(|token| token.kind == kind && token.text(source) == text)
+        .map(|token| Span::token(source, token))
+        .collect()
+}
+
+pub(crate) fn find_token(
+    compiler: &Compiler,
+    source: &str,
+    kind: TokenKind,
+    text: &str,
+) -> Option<Span> {
+    find_tokens(compiler, source, kind, text).into_iter().next()
+}
+
+/// Run Error Message
+///
+/// Render a [RunError] in the given [MessageStyle]. `command` is the keyword of the failing
+/// command, e.g. `OUTBOX`, if known.
+pub fn run_error_message(err: &RunError, command: Option<&str>, style: MessageStyle) -> String {
+    match style {
+        MessageStyle::Plain => err.to_string(),
+        MessageStyle::Game => game_run_error_message(err, command.unwrap_or("do that")),
+    }
+}
+
+fn game_run_error_message(err: &RunError, command: &str) -> String {
+    match err {
+        RunError::EmptyAcc => format!("Empty value! You can't {command} with empty hands!"),
+        RunError::EmptyMemory => format!(
+            "Empty value! You can't {command} with an empty tile on the floor! Try writing something to that tile first."
+        ),
+        RunError::WrongOutput {
+            expected, value, ..
+        } => format!("Bad outbox! Management expected {expected}, but you outboxed {value}."),
+        RunError::ExtraOutput { value, .. } => format!(
+            "Bad outbox! Management didn't expect anything more, but you outboxed {value}."
+        ),
+        RunError::MissingOutput { remaining, .. } => format!(
+            "Management expected more items in the outbox! {remaining} item(s) still missing."
+        ),
+        RunError::CharIndex(value) => {
+            format!("Bad address! You can't use the letter {value} as a tile address!")
+        }
+        RunError::IndexOutOfRange(value) => {
+            format!("Bad address! There is no tile {value} on the floor!")
+        }
+        RunError::JumpOutOfRange(value) => {
+            format!("Bad jump! There is no command {value} to jump to!")
+        }
+        RunError::Overflow(value) => {
+            format!("Overflow! {value} doesn't fit in a data box! Each box has a limited range.")
+        }
+        RunError::FloorHole(idx) => format!("Watch your step! Tile {idx} is a hole in the floor!"),
+        RunError::Add | RunError::Sub | RunError::Mul | RunError::Div | RunError::Mod => format!(
+            "You can't {command} with a letter and a number! What would that even mean?!"
+        ),
+        RunError::Neg => format!("You can't {command} a letter! What would that even mean?!"),
+        RunError::DivideByZero => String::from("You can't divide by zero! Nobody can!"),
+        RunError::EmptyStack => format!("Empty pockets! You can't {command} with nothing stashed!"),
+        RunError::EmptyCallStack => {
+            format!("Lost! You can't {command} without anywhere to return to!")
+        }
+        RunError::CallStackOverflow { depth } => {
+            format!("Too deep! You can't go more than {depth} calls deep!")
+        }
+        RunError::StepLimit(steps) => {
+            format!("You're taking too long! The workday ended after {steps} steps.")
+        }
+        RunError::UndefinedLabel(label) => {
+            format!("Lost! There is no label {label} to jump to!")
+        }
+        RunError::InfiniteLoop => {
+            String::from("Going in circles! You're back where you started & will never finish.")
+        }
+        RunError::TotalStepLimit(steps) => {
+            format!("You're taking too long! The workweek ended after {steps} steps.")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::problem::{ProblemBuilder, ProblemIO};
+    use crate::game::value::Value;
+
+    use super::*;
+
+    fn problem() -> Problem {
+        ProblemBuilder::new()
+            .add_io(ProblemIO {
+                input: vec![Value::Int(1)],
+                output: vec![Value::Int(1)],
+            })
+            .memory_dim(1)
+            .enable_all_commands()
+            .disable_command("SUB")
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn diagnose_parse_error() {
+        let source = "INBOX\n  JUMP b\n";
+        let diagnostics = diagnose(&Compiler::default(), source, &problem());
+
+        assert_eq!(
+            vec![Diagnostic {
+                code: String::from("E0102"),
+                severity: Severity::Error,
+                span: Some(Span {
+                    line: 1,
+                    start: 13,
+                    end: 14,
+                }),
+                message: String::from("Label `b` is not defined"),
+                related: vec![],
+            }],
+            diagnostics
+        );
+
+        let source = "INBOX\n  FOO\n";
+        let diagnostics = diagnose(&Compiler::default(), source, &problem());
+        assert_eq!("E0101", diagnostics[0].code);
+        assert_eq!(Some(Span::line(source, 1).unwrap()), diagnostics[0].span);
+    }
+
+    #[test]
+    fn diagnose_validation_and_analysis() {
+        let source = "\
+a:
+    SUB 0
+    COPYTO 3
+    JUMP a
+    OUTBOX
+";
+        let diagnostics = diagnose(&Compiler::default(), source, &problem());
+
+        let codes: Vec<(&str, Severity, Option<usize>)> = diagnostics
+            .iter()
+            .map(|diagnostic| {
+                (
+                    diagnostic.code.as_str(),
+                    diagnostic.severity,
+                    diagnostic.span.as_ref().map(|span| span.line),
+                )
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                ("E0201", Severity::Error, Some(1)),
+                ("E0202", Severity::Error, Some(2)),
+                (UNREACHABLE, Severity::Warning, Some(4)),
+                (INFINITE_LOOP, Severity::Warning, Some(1)),
+            ],
+            codes
+        );
+        assert_eq!(
+            Some(0),
+            diagnostics[3].related[0].span.as_ref().map(|s| s.line)
+        );
+    }
+
+    #[test]
+    fn diagnose_run_error() {
+        let source = "INBOX\nOUTBOX\nOUTBOX\n";
+        let diagnostics = diagnose(&Compiler::default(), source, &problem());
+
+        assert_eq!(1, diagnostics.len());
+        assert_eq!("E0304", diagnostics[0].code);
+        assert_eq!(
+            "Output 1: no output expected, got 1",
+            diagnostics[0].message
+        );
+        assert_eq!(2, diagnostics[0].span.as_ref().unwrap().line);
+
+        let json = serde_json::to_value(&diagnostics[0]).unwrap();
+        assert_eq!("error", json["severity"]);
+        assert_eq!(
+            "IO 0: 1 input(s) consumed, 1 output(s) produced",
+            json["related"][0]["message"]
+        );
+    }
+
+    #[test]
+    fn diagnose_valid() {
+        let source = "INBOX\nOUTBOX\n";
+        assert!(diagnose(&Compiler::default(), source, &problem()).is_empty());
+    }
+
+    #[test]
+    fn run_error_message_test() {
+        assert_eq!(
+            "Nothing in hands",
+            run_error_message(&RunError::EmptyAcc, Some("OUTBOX"), MessageStyle::Plain)
+        );
+        assert_eq!(
+            "Empty value! You can't OUTBOX with empty hands!",
+            run_error_message(&RunError::EmptyAcc, Some("OUTBOX"), MessageStyle::Game)
+        );
+        assert_eq!(
+            "Empty value! You can't do that with empty hands!",
+            run_error_message(&RunError::EmptyAcc, None, MessageStyle::Game)
+        );
+    }
+
+    #[test]
+    fn diagnose_with_game_style() {
+        let source = "OUTBOX\n";
+        let diagnostics =
+            diagnose_with_style(&Compiler::default(), source, &problem(), MessageStyle::Game);
+        assert_eq!(
+            "Empty value! You can't OUTBOX with empty hands!",
+            diagnostics[0].message
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use std::fmt::Write;
+
+use crate::{
+    code::program::{FailureReport, IOScore, Program},
+    game::problem::Problem,
+    model::{
+        diagnostic::{Diagnostic, MessageStyle},
+        session::check,
+    },
+};
+
+/// To JUnit
+///
+/// Write a JUnit XML report of the results of [Program::run_each], `program` being compiled from
+/// `source`. Every IO is a test case named `io <i>`, failing ones carry the [Diagnostic] code &
+/// message.
+pub fn to_junit(
+    suite: &str,
+    source: &str,
+    program: &Program,
+    results: &[Result<IOScore, FailureReport>],
+) -> String {
+    let failures = results.iter().filter(|result| result.is_err()).count();
+    let mut xml = open(suite, results.len(), failures);
+    for (i, result) in results.iter().enumerate() {
+        match result {
+            Ok(score) => {
+                write_case(&mut xml, suite, i, None);
+                writeln!(
+                    xml,
+                    "      <system-out>speed {}, steps {}</system-out>",
+                    score.speed, score.steps
+                )
+                .unwrap();
+                xml.push_str("    </testcase>\n");
+            }
+            Err(report) => {
+                let diagnostic =
+                    Diagnostic::from_failure(source, program, report, MessageStyle::Plain);
+                write_case(&mut xml, suite, i, Some(&[diagnostic]));
+            }
+        }
+    }
+    close(xml)
+}
+
+/// Evaluate JUnit
+///
+/// Compile, validate & run `source` for every IO of `problem`, see [to_junit]. The suite is named
+/// after the problem. If the program doesn't compile or validate, every IO fails with its errors.
+pub fn evaluate_junit(problem: &Problem, source: &str) -> String {
+    let suite = problem.title.as_str();
+    match check(problem, source) {
+        Ok(program) => to_junit(suite, source, &program, &program.run_each(problem)),
+        Err(diagnostics) => {
+            let ios = problem.get_ios().len();
+            let mut xml = open(suite, ios, ios);
+            (0..ios).for_each(|i| write_case(&mut xml, suite, i, Some(&diagnostics)));
+            close(xml)
+        }
+    }
+}
+
+fn open(suite: &str, tests: usize, failures: usize) -> String {
+    let suite = escape(suite);
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    writeln!(
+        xml,
+        "<testsuites name=\"hrm\" tests=\"{tests}\" failures=\"{failures}\" errors=\"0\">"
+    )
+    .unwrap();
+    writeln!(
+        xml,
+        "  <testsuite name=\"{suite}\" tests=\"{tests}\" failures=\"{failures}\" errors=\"0\" \
+         skipped=\"0\">"
+    )
+    .unwrap();
+    xml
+}
+
+fn close(mut xml: String) -> String {
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+/// Open the test case of IO `i`, also closing it if it failed with `diagnostics`
+fn write_case(xml: &mut String, suite: &str, i: usize, diagnostics: Option<&[Diagnostic]>) {
+    let suite = escape(suite);
+    writeln!(xml, "    <testcase classname=\"{suite}\" name=\"io {i}\">").unwrap();
+    let Some(diagnostics) = diagnostics else {
+        return;
+    };
+
+    let (code, message) = diagnostics.first().map_or(("", ""), |first| {
+        (first.code.as_str(), first.message.as_str())
+    });
+    writeln!(
+        xml,
+        "      <failure type=\"{}\" message=\"{}\">",
+        escape(code),
+        escape(message)
+    )
+    .unwrap();
+    for diagnostic in diagnostics {
+        let line = diagnostic
+            .span
+            .as_ref()
+            .map(|span| format!(" (line {})", span.line + 1))
+            .unwrap_or_default();
+        let text = format!("{}{line}: {}", diagnostic.code, diagnostic.message);
+        writeln!(xml, "{}", escape(&text)).unwrap();
+        for related in &diagnostic.related {
+            writeln!(xml, "    note: {}", escape(&related.message)).unwrap();
+        }
+    }
+    xml.push_str("      </failure>\n    </testcase>\n");
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Not allowed in XML 1.0
+            c if c.is_control() && !matches!(c, '\n' | '\r' | '\t') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{
+        problem::{ProblemBuilder, ProblemIO},
+        value::Value,
+    };
+
+    fn problem() -> Problem {
+        ProblemBuilder::new()
+            .title("Echo & <friends>".to_string())
+            .add_io(ProblemIO {
+                input: vec![Value::Int(1)],
+                output: vec![Value::Int(1)],
+            })
+            .add_io(ProblemIO {
+                input: vec![Value::Int(2)],
+                output: vec![Value::Int(3)],
+            })
+            .enable_all_commands()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn evaluate_junit_test() {
+        let xml = evaluate_junit(&problem(), "INBOX\nOUTBOX\n");
+
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n"));
+        assert!(xml
+            .contains("<testsuite name=\"Echo &amp; &lt;friends&gt;\" tests=\"2\" failures=\"1\""));
+        assert!(xml.contains("<testcase classname=\"Echo &amp; &lt;friends&gt;\" name=\"io 0\">\n      <system-out>speed 2, steps 2</system-out>"));
+        assert!(xml.contains("name=\"io 1\">\n      <failure type=\"E0303\""));
+        assert_eq!(2, xml.matches("<testcase ").count());
+        assert_eq!(2, xml.matches("</testcase>").count());
+        assert!(xml.ends_with("  </testsuite>\n</testsuites>\n"));
+    }
+
+    #[test]
+    fn evaluate_junit_invalid() {
+        let xml = evaluate_junit(&problem(), "COPYTO 0\n");
+
+        assert!(xml.contains("tests=\"2\" failures=\"2\""));
+        assert_eq!(2, xml.matches("<failure type=\"E0202\"").count());
+        assert!(xml.contains("E0202 (line 1): "));
+    }
+
+    #[test]
+    fn escape_test() {
+        assert_eq!(
+            "a &amp; &quot;b&quot; &apos;c&apos;\n",
+            escape("a & \"b\" 'c'\u{0}\n")
+        );
+    }
+}
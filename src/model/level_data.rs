@@ -0,0 +1,193 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use crate::code::commands::ALL_COMMANDS;
+use crate::game::value::Value;
+use crate::model::problem_definition::{
+    PartialMemory, ProblemDefinition, ProblemDefinitionIO, ProblemDefinitionMemory,
+};
+
+/// Level Data
+///
+/// A level in the format of the community `hrm-level-data` dumps (`levels.json`).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LevelData {
+    pub number: u32,
+    pub title: String,
+    #[serde(default)]
+    pub instructions: String,
+    #[serde(default)]
+    pub commands: Vec<String>,
+    #[serde(default)]
+    pub floor: Option<LevelFloor>,
+    #[serde(default)]
+    pub examples: Vec<LevelExample>,
+    #[serde(default)]
+    pub challenge: Option<LevelChallenge>,
+    #[serde(default)]
+    pub cutscene: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LevelFloor {
+    pub columns: usize,
+    pub rows: usize,
+    #[serde(default)]
+    pub tiles: Option<LevelTiles>,
+}
+
+/// Floor tiles are either listed in order or keyed by index.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum LevelTiles {
+    List(Vec<Option<Value>>),
+    Map(BTreeMap<String, Value>),
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LevelExample {
+    pub inbox: Vec<Value>,
+    pub outbox: Vec<Value>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct LevelChallenge {
+    pub size: usize,
+    pub speed: u64,
+}
+
+impl LevelData {
+    /// Id
+    ///
+    /// Returns the level's id, matching the built-in levels, e.g. `year_20`.
+    pub fn id(&self) -> String {
+        format!("year_{}", self.number)
+    }
+}
+
+impl From<LevelData> for ProblemDefinition {
+    /// Commands that are not vanilla commands (e.g. `COMMENT`) are dropped.
+    fn from(value: LevelData) -> Self {
+        let memory = value.floor.map(|floor| {
+            let values = match floor.tiles {
+                Some(LevelTiles::List(tiles)) => tiles
+                    .into_iter()
+                    .enumerate()
+                    .filter_map(|(i, tile)| tile.map(|tile| (i, tile)))
+                    .collect(),
+                Some(LevelTiles::Map(tiles)) => tiles
+                    .into_iter()
+                    .filter_map(|(i, tile)| Some((i.parse().ok()?, tile)))
+                    .collect(),
+                None => Default::default(),
+            };
+
+            ProblemDefinitionMemory {
+                full: None,
+                partial: Some(PartialMemory {
+                    dim: floor.columns * floor.rows,
+                    values,
+                }),
+            }
+        });
+
+        Self {
+            title: value.title,
+            description: value.instructions,
+            metadata: Default::default(),
+            ios: value
+                .examples
+                .into_iter()
+                .map(|example| ProblemDefinitionIO {
+                    input: example.inbox,
+                    output: example.outbox,
+                })
+                .collect(),
+            memory,
+            instruction_set: Default::default(),
+            commands: value
+                .commands
+                .into_iter()
+                .filter(|command| ALL_COMMANDS.contains(&command.as_str()))
+                .collect(),
+            command_limits: Default::default(),
+            value_bounds: None,
+            output_matching: Default::default(),
+            tile_labels: Default::default(),
+            holes: Default::default(),
+            unbounded_memory: false,
+            generator: None,
+            parameter: None,
+            size_target: value.challenge.map(|challenge| challenge.size),
+            speed_target: value.challenge.map(|challenge| challenge.speed),
+        }
+    }
+}
+
+/// Import Levels
+///
+/// Parse a `levels.json` dump into `(id, definition)` pairs, skipping cutscenes.
+pub fn import_levels(json: &str) -> Result<Vec<(String, ProblemDefinition)>, serde_json::Error> {
+    let levels: Vec<LevelData> = serde_json::from_str(json)?;
+    Ok(levels
+        .into_iter()
+        .filter(|level| !level.cutscene)
+        .map(|level| (level.id(), level.into()))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::problem::Problem;
+
+    use super::*;
+
+    #[test]
+    fn import_levels_test() {
+        let json = r#"[
+            {
+                "number": 3,
+                "title": "Copy Floor",
+                "instructions": "Send B U G to the OUTBOX.",
+                "commands": ["INBOX", "OUTBOX", "COPYFROM", "COPYTO", "JUMP", "COMMENT"],
+                "floor": {
+                    "columns": 3,
+                    "rows": 2,
+                    "tiles": ["U", "J", "X", "G", "B", "E"]
+                },
+                "examples": [{"inbox": [-99, -99], "outbox": ["B", "U", "G"]}],
+                "challenge": {"size": 6, "speed": 6}
+            },
+            {
+                "number": 5,
+                "title": "Coffee Time",
+                "cutscene": true
+            },
+            {
+                "number": 20,
+                "title": "Multiplication Workshop",
+                "commands": ["INBOX", "OUTBOX"],
+                "floor": {"columns": 5, "rows": 2, "tiles": {"9": 0}},
+                "examples": [{"inbox": [2, 3], "outbox": [6]}]
+            }
+        ]"#;
+
+        let levels = import_levels(json).unwrap();
+        assert_eq!(2, levels.len());
+
+        let (id, definition) = &levels[0];
+        assert_eq!("year_3", id);
+        assert_eq!(5, definition.commands.len());
+        assert_eq!(Some(6), definition.size_target);
+        definition.validate().unwrap();
+
+        let (id, definition) = levels.into_iter().nth(1).unwrap();
+        assert_eq!("year_20", id);
+        assert_eq!(None, definition.speed_target);
+
+        let problem = Problem::try_from(definition).unwrap();
+        assert_eq!(10, problem.get_memory().len());
+        assert_eq!(Some(Value::Int(0)), problem.get_memory()[9]);
+    }
+}
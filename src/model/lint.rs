@@ -0,0 +1,368 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    code::{
+        analysis::{successors, unreachable},
+        commands::{AnyCommand, Flow},
+        program::Program,
+    },
+    compiler::{compile::Compiler, tokenize::TokenKind},
+    game::problem::Problem,
+    model::diagnostic::{command_span, find_token, Diagnostic, Severity, Span, UNREACHABLE},
+};
+
+/// Lint Rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Rule {
+    /// Label no command jumps to
+    UnusedLabel,
+    /// Command that can never execute, see [unreachable]
+    UnreachableCode,
+    /// Jump or branch to the next command
+    JumpToNext,
+    /// Tile written by `COPYTO` that no command reads
+    WriteNeverRead,
+    /// `BUMPUP` or `BUMPDN` of a tile that may still be empty
+    BumpUninitialized,
+}
+
+impl Rule {
+    pub const ALL: [Rule; 5] = [
+        Rule::UnusedLabel,
+        Rule::UnreachableCode,
+        Rule::JumpToNext,
+        Rule::WriteNeverRead,
+        Rule::BumpUninitialized,
+    ];
+
+    /// Name
+    ///
+    /// Returns the kebab-case name, as serialized.
+    pub fn name(self) -> &'static str {
+        match self {
+            Rule::UnusedLabel => "unused-label",
+            Rule::UnreachableCode => "unreachable-code",
+            Rule::JumpToNext => "jump-to-next",
+            Rule::WriteNeverRead => "write-never-read",
+            Rule::BumpUninitialized => "bump-uninitialized",
+        }
+    }
+
+    /// Code
+    ///
+    /// Returns the warning code of the rule's [Diagnostic]s, whatever their [Severity].
+    pub fn code(self) -> &'static str {
+        match self {
+            Rule::UnusedLabel => "W0603",
+            Rule::UnreachableCode => UNREACHABLE,
+            Rule::JumpToNext => "W0604",
+            Rule::WriteNeverRead => "W0605",
+            Rule::BumpUninitialized => "W0606",
+        }
+    }
+}
+
+/// Lint Level
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintLevel {
+    /// Rule is disabled
+    Off,
+    #[default]
+    Warning,
+    Error,
+}
+
+/// Lint Config
+///
+/// Level of each rule, rules not listed are warnings. Deserializes from e.g.
+/// `{"rules": {"unused-label": "off", "bump-uninitialized": "error"}}`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LintConfig {
+    #[serde(default)]
+    pub rules: BTreeMap<Rule, LintLevel>,
+}
+
+impl LintConfig {
+    /// Set
+    ///
+    /// Set the level of `rule`.
+    pub fn set(mut self, rule: Rule, level: LintLevel) -> Self {
+        self.rules.insert(rule, level);
+        self
+    }
+
+    /// Level
+    ///
+    /// Returns the configured level of `rule`.
+    pub fn level(&self, rule: Rule) -> LintLevel {
+        self.rules.get(&rule).copied().unwrap_or_default()
+    }
+}
+
+/// Lint
+///
+/// Check the compiled `source` against every enabled rule, returning a [Diagnostic] per finding,
+/// ordered by rule. Tile rules assume the initial floor of `problem`, or an empty floor without
+/// one. A program with indirect tile arguments, e.g. `COPYTO [x]`, may read or write any tile, so
+/// tile rules don't report anything for it.
+pub fn lint(
+    compiler: &Compiler,
+    source: &str,
+    program: &Program,
+    problem: Option<&Problem>,
+    config: &LintConfig,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    for rule in Rule::ALL {
+        let severity = match config.level(rule) {
+            LintLevel::Off => continue,
+            LintLevel::Warning => Severity::Warning,
+            LintLevel::Error => Severity::Error,
+        };
+        let findings = match rule {
+            Rule::UnusedLabel => unused_labels(compiler, source, program),
+            Rule::UnreachableCode => unreachable(program)
+                .into_iter()
+                .map(|i| {
+                    let span = command_span(source, program, i);
+                    (span, String::from("Command can never execute"))
+                })
+                .collect(),
+            Rule::JumpToNext => jumps_to_next(source, program),
+            Rule::WriteNeverRead => writes_never_read(source, program),
+            Rule::BumpUninitialized => bumps_uninitialized(source, program, problem),
+        };
+        diagnostics.extend(findings.into_iter().map(|(span, message)| Diagnostic {
+            code: rule.code().to_string(),
+            severity,
+            span,
+            message,
+            related: vec![],
+        }));
+    }
+    diagnostics
+}
+
+/// Span & message of a rule violation
+type Finding = (Option<Span>, String);
+
+fn unused_labels(compiler: &Compiler, source: &str, program: &Program) -> Vec<Finding> {
+    let used: BTreeSet<_> = program
+        .get_commands()
+        .iter()
+        .filter_map(|command| command.requires_label())
+        .collect();
+    let mut labels: Vec<_> = program
+        .get_labels()
+        .filter(|(label, _)| !used.contains(*label))
+        .collect();
+    labels.sort_by_key(|&(_, i)| i);
+
+    labels
+        .into_iter()
+        .map(|(label, _)| {
+            let definition = format!("{label}:");
+            (
+                find_token(compiler, source, TokenKind::LabelDefinition, &definition),
+                format!("Label `{label}` is never jumped to"),
+            )
+        })
+        .collect()
+}
+
+fn jumps_to_next(source: &str, program: &Program) -> Vec<Finding> {
+    let targets = program.get_targets();
+    program
+        .get_commands()
+        .iter()
+        .enumerate()
+        .filter(|(i, command)| {
+            matches!(command.flow(), Flow::Jump | Flow::Branch) && targets[*i] == Some(i + 1)
+        })
+        .map(|(i, command)| {
+            (
+                command_span(source, program, i),
+                format!(
+                    "`{}` to the next command has no effect",
+                    command.to_source()
+                ),
+            )
+        })
+        .collect()
+}
+
+fn writes_never_read(source: &str, program: &Program) -> Vec<Finding> {
+    let commands = program.get_commands();
+    if commands.iter().any(is_indirect) {
+        return vec![];
+    }
+
+    let read: BTreeSet<usize> = commands
+        .iter()
+        .filter(|command| keyword(command) != "COPYTO")
+        .filter_map(|command| command.tile())
+        .collect();
+    commands
+        .iter()
+        .enumerate()
+        .filter(|(_, command)| keyword(command) == "COPYTO")
+        .filter_map(|(i, command)| {
+            let tile = command.tile().filter(|tile| !read.contains(tile))?;
+            Some((
+                command_span(source, program, i),
+                format!("Tile {tile} is written but never read"),
+            ))
+        })
+        .collect()
+}
+
+/// Bumps reachable from the start on a path that doesn't write their tile, which is initially
+/// empty
+fn bumps_uninitialized(source: &str, program: &Program, problem: Option<&Problem>) -> Vec<Finding> {
+    let commands = program.get_commands();
+    if commands.iter().any(is_indirect) {
+        return vec![];
+    }
+    let initialized = |tile: usize| {
+        problem.is_some_and(|problem| problem.get_memory().get(tile).is_some_and(Option::is_some))
+    };
+
+    let mut findings = vec![];
+    for (i, command) in commands.iter().enumerate() {
+        if !matches!(keyword(command), "BUMPUP" | "BUMPDN") {
+            continue;
+        }
+        let Some(tile) = command.tile().filter(|&tile| !initialized(tile)) else {
+            continue;
+        };
+
+        let writes =
+            |j: usize| keyword(&commands[j]) == "COPYTO" && commands[j].tile() == Some(tile);
+        let mut visited = vec![false; commands.len()];
+        let mut stack = if commands.is_empty() { vec![] } else { vec![0] };
+        while let Some(j) = stack.pop() {
+            if visited[j] || writes(j) {
+                continue;
+            }
+            visited[j] = true;
+            stack.extend(successors(program, j));
+        }
+
+        if visited[i] {
+            findings.push((
+                command_span(source, program, i),
+                format!("Tile {tile} may be empty when bumped"),
+            ));
+        }
+    }
+    findings
+}
+
+fn keyword(command: &AnyCommand) -> &'static str {
+    command.factory().command()
+}
+
+fn is_indirect(command: &AnyCommand) -> bool {
+    command.tile().is_some() && command.args().is_some_and(|args| args.starts_with('['))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{problem::ProblemBuilder, value::Value};
+
+    fn codes(source: &str, problem: Option<&Problem>, config: &LintConfig) -> Vec<&'static str> {
+        let compiler = Compiler::extended();
+        let program = compiler.compile(source).unwrap();
+        lint(&compiler, source, &program, problem, config)
+            .iter()
+            .map(|diagnostic| {
+                Rule::ALL
+                    .into_iter()
+                    .find(|rule| rule.code() == diagnostic.code)
+                    .unwrap()
+                    .name()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn lint_test() {
+        let source = "\
+a:
+b:
+    INBOX
+    JUMPZ c
+c:
+    COPYTO 0
+    BUMPUP 1
+    OUTBOX
+    JUMP a
+    OUTBOX
+";
+        let config = LintConfig::default();
+        assert_eq!(
+            vec![
+                "unused-label",
+                "unreachable-code",
+                "jump-to-next",
+                "write-never-read",
+                "bump-uninitialized"
+            ],
+            codes(source, None, &config)
+        );
+
+        let compiler = Compiler::extended();
+        let program = compiler.compile(source).unwrap();
+        let diagnostics = lint(&compiler, source, &program, None, &config);
+        assert_eq!("Label `b` is never jumped to", diagnostics[0].message);
+        assert_eq!(Some(1), diagnostics[0].span.as_ref().map(|span| span.line));
+        assert_eq!("Tile 0 is written but never read", diagnostics[3].message);
+        assert_eq!("Tile 1 may be empty when bumped", diagnostics[4].message);
+        assert_eq!(Some(6), diagnostics[4].span.as_ref().map(|span| span.line));
+
+        // Tile 1 is initialized
+        let problem = ProblemBuilder::new()
+            .memory_dim(3)
+            .add_memory_slot(1, Value::Int(0))
+            .build()
+            .unwrap();
+        assert!(!codes(source, Some(&problem), &config).contains(&"bump-uninitialized"));
+    }
+
+    #[test]
+    fn lint_tiles_test() {
+        let config = LintConfig::default();
+        // Written before every bump, read by the bump
+        let source = "INBOX\nCOPYTO 0\na:\nBUMPUP 0\nOUTBOX\nJUMP a\n";
+        assert!(codes(source, None, &config).is_empty());
+        // Written only after the first bump
+        let source = "a:\nBUMPDN 0\nOUTBOX\nINBOX\nCOPYTO 0\nJUMP a\n";
+        assert_eq!(vec!["bump-uninitialized"], codes(source, None, &config));
+        // Indirect arguments may read or write any tile
+        let source = "INBOX\nCOPYTO 0\nCOPYTO [1]\nBUMPUP 2\n";
+        assert!(codes(source, None, &config).is_empty());
+    }
+
+    #[test]
+    fn lint_config_test() {
+        let source = "a:\nINBOX\nJUMP b\nb:\nOUTBOX\n";
+        let config = LintConfig::default()
+            .set(Rule::UnusedLabel, LintLevel::Off)
+            .set(Rule::JumpToNext, LintLevel::Error);
+        let compiler = Compiler::extended();
+        let program = compiler.compile(source).unwrap();
+        let diagnostics = lint(&compiler, source, &program, None, &config);
+        assert_eq!(1, diagnostics.len());
+        assert_eq!("W0604", diagnostics[0].code);
+        assert_eq!(Severity::Error, diagnostics[0].severity);
+
+        let json = r#"{"rules": {"unused-label": "off", "jump-to-next": "error"}}"#;
+        assert_eq!(config, serde_json::from_str(json).unwrap());
+        assert_eq!(LintLevel::Warning, config.level(Rule::WriteNeverRead));
+    }
+}
@@ -1,25 +1,357 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
 
-use crate::game::problem::{Problem, ProblemBuilder, ProblemIO};
-use crate::game::value::Value;
+use crate::code::commands::{InstructionSet, ALL_COMMANDS, EXTENDED_COMMANDS};
+use crate::game::generator::IOGenerator;
+use crate::game::problem::{
+    Metadata, OutputMatching, Problem, ProblemBuildError, ProblemBuilder, ProblemIO,
+};
+use crate::game::value::{Value, ValueBounds};
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ProblemDefinition {
     pub title: String,
     pub description: String,
+    #[serde(default, skip_serializing_if = "Metadata::is_empty")]
+    pub metadata: Metadata,
     pub ios: Vec<ProblemDefinitionIO>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub memory: Option<ProblemDefinitionMemory>,
+    #[serde(default, skip_serializing_if = "InstructionSet::is_vanilla")]
+    pub instruction_set: InstructionSet,
     pub commands: Vec<String>,
+    /// Max number of times a command may be used, e.g. at most 1 `JUMP`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub command_limits: BTreeMap<String, usize>,
+    /// Allowed range of int values, defaults to [ValueBounds::GAME]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value_bounds: Option<ValueBounds>,
+    #[serde(default, skip_serializing_if = "OutputMatching::is_exact")]
+    pub output_matching: OutputMatching,
+    /// Labels written on floor tiles, e.g. `ZERO`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tile_labels: BTreeMap<usize, String>,
+    /// Floor tiles that don't exist
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub holes: BTreeSet<usize>,
+    /// Memory grows on demand, see [Problem::has_unbounded_memory]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub unbounded_memory: bool,
+    /// Random inputs, see [ProblemDefinition::with_reference]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generator: Option<IOGenerator>,
+    /// Parameter of a problem family, see [ProblemDefinition::variants]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parameter: Option<Parameter>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size_target: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speed_target: Option<u64>,
 }
 
-impl From<ProblemDefinition> for Problem {
+/// Parameter
+///
+/// Variable of a parameterized problem, e.g. `N` in "multiply by N". `{N}` in the title &
+/// description is replaced by the value of each variant.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Parameter {
+    pub name: String,
+    pub values: Vec<i32>,
+}
+
+/// Problem Definition Error
+///
+/// Error codes `E05xx`, see [ProblemDefinitionError::code].
+#[derive(Debug, PartialEq, thiserror::Error)]
+#[non_exhaustive]
+pub enum ProblemDefinitionError {
+    #[error("Unknown command `{0}`")]
+    UnknownCommand(String),
+    #[error("Memory slot {0} is defined more than once")]
+    DuplicateMemoryIndex(usize),
+    #[error("Memory slot {0} is outside the floor")]
+    MemoryIndex(usize),
+    #[error("Problem has no IOs")]
+    EmptyIOs,
+    #[error("Hole {0} is outside the floor or on an occupied or labelled tile")]
+    HoleIndex(usize),
+    #[error("Value {0} is out of bounds")]
+    ValueOutOfBounds(Value),
+}
+
+impl ProblemDefinitionError {
+    /// Code
+    ///
+    /// Returns the stable error code, e.g. `E0501`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ProblemDefinitionError::UnknownCommand(_) => "E0501",
+            ProblemDefinitionError::DuplicateMemoryIndex(_) => "E0502",
+            ProblemDefinitionError::MemoryIndex(_) => "E0503",
+            ProblemDefinitionError::EmptyIOs => "E0504",
+            ProblemDefinitionError::HoleIndex(_) => "E0505",
+            ProblemDefinitionError::ValueOutOfBounds(_) => "E0506",
+        }
+    }
+}
+
+impl ProblemDefinition {
+    /// From YAML
+    ///
+    /// Parse a [ProblemDefinition] from YAML, which unlike JSON allows comments.
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(yaml: &str) -> Result<Self, serde_yaml::Error> {
+        serde_yaml::from_str(yaml)
+    }
+
+    /// From JSON5
+    ///
+    /// Parse a [ProblemDefinition] from JSON5, a superset of JSON (and JSONC) allowing comments,
+    /// trailing commas & unquoted keys.
+    #[cfg(feature = "json5")]
+    pub fn from_json5(json5: &str) -> Result<Self, json5::Error> {
+        // Parsed via JSON, which (unlike json5) supports quoted integer keys, e.g. memory slots
+        let value: serde_json::Value = json5::from_str(json5)?;
+        serde_json::from_value(value).map_err(serde::de::Error::custom)
+    }
+
+    /// Validate
+    ///
+    /// Check the definition without converting it into [Problem], covering every
+    /// [ProblemBuildError] a definition can cause. Returns:
+    /// - [Ok] if the definition converts, i.e. [Problem::try_from] succeeds
+    /// - [Err(ProblemDefinitionError)] for the first unknown command or command limit (or command
+    ///   outside the instruction set), memory slot defined in both `full` & `partial`, memory
+    ///   slot or tile label outside `dim`, hole outside `dim` or on an occupied or labelled tile,
+    ///   IO or memory value outside the value bounds or if there are neither IOs nor a generator
+    pub fn validate(&self) -> Result<(), ProblemDefinitionError> {
+        if self.ios.is_empty() && self.generator.is_none() {
+            return Err(ProblemDefinitionError::EmptyIOs);
+        }
+
+        for command in &self.commands {
+            let is_known = ALL_COMMANDS.contains(&command.as_str())
+                || EXTENDED_COMMANDS.contains(&command.as_str());
+            if !is_known || !self.instruction_set.contains(command) {
+                return Err(ProblemDefinitionError::UnknownCommand(command.clone()));
+            }
+        }
+        let is_known = |command: &&String| {
+            ALL_COMMANDS.contains(&command.as_str())
+                || EXTENDED_COMMANDS.contains(&command.as_str())
+        };
+        if let Some(command) = self
+            .command_limits
+            .keys()
+            .find(|command| !is_known(command))
+        {
+            return Err(ProblemDefinitionError::UnknownCommand(command.clone()));
+        }
+
+        let dim = match &self.memory {
+            Some(ProblemDefinitionMemory {
+                full: Some(full), ..
+            }) => full.len(),
+            Some(ProblemDefinitionMemory {
+                partial: Some(partial),
+                ..
+            }) => partial.dim,
+            _ => 0,
+        };
+        if let Some(&idx) = self.tile_labels.keys().find(|&&idx| idx >= dim) {
+            return Err(ProblemDefinitionError::MemoryIndex(idx));
+        }
+
+        if let Some(memory) = &self.memory {
+            if let Some(partial) = &memory.partial {
+                let mut indices: Vec<usize> = partial.values.keys().copied().collect();
+                indices.sort();
+                for idx in indices {
+                    if memory.full.is_some() {
+                        return Err(ProblemDefinitionError::DuplicateMemoryIndex(idx));
+                    }
+                    if idx >= partial.dim {
+                        return Err(ProblemDefinitionError::MemoryIndex(idx));
+                    }
+                }
+            }
+        }
+
+        // Memory as built, `full` taking precedence over `partial`
+        let memory: Vec<Option<Value>> = match &self.memory {
+            Some(ProblemDefinitionMemory {
+                full: Some(full), ..
+            }) => full.clone(),
+            Some(ProblemDefinitionMemory {
+                partial: Some(partial),
+                ..
+            }) => (0..partial.dim)
+                .map(|idx| partial.values.get(&idx).copied())
+                .collect(),
+            _ => vec![],
+        };
+        let is_hole = |idx: &&usize| {
+            !matches!(memory.get(**idx), Some(None)) || self.tile_labels.contains_key(*idx)
+        };
+        if let Some(&idx) = self.holes.iter().find(is_hole) {
+            return Err(ProblemDefinitionError::HoleIndex(idx));
+        }
+
+        let bounds = self.value_bounds.unwrap_or(ValueBounds::GAME);
+        let out_of_bounds = self
+            .ios
+            .iter()
+            .flat_map(|io| io.input.iter().chain(&io.output))
+            .chain(memory.iter().flatten())
+            .find(|&&value| !bounds.contains(value));
+        if let Some(&value) = out_of_bounds {
+            return Err(ProblemDefinitionError::ValueOutOfBounds(value));
+        }
+
+        Ok(())
+    }
+
+    /// With Reference
+    ///
+    /// Convert into a [Problem], computing outputs of generated inputs with given reference
+    /// implementation. Without a reference (i.e. [TryFrom]) generated inputs are ignored.
+    pub fn with_reference(
+        mut self,
+        reference: impl Fn(&[Value]) -> Vec<Value> + Send + Sync + 'static,
+    ) -> Result<Problem, Vec<ProblemBuildError>> {
+        let generator = self.generator.take();
+        let mut builder = ProblemBuilder::from(self).reference(reference);
+        if let Some(generator) = generator {
+            for input in generator.generate() {
+                builder = builder.add_input(input);
+            }
+        }
+
+        builder.build()
+    }
+
+    /// Variants
+    ///
+    /// Expand a parameterized definition into one [Problem] per parameter value, with outputs of
+    /// generated inputs computed by `reference` given the value. A definition without a
+    /// parameter expands into a single variant with value `0`.
+    pub fn variants(
+        mut self,
+        reference: impl Fn(i32, &[Value]) -> Vec<Value> + Send + Sync + 'static,
+    ) -> Result<Vec<(i32, Problem)>, Vec<ProblemBuildError>> {
+        let parameter = self.parameter.take().unwrap_or(Parameter {
+            name: String::new(),
+            values: vec![0],
+        });
+        let reference = Arc::new(reference);
+        let pattern = format!("{{{}}}", parameter.name);
+
+        parameter
+            .values
+            .into_iter()
+            .map(|value| {
+                let mut definition = self.clone();
+                if !parameter.name.is_empty() {
+                    let replacement = value.to_string();
+                    definition.title = definition.title.replace(&pattern, &replacement);
+                    definition.description = definition.description.replace(&pattern, &replacement);
+                }
+
+                let reference = Arc::clone(&reference);
+                let problem = definition.with_reference(move |input| reference(value, input))?;
+                Ok((value, problem))
+            })
+            .collect()
+    }
+}
+
+impl TryFrom<ProblemDefinition> for Problem {
+    type Error = Vec<ProblemBuildError>;
+
+    fn try_from(value: ProblemDefinition) -> Result<Self, Self::Error> {
+        ProblemBuilder::from(value).build()
+    }
+}
+
+impl From<&Problem> for ProblemDefinition {
+    /// The reference implementation is not exported, IOs are exported as computed. Unbounded
+    /// values are exported as the full `i32` range.
+    fn from(value: &Problem) -> Self {
+        let memory = value.get_memory();
+        let memory = (!memory.is_empty()).then(|| ProblemDefinitionMemory {
+            full: None,
+            partial: Some(PartialMemory {
+                dim: memory.len(),
+                values: memory
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, value)| value.map(|value| (i, value)))
+                    .collect(),
+            }),
+        });
+
+        let commands = ALL_COMMANDS
+            .iter()
+            .chain(EXTENDED_COMMANDS.iter())
+            .filter(|command| value.is_command_available(command))
+            .map(|command| command.to_string())
+            .collect();
+
+        let value_bounds = match value.get_value_bounds() {
+            Some(ValueBounds::GAME) => None,
+            Some(value_bounds) => Some(value_bounds),
+            None => Some(ValueBounds {
+                min: i32::MIN,
+                max: i32::MAX,
+            }),
+        };
+
+        Self {
+            title: value.title.clone(),
+            description: value.description.clone(),
+            metadata: value.get_metadata().clone(),
+            ios: value
+                .get_ios()
+                .iter()
+                .map(|problem_io| ProblemDefinitionIO {
+                    input: problem_io.input.clone(),
+                    output: problem_io.output.clone(),
+                })
+                .collect(),
+            memory,
+            instruction_set: value.get_instruction_set().clone(),
+            commands,
+            command_limits: value
+                .get_command_limits()
+                .iter()
+                .map(|(command, limit)| (command.clone(), *limit))
+                .collect(),
+            value_bounds,
+            output_matching: value.get_output_matching(),
+            tile_labels: value
+                .get_tile_labels()
+                .iter()
+                .map(|(idx, label)| (*idx, label.clone()))
+                .collect(),
+            holes: value.get_holes().iter().copied().collect(),
+            unbounded_memory: value.has_unbounded_memory(),
+            generator: None,
+            parameter: None,
+            size_target: value.get_size_target(),
+            speed_target: value.get_speed_target(),
+        }
+    }
+}
+
+impl From<ProblemDefinition> for ProblemBuilder {
     fn from(value: ProblemDefinition) -> Self {
         let mut builder = ProblemBuilder::new()
             .title(value.title)
-            .description(value.description);
+            .description(value.description)
+            .metadata(value.metadata)
+            .instruction_set(value.instruction_set);
 
         for problem_io in value.ios {
             builder = builder.add_io(problem_io.into());
@@ -55,7 +387,33 @@ impl From<ProblemDefinition> for Problem {
             builder = builder.enable_command(command);
         }
 
-        builder.build()
+        for (command, limit) in value.command_limits {
+            builder = builder.command_limit(command, limit);
+        }
+
+        builder = builder.output_matching(value.output_matching);
+        if let Some(value_bounds) = value.value_bounds {
+            builder = builder.value_bounds(Some(value_bounds));
+        }
+
+        for (idx, label) in value.tile_labels {
+            builder = builder.tile_label(idx, label);
+        }
+
+        for idx in value.holes {
+            builder = builder.floor_hole(idx);
+        }
+        builder = builder.unbounded_memory(value.unbounded_memory);
+
+        if let Some(size_target) = value.size_target {
+            builder = builder.size_target(size_target);
+        }
+
+        if let Some(speed_target) = value.speed_target {
+            builder = builder.speed_target(speed_target);
+        }
+
+        builder
     }
 }
 
@@ -90,6 +448,7 @@ pub struct PartialMemory {
 
 #[cfg(test)]
 mod tests {
+    use crate::game::generator::ValueRange;
     use crate::game::value::Value;
 
     use super::*;
@@ -107,10 +466,23 @@ mod tests {
     #[test]
     fn into_problem() {
         let problem_definition = create_problem_definition();
-        let problem: Problem = problem_definition.into();
+        let problem = Problem::try_from(problem_definition).unwrap();
 
+        assert_eq!("Title", problem.title);
+        assert_eq!("Description", problem.description);
+        assert_eq!(Some("Author"), problem.get_metadata().author.as_deref());
+        assert_eq!(vec![String::from("tag")], problem.get_metadata().tags);
         assert_eq!(1, problem.get_ios().len());
         assert_eq!(2, problem.get_memory().len());
+        assert!(problem.is_command_available("MUL"));
+        assert_eq!(Some(4), problem.get_size_target());
+        assert_eq!(None, problem.get_speed_target());
+        assert_eq!(Some("ONE"), problem.get_tile_label(1));
+        assert_eq!(OutputMatching::Multiset, problem.get_output_matching());
+        assert_eq!(
+            Some(ValueBounds { min: -9, max: 9 }),
+            problem.get_value_bounds()
+        );
     }
 
     #[test]
@@ -132,13 +504,288 @@ mod tests {
 
         assert_eq!(1, problem_definition.ios.len());
         assert_eq!(None, problem_definition.memory);
-        assert_eq!(2, problem_definition.commands.len())
+        assert_eq!(InstructionSet::Vanilla, problem_definition.instruction_set);
+        assert_eq!(2, problem_definition.commands.len());
+        assert!(problem_definition.metadata.is_empty());
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn deserialize_problem_definition_yaml() {
+        let yaml = "\
+# Copy the floor
+title: Title
+description: Description
+ios:
+  - input: [1, A]
+    output: [A, 1] # reversed
+memory:
+  partial:
+    dim: 3
+    values:
+      0: B
+      2: -4
+commands: [INBOX, OUTBOX, COPYFROM]
+";
+
+        let problem_definition = ProblemDefinition::from_yaml(yaml).unwrap();
+        problem_definition.validate().unwrap();
+
+        assert_eq!(
+            vec![Value::Int(1), Value::Char('A')],
+            problem_definition.ios[0].input
+        );
+        let partial = problem_definition.memory.unwrap().partial.unwrap();
+        assert_eq!(Some(&Value::Int(-4)), partial.values.get(&2));
+        assert_eq!(Some(&Value::Char('B')), partial.values.get(&0));
+        assert_eq!(3, problem_definition.commands.len());
+    }
+
+    #[test]
+    #[cfg(feature = "json5")]
+    fn deserialize_problem_definition_json5() {
+        let json5 = r#"{
+    // Hand-maintained level
+    title: "Title",
+    description: "Description",
+    ios: [
+        {input: [1, "A"], output: [1, "A"]}, // trailing comma
+    ],
+    memory: {partial: {dim: 3, values: {"0": "B", "2": -4}}},
+    commands: ["INBOX", "OUTBOX", "COPYFROM"],
+}"#;
+
+        let problem_definition = ProblemDefinition::from_json5(json5).unwrap();
+        problem_definition.validate().unwrap();
+
+        assert_eq!(
+            vec![Value::Int(1), Value::Char('A')],
+            problem_definition.ios[0].input
+        );
+        let partial = problem_definition.memory.unwrap().partial.unwrap();
+        assert_eq!(Some(&Value::Int(-4)), partial.values.get(&2));
+        assert_eq!(3, problem_definition.commands.len());
+    }
+
+    #[test]
+    fn with_reference() {
+        let mut problem_definition = create_problem_definition();
+        problem_definition.generator = Some(IOGenerator {
+            count: 3,
+            min_length: 1,
+            max_length: 5,
+            values: ValueRange::Int { min: 0, max: 9 },
+            seed: 1,
+        });
+
+        let problem = Problem::try_from(create_problem_definition()).unwrap();
+        assert_eq!(1, problem.get_ios().len());
+
+        let problem =
+            problem_definition.with_reference(|input| input.iter().rev().copied().collect());
+        let problem = problem.unwrap();
+        assert_eq!(4, problem.get_ios().len());
+        for problem_io in &problem.get_ios()[1..] {
+            let mut output = problem_io.output.clone();
+            output.reverse();
+            assert_eq!(problem_io.input, output);
+        }
+    }
+
+    #[test]
+    fn variants() {
+        let json = r#"{
+            "title": "Multiply by {N}",
+            "description": "Output each input multiplied by {N}.",
+            "ios": [],
+            "commands": ["INBOX", "OUTBOX", "ADD", "COPYTO"],
+            "memory": {"partial": {"dim": 1, "values": {}}},
+            "generator": {
+                "count": 2,
+                "min_length": 3,
+                "max_length": 3,
+                "values": {"Int": {"min": -9, "max": 9}},
+                "seed": 5
+            },
+            "parameter": {"name": "N", "values": [2, 3]}
+        }"#;
+        let problem_definition: ProblemDefinition = serde_json::from_str(json).unwrap();
+
+        let variants = problem_definition
+            .variants(|n, input| {
+                input
+                    .iter()
+                    .map(|value| match value {
+                        Value::Int(value) => Value::Int(n * value),
+                        value => *value,
+                    })
+                    .collect()
+            })
+            .unwrap();
+
+        assert_eq!(2, variants.len());
+        for (n, problem) in variants {
+            assert_eq!(format!("Multiply by {n}"), problem.title);
+            assert_eq!(2, problem.get_ios().len());
+            for problem_io in problem.get_ios() {
+                for (input, output) in problem_io.input.iter().zip(&problem_io.output) {
+                    let (Value::Int(input), Value::Int(output)) = (input, output) else {
+                        panic!("expected ints");
+                    };
+                    assert_eq!(n * input, *output);
+                }
+            }
+        }
+
+        let variants = create_problem_definition()
+            .variants(|_, input| input.to_vec())
+            .unwrap();
+        assert_eq!(1, variants.len());
+        assert_eq!(0, variants[0].0);
+    }
+
+    #[test]
+    fn from_problem() {
+        let problem = ProblemBuilder::new()
+            .title(String::from("Title"))
+            .add_io(ProblemIO {
+                input: vec![Value::Int(1)],
+                output: vec![Value::Char('A')],
+            })
+            .memory_dim(3)
+            .add_memory_slot(2, Value::Int(0))
+            .tile_label(2, String::from("ZERO"))
+            .instruction_set(InstructionSet::Extended)
+            .enable_command(String::from("INBOX"))
+            .enable_command(String::from("MUL"))
+            .value_bounds(None)
+            .size_target(3)
+            .build()
+            .unwrap();
+
+        let definition = ProblemDefinition::from(&problem);
+        assert_eq!(
+            vec![String::from("INBOX"), String::from("MUL")],
+            definition.commands
+        );
+
+        let serialized = serde_json::to_string(&definition).unwrap();
+        let deserialized: ProblemDefinition = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(definition, deserialized);
+
+        let restored = Problem::try_from(deserialized).unwrap();
+        assert_eq!(problem.title, restored.title);
+        assert_eq!(problem.get_ios(), restored.get_ios());
+        assert_eq!(problem.get_memory(), restored.get_memory());
+        assert_eq!(problem.get_tile_labels(), restored.get_tile_labels());
+        assert_eq!(
+            problem.get_available_commands(),
+            restored.get_available_commands()
+        );
+        assert_eq!(Some(3), restored.get_size_target());
+        assert!(restored
+            .get_value_bounds()
+            .unwrap()
+            .contains(Value::Int(i32::MAX)));
+    }
+
+    #[test]
+    fn validate_succeeds() {
+        create_problem_definition().validate().unwrap();
+    }
+
+    #[test]
+    fn validate_fails() {
+        let mut definition = create_problem_definition();
+        definition.commands.push(String::from("FOO"));
+        assert_eq!(
+            Err(ProblemDefinitionError::UnknownCommand(String::from("FOO"))),
+            definition.validate()
+        );
+
+        let mut definition = create_problem_definition();
+        definition.instruction_set = InstructionSet::Vanilla;
+        assert_eq!(
+            Err(ProblemDefinitionError::UnknownCommand(String::from("MUL"))),
+            definition.validate()
+        );
+
+        let mut definition = create_problem_definition();
+        definition.tile_labels.insert(2, String::from("TWO"));
+        assert_eq!(
+            Err(ProblemDefinitionError::MemoryIndex(2)),
+            definition.validate()
+        );
+
+        let mut definition = create_problem_definition();
+        definition.ios.clear();
+        assert_eq!(Err(ProblemDefinitionError::EmptyIOs), definition.validate());
+
+        let mut definition = create_problem_definition();
+        definition.memory = Some(ProblemDefinitionMemory {
+            full: None,
+            partial: Some(PartialMemory {
+                dim: 2,
+                values: HashMap::from([(1, Value::Int(0)), (2, Value::Int(0))]),
+            }),
+        });
+        assert_eq!(
+            Err(ProblemDefinitionError::MemoryIndex(2)),
+            definition.validate()
+        );
+
+        let mut definition = create_problem_definition();
+        definition.memory.as_mut().unwrap().partial = Some(PartialMemory {
+            dim: 2,
+            values: HashMap::from([(0, Value::Int(0))]),
+        });
+        assert_eq!(
+            Err(ProblemDefinitionError::DuplicateMemoryIndex(0)),
+            definition.validate()
+        );
+
+        let mut definition = create_problem_definition();
+        definition.command_limits.insert(String::from("FOO"), 1);
+        assert_eq!(
+            Err(ProblemDefinitionError::UnknownCommand(String::from("FOO"))),
+            definition.validate()
+        );
+
+        for hole in [1, 5] {
+            let mut definition = create_problem_definition();
+            definition.holes.insert(hole);
+            assert_eq!(
+                Err(ProblemDefinitionError::HoleIndex(hole)),
+                definition.validate()
+            );
+        }
+
+        let mut definition = create_problem_definition();
+        definition.tile_labels.insert(0, String::from("ZERO"));
+        assert_eq!(
+            Err(ProblemDefinitionError::HoleIndex(0)),
+            definition.validate()
+        );
+
+        let mut definition = create_problem_definition();
+        definition.ios[0].output.push(Value::Int(10));
+        assert_eq!(
+            Err(ProblemDefinitionError::ValueOutOfBounds(Value::Int(10))),
+            definition.validate()
+        );
+
+        let mut definition = create_problem_definition();
+        definition.memory.as_mut().unwrap().full = Some(vec![None, Some(Value::Int(-10))]);
+        assert_eq!(
+            Err(ProblemDefinitionError::ValueOutOfBounds(Value::Int(-10))),
+            definition.validate()
+        );
     }
 
     fn create_problem_definition() -> ProblemDefinition {
         let problem_io = ProblemDefinitionIO {
             input: vec![Value::Int(-5), Value::Char('A')],
-            output: vec![Value::Int(123), Value::Char('0')],
+            output: vec![Value::Int(-3), Value::Char('0')],
         };
 
         let memory = ProblemDefinitionMemory {
@@ -146,14 +793,34 @@ mod tests {
             partial: None,
         };
 
-        let commands = vec![String::from("INBOX"), String::from("OUTBOX")];
+        let commands = vec![
+            String::from("INBOX"),
+            String::from("OUTBOX"),
+            String::from("MUL"),
+        ];
 
         ProblemDefinition {
             title: String::from("Title"),
             description: String::from("Description"),
+            metadata: Metadata {
+                author: Some(String::from("Author")),
+                difficulty: None,
+                tags: vec![String::from("tag")],
+            },
             ios: vec![problem_io],
             memory: Some(memory),
+            instruction_set: InstructionSet::Extended,
             commands,
+            command_limits: BTreeMap::from([(String::from("MUL"), 2)]),
+            value_bounds: Some(ValueBounds { min: -9, max: 9 }),
+            output_matching: OutputMatching::Multiset,
+            tile_labels: BTreeMap::from([(1, String::from("ONE"))]),
+            holes: BTreeSet::from([0]),
+            unbounded_memory: false,
+            generator: None,
+            parameter: None,
+            size_target: Some(4),
+            speed_target: None,
         }
     }
 }
@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+
+use crate::game::problem::{Problem, ProblemBuildError};
+use crate::game::problem_set::ProblemSet;
+use crate::model::problem_definition::ProblemDefinition;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProblemSetDefinition {
+    #[serde(default)]
+    pub title: String,
+    pub problems: Vec<ProblemDefinition>,
+}
+
+impl TryFrom<ProblemSetDefinition> for ProblemSet {
+    type Error = Vec<ProblemBuildError>;
+
+    fn try_from(value: ProblemSetDefinition) -> Result<Self, Self::Error> {
+        let problems = value
+            .problems
+            .into_iter()
+            .map(Problem::try_from)
+            .collect::<Result<_, _>>()?;
+
+        Ok(ProblemSet::new(value.title, problems))
+    }
+}
+
+impl From<&ProblemSet> for ProblemSetDefinition {
+    fn from(value: &ProblemSet) -> Self {
+        Self {
+            title: value.title.clone(),
+            problems: value
+                .get_problems()
+                .iter()
+                .map(ProblemDefinition::from)
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_problem_set() {
+        let json = r#"{
+            "title": "Practice",
+            "problems": [
+                {
+                    "title": "Echo",
+                    "description": "",
+                    "metadata": {"difficulty": 1, "tags": ["io"]},
+                    "ios": [{"input": [1], "output": [1]}],
+                    "commands": ["INBOX", "OUTBOX"]
+                },
+                {
+                    "title": "Double",
+                    "description": "",
+                    "metadata": {"tags": ["math"]},
+                    "ios": [{"input": [1], "output": [2]}],
+                    "commands": ["INBOX", "OUTBOX", "ADD", "COPYTO"],
+                    "memory": {"partial": {"dim": 1, "values": {}}}
+                }
+            ]
+        }"#;
+
+        let definition: ProblemSetDefinition = serde_json::from_str(json).unwrap();
+        let set = ProblemSet::try_from(definition).unwrap();
+
+        assert_eq!("Practice", set.title);
+        assert_eq!(vec!["io", "math"], set.tags());
+        assert_eq!(1, set.filter(|p| p.has_tag("math")).count());
+
+        let definition = ProblemSetDefinition::from(&set);
+        let json = serde_json::to_string(&definition).unwrap();
+        let deserialized: ProblemSetDefinition = serde_json::from_str(&json).unwrap();
+        assert_eq!(definition, deserialized);
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::BTreeMap;
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::code::program::{Define, Program, ProgramBuilder};
+use crate::code::symbol::Label;
+use crate::compiler::compile::{Compiler, ParseError};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProgramDefinition {
+    pub commands: Vec<String>,
+    pub labels: BTreeMap<String, usize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub comments: Vec<(usize, u32)>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub defines: Vec<Define>,
+}
+
+impl From<&Program> for ProgramDefinition {
+    fn from(value: &Program) -> Self {
+        let commands = value
+            .get_commands()
+            .iter()
+            .map(|command| command.to_source())
+            .collect();
+
+        Self {
+            commands,
+            labels: BTreeMap::from_iter(
+                value
+                    .get_labels()
+                    .map(|(label, idx)| (label.to_string(), idx)),
+            ),
+            comments: value.get_comments().clone(),
+            defines: value.get_defines().clone(),
+        }
+    }
+}
+
+impl TryFrom<ProgramDefinition> for Program {
+    type Error = ParseError;
+
+    /// Commands are compiled with [Compiler::extended], so programs using extension commands
+    /// round-trip losslessly.
+    fn try_from(value: ProgramDefinition) -> Result<Self, Self::Error> {
+        let compiler = Compiler::extended();
+        let mut builder = ProgramBuilder::new();
+
+        let mut labels: Vec<(String, usize)> = value.labels.into_iter().collect();
+        labels.sort_by_key(|(_, idx)| *idx);
+        let mut labels = labels.into_iter().peekable();
+        let mut comments = value.comments.into_iter().peekable();
+
+        for (i, command) in value.commands.iter().enumerate() {
+            while let Some((label, _)) = labels.next_if(|(_, idx)| *idx == i) {
+                builder.add_label_ref(Label::from(label));
+            }
+            while let Some((_, comment)) = comments.next_if(|(idx, _)| *idx == i) {
+                builder.add_comment_ref(comment);
+            }
+
+            let command = compiler
+                .compile_command(command)
+                .ok_or_else(|| ParseError::IllegalLine(command.clone()))?;
+            builder.add_command_ref(command);
+        }
+
+        labels.for_each(|(label, _)| builder.add_label_ref(Label::from(label)));
+        comments.for_each(|(_, comment)| builder.add_comment_ref(comment));
+        value
+            .defines
+            .into_iter()
+            .for_each(|define| builder.add_define_ref(define));
+
+        Ok(builder.build())
+    }
+}
+
+impl Serialize for Program {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ProgramDefinition::from(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Program {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let definition = ProgramDefinition::deserialize(deserializer)?;
+        Program::try_from(definition).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_program() {
+        let code = "\
+a:
+    INBOX
+    COMMENT 0
+    COPYTO [4]
+b:
+    JUMPZ a
+    COMMENT 1
+DEFINE LABEL 4
+eJxTYGAQ;
+";
+        let program = Compiler::default().compile(code).unwrap();
+
+        let serialized = serde_json::to_string(&program).unwrap();
+        let deserialized: Program = serde_json::from_str(&serialized).unwrap();
+
+        assert_eq!(
+            ProgramDefinition::from(&program),
+            ProgramDefinition::from(&deserialized)
+        );
+        assert_eq!(&vec![(1, 0), (3, 1)], deserialized.get_comments());
+        assert_eq!(program.get_defines(), deserialized.get_defines());
+        assert_eq!(1, deserialized.get_defines().len());
+        assert_eq!(3, deserialized.get_commands().len());
+    }
+
+    #[test]
+    fn deserialize_program() {
+        let json = "\
+        {
+            \"commands\": [\"INBOX\", \"OUTBOX\", \"JUMP a\"],
+            \"labels\": {\"a\": 0}
+        }";
+
+        let program: Program = serde_json::from_str(json).unwrap();
+
+        assert_eq!(3, program.get_commands().len());
+        assert_eq!(Some(0), program.get_label("a"));
+        assert!(program.get_comments().is_empty());
+    }
+
+    #[test]
+    fn serde_program_extended() {
+        let code = "\
+a:
+    INBOX
+    PUSH
+    CALL b
+    JUMP [0]
+b:
+    MUL 2
+    RET
+";
+        let program = Compiler::extended().compile(code).unwrap();
+
+        let serialized = serde_json::to_string(&program).unwrap();
+        let deserialized: Program = serde_json::from_str(&serialized).unwrap();
+
+        assert_eq!(
+            ProgramDefinition::from(&program),
+            ProgramDefinition::from(&deserialized)
+        );
+        assert_eq!(6, deserialized.get_commands().len());
+    }
+
+    #[test]
+    fn deserialize_program_fails() {
+        let json = "{\"commands\": [\"JUMP A\"], \"labels\": {}}";
+        assert!(serde_json::from_str::<Program>(json).is_err());
+    }
+}
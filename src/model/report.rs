@@ -0,0 +1,409 @@
+use std::fmt::Write;
+
+use crate::{
+    code::program::{ChallengeResult, IOScore, Score},
+    compiler::compile::Compiler,
+    game::problem::Problem,
+    model::{
+        diagnostic::{Diagnostic, MessageStyle, Severity},
+        session::check,
+    },
+};
+
+/// Report
+///
+/// Full evaluation of a solution, rendered as a self-contained [Report::to_markdown] or
+/// [Report::to_html] document, e.g. to hand back to students.
+#[derive(Debug, PartialEq)]
+pub struct Report {
+    /// Title of the problem
+    pub title: String,
+    /// Whether the solution compiles & validates, IOs are only run if it does
+    pub valid: bool,
+    /// Result of each IO, failing ones as [Diagnostic::from_failure]
+    pub results: Vec<Result<IOScore, Diagnostic>>,
+    /// Score if every IO passes
+    pub score: Option<Score>,
+    pub challenges: Option<ChallengeResult>,
+    /// Errors if the solution is invalid, analysis warnings otherwise
+    pub diagnostics: Vec<Diagnostic>,
+    /// Source of each command, by command index
+    pub commands: Vec<String>,
+}
+
+impl Report {
+    /// New
+    ///
+    /// Compile, validate & run `source` for every IO of `problem`, even if some fail.
+    pub fn new(problem: &Problem, source: &str) -> Self {
+        let mut report = Self {
+            title: problem.title.clone(),
+            valid: false,
+            results: vec![],
+            score: None,
+            challenges: None,
+            diagnostics: vec![],
+            commands: vec![],
+        };
+        let program = match check(problem, source) {
+            Ok(program) => program,
+            Err(diagnostics) => {
+                report.diagnostics = diagnostics;
+                return report;
+            }
+        };
+
+        let compiler = Compiler::new(problem.get_instruction_set());
+        report.valid = true;
+        report.diagnostics = Diagnostic::from_analysis(&compiler, source, &program);
+        report.commands = program
+            .get_commands()
+            .iter()
+            .map(|command| command.to_source())
+            .collect();
+        report.results = program
+            .run_each(problem)
+            .into_iter()
+            .map(|result| {
+                result.map_err(|report| {
+                    Diagnostic::from_failure(source, &program, &report, MessageStyle::Plain)
+                })
+            })
+            .collect();
+        if report.results.iter().all(Result::is_ok) {
+            report.score = program.run(problem).ok();
+            report.challenges = report.score.as_ref().map(|score| score.evaluate(problem));
+        }
+        report
+    }
+
+    /// Solved
+    ///
+    /// Returns `true` if the solution is valid & every IO passes.
+    pub fn solved(&self) -> bool {
+        self.score.is_some()
+    }
+
+    /// To Markdown
+    ///
+    /// Render the report as GitHub flavored Markdown.
+    pub fn to_markdown(&self) -> String {
+        let mut md = format!("# {}\n", escape_markdown(&self.title));
+        for block in self.blocks() {
+            md.push('\n');
+            match block {
+                Block::Heading(text) => writeln!(md, "## {text}").unwrap(),
+                Block::Paragraph(text) => writeln!(md, "{}", escape_markdown(&text)).unwrap(),
+                Block::List(items) => items
+                    .iter()
+                    .for_each(|item| writeln!(md, "- {}", escape_markdown(item)).unwrap()),
+                Block::Table(header, rows) => {
+                    writeln!(md, "| {} |", header.join(" | ")).unwrap();
+                    writeln!(md, "|{}", " --- |".repeat(header.len())).unwrap();
+                    for row in rows {
+                        let cells: Vec<String> = row.iter().map(|c| escape_markdown(c)).collect();
+                        writeln!(md, "| {} |", cells.join(" | ")).unwrap();
+                    }
+                }
+            }
+        }
+        md
+    }
+
+    /// To HTML
+    ///
+    /// Render the report as a standalone HTML page, styles included.
+    pub fn to_html(&self) -> String {
+        let title = escape_html(&self.title);
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>{title}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n<h1>{title}</h1>\n"
+        );
+        for block in self.blocks() {
+            match block {
+                Block::Heading(text) => writeln!(html, "<h2>{text}</h2>").unwrap(),
+                Block::Paragraph(text) => writeln!(html, "<p>{}</p>", escape_html(&text)).unwrap(),
+                Block::List(items) => {
+                    html.push_str("<ul>\n");
+                    for item in items {
+                        writeln!(html, "<li>{}</li>", escape_html(&item)).unwrap();
+                    }
+                    html.push_str("</ul>\n");
+                }
+                Block::Table(header, rows) => {
+                    html.push_str("<table>\n<tr>");
+                    header
+                        .iter()
+                        .for_each(|cell| write!(html, "<th>{cell}</th>").unwrap());
+                    html.push_str("</tr>\n");
+                    for row in rows {
+                        html.push_str("<tr>");
+                        for cell in row {
+                            write!(html, "<td>{}</td>", escape_html(&cell)).unwrap();
+                        }
+                        html.push_str("</tr>\n");
+                    }
+                    html.push_str("</table>\n");
+                }
+            }
+        }
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+
+    /// Sections of the report, shared by every format
+    fn blocks(&self) -> Vec<Block> {
+        let mut blocks = vec![Block::Paragraph(self.summary())];
+
+        if let Some(score) = &self.score {
+            blocks.push(Block::Heading("Score"));
+            let mut rows = vec![
+                vec![String::from("Size"), score.size.to_string()],
+                vec![
+                    String::from("Speed"),
+                    format!(
+                        "{:.2} (min {}, max {})",
+                        score.speed_avg, score.speed_min, score.speed_max
+                    ),
+                ],
+                vec![
+                    String::from("Steps"),
+                    format!(
+                        "{:.2} (min {}, max {})",
+                        score.steps_avg, score.steps_min, score.steps_max
+                    ),
+                ],
+                vec![
+                    String::from("Tiles touched"),
+                    score.memory.touched.len().to_string(),
+                ],
+            ];
+            if let Some(challenges) = self.challenges {
+                rows.push(vec![String::from("Size challenge"), met(challenges.size)]);
+                rows.push(vec![String::from("Speed challenge"), met(challenges.speed)]);
+            }
+            blocks.push(Block::Table(vec!["Metric", "Value"], rows));
+        }
+
+        if !self.results.is_empty() {
+            blocks.push(Block::Heading("IOs"));
+            let rows = self
+                .results
+                .iter()
+                .enumerate()
+                .map(|(i, result)| match result {
+                    Ok(score) => vec![
+                        i.to_string(),
+                        String::from("passed"),
+                        score.speed.to_string(),
+                        score.steps.to_string(),
+                        String::new(),
+                    ],
+                    Err(diagnostic) => vec![
+                        i.to_string(),
+                        String::from("failed"),
+                        String::new(),
+                        String::new(),
+                        describe(diagnostic),
+                    ],
+                })
+                .collect();
+            blocks.push(Block::Table(
+                vec!["IO", "Result", "Speed", "Steps", "Details"],
+                rows,
+            ));
+        }
+
+        if !self.diagnostics.is_empty() {
+            blocks.push(Block::Heading("Diagnostics"));
+            blocks.push(Block::List(self.diagnostics.iter().map(describe).collect()));
+        }
+
+        if let Some(score) = &self.score {
+            let mut histogram: Vec<_> = score.histogram().into_iter().collect();
+            histogram.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+            blocks.push(Block::Heading("Commands"));
+            let rows = histogram
+                .into_iter()
+                .map(|(command, count)| vec![command.to_string(), count.to_string()])
+                .collect();
+            blocks.push(Block::Table(vec!["Command", "Executions"], rows));
+
+            blocks.push(Block::Heading("Coverage"));
+            let reached = score.coverage.iter().filter(|&&reached| reached).count();
+            blocks.push(Block::Paragraph(format!(
+                "{reached} of {} commands reached.",
+                self.commands.len()
+            )));
+            let rows = self
+                .commands
+                .iter()
+                .enumerate()
+                .map(|(i, command)| {
+                    let executions = match score.executions.get(i) {
+                        Some(&count) if score.coverage[i] => count.to_string(),
+                        _ => String::from("0 (never reached)"),
+                    };
+                    vec![i.to_string(), command.clone(), executions]
+                })
+                .collect();
+            blocks.push(Block::Table(vec!["#", "Command", "Executions"], rows));
+        }
+        blocks
+    }
+
+    fn summary(&self) -> String {
+        let failed = self.results.iter().filter(|result| result.is_err()).count();
+        if !self.valid {
+            String::from("Invalid: the solution doesn't compile or validate.")
+        } else if failed > 0 {
+            format!("Failed: {failed} of {} IOs failed.", self.results.len())
+        } else {
+            format!("Solved: all {} IOs passed.", self.results.len())
+        }
+    }
+}
+
+/// Format independent part of a report
+enum Block {
+    Heading(&'static str),
+    Paragraph(String),
+    List(Vec<String>),
+    /// Header & rows of cells
+    Table(Vec<&'static str>, Vec<Vec<String>>),
+}
+
+const STYLE: &str = "body{font-family:sans-serif;max-width:60em;margin:2em auto;color:#222}\
+table{border-collapse:collapse;margin:1em 0}\
+th,td{border:1px solid #ccc;padding:.3em .6em;text-align:left}\
+th{background:#f3f3f3}";
+
+fn met(result: Option<bool>) -> String {
+    let text = match result {
+        Some(true) => "met",
+        Some(false) => "missed",
+        None => "none",
+    };
+    String::from(text)
+}
+
+/// One line description of a diagnostic, e.g. `error[E0303] (line 2): ...`
+fn describe(diagnostic: &Diagnostic) -> String {
+    let severity = match diagnostic.severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+    };
+    let line = diagnostic
+        .span
+        .as_ref()
+        .map(|span| format!(" (line {})", span.line + 1))
+        .unwrap_or_default();
+    let mut text = format!(
+        "{severity}[{}]{line}: {}",
+        diagnostic.code, diagnostic.message
+    );
+    for related in &diagnostic.related {
+        write!(text, "; {}", related.message).unwrap();
+    }
+    text
+}
+
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | '`' | '*' | '_' | '[' | ']' | '|' | '#' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            // Raw HTML is rendered by most Markdown viewers
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '\n' | '\r' => escaped.push(' '),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{
+        problem::{ProblemBuilder, ProblemIO},
+        value::Value,
+    };
+
+    fn problem(outputs: [i32; 2]) -> Problem {
+        ProblemBuilder::new()
+            .title("Echo <&> | friends".to_string())
+            .add_io(ProblemIO {
+                input: vec![Value::Int(1)],
+                output: vec![Value::Int(outputs[0])],
+            })
+            .add_io(ProblemIO {
+                input: vec![Value::Int(2)],
+                output: vec![Value::Int(outputs[1])],
+            })
+            .enable_all_commands()
+            .size_target(2)
+            .build()
+            .unwrap()
+    }
+
+    const SOURCE: &str = "INBOX\nOUTBOX\nJUMP a\nOUTBOX\na:\n";
+
+    #[test]
+    fn report_solved() {
+        let report = Report::new(&problem([1, 2]), SOURCE);
+        assert!(report.solved());
+
+        let md = report.to_markdown();
+        assert!(md.starts_with("# Echo &lt;&&gt; \\| friends\n\nSolved: all 2 IOs passed.\n"));
+        assert!(md.contains("| Size challenge | missed |\n| Speed challenge | none |\n"));
+        assert!(md.contains("| IO | Result | Speed | Steps | Details |\n"));
+        assert!(md.contains("| 1 | passed | 3 | 3 |  |\n"));
+        assert!(md.contains("| INBOX | 2 |\n"));
+        assert!(md.contains("3 of 4 commands reached."));
+        assert!(md.contains("| 3 | OUTBOX | 0 (never reached) |\n"));
+
+        let html = report.to_html();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<h1>Echo &lt;&amp;&gt; | friends</h1>"));
+        assert!(html.contains("<tr><td>Size</td><td>4</td></tr>"));
+        assert!(html.ends_with("</body>\n</html>\n"));
+    }
+
+    #[test]
+    fn report_failed() {
+        let report = Report::new(&problem([1, 3]), SOURCE);
+        assert!(!report.solved());
+        assert_eq!(None, report.score);
+
+        let md = report.to_markdown();
+        assert!(md.contains("Failed: 1 of 2 IOs failed."));
+        assert!(md.contains("| 1 | failed |  |  | error\\[E0303\\] (line 2): "));
+        assert!(!md.contains("## Coverage"));
+
+        let report = Report::new(&problem([1, 2]), "COPYTO 0\n");
+        assert!(!report.valid);
+        let html = report.to_html();
+        assert!(html.contains("<p>Invalid: the solution doesn&#39;t compile or validate.</p>"));
+        assert!(html.contains("<li>error[E0202] (line 1): "));
+    }
+}
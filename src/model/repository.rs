@@ -0,0 +1,285 @@
+//! Solution Repositories
+//!
+//! Verification of solution trees in the layout of the community `hrm-solutions` repository:
+//! solutions to year `N` are in a directory named `NN-Level-Name`, each file named after its
+//! claimed score & author as `<size>.<speed>-<author>.asm`, e.g.
+//! `solutions/01-Mail-Room/3.6-author.asm`. Other files are ignored.
+
+use std::{
+    fs, io,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+};
+
+use serde::Serialize;
+
+use crate::{
+    code::program::ScoreTarget,
+    model::solution_definition::{MeasuredScore, SolutionDefinition, SolutionError},
+};
+
+/// Repository Solution
+///
+/// Solution found by [scan], with the path of its file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepositorySolution {
+    pub path: PathBuf,
+    /// Level, author & claimed score parsed from the path
+    pub solution: SolutionDefinition,
+}
+
+/// Verdict
+///
+/// Measured score of a solution compared to its claim.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Verdict {
+    /// Exactly as claimed
+    Verified,
+    /// Better than claimed in size or speed, worse in neither
+    Mismatched,
+    /// Worse than claimed in size or speed
+    Regressed,
+    /// Doesn't compile, validate or solve the level
+    Failed,
+}
+
+impl Verdict {
+    /// As Str
+    ///
+    /// Returns the lowercase name, as serialized.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Verdict::Verified => "verified",
+            Verdict::Mismatched => "mismatched",
+            Verdict::Regressed => "regressed",
+            Verdict::Failed => "failed",
+        }
+    }
+}
+
+/// Verification
+///
+/// Result of verifying a [RepositorySolution].
+#[derive(Debug, PartialEq)]
+pub struct Verification {
+    pub path: PathBuf,
+    pub solution: SolutionDefinition,
+    pub result: Result<MeasuredScore, SolutionError>,
+}
+
+impl Verification {
+    /// Verdict
+    ///
+    /// Compare the measured score to the claim. Speed is compared on average.
+    pub fn verdict(&self) -> Verdict {
+        let Ok(measured) = &self.result else {
+            return Verdict::Failed;
+        };
+        if measured.claim.size == Some(false) || measured.claim.speed == Some(false) {
+            return Verdict::Regressed;
+        }
+
+        let claimed = self.solution.claimed;
+        let size = claimed.size.is_some_and(|size| measured.size < size);
+        let speed = claimed
+            .speed
+            .is_some_and(|speed| measured.speed_avg < speed as f64);
+        if size || speed {
+            Verdict::Mismatched
+        } else {
+            Verdict::Verified
+        }
+    }
+}
+
+/// Scan
+///
+/// Find & read every solution under `root`, ordered by path.
+pub fn scan(root: &Path) -> io::Result<Vec<RepositorySolution>> {
+    let mut solutions = vec![];
+    let mut directories = vec![root.to_path_buf()];
+    while let Some(directory) = directories.pop() {
+        for entry in fs::read_dir(&directory)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                directories.push(path);
+            } else if let Some(solution) = parse_path(&path) {
+                let source = fs::read_to_string(&path)?;
+                solutions.push(RepositorySolution {
+                    path,
+                    solution: SolutionDefinition { source, ..solution },
+                });
+            }
+        }
+    }
+    solutions.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(solutions)
+}
+
+/// Verify Repository
+///
+/// [scan] `root` & verify every solution against its official level, see
+/// [SolutionDefinition::verify]. Solutions are verified on one worker per available core,
+/// results are ordered by path.
+pub fn verify_repository(root: &Path) -> io::Result<Vec<Verification>> {
+    let workers = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    Ok(verify_solutions(scan(root)?, workers))
+}
+
+/// Verify Solutions
+///
+/// Verify `solutions` on a pool of `workers` threads (at least one), keeping their order.
+pub fn verify_solutions(solutions: Vec<RepositorySolution>, workers: usize) -> Vec<Verification> {
+    let workers = workers.clamp(1, solutions.len().max(1));
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    let mut results: Vec<(usize, Verification)> = thread::scope(|scope| {
+        for _ in 0..workers {
+            let sender = sender.clone();
+            let (next, solutions) = (&next, &solutions);
+            scope.spawn(move || loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(RepositorySolution { path, solution }) = solutions.get(index) else {
+                    break;
+                };
+
+                let result = solution
+                    .level_problem()
+                    .and_then(|problem| solution.verify(&problem));
+                let verification = Verification {
+                    path: path.clone(),
+                    solution: solution.clone(),
+                    result,
+                };
+                if sender.send((index, verification)).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        receiver.into_iter().collect()
+    });
+    results.sort_by_key(|(index, _)| *index);
+    results
+        .into_iter()
+        .map(|(_, verification)| verification)
+        .collect()
+}
+
+/// Solution without source described by a path in the repository layout, see the
+/// [module docs](self)
+fn parse_path(path: &Path) -> Option<SolutionDefinition> {
+    if path.extension()? != "asm" {
+        return None;
+    }
+    let directory = path.parent()?.file_name()?.to_str()?;
+    let (year, _) = directory.split_once('-')?;
+    let year: u32 = year.parse().ok()?;
+
+    let (score, author) = path.file_stem()?.to_str()?.split_once('-')?;
+    let (size, speed) = score.split_once('.')?;
+    Some(SolutionDefinition {
+        level: format!("year_{year}"),
+        source: String::new(),
+        author: Some(author.to_string()),
+        claimed: ScoreTarget {
+            size: Some(size.parse().ok()?),
+            speed: Some(speed.parse().ok()?),
+        },
+        measured: None,
+        created_at: None,
+        verified_at: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAIL_ROOM: &str = "\
+-- HUMAN RESOURCE MACHINE PROGRAM --
+
+    INBOX
+    OUTBOX
+    INBOX
+    OUTBOX
+    INBOX
+    OUTBOX
+";
+
+    #[test]
+    fn parse_path_test() {
+        let solution = parse_path(Path::new("solutions/01-Mail-Room/3.6-someone-else.asm"));
+        let solution = solution.unwrap();
+        assert_eq!("year_1", solution.level);
+        assert_eq!(Some("someone-else"), solution.author.as_deref());
+        assert_eq!(
+            ScoreTarget {
+                size: Some(3),
+                speed: Some(6)
+            },
+            solution.claimed
+        );
+
+        for ignored in [
+            "solutions/01-Mail-Room/README.md",
+            "solutions/Mail-Room/3.6-a.asm",
+            "solutions/01-Mail-Room/fast-a.asm",
+            "solutions/01-Mail-Room/3.6.asm",
+        ] {
+            assert_eq!(None, parse_path(Path::new(ignored)), "{ignored}");
+        }
+    }
+
+    #[test]
+    fn verify_repository_test() {
+        let root = std::env::temp_dir().join(format!("hrm-repository-{}", std::process::id()));
+        let level = root.join("solutions").join("01-Mail-Room");
+        fs::create_dir_all(&level).unwrap();
+        fs::write(root.join("README.md"), "# Solutions").unwrap();
+        for (name, source) in [
+            ("6.6-exact.asm", MAIL_ROOM),
+            ("7.8-modest.asm", MAIL_ROOM),
+            ("5.6-bold.asm", MAIL_ROOM),
+            ("1.1-broken.asm", "INBOX\n"),
+        ] {
+            fs::write(level.join(name), source).unwrap();
+        }
+        let unknown = root.join("solutions").join("05-Coffee-Time");
+        fs::create_dir_all(&unknown).unwrap();
+        fs::write(unknown.join("1.1-a.asm"), "").unwrap();
+
+        let results = verify_repository(&root).unwrap();
+        let verdicts: Vec<(&str, Verdict)> = results
+            .iter()
+            .map(|result| {
+                let name = result.path.file_name().unwrap().to_str().unwrap();
+                (name, result.verdict())
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                ("1.1-broken.asm", Verdict::Failed),
+                ("5.6-bold.asm", Verdict::Regressed),
+                ("6.6-exact.asm", Verdict::Verified),
+                ("7.8-modest.asm", Verdict::Mismatched),
+                ("1.1-a.asm", Verdict::Failed),
+            ],
+            verdicts
+        );
+        assert_eq!("mismatched", verdicts[3].1.as_str());
+        assert_eq!(
+            Err(SolutionError::UnknownLevel(String::from("year_5"))),
+            results[4].result
+        );
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,562 @@
+//! Session
+//!
+//! Compile, validate, run & step through programs from source, reporting every issue as a
+//! [Diagnostic]. Shared by the `wasm` feature & the Python bindings in `python/`.
+
+use serde::Serialize;
+
+use crate::{
+    code::{
+        game_state::GameState,
+        program::{FailureReport, Memory, Program, RunError, RunLimits, Score},
+    },
+    compiler::compile::Compiler,
+    game::{problem::Problem, value::Value},
+    model::{
+        diagnostic::{Diagnostic, MessageStyle},
+        program_definition::ProgramDefinition,
+        trace::{MemoryChange, Trace, TraceStep, TRACE_VERSION},
+    },
+};
+
+/// Result of [compile_source]
+#[derive(Debug, Serialize)]
+pub struct CompileResult {
+    pub program: Option<ProgramDefinition>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Result of [run_source]
+#[derive(Debug, Serialize)]
+pub struct RunResult {
+    pub score: Option<Score>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Compile Source
+///
+/// Compile `source` with the vanilla or the extended instruction set.
+pub fn compile_source(source: &str, extended: bool) -> CompileResult {
+    let compiler = if extended {
+        Compiler::extended()
+    } else {
+        Compiler::default()
+    };
+
+    match compiler.compile(source) {
+        Ok(program) => CompileResult {
+            program: Some(ProgramDefinition::from(&program)),
+            diagnostics: Diagnostic::from_analysis(&compiler, source, &program),
+        },
+        Err(err) => CompileResult {
+            program: None,
+            diagnostics: vec![Diagnostic::from_parse_error(&compiler, source, &err)],
+        },
+    }
+}
+
+/// Validate Source
+///
+/// Returns the compile, validation & analysis [Diagnostic]s of `source` for `problem`.
+pub fn validate_source(problem: &Problem, source: &str) -> Vec<Diagnostic> {
+    let compiler = Compiler::new(problem.get_instruction_set());
+    match compiler.compile(source) {
+        Ok(program) => {
+            let mut diagnostics = Diagnostic::from_validation(&compiler, source, &program, problem);
+            diagnostics.extend(Diagnostic::from_analysis(&compiler, source, &program));
+            diagnostics
+        }
+        Err(err) => vec![Diagnostic::from_parse_error(&compiler, source, &err)],
+    }
+}
+
+/// Run Source
+///
+/// Compile, validate & run `source` for every IO of `problem`.
+pub fn run_source(problem: &Problem, source: &str) -> RunResult {
+    run_source_with_limits(problem, source, RunLimits::default())
+}
+
+/// Run Source With Limits
+///
+/// See [run_source], IOs exceeding the [RunLimits] fail.
+pub fn run_source_with_limits(problem: &Problem, source: &str, limits: RunLimits) -> RunResult {
+    let program = match check(problem, source) {
+        Ok(program) => program,
+        Err(diagnostics) => {
+            return RunResult {
+                score: None,
+                diagnostics,
+            }
+        }
+    };
+
+    match program.run_with_limits(problem, limits) {
+        Ok(score) => RunResult {
+            score: Some(score),
+            diagnostics: vec![],
+        },
+        Err(report) => RunResult {
+            score: None,
+            diagnostics: vec![Diagnostic::from_failure(
+                source,
+                &program,
+                &report,
+                MessageStyle::Game,
+            )],
+        },
+    }
+}
+
+/// Check
+///
+/// Compile & validate `source` for `problem`, returns the errors if either fails.
+pub fn check(problem: &Problem, source: &str) -> Result<Program, Vec<Diagnostic>> {
+    let compiler = Compiler::new(problem.get_instruction_set());
+    let program = compiler
+        .compile(source)
+        .map_err(|err| vec![Diagnostic::from_parse_error(&compiler, source, &err)])?;
+
+    let diagnostics = Diagnostic::from_validation(&compiler, source, &program, problem);
+    if diagnostics.is_empty() {
+        Ok(program)
+    } else {
+        Err(diagnostics)
+    }
+}
+
+/// Step State
+///
+/// Snapshot of a [Session] after a step.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StepState {
+    /// Index of the next command
+    pub i_command: usize,
+    /// Source line (0-based) of the next command, if any
+    pub line: Option<usize>,
+    pub acc: Option<Value>,
+    pub memory: Memory,
+    pub inputs_consumed: usize,
+    pub produced: Vec<Value>,
+    pub speed: u64,
+    pub steps: u64,
+    /// The run ended, successfully unless there's an error
+    pub finished: bool,
+    pub error: Option<Diagnostic>,
+}
+
+/// Session
+///
+/// Step-by-step run of a program on a single IO, for debuggers. Mirrors [Program::run], i.e.
+/// debug commands are skipped & the run fails if outputs are missing once it ends.
+pub struct Session {
+    program: Program,
+    problem: Problem,
+    source: String,
+    i_io: usize,
+    input: Vec<Value>,
+    output: Vec<Value>,
+    state: SavedState,
+    steps: u64,
+    /// Command counted by the last step
+    executed: Option<usize>,
+    finished: bool,
+    error: Option<Diagnostic>,
+}
+
+impl Session {
+    /// Create
+    ///
+    /// Start a session on IO `io` of `problem`. Fails if the program doesn't compile or validate,
+    /// or the IO doesn't exist.
+    pub fn create(problem: Problem, source: &str, io: usize) -> Result<Self, String> {
+        let program = check(&problem, source).map_err(|diagnostics| {
+            let messages: Vec<String> = diagnostics.into_iter().map(|d| d.message).collect();
+            messages.join("\n")
+        })?;
+        let problem_io = problem
+            .get_ios()
+            .get(io)
+            .ok_or_else(|| format!("IO {io} does not exist"))?;
+        let (input, output) = (problem_io.input.clone(), problem_io.output.clone());
+
+        Ok(Self {
+            state: SavedState::new(&problem, &input, &output),
+            program,
+            problem,
+            source: source.to_string(),
+            i_io: io,
+            input,
+            output,
+            steps: 0,
+            executed: None,
+            finished: false,
+            error: None,
+        })
+    }
+
+    /// Get Program
+    ///
+    /// Get the compiled program.
+    pub fn get_program(&self) -> &Program {
+        &self.program
+    }
+
+    /// Get Problem
+    ///
+    /// Get the problem the program runs for.
+    pub fn get_problem(&self) -> &Problem {
+        &self.problem
+    }
+
+    /// Get Input
+    ///
+    /// Get the inputs of the IO, see [StepState::inputs_consumed].
+    pub fn get_input(&self) -> &[Value] {
+        &self.input
+    }
+
+    /// Get Output
+    ///
+    /// Get the expected outputs of the IO.
+    pub fn get_output(&self) -> &[Value] {
+        &self.output
+    }
+
+    /// Step
+    ///
+    /// Execute the next command, skipping debug commands. Returns `false` once the run ended.
+    pub fn step(&mut self) -> bool {
+        if self.finished {
+            return false;
+        }
+
+        let mut game_state = self.state.restore(&self.input, &self.output);
+        let result = Self::step_state(&self.program, &mut game_state);
+        self.executed = result.as_ref().ok().copied().flatten();
+        if self.executed.is_some() {
+            self.steps += 1;
+        }
+
+        let ended = game_state.halted || game_state.i_command >= self.program.get_commands().len();
+        let result = match result {
+            Ok(_) if ended => self.program.check_finished(&game_state),
+            result => result.map(|_| ()),
+        };
+
+        if let Err(error) = result {
+            let report = FailureReport {
+                error,
+                i_io: self.i_io,
+                input: self.input.clone(),
+                i_command: game_state.i_command,
+                acc: game_state.acc,
+                memory: game_state.memory.clone(),
+                inputs_consumed: game_state.i_input,
+                outputs_produced: game_state.produced.len(),
+            };
+            self.error = Some(Diagnostic::from_failure(
+                &self.source,
+                &self.program,
+                &report,
+                MessageStyle::Game,
+            ));
+            self.finished = true;
+        } else {
+            self.finished = ended;
+        }
+        self.state.save(game_state);
+        !self.finished
+    }
+
+    /// Execute a single command, returns its index if it counts as a step
+    pub(crate) fn step_state(
+        program: &Program,
+        game_state: &mut GameState,
+    ) -> Result<Option<usize>, RunError> {
+        let commands = program.get_commands();
+        while let Some(command) = commands.get(game_state.i_command) {
+            if command.debug(game_state).is_some() {
+                game_state.i_command += 1;
+                continue;
+            }
+
+            let i_command = game_state.i_command;
+            command.execute(program, game_state)?;
+            if game_state.halted {
+                return Ok(None);
+            }
+            game_state.speed = game_state.speed.saturating_add(command.cost() as u64);
+            game_state.i_command = command.next(program, game_state)?;
+            return Ok(Some(i_command));
+        }
+
+        Ok(None)
+    }
+
+    /// Run
+    ///
+    /// Step until the run ends or `max_steps` more steps were taken.
+    pub fn run(&mut self, max_steps: u32) {
+        for _ in 0..max_steps {
+            if !self.step() {
+                break;
+            }
+        }
+    }
+
+    /// Trace
+    ///
+    /// Like [Session::run], but returns the [StepState] after every step.
+    pub fn trace(&mut self, max_steps: u32) -> Vec<StepState> {
+        let mut trace = vec![];
+        for _ in 0..max_steps {
+            if self.finished {
+                break;
+            }
+            self.step();
+            trace.push(self.state());
+        }
+        trace
+    }
+
+    /// Export Trace
+    ///
+    /// Restart & run for at most `max_steps` steps, recording a [Trace] to replay the run.
+    pub fn export_trace(&mut self, max_steps: u32) -> Trace {
+        self.reset();
+        let memory = self.state.memory.clone();
+        let mut steps = vec![];
+        let mut before = memory.clone();
+        for _ in 0..max_steps {
+            if self.finished {
+                break;
+            }
+            let produced = self.state.produced.len();
+            self.step();
+
+            let Some(i_command) = self.executed else {
+                continue;
+            };
+            let state = &self.state;
+            steps.push(TraceStep {
+                step: self.steps - 1,
+                i_command,
+                command: self.program.get_commands()[i_command].to_source(),
+                line: self.program.get_line(i_command),
+                acc: state.acc,
+                memory: MemoryChange::diff(&before, &state.memory),
+                i_input: state.i_input,
+                i_output: state.produced.len(),
+                output: state.produced.get(produced).copied(),
+                speed: state.speed,
+            });
+            before.clone_from(&state.memory);
+        }
+
+        Trace {
+            version: TRACE_VERSION,
+            io: self.i_io,
+            input: self.input.clone(),
+            memory,
+            steps,
+            finished: self.finished,
+            error: self.error.clone(),
+        }
+    }
+
+    /// Reset
+    ///
+    /// Restart from the first command.
+    pub fn reset(&mut self) {
+        self.state = SavedState::new(&self.problem, &self.input, &self.output);
+        self.steps = 0;
+        self.executed = None;
+        self.finished = false;
+        self.error = None;
+    }
+
+    /// State
+    ///
+    /// Returns the current [StepState].
+    pub fn state(&self) -> StepState {
+        let state = &self.state;
+        StepState {
+            i_command: state.i_command,
+            line: self.program.get_line(state.i_command),
+            acc: state.acc,
+            memory: state.memory.clone(),
+            inputs_consumed: state.i_input,
+            produced: state.produced.clone(),
+            speed: state.speed,
+            steps: self.steps,
+            finished: self.finished,
+            error: self.error.clone(),
+        }
+    }
+}
+
+/// Game state between steps, without the borrowed IO
+pub(crate) struct SavedState {
+    memory: Memory,
+    acc: Option<Value>,
+    i_input: usize,
+    i_output: usize,
+    i_command: usize,
+    speed: u64,
+    halted: bool,
+    stack: Vec<Value>,
+    stack_max: usize,
+    call_stack: Vec<usize>,
+    produced: Vec<Value>,
+}
+
+impl SavedState {
+    pub(crate) fn new(problem: &Problem, input: &Vec<Value>, output: &Vec<Value>) -> Self {
+        let mut saved = Self {
+            memory: vec![],
+            acc: None,
+            i_input: 0,
+            i_output: 0,
+            i_command: 0,
+            speed: 0,
+            halted: false,
+            stack: vec![],
+            stack_max: 0,
+            call_stack: vec![],
+            produced: vec![],
+        };
+        saved.save(GameState::new(input, output, problem.get_memory().clone()));
+        saved
+    }
+
+    pub(crate) fn restore<'a>(
+        &mut self,
+        input: &'a Vec<Value>,
+        output: &'a Vec<Value>,
+    ) -> GameState<'a> {
+        let mut game_state = GameState::new(input, output, core::mem::take(&mut self.memory));
+        game_state.acc = self.acc;
+        game_state.i_input = self.i_input;
+        game_state.i_output = self.i_output;
+        game_state.i_command = self.i_command;
+        game_state.speed = self.speed;
+        game_state.halted = self.halted;
+        game_state.stack = core::mem::take(&mut self.stack);
+        game_state.stack_max = self.stack_max;
+        game_state.call_stack = core::mem::take(&mut self.call_stack);
+        game_state.produced = core::mem::take(&mut self.produced);
+        game_state
+    }
+
+    pub(crate) fn save(&mut self, game_state: GameState) {
+        self.memory = game_state.memory;
+        self.acc = game_state.acc;
+        self.i_input = game_state.i_input;
+        self.i_output = game_state.i_output;
+        self.i_command = game_state.i_command;
+        self.speed = game_state.speed;
+        self.halted = game_state.halted;
+        self.stack = game_state.stack;
+        self.stack_max = game_state.stack_max;
+        self.call_stack = game_state.call_stack;
+        self.produced = game_state.produced;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::problem::{ProblemBuilder, ProblemIO};
+
+    fn problem() -> Problem {
+        ProblemBuilder::new()
+            .add_io(ProblemIO {
+                input: vec![Value::Int(1), Value::Int(2)],
+                output: vec![Value::Int(1), Value::Int(2)],
+            })
+            .memory_dim(1)
+            .enable_all_commands()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn compile_source_test() {
+        let result = compile_source("a:\nINBOX\nOUTBOX\nJUMP a\n", false);
+        assert_eq!(3, result.program.unwrap().commands.len());
+        assert!(result.diagnostics.is_empty());
+
+        let result = compile_source("MUL 0\n", false);
+        assert!(result.program.is_none());
+        assert_eq!("E0101", result.diagnostics[0].code);
+        assert!(compile_source("MUL 0\n", true).program.is_some());
+    }
+
+    #[test]
+    fn validate_run_source() {
+        let problem = problem();
+        assert_eq!(
+            "E0202",
+            validate_source(&problem, "INBOX\nCOPYTO 3\n")[0].code
+        );
+
+        let result = run_source(&problem, "a:\nINBOX\nOUTBOX\nJUMP a\n");
+        assert_eq!(6, result.score.unwrap().speed_max);
+
+        let result = run_source(&problem, "INBOX\nOUTBOX\n");
+        assert!(result.score.is_none());
+        assert_eq!("E0305", result.diagnostics[0].code);
+    }
+
+    #[test]
+    fn session_steps() {
+        let source = "a:\nINBOX\nDEBUG \"x\" 0\nCOPYTO 0\nOUTBOX\nJUMP a\n";
+        let mut session = Session::create(problem(), source, 0).unwrap();
+
+        assert!(session.step());
+        let state = session.state();
+        assert_eq!(Some(Value::Int(1)), state.acc);
+        assert_eq!(1, state.i_command);
+
+        assert!(session.step()); // Skips DEBUG
+        assert_eq!(vec![Some(Value::Int(1))], session.state().memory);
+        assert_eq!(3, session.state().i_command);
+
+        session.run(100);
+        let state = session.state();
+        assert!(state.finished);
+        assert_eq!(None, state.error);
+        assert_eq!(vec![Value::Int(1), Value::Int(2)], state.produced);
+        assert_eq!(
+            Some(state.speed),
+            run_source(&problem(), source)
+                .score
+                .map(|score| score.speed_max)
+        );
+        assert!(!session.step());
+
+        session.reset();
+        assert_eq!(0, session.state().speed);
+        assert!(!session.state().finished);
+    }
+
+    #[test]
+    fn session_fails() {
+        let mut session = Session::create(problem(), "INBOX\nOUTBOX\n", 0).unwrap();
+        session.run(10);
+        let state = session.state();
+        assert!(state.finished);
+        assert_eq!("E0305", state.error.as_ref().unwrap().code);
+        assert_eq!(2, state.steps);
+
+        session.reset();
+        let trace = session.trace(10);
+        assert_eq!(2, trace.len());
+        assert_eq!(Some(Value::Int(1)), trace[0].acc);
+        assert_eq!(state, trace[1]);
+
+        assert!(Session::create(problem(), "INBOX\n", 1).is_err());
+        assert!(Session::create(problem(), "COPYTO 5\n", 0).is_err());
+    }
+}
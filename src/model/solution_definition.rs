@@ -0,0 +1,251 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    code::{
+        batch::evaluate,
+        program::{ChallengeResult, ProgramError, ScoreTarget},
+    },
+    compiler::compile::{Compiler, ParseError},
+    game::problem::Problem,
+};
+
+/// Solution Definition
+///
+/// Source of a solution to a level with its metadata, the container of community solution
+/// archives. Timestamps are Unix seconds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SolutionDefinition {
+    /// Id of the solved level, e.g. `year_2`
+    pub level: String,
+    pub source: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// Size & speed claimed by the author
+    #[serde(default)]
+    pub claimed: ScoreTarget,
+    /// Score measured by [SolutionDefinition::annotate]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub measured: Option<MeasuredScore>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<u64>,
+    /// Time of the last [SolutionDefinition::annotate]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verified_at: Option<u64>,
+}
+
+/// Measured Score
+///
+/// Size & speed of a verified solution, see [SolutionDefinition::verify].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MeasuredScore {
+    pub size: usize,
+    pub speed_min: u64,
+    pub speed_max: u64,
+    pub speed_avg: f64,
+    /// Whether the solution is at least as good as claimed, [None] for claims not made
+    pub claim: ChallengeResult,
+}
+
+/// Solution Error
+///
+/// Error codes `E08xx`, others are of the underlying error, see [SolutionError::code].
+#[derive(Debug, PartialEq, thiserror::Error)]
+#[non_exhaustive]
+pub enum SolutionError {
+    #[error("Level `{0}` does not exist")]
+    UnknownLevel(String),
+    #[error(transparent)]
+    Parse(ParseError),
+    #[error(transparent)]
+    Program(ProgramError),
+}
+
+impl SolutionError {
+    /// Code
+    ///
+    /// Returns the stable error code, e.g. `E0801`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            SolutionError::UnknownLevel(_) => "E0801",
+            SolutionError::Parse(err) => err.code(),
+            SolutionError::Program(err) => err.code(),
+        }
+    }
+}
+
+impl SolutionDefinition {
+    /// New
+    ///
+    /// Solution of `level` without metadata, created now.
+    pub fn new(level: String, source: String) -> Self {
+        Self {
+            level,
+            source,
+            author: None,
+            claimed: ScoreTarget::default(),
+            measured: None,
+            created_at: Some(now()),
+            verified_at: None,
+        }
+    }
+
+    /// Verify
+    ///
+    /// Compile, validate & run the source for `problem`, the solved level, returning the measured
+    /// score. Compiled with the problem's instruction set.
+    pub fn verify(&self, problem: &Problem) -> Result<MeasuredScore, SolutionError> {
+        let program = Compiler::new(problem.get_instruction_set())
+            .compile(&self.source)
+            .map_err(SolutionError::Parse)?;
+        let score = evaluate(&program, problem).map_err(SolutionError::Program)?;
+
+        Ok(MeasuredScore {
+            size: score.size,
+            speed_min: score.speed_min,
+            speed_max: score.speed_max,
+            speed_avg: score.speed_avg,
+            claim: score.meets(&self.claimed),
+        })
+    }
+
+    /// Annotate
+    ///
+    /// [SolutionDefinition::verify] & record the measured score with the time of verification.
+    /// A failed verification clears a previously measured score.
+    pub fn annotate(&mut self, problem: &Problem) -> Result<&MeasuredScore, SolutionError> {
+        self.verified_at = Some(now());
+        self.measured = None;
+        let measured = self.verify(problem)?;
+        Ok(self.measured.insert(measured))
+    }
+
+    /// Level Problem
+    ///
+    /// Returns the official level solved by this solution, see [crate::levels::get].
+    #[cfg(feature = "levels")]
+    pub fn level_problem(&self) -> Result<Problem, SolutionError> {
+        let definition = crate::levels::get(&self.level)
+            .ok_or_else(|| SolutionError::UnknownLevel(self.level.clone()))?;
+        Ok(Problem::try_from(definition).expect("official levels are valid"))
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        code::program::ValidationError,
+        game::{
+            problem::{ProblemBuilder, ProblemIO},
+            value::Value,
+        },
+    };
+
+    const BUSY_MAIL_ROOM: &str = "a:\nINBOX\nOUTBOX\nJUMP a\n";
+
+    fn problem() -> Problem {
+        ProblemBuilder::new()
+            .add_io(ProblemIO {
+                input: vec![Value::Int(1), Value::Int(2)],
+                output: vec![Value::Int(1), Value::Int(2)],
+            })
+            .enable_command(String::from("INBOX"))
+            .enable_command(String::from("OUTBOX"))
+            .enable_command(String::from("JUMP"))
+            .build()
+            .unwrap()
+    }
+
+    fn solution(source: &str) -> SolutionDefinition {
+        SolutionDefinition {
+            author: Some(String::from("Ada")),
+            claimed: ScoreTarget {
+                size: Some(3),
+                speed: Some(5),
+            },
+            ..SolutionDefinition::new(String::from("year_2"), source.to_string())
+        }
+    }
+
+    #[test]
+    fn annotate_test() {
+        let mut solution = solution(BUSY_MAIL_ROOM);
+        let problem = problem();
+        let measured = *solution.annotate(&problem).unwrap();
+
+        assert_eq!(3, measured.size);
+        assert_eq!(6.0, measured.speed_avg);
+        assert_eq!(
+            ChallengeResult {
+                size: Some(true),
+                speed: Some(false),
+            },
+            measured.claim
+        );
+        assert_eq!(Some(measured), solution.measured);
+        assert!(solution.verified_at >= solution.created_at);
+
+        solution.source = String::from("INBOX\nCOPYTO 0\n");
+        let err = solution.annotate(&problem).unwrap_err();
+        assert_eq!(
+            SolutionError::Program(ProgramError::Validation(
+                ValidationError::CommandNotAvailable(String::from("COPYTO"))
+            )),
+            err
+        );
+        assert_eq!(None, solution.measured);
+    }
+
+    #[test]
+    fn verify_fails() {
+        let solution = solution("JUMP b\n");
+        assert_eq!("E0102", solution.verify(&problem()).unwrap_err().code());
+    }
+
+    #[cfg(feature = "levels")]
+    #[test]
+    fn level_problem_test() {
+        let solution = solution(BUSY_MAIL_ROOM);
+        let problem = solution.level_problem().unwrap();
+        assert_eq!(
+            Ok(3),
+            solution.verify(&problem).map(|measured| measured.size)
+        );
+
+        let solution = SolutionDefinition::new(String::from("year_0"), String::new());
+        assert_eq!(
+            SolutionError::UnknownLevel(String::from("year_0")),
+            solution.level_problem().unwrap_err()
+        );
+    }
+
+    #[test]
+    fn serde_solution() {
+        let json = r#"{
+            "level": "year_2",
+            "source": "a:\nINBOX\nOUTBOX\nJUMP a\n",
+            "claimed": {"size": 3, "speed": 6}
+        }"#;
+        let mut solution: SolutionDefinition = serde_json::from_str(json).unwrap();
+        assert_eq!(None, solution.author);
+        assert_eq!(None, solution.created_at);
+
+        solution.annotate(&problem()).unwrap();
+        let serialized = serde_json::to_value(&solution).unwrap();
+        assert_eq!(3, serialized["measured"]["size"]);
+        assert_eq!(true, serialized["measured"]["claim"]["speed"]);
+        assert!(serialized.get("author").is_none());
+        assert_eq!(
+            solution,
+            serde_json::from_value::<SolutionDefinition>(serialized).unwrap()
+        );
+    }
+}
@@ -0,0 +1,279 @@
+//! Stream
+//!
+//! Run programs as filters, e.g. in shell pipelines: `INBOX` reads the next value only once it's
+//! needed & `OUTBOX` writes values as soon as they're produced. There are no expected outputs.
+
+use std::{
+    collections::VecDeque,
+    io::{self, BufRead, Write},
+};
+
+use crate::{
+    code::{
+        bytecode::Op,
+        game_state::GameState,
+        program::{check_bounds, FailureReport, Program, RunError},
+    },
+    game::{problem::Problem, value::Value},
+    model::session::{SavedState, Session},
+};
+
+/// Stream Error
+///
+/// Error codes `E09xx`, run errors keep their own, see [StreamError::code].
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum StreamError {
+    /// Input token that is neither a number nor a single letter
+    #[error("Input `{0}` is not a number or a letter")]
+    InvalidValue(String),
+    #[error(transparent)]
+    Io(io::Error),
+    /// Run failed, [FailureReport::input] holds the inputs read so far
+    #[error(transparent)]
+    Run(FailureReport),
+}
+
+impl StreamError {
+    /// Code
+    ///
+    /// Returns the stable error code, e.g. `E0901`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            StreamError::InvalidValue(_) => "E0901",
+            StreamError::Io(_) => "E0902",
+            StreamError::Run(report) => report.error.code(),
+        }
+    }
+}
+
+/// Stream Stats
+///
+/// Summary of a finished [run_stream].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamStats {
+    pub inputs_consumed: usize,
+    pub outputs_produced: usize,
+    pub speed: u64,
+    pub steps: u64,
+}
+
+/// Run Stream
+///
+/// Run `program` with the floor & value bounds of `problem`, reading whitespace separated values
+/// from `reader` & writing every output on its own line to `writer`. Reads a line at a time, so
+/// interactive input works. The run ends once `INBOX` finds no more input, like in the game, or
+/// fails with [RunError::StepLimit] after `max_steps` steps. Debug commands are skipped.
+pub fn run_stream(
+    program: &Program,
+    problem: &Problem,
+    max_steps: Option<u32>,
+    reader: impl BufRead,
+    mut writer: impl Write,
+) -> Result<StreamStats, StreamError> {
+    let mut tokens = Tokens {
+        reader,
+        pending: VecDeque::new(),
+    };
+    let (mut input, unchecked) = (vec![], vec![]);
+    let mut state = SavedState::new(problem, &input, &unchecked);
+    let mut stats = StreamStats {
+        inputs_consumed: 0,
+        outputs_produced: 0,
+        speed: 0,
+        steps: 0,
+    };
+
+    loop {
+        let mut game_state = state.restore(&input, &unchecked);
+        game_state.bounds = problem.get_value_bounds();
+        game_state.holes = problem.get_holes().to_vec();
+        game_state.unbounded_memory = problem.has_unbounded_memory();
+        game_state.check_output = false;
+
+        let pause = loop {
+            let result = step(program, &mut game_state, max_steps, &mut stats);
+            stats.inputs_consumed = game_state.i_input;
+            stats.speed = game_state.speed;
+            // Written right away, so interactive runs show outputs before asking for more input
+            if !game_state.produced.is_empty() {
+                for value in game_state.produced.drain(..) {
+                    writeln!(writer, "{value}").map_err(StreamError::Io)?;
+                    stats.outputs_produced += 1;
+                }
+                writer.flush().map_err(StreamError::Io)?;
+            }
+
+            match result {
+                Ok(None) => {}
+                Ok(Some(pause)) => break pause,
+                Err(error) => {
+                    return Err(StreamError::Run(FailureReport {
+                        error,
+                        i_io: 0,
+                        input: input.clone(),
+                        i_command: game_state.i_command,
+                        acc: game_state.acc,
+                        memory: game_state.memory,
+                        inputs_consumed: game_state.i_input,
+                        outputs_produced: stats.outputs_produced,
+                    }))
+                }
+            }
+        };
+        if pause == Pause::End {
+            return Ok(stats);
+        }
+
+        // Halted on `INBOX` only because the input hasn't been read yet
+        game_state.halted = false;
+        state.save(game_state);
+        match tokens.next()? {
+            Some(token) => input.push(parse_value(&token, problem)?),
+            None => return Ok(stats),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Pause {
+    /// `INBOX` needs more input
+    Input,
+    End,
+}
+
+/// Execute a single command, returns [Some] once the run pauses
+fn step(
+    program: &Program,
+    game_state: &mut GameState,
+    max_steps: Option<u32>,
+    stats: &mut StreamStats,
+) -> Result<Option<Pause>, RunError> {
+    if game_state.i_command >= program.get_commands().len() {
+        return Ok(Some(Pause::End));
+    }
+    if let Some(max_steps) = max_steps {
+        if stats.steps == max_steps as u64 {
+            return Err(RunError::StepLimit(max_steps));
+        }
+    }
+
+    let i_command = game_state.i_command;
+    if Session::step_state(program, game_state)?.is_some() {
+        stats.steps += 1;
+    }
+    if !game_state.halted {
+        return Ok(None);
+    }
+
+    let inbox = program.get_bytecode().get_op(i_command) == &Op::Inbox;
+    if inbox && game_state.i_input == game_state.input.len() {
+        Ok(Some(Pause::Input))
+    } else {
+        Ok(Some(Pause::End))
+    }
+}
+
+/// Whitespace separated tokens of a reader, read a line at a time
+struct Tokens<R> {
+    reader: R,
+    pending: VecDeque<String>,
+}
+
+impl<R: BufRead> Tokens<R> {
+    fn next(&mut self) -> Result<Option<String>, StreamError> {
+        while self.pending.is_empty() {
+            let mut line = String::new();
+            if self.reader.read_line(&mut line).map_err(StreamError::Io)? == 0 {
+                return Ok(None);
+            }
+            self.pending
+                .extend(line.split_whitespace().map(str::to_string));
+        }
+        Ok(self.pending.pop_front())
+    }
+}
+
+/// Parse a number or a single letter, within the problem's value bounds
+fn parse_value(token: &str, problem: &Problem) -> Result<Value, StreamError> {
+    let invalid = || StreamError::InvalidValue(token.to_string());
+    let value = match token.parse::<i32>() {
+        Ok(value) => Value::Int(value),
+        Err(_) => {
+            let mut chars = token.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Value::Char(c),
+                _ => return Err(invalid()),
+            }
+        }
+    };
+    check_bounds(value, problem.get_value_bounds()).map_err(|_| invalid())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{compiler::compile::Compiler, game::problem::ProblemBuilder};
+
+    fn problem() -> Problem {
+        ProblemBuilder::new()
+            .memory_dim(1)
+            .enable_all_commands()
+            .build()
+            .unwrap()
+    }
+
+    fn stream(source: &str, input: &str) -> (Result<StreamStats, StreamError>, String) {
+        let program = Compiler::extended().compile(source).unwrap();
+        let mut out = vec![];
+        let result = run_stream(&program, &problem(), Some(100), input.as_bytes(), &mut out);
+        (result, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn run_stream_test() {
+        let (result, out) = stream(
+            "a:\nINBOX\nDEBUG \"x\" 0\nADD 0\nOUTBOX\nCOPYTO 0\nJUMP a\n",
+            "",
+        );
+        assert_eq!(0, result.unwrap().steps);
+        assert_eq!("", out);
+
+        let (result, out) = stream(
+            "INBOX\nCOPYTO 0\na:\nINBOX\nADD 0\nOUTBOX\nJUMP a\n",
+            "1 2\n\n 3\tA\n",
+        );
+        assert_eq!(Err("E0311"), result.as_ref().map_err(StreamError::code));
+        assert_eq!("3\n4\n", out);
+        let Err(StreamError::Run(report)) = result else {
+            unreachable!()
+        };
+        assert_eq!(4, report.inputs_consumed);
+        assert_eq!(2, report.outputs_produced);
+
+        let (result, out) = stream("a:\nINBOX\nOUTBOX\nJUMP a\n", "B -7\n");
+        assert_eq!(
+            StreamStats {
+                inputs_consumed: 2,
+                outputs_produced: 2,
+                speed: 6,
+                steps: 6,
+            },
+            result.unwrap()
+        );
+        assert_eq!("B\n-7\n", out);
+    }
+
+    #[test]
+    fn run_stream_fails() {
+        let (result, out) = stream("a:\nINBOX\nOUTBOX\nJUMP a\n", "1 ab 2");
+        assert_eq!("E0901", result.unwrap_err().code());
+        assert_eq!("1\n", out);
+
+        let (result, _) = stream("a:\nINBOX\nOUTBOX\nJUMP a\n", "1000");
+        assert_eq!("E0901", result.unwrap_err().code());
+
+        let (result, _) = stream("a:\nJUMP a\n", "");
+        assert_eq!("E0321", result.unwrap_err().code());
+    }
+}
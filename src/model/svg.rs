@@ -0,0 +1,397 @@
+//! SVG
+//!
+//! Render the state of a run as SVG - the inbox, the worker's hands, the outbox & the floor. A
+//! single [Frame] is a static image, e.g. for write-ups, [animate] plays frames in a loop, e.g.
+//! for web frontends.
+
+use std::{collections::BTreeMap, fmt::Write, time::Duration};
+
+use crate::{
+    code::{game_state::GameState, program::Memory},
+    game::value::Value,
+    model::{session::Session, trace::Trace},
+};
+
+const MARGIN: u32 = 16;
+/// Side of a floor tile, values are drawn slightly smaller
+const TILE: u32 = 48;
+const GAP: u32 = 8;
+/// Tiles in a floor row, like in the game
+const FLOOR_COLUMNS: usize = 5;
+/// Values drawn in the inbox & outbox, the rest is counted
+const MAX_QUEUE: usize = 8;
+/// Top of the inbox, the outbox & the worker
+const TOP: u32 = 40;
+const FLOOR_TOP: u32 = 128;
+/// Height of a floor row, incl. the tile indices under the tiles
+const FLOOR_ROW: u32 = TILE + 2 * GAP;
+
+const STYLE: &str = "\
+text{font-family:monospace;text-anchor:middle;dominant-baseline:central}\
+.background{fill:#2b2b36}\
+.title{font-size:12px;fill:#d0d0d8}\
+.label{font-size:9px;fill:#9a9aa8}\
+.value rect{fill:#d9bf7a;stroke:#7a5f2a;stroke-width:2}\
+.value text{font-size:18px;font-weight:bold;fill:#2b2b36}\
+.tile{fill:#3a3a48;stroke:#51515f}\
+.hands{fill:none;stroke:#e0c05a;stroke-width:2;stroke-dasharray:4 3}";
+
+/// Frame
+///
+/// State of a run to render, see [Frame::to_svg].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    /// Inputs waiting, the next one first
+    pub inbox: Vec<Value>,
+    pub acc: Option<Value>,
+    /// Outputs produced, the first one first
+    pub outbox: Vec<Value>,
+    pub memory: Memory,
+    /// Labels written on floor tiles
+    pub tile_labels: BTreeMap<usize, String>,
+    /// Tiles that don't exist, left out of the floor
+    pub holes: Vec<usize>,
+    /// Text under the worker, e.g. the executed command
+    pub caption: Option<String>,
+}
+
+impl Frame {
+    /// From Game State
+    ///
+    /// Frame of an interpreter state, without tile labels & caption.
+    pub fn from_game_state(state: &GameState) -> Self {
+        Self {
+            inbox: state.input[state.i_input.min(state.input.len())..].to_vec(),
+            acc: state.acc,
+            outbox: state.produced.clone(),
+            memory: state.memory.clone(),
+            tile_labels: BTreeMap::new(),
+            holes: state.holes.clone(),
+            caption: None,
+        }
+    }
+
+    /// From Session
+    ///
+    /// Frame of the current state of a [Session], captioned with the step count or the error.
+    pub fn from_session(session: &Session) -> Self {
+        let state = session.state();
+        let input = session.get_input();
+        let problem = session.get_problem();
+        let caption = match &state.error {
+            Some(error) => format!("{}: {}", error.code, error.message),
+            None => format!("step {}", state.steps),
+        };
+
+        Self {
+            inbox: input[state.inputs_consumed.min(input.len())..].to_vec(),
+            acc: state.acc,
+            outbox: state.produced,
+            memory: state.memory,
+            tile_labels: problem.get_tile_labels().clone(),
+            holes: problem.get_holes().to_vec(),
+            caption: Some(caption),
+        }
+    }
+
+    /// To SVG
+    ///
+    /// Render as a standalone SVG image.
+    pub fn to_svg(&self) -> String {
+        let (width, height) = self.size();
+        let mut svg = header(width, height);
+        svg.push_str(&self.body(width, height));
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    fn size(&self) -> (u32, u32) {
+        let floor = floor_width(self.memory.len());
+        let width = 2 * MARGIN + 2 * (TILE + 2 * GAP) + floor.max(3 * TILE);
+        let queue = queue_len(self.inbox.len()).max(queue_len(self.outbox.len())) as u32;
+        let rows = self.memory.len().div_ceil(FLOOR_COLUMNS) as u32;
+        let height = (TOP + queue * (TILE + GAP)).max(FLOOR_TOP + rows * FLOOR_ROW) + MARGIN;
+        (width, height)
+    }
+
+    /// Drawing without the `<svg>` element, on a canvas of the given size
+    fn body(&self, width: u32, height: u32) -> String {
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            "<rect class=\"background\" width=\"{width}\" height=\"{height}\"/>"
+        )
+        .unwrap();
+
+        let center = width / 2;
+        let outbox_x = width - MARGIN - TILE;
+        draw_queue(&mut svg, "IN", MARGIN, &self.inbox);
+        draw_queue(&mut svg, "OUT", outbox_x, &self.outbox);
+
+        text(&mut svg, "title", center, TOP - 16, "WORKER");
+        let hands_x = center - TILE / 2;
+        writeln!(
+            svg,
+            "<rect class=\"hands\" x=\"{hands_x}\" y=\"{TOP}\" width=\"{TILE}\" \
+            height=\"{TILE}\" rx=\"6\"/>"
+        )
+        .unwrap();
+        if let Some(acc) = self.acc {
+            draw_value(&mut svg, hands_x, TOP, acc);
+        }
+        if let Some(caption) = &self.caption {
+            text(&mut svg, "title", center, TOP + TILE + 20, caption);
+        }
+
+        let floor_x = center - floor_width(self.memory.len()) / 2;
+        for (i_tile, value) in self.memory.iter().enumerate() {
+            if self.holes.binary_search(&i_tile).is_ok() {
+                continue;
+            }
+            let x = floor_x + (i_tile % FLOOR_COLUMNS) as u32 * (TILE + GAP);
+            let y = FLOOR_TOP + (i_tile / FLOOR_COLUMNS) as u32 * FLOOR_ROW;
+            writeln!(
+                svg,
+                "<rect class=\"tile\" x=\"{x}\" y=\"{y}\" width=\"{TILE}\" height=\"{TILE}\"/>"
+            )
+            .unwrap();
+            if let Some(value) = value {
+                draw_value(&mut svg, x, y, *value);
+            }
+            let index = match self.tile_labels.get(&i_tile) {
+                Some(label) => format!("{i_tile} {label}"),
+                None => i_tile.to_string(),
+            };
+            text(&mut svg, "label", x + TILE / 2, y + TILE + GAP - 1, &index);
+        }
+
+        svg
+    }
+}
+
+/// Trace Frames
+///
+/// Returns the frame before the first step & after every step of `trace`, captioned with the
+/// executed command. Frames have no tile labels.
+pub fn trace_frames(trace: &Trace) -> Vec<Frame> {
+    let mut frame = Frame {
+        inbox: trace.input.clone(),
+        acc: None,
+        outbox: vec![],
+        memory: trace.memory.clone(),
+        tile_labels: BTreeMap::new(),
+        holes: vec![],
+        caption: None,
+    };
+    let mut frames = Vec::with_capacity(trace.steps.len() + 1);
+    frames.push(frame.clone());
+
+    for step in &trace.steps {
+        frame.inbox = trace.input[step.i_input.min(trace.input.len())..].to_vec();
+        frame.acc = step.acc;
+        frame.outbox.extend(step.output);
+        for change in &step.memory {
+            if change.tile >= frame.memory.len() {
+                frame.memory.resize(change.tile + 1, None);
+            }
+            frame.memory[change.tile] = change.value;
+        }
+        frame.caption = Some(format!("{}: {}", step.step + 1, step.command));
+        frames.push(frame.clone());
+    }
+
+    frames
+}
+
+/// Animate
+///
+/// Render `frames` as a single SVG image showing each frame for `frame_duration`, in a loop.
+/// Uses SMIL animation, supported by browsers.
+pub fn animate(frames: &[Frame], frame_duration: Duration) -> String {
+    let (width, height) = frames
+        .iter()
+        .map(Frame::size)
+        .fold((0, 0), |(w, h), (width, height)| {
+            (w.max(width), h.max(height))
+        });
+    let mut svg = header(width, height);
+    let total = frame_duration.as_millis() * frames.len() as u128;
+
+    for (i_frame, frame) in frames.iter().enumerate() {
+        // Visible between its key times only
+        let key = |i: usize| i as f64 / frames.len() as f64;
+        let (values, times) = match i_frame {
+            0 => (String::from("inline;none"), format!("0;{}", key(1))),
+            _ => (
+                String::from("none;inline;none"),
+                format!("0;{};{}", key(i_frame), key(i_frame + 1)),
+            ),
+        };
+        writeln!(
+            svg,
+            "<g display=\"{}\">",
+            if i_frame == 0 { "inline" } else { "none" }
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            "<animate attributeName=\"display\" values=\"{values}\" keyTimes=\"{times}\" \
+            dur=\"{total}ms\" calcMode=\"discrete\" repeatCount=\"indefinite\"/>"
+        )
+        .unwrap();
+        svg.push_str(&frame.body(width, height));
+        svg.push_str("</g>\n");
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn header(width: u32, height: u32) -> String {
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+        viewBox=\"0 0 {width} {height}\">\n<style>{STYLE}</style>\n"
+    )
+}
+
+fn floor_width(tiles: usize) -> u32 {
+    let columns = tiles.clamp(1, FLOOR_COLUMNS) as u32;
+    columns * TILE + (columns - 1) * GAP
+}
+
+/// Rows drawn for a queue of `len` values, incl. the row counting the rest
+fn queue_len(len: usize) -> usize {
+    if len > MAX_QUEUE {
+        MAX_QUEUE + 1
+    } else {
+        len
+    }
+}
+
+fn draw_queue(svg: &mut String, title: &str, x: u32, values: &[Value]) {
+    text(svg, "title", x + TILE / 2, TOP - 16, title);
+    for (i, &value) in values.iter().take(MAX_QUEUE).enumerate() {
+        draw_value(svg, x, TOP + i as u32 * (TILE + GAP), value);
+    }
+    if values.len() > MAX_QUEUE {
+        let y = TOP + MAX_QUEUE as u32 * (TILE + GAP) + TILE / 4;
+        let more = format!("+{}", values.len() - MAX_QUEUE);
+        text(svg, "title", x + TILE / 2, y, &more);
+    }
+}
+
+fn draw_value(svg: &mut String, x: u32, y: u32, value: Value) {
+    let (x, y, side) = (x + 4, y + 4, TILE - 8);
+    writeln!(
+        svg,
+        "<g class=\"value\"><rect x=\"{x}\" y=\"{y}\" width=\"{side}\" height=\"{side}\" \
+        rx=\"4\"/><text x=\"{}\" y=\"{}\">{}</text></g>",
+        x + side / 2,
+        y + side / 2,
+        escape(&value.to_string())
+    )
+    .unwrap();
+}
+
+fn text(svg: &mut String, class: &str, x: u32, y: u32, content: &str) {
+    writeln!(
+        svg,
+        "<text class=\"{class}\" x=\"{x}\" y=\"{y}\">{}</text>",
+        escape(content)
+    )
+    .unwrap();
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::problem::{Problem, ProblemBuilder, ProblemIO};
+
+    fn problem() -> Problem {
+        ProblemBuilder::new()
+            .add_io(ProblemIO {
+                input: vec![Value::Int(3), Value::Char('<')],
+                output: vec![Value::Int(3), Value::Char('<')],
+            })
+            .memory_dim(7)
+            .tile_label(5, String::from("zero"))
+            .floor_hole(6)
+            .enable_all_commands()
+            .build()
+            .unwrap()
+    }
+
+    const SOURCE: &str = "a:\nINBOX\nCOPYTO 2\nOUTBOX\nJUMP a\n";
+
+    #[test]
+    fn to_svg_test() {
+        let mut session = Session::create(problem(), SOURCE, 0).unwrap();
+        session.run(3);
+        let svg = Frame::from_session(&session).to_svg();
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(6, svg.matches("class=\"tile\"").count());
+        // Inbox, hands, outbox & tile 2
+        assert_eq!(4, svg.matches("class=\"value\"").count());
+        assert!(svg.contains(">&lt;</text>"));
+        assert!(svg.contains(">5 zero</text>"));
+        assert!(svg.contains(">step 3</text>"));
+    }
+
+    #[test]
+    fn trace_frames_test() {
+        let mut session = Session::create(problem(), SOURCE, 0).unwrap();
+        let trace = session.export_trace(100);
+        let frames = trace_frames(&trace);
+        assert_eq!(trace.steps.len() + 1, frames.len());
+
+        let first = &frames[0];
+        assert_eq!(vec![Value::Int(3), Value::Char('<')], first.inbox);
+        assert_eq!(None, first.caption);
+        let third = &frames[3];
+        assert_eq!(vec![Value::Char('<')], third.inbox);
+        assert_eq!(vec![Value::Int(3)], third.outbox);
+        assert_eq!(Some(Value::Int(3)), third.memory[2]);
+        assert_eq!(Some(String::from("3: OUTBOX")), third.caption);
+
+        let last = frames.last().unwrap();
+        assert_eq!(Some(Value::Char('<')), last.memory[2]);
+        assert!(last.inbox.is_empty());
+    }
+
+    #[test]
+    fn animate_test() {
+        let mut session = Session::create(problem(), SOURCE, 0).unwrap();
+        let frames = trace_frames(&session.export_trace(2));
+        let svg = animate(&frames, Duration::from_millis(500));
+
+        assert_eq!(1, svg.matches("<svg").count());
+        assert_eq!(3, svg.matches("<animate ").count());
+        assert!(svg.contains("values=\"inline;none\" keyTimes=\"0;0.3333333333333333\""));
+        assert!(svg.contains("dur=\"1500ms\""));
+    }
+
+    #[test]
+    fn queue_overflow() {
+        let frame = Frame {
+            inbox: vec![Value::Int(1); 10],
+            acc: None,
+            outbox: vec![],
+            memory: vec![],
+            tile_labels: BTreeMap::new(),
+            holes: vec![],
+            caption: None,
+        };
+        let svg = frame.to_svg();
+        assert_eq!(MAX_QUEUE, svg.matches("class=\"value\"").count());
+        assert!(svg.contains(">+2</text>"));
+    }
+}
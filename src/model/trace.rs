@@ -0,0 +1,171 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{code::program::Memory, game::value::Value, model::diagnostic::Diagnostic};
+
+/// Version of the [Trace] schema, bumped on breaking changes. New optional fields may be added
+/// without a bump, so readers should ignore unknown fields.
+pub const TRACE_VERSION: u32 = 1;
+
+/// Trace
+///
+/// Recorded run of a program on a single IO, so visualizers in any language can replay it
+/// without an interpreter - apply [TraceStep::memory] changes to [Trace::memory] in order. See
+/// [crate::model::session::Session::export_trace].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Trace {
+    /// Schema version, see [TRACE_VERSION]
+    pub version: u32,
+    /// Index of the traced IO
+    pub io: usize,
+    pub input: Vec<Value>,
+    /// Memory before the first step
+    pub memory: Memory,
+    pub steps: Vec<TraceStep>,
+    /// The run ended, successfully unless there's an error. Else the trace was cut short.
+    pub finished: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<Diagnostic>,
+}
+
+/// Trace Step
+///
+/// An executed command & the state after it. Debug commands aren't steps.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraceStep {
+    /// Index of the step, from 0
+    pub step: u64,
+    /// Index of the executed command
+    pub i_command: usize,
+    /// Executed command as source, e.g. `COPYTO [2]`
+    pub command: String,
+    /// Source line (0-based) of the executed command
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    pub acc: Option<Value>,
+    /// Tiles written by this step
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub memory: Vec<MemoryChange>,
+    /// Inputs consumed
+    pub i_input: usize,
+    /// Outputs produced
+    pub i_output: usize,
+    /// Value produced by this step
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<Value>,
+    /// Speed so far
+    pub speed: u64,
+}
+
+/// Memory Change
+///
+/// New value of a tile.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MemoryChange {
+    pub tile: usize,
+    pub value: Option<Value>,
+}
+
+impl MemoryChange {
+    /// Diff
+    ///
+    /// Returns changes turning memory `before` into `after`. Memory only grows, tiles past the end
+    /// of `before` are empty.
+    pub fn diff(before: &Memory, after: &Memory) -> Vec<MemoryChange> {
+        after
+            .iter()
+            .enumerate()
+            .filter(|(tile, value)| before.get(*tile).copied().flatten() != **value)
+            .map(|(tile, &value)| MemoryChange { tile, value })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        game::problem::{ProblemBuilder, ProblemIO},
+        model::session::Session,
+    };
+
+    #[test]
+    fn diff_test() {
+        let before = vec![Some(Value::Int(1)), None];
+        let after = vec![None, None, Some(Value::Char('A'))];
+        assert_eq!(
+            vec![
+                MemoryChange {
+                    tile: 0,
+                    value: None,
+                },
+                MemoryChange {
+                    tile: 2,
+                    value: Some(Value::Char('A')),
+                },
+            ],
+            MemoryChange::diff(&before, &after)
+        );
+        assert!(MemoryChange::diff(&after, &after).is_empty());
+    }
+
+    #[test]
+    fn export_trace() {
+        let problem = ProblemBuilder::new()
+            .add_io(ProblemIO {
+                input: vec![Value::Int(3)],
+                output: vec![Value::Int(6)],
+            })
+            .memory_dim(1)
+            .enable_all_commands()
+            .build()
+            .unwrap();
+        let source = "INBOX\nDEBUG \"x\" 0\nCOPYTO 0\nADD 0\nOUTBOX\n";
+        let mut session = Session::create(problem, source, 0).unwrap();
+        session.step();
+
+        let trace = session.export_trace(100);
+        assert_eq!(TRACE_VERSION, trace.version);
+        assert_eq!(vec![None], trace.memory);
+        assert!(trace.finished);
+        assert_eq!(None, trace.error);
+
+        let commands: Vec<&str> = trace.steps.iter().map(|s| s.command.as_str()).collect();
+        assert_eq!(vec!["INBOX", "COPYTO 0", "ADD 0", "OUTBOX"], commands);
+        assert_eq!(2, trace.steps[1].i_command);
+        assert_eq!(
+            vec![MemoryChange {
+                tile: 0,
+                value: Some(Value::Int(3)),
+            }],
+            trace.steps[1].memory
+        );
+        assert!(trace.steps[2].memory.is_empty());
+        assert_eq!(Some(Value::Int(6)), trace.steps[3].output);
+        assert_eq!((1, 1), (trace.steps[3].i_input, trace.steps[3].i_output));
+
+        let json = serde_json::to_string(&trace).unwrap();
+        assert_eq!(trace, serde_json::from_str(&json).unwrap());
+    }
+
+    #[test]
+    fn export_trace_cut_short() {
+        let problem = ProblemBuilder::new()
+            .add_io(ProblemIO {
+                input: vec![Value::Int(1), Value::Int(2)],
+                output: vec![Value::Int(1), Value::Int(2)],
+            })
+            .enable_all_commands()
+            .build()
+            .unwrap();
+        let mut session = Session::create(problem, "a:\nINBOX\nOUTBOX\nJUMP a\n", 0).unwrap();
+
+        let trace = session.export_trace(2);
+        assert_eq!(2, trace.steps.len());
+        assert!(!trace.finished);
+        assert_eq!(1, trace.steps[1].step);
+
+        let json = serde_json::to_value(&trace).unwrap();
+        assert_eq!(None, json.get("error"));
+        assert_eq!(None, json["steps"][0].get("output"));
+    }
+}
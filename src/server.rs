@@ -0,0 +1,430 @@
+//! Grading Service
+//!
+//! HTTP API compiling, running & scoring solutions, run by the `hrm-server` binary. Requests &
+//! responses are JSON, problems are in the [ProblemDefinition] format:
+//! - `POST /compile` with `{"source": "...", "extended": false}` returns a [CompileResult]
+//! - `POST /run` with `{"problem": {...}, "source": "..."}` returns a [RunResult]
+//! - `POST /score` with the same body returns a [ScoreResult]
+//!
+//! Solutions are untrusted, every request is bounded by the [ServerLimits]. Invalid problems &
+//! exceeded limits are answered with `{"code": "...", "message": "..."}`, see [ServerError].
+
+use std::{io, panic, time::Duration};
+
+use axum::{
+    extract::{DefaultBodyLimit, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::net::TcpListener;
+
+use crate::{
+    code::program::{ChallengeResult, RunLimits},
+    game::problem::{Problem, ProblemBuildError},
+    model::{
+        diagnostic::Diagnostic,
+        problem_definition::{ProblemDefinition, ProblemDefinitionError},
+        session::{compile_source, run_source_with_limits, CompileResult, RunResult},
+    },
+};
+
+/// Server Limits
+///
+/// Sandboxing of untrusted problems & solutions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ServerLimits {
+    /// Steps per IO, longer runs fail with [crate::code::program::RunError::StepLimit]. Runs
+    /// repeating a state fail right away with [crate::code::program::RunError::InfiniteLoop].
+    pub max_steps: u32,
+    /// Steps over all IOs, longer runs fail with
+    /// [crate::code::program::RunError::TotalStepLimit]
+    pub max_total_steps: u64,
+    /// Time to answer a request, fails with [ServerError::Timeout]
+    pub timeout: Duration,
+    /// Largest request body in bytes
+    pub max_body: usize,
+    /// Largest floor in tiles, see [ServerError::MemoryLimit]
+    pub max_memory: usize,
+    /// Most IOs of a problem, see [ServerError::IOLimit]
+    pub max_ios: usize,
+}
+
+impl Default for ServerLimits {
+    fn default() -> Self {
+        Self {
+            max_steps: 1_000_000,
+            max_total_steps: 10_000_000,
+            timeout: Duration::from_secs(5),
+            max_body: 1 << 20,
+            max_memory: 1_024,
+            max_ios: 1_024,
+        }
+    }
+}
+
+/// Server Error
+///
+/// Error codes `E11xx`, others are of the underlying error, see [ServerError::code].
+#[derive(Debug, PartialEq, thiserror::Error)]
+#[non_exhaustive]
+pub enum ServerError {
+    #[error(transparent)]
+    Definition(ProblemDefinitionError),
+    /// Not empty
+    #[error("{}", .0.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+    Build(Vec<ProblemBuildError>),
+    #[error("Request took longer than {0:?}")]
+    Timeout(Duration),
+    #[error("Floor of {tiles} tiles exceeds the limit of {max}")]
+    MemoryLimit { tiles: usize, max: usize },
+    #[error("{ios} IOs exceed the limit of {max}")]
+    IOLimit { ios: usize, max: usize },
+    /// Problems with [ProblemDefinition::unbounded_memory] could grow the floor without limit
+    #[error("Unbounded memory is not allowed")]
+    UnboundedMemory,
+}
+
+impl ServerError {
+    /// Code
+    ///
+    /// Returns the stable error code, e.g. `E1101`. Of problem build errors, the first one's.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ServerError::Definition(err) => err.code(),
+            ServerError::Build(errors) => errors.first().map_or("E0400", ProblemBuildError::code),
+            ServerError::Timeout(_) => "E1101",
+            ServerError::MemoryLimit { .. } => "E1102",
+            ServerError::IOLimit { .. } => "E1103",
+            ServerError::UnboundedMemory => "E1104",
+        }
+    }
+}
+
+impl IntoResponse for ServerError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            ServerError::Timeout(_) => StatusCode::SERVICE_UNAVAILABLE,
+            _ => StatusCode::UNPROCESSABLE_ENTITY,
+        };
+        let body = json!({"code": self.code(), "message": self.to_string()});
+        (status, Json(body)).into_response()
+    }
+}
+
+/// Body of `POST /compile`
+#[derive(Debug, Deserialize)]
+pub struct CompileRequest {
+    pub source: String,
+    /// Compile with [crate::compiler::compile::Compiler::extended]
+    #[serde(default)]
+    pub extended: bool,
+}
+
+/// Body of `POST /run` & `POST /score`
+#[derive(Debug, Deserialize)]
+pub struct RunRequest {
+    pub problem: ProblemDefinition,
+    pub source: String,
+}
+
+/// Score Result
+///
+/// Grade of a solution, the response of `POST /score`.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ScoreResult {
+    /// Whether the solution is valid & produces the expected output for every IO
+    pub solved: bool,
+    pub size: Option<usize>,
+    pub speed_avg: Option<f64>,
+    /// Size & speed challenges of the problem, if solved
+    pub challenge: Option<ChallengeResult>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Router
+///
+/// Routes of the grading service, see the [module docs](self).
+pub fn router(limits: ServerLimits) -> Router {
+    Router::new()
+        .route("/compile", post(compile))
+        .route("/run", post(run))
+        .route("/score", post(score))
+        .layer(DefaultBodyLimit::max(limits.max_body))
+        .with_state(limits)
+}
+
+/// Serve
+///
+/// Answer requests on `listener` until the process ends.
+pub async fn serve(listener: TcpListener, limits: ServerLimits) -> io::Result<()> {
+    axum::serve(listener, router(limits)).await
+}
+
+async fn compile(
+    State(limits): State<ServerLimits>,
+    Json(request): Json<CompileRequest>,
+) -> Result<Json<CompileResult>, ServerError> {
+    limited(limits, move || {
+        compile_source(&request.source, request.extended)
+    })
+    .await
+    .map(Json)
+}
+
+async fn run(
+    State(limits): State<ServerLimits>,
+    Json(request): Json<RunRequest>,
+) -> Result<Json<RunResult>, ServerError> {
+    limited(limits, move || {
+        let problem = problem(request.problem, limits)?;
+        Ok(run_source_with_limits(
+            &problem,
+            &request.source,
+            run_limits(limits),
+        ))
+    })
+    .await?
+    .map(Json)
+}
+
+async fn score(
+    State(limits): State<ServerLimits>,
+    Json(request): Json<RunRequest>,
+) -> Result<Json<ScoreResult>, ServerError> {
+    limited(limits, move || {
+        let problem = problem(request.problem, limits)?;
+        let result = run_source_with_limits(&problem, &request.source, run_limits(limits));
+        Ok(ScoreResult {
+            solved: result.score.is_some(),
+            size: result.score.as_ref().map(|score| score.size),
+            speed_avg: result.score.as_ref().map(|score| score.speed_avg),
+            challenge: result.score.as_ref().map(|score| score.evaluate(&problem)),
+            diagnostics: result.diagnostics,
+        })
+    })
+    .await?
+    .map(Json)
+}
+
+/// Build the problem of an untrusted definition, whose size is checked before anything is
+/// allocated for it
+fn problem(definition: ProblemDefinition, limits: ServerLimits) -> Result<Problem, ServerError> {
+    let tiles = definition.memory.as_ref().map_or(0, |memory| {
+        let full = memory.full.as_ref().map_or(0, Vec::len);
+        let partial = memory.partial.as_ref().map_or(0, |partial| partial.dim);
+        full.max(partial)
+    });
+    if tiles > limits.max_memory {
+        return Err(ServerError::MemoryLimit {
+            tiles,
+            max: limits.max_memory,
+        });
+    }
+    if definition.ios.len() > limits.max_ios {
+        return Err(ServerError::IOLimit {
+            ios: definition.ios.len(),
+            max: limits.max_ios,
+        });
+    }
+    if definition.unbounded_memory {
+        return Err(ServerError::UnboundedMemory);
+    }
+
+    definition.validate().map_err(ServerError::Definition)?;
+    Problem::try_from(definition).map_err(ServerError::Build)
+}
+
+fn run_limits(limits: ServerLimits) -> RunLimits {
+    RunLimits {
+        max_steps: Some(limits.max_steps),
+        detect_loops: true,
+        max_total_steps: Some(limits.max_total_steps),
+    }
+}
+
+/// Run `work` on a blocking thread, failing once [ServerLimits::timeout] passes.
+/// [ServerLimits::max_total_steps] bounds how long a timed out run keeps its thread.
+async fn limited<T: Send + 'static>(
+    limits: ServerLimits,
+    work: impl FnOnce() -> T + Send + 'static,
+) -> Result<T, ServerError> {
+    let task = tokio::task::spawn_blocking(work);
+    match tokio::time::timeout(limits.timeout, task).await {
+        Ok(Ok(result)) => Ok(result),
+        Ok(Err(err)) => panic::resume_unwind(err.into_panic()),
+        Err(_) => Err(ServerError::Timeout(limits.timeout)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        net::{SocketAddr, TcpStream},
+    };
+
+    use serde_json::Value;
+    use tokio::runtime::Runtime;
+
+    use super::*;
+
+    const PROBLEM: &str = r#"{
+        "title": "Mail Room",
+        "description": "",
+        "commands": ["INBOX", "OUTBOX", "JUMP"],
+        "ios": [{"input": [1, "A"], "output": [1, "A"]}],
+        "size_target": 3,
+        "speed_target": 5
+    }"#;
+
+    fn start(limits: ServerLimits) -> (Runtime, SocketAddr) {
+        let runtime = Runtime::new().unwrap();
+        let listener = runtime.block_on(TcpListener::bind("127.0.0.1:0")).unwrap();
+        let addr = listener.local_addr().unwrap();
+        runtime.spawn(serve(listener, limits));
+        (runtime, addr)
+    }
+
+    fn post(addr: SocketAddr, path: &str, body: &str) -> (u16, Value) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(
+            stream,
+            "POST {path} HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\n\
+            Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        let status = response[9..12].parse().unwrap();
+        let body = &response[response.find("\r\n\r\n").unwrap() + 4..];
+        (status, serde_json::from_str(body).unwrap_or(Value::Null))
+    }
+
+    fn run_body(source: &str) -> String {
+        format!(r#"{{"problem": {PROBLEM}, "source": {}}}"#, json!(source))
+    }
+
+    #[test]
+    fn compile_test() {
+        let (_runtime, addr) = start(ServerLimits::default());
+        let (status, body) = post(addr, "/compile", r#"{"source": "INBOX\nOUTBOX\n"}"#);
+        assert_eq!(200, status);
+        assert!(body["program"].is_object());
+
+        let (_, body) = post(addr, "/compile", r#"{"source": "NEG\n"}"#);
+        assert_eq!("E0101", body["diagnostics"][0]["code"]);
+        let (_, body) = post(addr, "/compile", r#"{"source": "NEG\n", "extended": true}"#);
+        assert_eq!(json!([]), body["diagnostics"]);
+    }
+
+    #[test]
+    fn run_score_test() {
+        let (_runtime, addr) = start(ServerLimits::default());
+        let (status, body) = post(addr, "/run", &run_body("a:\nINBOX\nOUTBOX\nJUMP a\n"));
+        assert_eq!(200, status);
+        assert_eq!(3, body["score"]["size"]);
+
+        let (_, body) = post(addr, "/score", &run_body("a:\nINBOX\nOUTBOX\nJUMP a\n"));
+        assert_eq!(
+            json!({
+                "solved": true,
+                "size": 3,
+                "speed_avg": 6.0,
+                "challenge": {"size": true, "speed": false},
+                "diagnostics": [],
+            }),
+            body
+        );
+
+        let (_, body) = post(addr, "/score", &run_body("INBOX\nOUTBOX\n"));
+        assert_eq!(false, body["solved"]);
+        assert_eq!(Value::Null, body["challenge"]);
+        assert!(!body["diagnostics"].as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn limits_test() {
+        let limits = ServerLimits {
+            max_steps: 10,
+            max_body: 200,
+            ..ServerLimits::default()
+        };
+        let (_runtime, addr) = start(limits);
+        let source = "a:\nINBOX\nOUTBOX\nOUTBOX\nJUMP a\n";
+        let body = format!(r#"{{"problem": {PROBLEM}, "source": {}}}"#, json!(source));
+        let (status, _) = post(addr, "/run", &body);
+        assert_eq!(413, status);
+
+        let (_runtime, addr) = start(ServerLimits {
+            max_steps: 10,
+            ..ServerLimits::default()
+        });
+        let mut problem: Value = serde_json::from_str(PROBLEM).unwrap();
+        problem["ios"] = json!([{"input": [1, 2, 3, 4, 5, 6], "output": [1, 2, 3, 4, 5, 6]}]);
+        let body = json!({"problem": problem, "source": "a:\nINBOX\nOUTBOX\nJUMP a\n"});
+        let (_, body) = post(addr, "/run", &body.to_string());
+        assert_eq!("E0321", body["diagnostics"][0]["code"]);
+
+        problem["ios"] = json!([]);
+        let body = json!({"problem": problem, "source": ""});
+        let (status, body) = post(addr, "/score", &body.to_string());
+        assert_eq!(422, status);
+        assert_eq!("E0504", body["code"]);
+    }
+
+    #[test]
+    fn problem_limits_test() {
+        let (_runtime, addr) = start(ServerLimits {
+            max_total_steps: 10,
+            max_memory: 4,
+            max_ios: 2,
+            ..ServerLimits::default()
+        });
+        let problem: Value = serde_json::from_str(PROBLEM).unwrap();
+        let post_problem = |patch: Value| {
+            let mut problem = problem.clone();
+            problem
+                .as_object_mut()
+                .unwrap()
+                .extend(patch.as_object().unwrap().clone());
+            let body = json!({"problem": problem, "source": "a:\nINBOX\nOUTBOX\nJUMP a\n"});
+            post(addr, "/run", &body.to_string())
+        };
+
+        let (status, body) = post_problem(json!({"memory": {"partial": {"dim": 5, "values": {}}}}));
+        assert_eq!(422, status);
+        assert_eq!("E1102", body["code"]);
+        let (_, body) = post_problem(json!({"memory": {"full": [null, null, null, null, null]}}));
+        assert_eq!("E1102", body["code"]);
+        let ios = vec![json!({"input": [], "output": []}); 3];
+        let (_, body) = post_problem(json!({ "ios": ios }));
+        assert_eq!("E1103", body["code"]);
+        let (_, body) = post_problem(json!({"unbounded_memory": true}));
+        assert_eq!("E1104", body["code"]);
+
+        let ios = vec![json!({"input": [1, 2, 3], "output": [1, 2, 3]}); 2];
+        let (status, body) = post_problem(json!({ "ios": ios }));
+        assert_eq!(200, status);
+        assert_eq!("E0324", body["diagnostics"][0]["code"]);
+    }
+
+    #[test]
+    fn timeout_test() {
+        let limits = ServerLimits {
+            timeout: Duration::from_millis(10),
+            ..ServerLimits::default()
+        };
+        let runtime = Runtime::new().unwrap();
+        let result = runtime.block_on(limited(limits, || {
+            std::thread::sleep(Duration::from_millis(200))
+        }));
+        assert_eq!(Err(ServerError::Timeout(limits.timeout)), result);
+        assert_eq!("E1101", result.unwrap_err().code());
+    }
+}
@@ -0,0 +1,343 @@
+//! Terminal UI
+//!
+//! Watch a program run in the terminal - the inbox, the worker holding a value, the outbox, the
+//! floor & the program with the next command highlighted. Driven by [Session], see [watch].
+
+use std::{
+    io,
+    time::{Duration, Instant},
+};
+
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    text::Line,
+    widgets::{Block, List, ListState, Paragraph},
+    DefaultTerminal, Frame,
+};
+
+use crate::{
+    game::value::Value,
+    model::session::{Session, StepState},
+};
+
+/// Help shown in the status bar
+const KEYS: &str = "space step · p play/pause · +/- speed · r restart · q quit";
+/// Width of a floor tile, incl. its border
+const TILE_WIDTH: u16 = 10;
+const MIN_DELAY: Duration = Duration::from_millis(25);
+const MAX_DELAY: Duration = Duration::from_millis(1600);
+
+/// Watch
+///
+/// Take over the terminal & show the [Session] until the user quits:
+/// - `space` or `n` takes a step, `p` plays & pauses, `+` & `-` change the playing speed
+/// - `r` restarts, `q` or `esc` quits
+pub fn watch(session: Session) -> io::Result<()> {
+    let mut terminal = ratatui::init();
+    let result = Watcher::new(session).run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+/// Watcher
+///
+/// State of the visualizer, see [watch]. Rendering & key handling are separate from the terminal,
+/// so any ratatui backend can draw it.
+pub struct Watcher {
+    session: Session,
+    playing: bool,
+    /// Time between steps while playing
+    delay: Duration,
+    quit: bool,
+}
+
+impl Watcher {
+    pub fn new(session: Session) -> Self {
+        Self {
+            session,
+            playing: false,
+            delay: Duration::from_millis(200),
+            quit: false,
+        }
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        let mut stepped_at = Instant::now();
+        while !self.quit {
+            terminal.draw(|frame| self.render(frame))?;
+
+            let key = if self.playing {
+                let timeout = self.delay.saturating_sub(stepped_at.elapsed());
+                event::poll(timeout)?.then(event::read).transpose()?
+            } else {
+                Some(event::read()?)
+            };
+            match key {
+                Some(Event::Key(key)) if key.kind == KeyEventKind::Press => {
+                    self.handle_key(key.code)
+                }
+                Some(_) => {}
+                None => {
+                    self.playing = self.session.step();
+                    stepped_at = Instant::now();
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Handle Key
+    ///
+    /// React to a key press, see [watch] for the keys.
+    pub fn handle_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char(' ' | 'n') => {
+                self.playing = false;
+                self.session.step();
+            }
+            KeyCode::Char('p') => self.playing = !self.playing && !self.session.state().finished,
+            KeyCode::Char('+') => self.delay = (self.delay / 2).max(MIN_DELAY),
+            KeyCode::Char('-') => self.delay = (self.delay * 2).min(MAX_DELAY),
+            KeyCode::Char('r') => {
+                self.playing = false;
+                self.session.reset();
+            }
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            _ => {}
+        }
+    }
+
+    /// Is Playing
+    ///
+    /// Returns `true` if steps are taken without key presses.
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    /// Render
+    ///
+    /// Draw the current state on the whole frame.
+    pub fn render(&self, frame: &mut Frame) {
+        let state = self.session.state();
+        let area = frame.area();
+        let tiles = state.memory.len().max(1) as u16;
+        let columns = (area.width.saturating_sub(2) / TILE_WIDTH).max(1);
+        let floor_height = tiles.div_ceil(columns) * 3 + 2;
+
+        let [top, floor, status] = Layout::vertical([
+            Constraint::Min(8),
+            Constraint::Length(floor_height),
+            Constraint::Length(2),
+        ])
+        .areas(area);
+        let [inbox, worker, outbox, program] = Layout::horizontal([
+            Constraint::Length(9),
+            Constraint::Length(20),
+            Constraint::Length(9),
+            Constraint::Min(24),
+        ])
+        .areas(top);
+
+        self.render_inbox(frame, inbox, &state);
+        render_worker(frame, worker, &state);
+        self.render_outbox(frame, outbox, &state);
+        self.render_program(frame, program, &state);
+        self.render_floor(frame, floor, &state, columns);
+        self.render_status(frame, status, &state);
+    }
+
+    fn render_inbox(&self, frame: &mut Frame, area: Rect, state: &StepState) {
+        let input = self.session.get_input();
+        let waiting = input.get(state.inputs_consumed..).unwrap_or_default();
+        let items = waiting.iter().map(|&value| value_line(value));
+        let title = format!("IN {}", waiting.len());
+        frame.render_widget(List::new(items).block(Block::bordered().title(title)), area);
+    }
+
+    fn render_outbox(&self, frame: &mut Frame, area: Rect, state: &StepState) {
+        // Newest on top, like values being pushed into the outbox
+        let items = state.produced.iter().rev().map(|&value| value_line(value));
+        let title = format!(
+            "OUT {}/{}",
+            state.produced.len(),
+            self.session.get_output().len()
+        );
+        frame.render_widget(List::new(items).block(Block::bordered().title(title)), area);
+    }
+
+    fn render_program(&self, frame: &mut Frame, area: Rect, state: &StepState) {
+        let program = self.session.get_program();
+        let mut labels: Vec<(usize, &str)> = program
+            .get_labels()
+            .map(|(label, idx)| (idx, label.as_str()))
+            .collect();
+        labels.sort();
+        let mut labels = labels.into_iter().peekable();
+
+        let mut lines = vec![];
+        let mut selected = None;
+        let commands = program.get_commands();
+        for i in 0..=commands.len() {
+            while let Some((_, label)) = labels.next_if(|(idx, _)| *idx <= i) {
+                lines.push(Line::from(format!("{label}:")).fg(Color::Cyan));
+            }
+            if i == state.i_command && !state.finished {
+                selected = Some(lines.len());
+            }
+            if let Some(command) = commands.get(i) {
+                lines.push(Line::from(format!("{:>3}  {}", i + 1, command.to_source())));
+            }
+        }
+
+        let highlight = Style::new().bg(Color::Yellow).fg(Color::Black);
+        let list = List::new(lines)
+            .block(Block::bordered().title("PROGRAM"))
+            .highlight_style(highlight)
+            .highlight_symbol("> ");
+        let mut list_state = ListState::default().with_selected(selected);
+        frame.render_stateful_widget(list, area, &mut list_state);
+    }
+
+    fn render_floor(&self, frame: &mut Frame, area: Rect, state: &StepState, columns: u16) {
+        let block = Block::bordered().title("FLOOR");
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let problem = self.session.get_problem();
+        for (tile, value) in state.memory.iter().enumerate() {
+            let (row, column) = (tile as u16 / columns, tile as u16 % columns);
+            let cell = Rect::new(
+                inner.x + column * TILE_WIDTH,
+                inner.y + row * 3,
+                TILE_WIDTH,
+                3,
+            )
+            .intersection(inner);
+            if cell.is_empty() {
+                continue;
+            }
+
+            let title = problem
+                .get_tile_label(tile)
+                .map_or_else(|| tile.to_string(), |label| format!("{tile} {label}"));
+            let text = value.map(|value| value.to_string()).unwrap_or_default();
+            let tile = Paragraph::new(text)
+                .centered()
+                .bold()
+                .block(Block::bordered().title(title).dark_gray());
+            frame.render_widget(tile, cell);
+        }
+    }
+
+    fn render_status(&self, frame: &mut Frame, area: Rect, state: &StepState) {
+        let progress = format!("steps {} · speed {}", state.steps, state.speed);
+        let line = match &state.error {
+            Some(error) => {
+                Line::from(format!("{progress} · {}: {}", error.code, error.message)).fg(Color::Red)
+            }
+            None if state.finished => Line::from(format!("{progress} · finished")).fg(Color::Green),
+            None if self.playing => Line::from(format!("{progress} · playing")),
+            None => Line::from(progress),
+        };
+        let help = Line::from(KEYS).add_modifier(Modifier::DIM);
+        frame.render_widget(Paragraph::new(vec![line, help]), area);
+    }
+}
+
+fn render_worker(frame: &mut Frame, area: Rect, state: &StepState) {
+    let block = Block::bordered().title("WORKER");
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let [hands] = Layout::vertical([Constraint::Length(3)])
+        .flex(Flex::Center)
+        .areas(inner);
+    let text = state.acc.map(|acc| acc.to_string()).unwrap_or_default();
+    let [hands] = Layout::horizontal([Constraint::Length(8)])
+        .flex(Flex::Center)
+        .areas(hands);
+    frame.render_widget(
+        Paragraph::new(text)
+            .centered()
+            .bold()
+            .block(Block::bordered().yellow()),
+        hands,
+    );
+}
+
+fn value_line(value: Value) -> Line<'static> {
+    Line::from(format!("{:^5}", value.to_string())).bold()
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{backend::TestBackend, Terminal};
+
+    use super::*;
+    use crate::game::problem::{Problem, ProblemBuilder, ProblemIO};
+
+    fn watcher() -> Watcher {
+        let problem: Problem = ProblemBuilder::new()
+            .add_io(ProblemIO {
+                input: vec![Value::Int(7), Value::Char('B')],
+                output: vec![Value::Int(7), Value::Char('B')],
+            })
+            .memory_dim(3)
+            .tile_label(2, String::from("zero"))
+            .enable_all_commands()
+            .build()
+            .unwrap();
+        let source = "a:\nINBOX\nCOPYTO 1\nOUTBOX\nJUMP a\n";
+        Watcher::new(Session::create(problem, source, 0).unwrap())
+    }
+
+    fn draw(watcher: &Watcher) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        terminal.draw(|frame| watcher.render(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn render_test() {
+        let mut watcher = watcher();
+        let screen = draw(&watcher);
+        assert!(screen.contains("IN 2"));
+        assert!(screen.contains("OUT 0/2"));
+        assert!(screen.contains(">   1  INBOX"));
+        assert!(screen.contains("2 zero"));
+
+        (0..3).for_each(|_| watcher.handle_key(KeyCode::Char(' ')));
+        let screen = draw(&watcher);
+        assert!(screen.contains("IN 1"));
+        assert!(screen.contains("OUT 1/2"));
+        assert!(screen.contains(">   4  JUMP a"));
+        assert!(screen.contains("steps 3 · speed 3"));
+    }
+
+    #[test]
+    fn handle_key_test() {
+        let mut watcher = watcher();
+        watcher.handle_key(KeyCode::Char('p'));
+        assert!(watcher.is_playing());
+        watcher.handle_key(KeyCode::Char('+'));
+        assert_eq!(Duration::from_millis(100), watcher.delay);
+
+        while watcher.session.step() {}
+        assert!(draw(&watcher).contains("finished"));
+        watcher.handle_key(KeyCode::Char('r'));
+        assert!(!watcher.is_playing());
+        assert_eq!(0, watcher.session.state().steps);
+        watcher.handle_key(KeyCode::Char('q'));
+        assert!(watcher.quit);
+    }
+}
@@ -0,0 +1,136 @@
+//! WASM Bindings
+//!
+//! Compile, validate, run & step through programs from JavaScript, so browser playgrounds embed
+//! this engine instead of re-implementing its semantics. Problems are passed as plain objects in
+//! the [ProblemDefinition] format, results are returned as plain objects. Issues are reported as
+//! [Diagnostic](crate::model::diagnostic::Diagnostic)s, only malformed arguments throw.
+
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    game::problem::Problem,
+    model::{
+        problem_definition::ProblemDefinition,
+        session::{self, compile_source, run_source, validate_source},
+        svg::Frame,
+    },
+};
+
+/// Compile
+///
+/// Compile `source` with the vanilla or the extended instruction set, see [compile_source].
+#[wasm_bindgen]
+pub fn compile(source: &str, extended: bool) -> Result<JsValue, JsError> {
+    to_js(&compile_source(source, extended))
+}
+
+/// Validate
+///
+/// Returns the compile, validation & analysis diagnostics of `source` for `problem`, see
+/// [validate_source].
+#[wasm_bindgen]
+pub fn validate(problem: JsValue, source: &str) -> Result<JsValue, JsError> {
+    to_js(&validate_source(&from_js(problem)?, source))
+}
+
+/// Run
+///
+/// Compile, validate & run `source` for every IO of `problem`, see [run_source].
+#[wasm_bindgen]
+pub fn run(problem: JsValue, source: &str) -> Result<JsValue, JsError> {
+    to_js(&run_source(&from_js(problem)?, source))
+}
+
+/// Session
+///
+/// Step-by-step run of a program on a single IO, see [session::Session].
+#[wasm_bindgen]
+pub struct Session(session::Session);
+
+#[wasm_bindgen]
+impl Session {
+    /// New
+    ///
+    /// Start a session on IO `io` of `problem`. Throws if the program doesn't compile or
+    /// validate, or the IO doesn't exist.
+    #[wasm_bindgen(constructor)]
+    pub fn new(problem: JsValue, source: &str, io: usize) -> Result<Session, JsError> {
+        session::Session::create(from_js(problem)?, source, io)
+            .map(Session)
+            .map_err(|err| JsError::new(&err))
+    }
+
+    /// Step
+    ///
+    /// Execute the next command & return the new state.
+    pub fn step(&mut self) -> Result<JsValue, JsError> {
+        self.0.step();
+        self.state()
+    }
+
+    /// Run
+    ///
+    /// Step until the run ends or `max_steps` more steps were taken & return the state.
+    pub fn run(&mut self, max_steps: u32) -> Result<JsValue, JsError> {
+        self.0.run(max_steps);
+        self.state()
+    }
+
+    /// Trace
+    ///
+    /// Like [Session::run], but returns the state after every step.
+    pub fn trace(&mut self, max_steps: u32) -> Result<JsValue, JsError> {
+        to_js(&self.0.trace(max_steps))
+    }
+
+    /// Export Trace
+    ///
+    /// Restart & run for at most `max_steps` steps, returning the replayable trace, see
+    /// [session::Session::export_trace].
+    #[wasm_bindgen(js_name = exportTrace)]
+    pub fn export_trace(&mut self, max_steps: u32) -> Result<JsValue, JsError> {
+        to_js(&self.0.export_trace(max_steps))
+    }
+
+    /// State
+    ///
+    /// Returns the current state.
+    pub fn state(&self) -> Result<JsValue, JsError> {
+        to_js(&self.0.state())
+    }
+
+    /// SVG
+    ///
+    /// Returns the current state drawn as an SVG image, see [Frame::to_svg].
+    pub fn svg(&self) -> String {
+        Frame::from_session(&self.0).to_svg()
+    }
+
+    /// Reset
+    ///
+    /// Restart from the first command.
+    pub fn reset(&mut self) {
+        self.0.reset();
+    }
+}
+
+fn from_js(problem: JsValue) -> Result<Problem, JsError> {
+    let definition: ProblemDefinition =
+        serde_wasm_bindgen::from_value(problem).map_err(|err| JsError::new(&err.to_string()))?;
+    definition
+        .validate()
+        .map_err(|err| JsError::new(&err.to_string()))?;
+    Problem::try_from(definition).map_err(|errors| {
+        let errors: Vec<String> = errors.iter().map(ToString::to_string).collect();
+        JsError::new(&errors.join(", "))
+    })
+}
+
+fn to_js<T: Serialize>(value: &T) -> Result<JsValue, JsError> {
+    // Maps become plain objects, e.g. histograms & labels
+    let serializer = serde_wasm_bindgen::Serializer::new().serialize_maps_as_objects(true);
+    value
+        .serialize(&serializer)
+        .map_err(|err| JsError::new(&err.to_string()))
+}